@@ -6,18 +6,160 @@ use psst_core::{
     cdn::{Cdn, CdnHandle},
     connection::Credentials,
     error::Error,
+    ipc::{self, IpcRequest, IpcResponse, IpcTrack},
     item_id::{ItemId, ItemIdType},
     session::{SessionConfig, SessionHandle},
 };
-use std::{env, io, io::BufRead, path::PathBuf, thread};
+use std::{
+    env,
+    io::{self, BufRead, BufReader},
+    path::PathBuf,
+    process, thread,
+};
+
+const USAGE: &str = "\
+Usage: psst-cli <command> [arguments]
+
+Controls a running psst instance:
+    status [--json]         Show the playback state
+    now-playing [--json]    Show the current track, e.g. for status bars
+    play [<spotify uri>]    Resume, or start playing the given URI
+    pause | toggle | next | previous | stop
+    seek <seconds>          Jump to a position in the current track
+    volume <0-100>          Set the volume
+    show                    Bring the window to the front
+    open <spotify uri>      Show the album, artist or playlist page
+
+Plays a track directly, without the app:
+    play-track <track id>   Needs SPOTIFY_USERNAME and SPOTIFY_PASSWORD";
 
 fn main() {
     env_logger::init();
 
-    let args: Vec<String> = env::args().collect();
-    let track_id = args
-        .get(1)
-        .expect("Expected <track_id> in the first parameter");
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let json = args.contains(&"--json");
+    let result = match args.as_slice() {
+        ["play-track", track_id] => {
+            play_track(track_id);
+            return;
+        }
+        ["status", ..] => request(IpcRequest::Status).map(|r| print_status(r, json)),
+        ["now-playing", ..] => request(IpcRequest::Status).map(|r| print_now_playing(r, json)),
+        ["play"] => request(IpcRequest::Play { uri: None }).map(print_result),
+        ["play", uri] => request(IpcRequest::Play {
+            uri: Some(uri.to_string()),
+        })
+        .map(print_result),
+        ["pause"] => request(IpcRequest::Pause).map(print_result),
+        ["toggle"] => request(IpcRequest::Toggle).map(print_result),
+        ["next"] => request(IpcRequest::Next).map(print_result),
+        ["previous"] => request(IpcRequest::Previous).map(print_result),
+        ["stop"] => request(IpcRequest::Stop).map(print_result),
+        ["show"] => request(IpcRequest::Show).map(print_result),
+        ["open", uri] => request(IpcRequest::Open {
+            uri: uri.to_string(),
+        })
+        .map(print_result),
+        ["seek", seconds] => match seconds.parse::<f64>() {
+            Ok(seconds) if seconds >= 0.0 => request(IpcRequest::Seek {
+                position_ms: (seconds * 1000.0) as u64,
+            })
+            .map(print_result),
+            _ => usage(),
+        },
+        ["volume", level] => match level.parse() {
+            Ok(level) => request(IpcRequest::Volume { level }).map(print_result),
+            _ => usage(),
+        },
+        _ => usage(),
+    };
+    if let Err(err) = result {
+        eprintln!("psst-cli: {}", err);
+        process::exit(1);
+    }
+}
+
+fn usage() -> io::Result<()> {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn request(request: IpcRequest) -> io::Result<IpcResponse> {
+    let stream = ipc::connect().map_err(|err| {
+        io::Error::new(
+            err.kind(),
+            format!("psst doesn't seem to be running ({})", err),
+        )
+    })?;
+    let mut writer = stream.try_clone()?;
+    ipc::write_message(&mut writer, &request)?;
+    ipc::read_message(&mut BufReader::new(stream))?
+        .ok_or_else(|| io::ErrorKind::UnexpectedEof.into())
+}
+
+fn print_result(response: IpcResponse) {
+    if let IpcResponse::Error { message } = response {
+        eprintln!("psst-cli: {}", message);
+        process::exit(1);
+    }
+}
+
+fn print_status(response: IpcResponse, json: bool) {
+    match response {
+        IpcResponse::Status(status) if json => {
+            println!("{}", serde_json::to_string(&status).unwrap());
+        }
+        IpcResponse::Status(status) => {
+            println!("State: {}", status.state);
+            if let Some(track) = &status.track {
+                println!("Track: {}", track_title(track));
+                println!(
+                    "Position: {} / {}",
+                    format_time(status.progress_ms),
+                    track.duration_ms.map_or("?".to_string(), format_time)
+                );
+            }
+            println!("Volume: {}%", status.volume);
+        }
+        other => print_result(other),
+    }
+}
+
+fn print_now_playing(response: IpcResponse, json: bool) {
+    match response {
+        IpcResponse::Status(status) if json => {
+            println!("{}", serde_json::to_string(&status.track).unwrap());
+        }
+        IpcResponse::Status(status) => {
+            // Print nothing while stopped, so status bars can hide the module.
+            if let Some(track) = &status.track {
+                let paused = if status.state == "paused" {
+                    " (paused)"
+                } else {
+                    ""
+                };
+                println!("{}{}", track_title(track), paused);
+            }
+        }
+        other => print_result(other),
+    }
+}
+
+fn track_title(track: &IpcTrack) -> String {
+    match (&track.artist, &track.name) {
+        (Some(artist), Some(name)) => format!("{} - {}", artist, name),
+        (None, Some(name)) => name.to_owned(),
+        _ => track.uri.to_owned(),
+    }
+}
+
+fn format_time(millis: u64) -> String {
+    let secs = millis / 1000;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+fn play_track(track_id: &str) {
     let login_creds = Credentials::from_username_and_password(
         env::var("SPOTIFY_USERNAME").unwrap(),
         env::var("SPOTIFY_PASSWORD").unwrap(),
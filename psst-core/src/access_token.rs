@@ -9,7 +9,7 @@ use std::{
 const CLIENT_ID: &str = "65b708073fc0480ea92a077233ca87bd";
 
 // All scopes we could possibly require.
-const ACCESS_SCOPES: &str = "streaming,user-read-email,user-read-private,playlist-read-private,playlist-read-collaborative,playlist-modify-public,playlist-modify-private,user-follow-modify,user-follow-read,user-library-read,user-library-modify,user-top-read,user-read-recently-played";
+const ACCESS_SCOPES: &str = "streaming,user-read-email,user-read-private,playlist-read-private,playlist-read-collaborative,playlist-modify-public,playlist-modify-private,user-follow-modify,user-follow-read,user-library-read,user-library-modify,user-top-read,user-read-recently-played,user-read-playback-position";
 
 // Consider token expired even before the official expiration time.  Spotify
 // seems to be reporting excessive token TTLs so let's cut it down by 30
@@ -76,4 +76,12 @@ impl TokenProvider {
         }
         Ok(token.clone())
     }
+
+    /// Throw away the current token, so the next `get` requests a new one.
+    pub fn invalidate(&self) {
+        *self
+            .token
+            .lock()
+            .expect("Failed to acquire access token lock") = AccessToken::expired();
+    }
 }
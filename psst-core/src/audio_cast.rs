@@ -0,0 +1,361 @@
+//! Playback on DLNA / UPnP media renderers on the local network.  The decoded
+//! audio is served over HTTP as an endless WAV stream, and the renderer is told
+//! to play it through its AVTransport service.
+
+use crate::{
+    audio_output::{AudioSample, AudioSource},
+    error::Error,
+};
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{Ipv4Addr, Shutdown, TcpListener, TcpStream, UdpSocket},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use url::Url;
+
+const SSDP_ADDR: (Ipv4Addr, u16) = (Ipv4Addr::new(239, 255, 255, 250), 1900);
+const AV_TRANSPORT: &str = "urn:schemas-upnp-org:service:AVTransport:1";
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+// Number of samples pulled from the source at once.
+const CHUNK_SAMPLES: usize = 4096;
+// How far ahead of the wall clock we let the stream get.  Renderers read as
+// fast as they can, but the playback progress follows the samples we pull
+// from the source, so it has to move in real time.
+const MAX_LEAD: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Renderer {
+    pub name: String,
+    /// Control endpoint of the AVTransport service.
+    pub control_url: String,
+}
+
+/// Look for media renderers on the local network, waiting `timeout` for the
+/// answers.
+pub fn discover(timeout: Duration) -> Result<Vec<Renderer>, Error> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.set_read_timeout(Some(Duration::from_millis(200)))?;
+    let request = format!(
+        "M-SEARCH * HTTP/1.1\r\n\
+         HOST: 239.255.255.250:1900\r\n\
+         MAN: \"ssdp:discover\"\r\n\
+         MX: {}\r\n\
+         ST: {}\r\n\r\n",
+        timeout.as_secs().max(1),
+        AV_TRANSPORT
+    );
+    socket.send_to(request.as_bytes(), SSDP_ADDR)?;
+
+    let deadline = Instant::now() + timeout;
+    let mut locations = Vec::new();
+    let mut buf = [0_u8; 2048];
+    while Instant::now() < deadline {
+        match socket.recv_from(&mut buf) {
+            Ok((len, _)) => {
+                let response = String::from_utf8_lossy(&buf[..len]);
+                if let Some(location) = header_value(&response, "location") {
+                    if !locations.contains(&location) {
+                        locations.push(location);
+                    }
+                }
+            }
+            Err(err)
+                if err.kind() == io::ErrorKind::WouldBlock
+                    || err.kind() == io::ErrorKind::TimedOut => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    let renderers = locations
+        .iter()
+        .filter_map(|location| match describe(location) {
+            Ok(renderer) => Some(renderer),
+            Err(err) => {
+                log::warn!("failed to describe renderer at {}: {}", location, err);
+                None
+            }
+        })
+        .collect();
+    Ok(renderers)
+}
+
+/// Fetch the device description and find the name and the AVTransport control
+/// endpoint of the renderer.
+fn describe(location: &str) -> Result<Renderer, Error> {
+    let xml = ureq::get(location)
+        .timeout(HTTP_TIMEOUT)
+        .call()?
+        .into_string()?;
+    let name = xml_element(&xml, "friendlyName").unwrap_or(location);
+    let control_path = xml
+        .split("<service>")
+        .find(|service| service.contains(AV_TRANSPORT))
+        .and_then(|service| xml_element(service, "controlURL"))
+        .ok_or(Error::UnexpectedResponse)?;
+    let base = xml_element(&xml, "URLBase").unwrap_or(location);
+    let control_url = Url::parse(base)
+        .and_then(|base| base.join(control_path))
+        .map_err(|_| Error::UnexpectedResponse)?;
+    Ok(Renderer {
+        name: xml_unescape(name),
+        control_url: control_url.to_string(),
+    })
+}
+
+/// Audio currently being played on a renderer.  Stops the renderer when
+/// dropped.
+pub struct CastSession {
+    renderer: Renderer,
+    is_streaming: Arc<AtomicBool>,
+    connection: Arc<Mutex<Option<TcpStream>>>,
+}
+
+impl CastSession {
+    pub fn start<T>(renderer: Renderer, source: Arc<Mutex<T>>) -> Result<Self, Error>
+    where
+        T: AudioSource + Send + 'static,
+    {
+        let control_url =
+            Url::parse(&renderer.control_url).map_err(|_| Error::UnexpectedResponse)?;
+        let renderer_addr = control_url
+            .socket_addrs(|| Some(80))?
+            .into_iter()
+            .next()
+            .ok_or(Error::UnexpectedResponse)?;
+
+        // Find out the address the renderer can reach us on, by letting the OS
+        // pick the route.
+        let local_ip = {
+            let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+            socket.connect(renderer_addr)?;
+            socket.local_addr()?.ip()
+        };
+        let listener = TcpListener::bind((local_ip, 0))?;
+        let stream_url = format!("http://{}/stream.wav", listener.local_addr()?);
+
+        let session = Self {
+            renderer,
+            is_streaming: Arc::new(AtomicBool::new(true)),
+            connection: Arc::new(Mutex::new(None)),
+        };
+        thread::spawn({
+            let is_streaming = session.is_streaming.clone();
+            let connection = session.connection.clone();
+            move || serve_stream(listener, source, is_streaming, connection)
+        });
+
+        log::info!("casting to {:?} from {}", session.renderer.name, stream_url);
+        session.soap(
+            "SetAVTransportURI",
+            &format!(
+                "<CurrentURI>{}</CurrentURI><CurrentURIMetaData></CurrentURIMetaData>",
+                stream_url
+            ),
+        )?;
+        session.play()?;
+        Ok(session)
+    }
+
+    pub fn play(&self) -> Result<(), Error> {
+        self.soap("Play", "<Speed>1</Speed>")
+    }
+
+    pub fn pause(&self) -> Result<(), Error> {
+        self.soap("Pause", "")
+    }
+
+    fn soap(&self, action: &str, arguments: &str) -> Result<(), Error> {
+        let body = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+             <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+             s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+             <s:Body><u:{action} xmlns:u=\"{service}\">\
+             <InstanceID>0</InstanceID>{arguments}\
+             </u:{action}></s:Body></s:Envelope>",
+            action = action,
+            service = AV_TRANSPORT,
+            arguments = arguments
+        );
+        ureq::post(&self.renderer.control_url)
+            .timeout(HTTP_TIMEOUT)
+            .set("Content-Type", "text/xml; charset=\"utf-8\"")
+            .set("SOAPAction", &format!("\"{}#{}\"", AV_TRANSPORT, action))
+            .send_string(&body)?;
+        Ok(())
+    }
+}
+
+impl Drop for CastSession {
+    fn drop(&mut self) {
+        self.is_streaming.store(false, Ordering::SeqCst);
+        if let Some(connection) = self.connection.lock().unwrap().take() {
+            // Unblocks the streaming thread if it's stuck writing.
+            connection.shutdown(Shutdown::Both).ok();
+        }
+        if let Err(err) = self.soap("Stop", "") {
+            log::warn!("failed to stop renderer: {}", err);
+        }
+    }
+}
+
+fn serve_stream<T>(
+    listener: TcpListener,
+    source: Arc<Mutex<T>>,
+    is_streaming: Arc<AtomicBool>,
+    connection: Arc<Mutex<Option<TcpStream>>>,
+) where
+    T: AudioSource,
+{
+    if let Err(err) = listener.set_nonblocking(true) {
+        log::error!("failed to set up cast stream: {}", err);
+        return;
+    }
+    while is_streaming.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, peer)) => {
+                log::debug!("renderer {} connected to the cast stream", peer);
+                if let Ok(clone) = stream.try_clone() {
+                    connection.lock().unwrap().replace(clone);
+                }
+                // Renderers usually probe with `HEAD` or re-open the stream, so
+                // just serve connections one by one.
+                if let Err(err) = stream_to(stream, &source, &is_streaming) {
+                    log::debug!("cast stream connection closed: {}", err);
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(100));
+            }
+            Err(err) => {
+                log::error!("cast stream failed: {}", err);
+                break;
+            }
+        }
+    }
+}
+
+fn stream_to<T>(
+    stream: TcpStream,
+    source: &Mutex<T>,
+    is_streaming: &AtomicBool,
+) -> Result<(), io::Error>
+where
+    T: AudioSource,
+{
+    stream.set_nonblocking(false)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Skip the rest of the request headers.
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+
+    let (channels, sample_rate) = {
+        let source = source.lock().unwrap();
+        (source.channels(), source.sample_rate())
+    };
+    let mut stream = stream;
+    stream.write_all(
+        b"HTTP/1.1 200 OK\r\n\
+          Content-Type: audio/wav\r\n\
+          Cache-Control: no-cache\r\n\
+          Connection: close\r\n\
+          transferMode.dlna.org: Streaming\r\n\r\n",
+    )?;
+    if request_line.starts_with("HEAD") {
+        return Ok(());
+    }
+    stream.write_all(&wav_header(channels, sample_rate))?;
+
+    let samples_per_sec = sample_rate as f64 * channels as f64;
+    let mut started = Instant::now();
+    let mut written = 0_u64;
+    let mut bytes = Vec::with_capacity(CHUNK_SAMPLES * 2);
+    while is_streaming.load(Ordering::SeqCst) {
+        let written_duration = Duration::from_secs_f64(written as f64 / samples_per_sec);
+        let elapsed = started.elapsed();
+        if written_duration > elapsed + MAX_LEAD {
+            thread::sleep(written_duration - elapsed - MAX_LEAD);
+            continue;
+        }
+        if elapsed > written_duration + MAX_LEAD {
+            // The renderer stopped reading for a while, most probably because it
+            // got paused.  Don't try to catch up, pick up from where we are.
+            started += elapsed - written_duration;
+        }
+
+        // Only pull what we are about to write, so the progress reported by
+        // the source matches what the renderer got.
+        bytes.clear();
+        {
+            let mut source = source.lock().unwrap();
+            for _ in 0..CHUNK_SAMPLES {
+                // Silence in case the source has finished.
+                let sample: AudioSample = source.next().unwrap_or(0.0);
+                let sample = (sample.max(-1.0).min(1.0) * i16::MAX as f32) as i16;
+                bytes.extend_from_slice(&sample.to_le_bytes());
+            }
+        }
+        stream.write_all(&bytes)?;
+        written += CHUNK_SAMPLES as u64;
+    }
+    Ok(())
+}
+
+/// Header of a 16-bit PCM WAV file of unknown, i.e. maximal, length.
+fn wav_header(channels: u8, sample_rate: u32) -> Vec<u8> {
+    const UNKNOWN_LENGTH: u32 = u32::MAX;
+    let channels = u16::from(channels);
+    let bits_per_sample: u16 = 16;
+    let block_align = channels * bits_per_sample / 8;
+    let byte_rate = sample_rate * u32::from(block_align);
+
+    let mut header = Vec::with_capacity(44);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&UNKNOWN_LENGTH.to_le_bytes());
+    header.extend_from_slice(b"WAVEfmt ");
+    header.extend_from_slice(&16_u32.to_le_bytes());
+    header.extend_from_slice(&1_u16.to_le_bytes()); // PCM
+    header.extend_from_slice(&channels.to_le_bytes());
+    header.extend_from_slice(&sample_rate.to_le_bytes());
+    header.extend_from_slice(&byte_rate.to_le_bytes());
+    header.extend_from_slice(&block_align.to_le_bytes());
+    header.extend_from_slice(&bits_per_sample.to_le_bytes());
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&(UNKNOWN_LENGTH - 36).to_le_bytes());
+    header
+}
+
+fn header_value(response: &str, name: &str) -> Option<String> {
+    response.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        if key.trim().eq_ignore_ascii_case(name) {
+            Some(value.trim().to_string())
+        } else {
+            None
+        }
+    })
+}
+
+/// Text content of the first `<name>` element.  The descriptions are simple
+/// enough that we don't need a real XML parser.
+fn xml_element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", name))? + name.len() + 2;
+    let end = start + xml[start..].find(&format!("</{}>", name))?;
+    Some(xml[start..end].trim())
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
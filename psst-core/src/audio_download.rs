@@ -0,0 +1,62 @@
+use crate::{
+    audio_file::AudioFile,
+    audio_player::{load_audio_key, load_audio_path, PlaybackConfig},
+    cache::CacheHandle,
+    cdn::CdnHandle,
+    error::Error,
+    item_id::ItemId,
+    session::SessionHandle,
+};
+
+/// Downloads tracks into the cache and pins them, so they can be played back
+/// without a connection.  Audio files are stored encrypted, exactly as received
+/// from the CDN, and decrypted on playback.
+pub struct Downloader {
+    session: SessionHandle,
+    cdn: CdnHandle,
+    cache: CacheHandle,
+    config: PlaybackConfig,
+}
+
+impl Downloader {
+    pub fn new(
+        session: SessionHandle,
+        cdn: CdnHandle,
+        cache: CacheHandle,
+        config: PlaybackConfig,
+    ) -> Self {
+        Self {
+            session,
+            cdn,
+            cache,
+            config,
+        }
+    }
+
+    pub fn configure(&mut self, config: PlaybackConfig) {
+        self.config = config;
+    }
+
+    /// Fetch the metadata, the audio key and the audio file of `item_id`, and pin
+    /// the file for offline playback.  `progress` is called with the number of
+    /// downloaded and total bytes.
+    pub fn download(&self, item_id: ItemId, progress: impl FnMut(u64, u64)) -> Result<(), Error> {
+        let path = load_audio_path(item_id, &self.session, &self.cache, &self.config)?;
+        load_audio_key(&path, &self.session, &self.cache)?;
+        if !self.cache.audio_file_path(path.file_id).exists() {
+            AudioFile::download(path, &self.cdn, &self.cache, progress)?;
+        }
+        self.cache.pin_audio_file(item_id, path.file_id)?;
+        Ok(())
+    }
+
+    /// Unpin `item_id`.  The audio file stays in the cache, but is not guaranteed
+    /// to be available offline anymore.
+    pub fn remove(&self, item_id: ItemId) -> Result<(), Error> {
+        self.cache.unpin_audio_file(item_id)
+    }
+
+    pub fn pinned_items(&self) -> Result<Vec<ItemId>, Error> {
+        self.cache.pinned_items()
+    }
+}
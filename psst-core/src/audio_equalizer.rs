@@ -0,0 +1,151 @@
+use crate::audio_output::AudioSample;
+use std::f32::consts::PI;
+
+pub const EQUALIZER_BANDS: usize = 10;
+
+/// Center frequencies of the equalizer bands, one octave apart.
+pub const EQUALIZER_FREQUENCIES: [f32; EQUALIZER_BANDS] = [
+    31.0, 62.0, 125.0, 250.0, 500.0, 1000.0, 2000.0, 4000.0, 8000.0, 16000.0,
+];
+
+/// Gain of each equalizer band, in decibels.
+pub type EqualizerGains = [f32; EQUALIZER_BANDS];
+
+// Quality factor giving roughly one octave of bandwidth.
+const BAND_Q: f32 = 1.41;
+
+// Portion of the remaining distance the headroom moves every sample, so
+// changing it doesn't click.
+const HEADROOM_SMOOTHING: f32 = 0.001;
+
+pub struct Equalizer {
+    channels: usize,
+    sample_rate: u32,
+    gains: EqualizerGains,
+    // Filters of all bands, indexed by channel.  Flat bands pass the signal
+    // through unchanged, but keep running, so changing the gains doesn't reset
+    // the filter history.
+    filters: Vec<Vec<Biquad>>,
+    // Attenuation making room for the boosted bands, so they don't clip.
+    headroom: f32,
+    headroom_target: f32,
+    // Channel of the next sample, samples are expected to be interleaved.
+    channel: usize,
+}
+
+impl Equalizer {
+    pub fn new(channels: u8, sample_rate: u32) -> Self {
+        let gains = [0.0; EQUALIZER_BANDS];
+        let band_filters: Vec<Biquad> = EQUALIZER_FREQUENCIES
+            .iter()
+            .map(|&freq| Biquad::peaking(freq, 0.0, BAND_Q, sample_rate))
+            .collect();
+        Self {
+            channels: channels.into(),
+            sample_rate,
+            gains,
+            filters: vec![band_filters; channels.into()],
+            headroom: 1.0,
+            headroom_target: 1.0,
+            channel: 0,
+        }
+    }
+
+    pub fn set_gains(&mut self, gains: &EqualizerGains) {
+        if &self.gains == gains {
+            return;
+        }
+        self.gains = *gains;
+        for band_filters in &mut self.filters {
+            for ((filter, &freq), &gain) in band_filters
+                .iter_mut()
+                .zip(EQUALIZER_FREQUENCIES.iter())
+                .zip(gains.iter())
+            {
+                filter.set_peaking(freq, gain, BAND_Q, self.sample_rate);
+            }
+        }
+        let max_boost = gains.iter().copied().fold(0.0_f32, f32::max);
+        self.headroom_target = 10.0_f32.powf(-max_boost / 20.0);
+    }
+
+    fn is_flat(&self) -> bool {
+        self.gains.iter().all(|&gain| gain == 0.0)
+    }
+
+    pub fn process(&mut self, sample: AudioSample) -> AudioSample {
+        self.headroom += (self.headroom_target - self.headroom) * HEADROOM_SMOOTHING;
+        let is_flat = self.is_flat();
+        let filters = &mut self.filters[self.channel];
+        self.channel = (self.channel + 1) % self.channels;
+        let output = filters
+            .iter_mut()
+            .fold(sample * self.headroom, |sample, filter| {
+                filter.process(sample)
+            });
+        if is_flat {
+            // Keep the filters running, but play the input untouched.
+            sample * self.headroom
+        } else {
+            output
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl Biquad {
+    fn peaking(freq: f32, gain_db: f32, q: f32, sample_rate: u32) -> Self {
+        let mut filter = Self {
+            b0: 1.0,
+            b1: 0.0,
+            b2: 0.0,
+            a1: 0.0,
+            a2: 0.0,
+            x1: 0.0,
+            x2: 0.0,
+            y1: 0.0,
+            y2: 0.0,
+        };
+        filter.set_peaking(freq, gain_db, q, sample_rate);
+        filter
+    }
+
+    // Peaking EQ filter, as described in the Audio EQ Cookbook by Robert
+    // Bristow-Johnson.  Keeps the history, so the signal continues smoothly.
+    fn set_peaking(&mut self, freq: f32, gain_db: f32, q: f32, sample_rate: u32) {
+        let a = 10.0_f32.powf(gain_db / 40.0);
+        let w0 = 2.0 * PI * freq / sample_rate as f32;
+        let alpha = w0.sin() / (2.0 * q);
+        let cos_w0 = w0.cos();
+
+        let a0 = 1.0 + alpha / a;
+        self.b0 = (1.0 + alpha * a) / a0;
+        self.b1 = (-2.0 * cos_w0) / a0;
+        self.b2 = (1.0 - alpha * a) / a0;
+        self.a1 = (-2.0 * cos_w0) / a0;
+        self.a2 = (1.0 - alpha / a) / a0;
+    }
+
+    fn process(&mut self, x: f32) -> f32 {
+        let y = self.b0 * x + self.b1 * self.x1 + self.b2 * self.x2
+            - self.a1 * self.y1
+            - self.a2 * self.y2;
+        self.x2 = self.x1;
+        self.x1 = x;
+        self.y2 = self.y1;
+        self.y1 = y;
+        y
+    }
+}
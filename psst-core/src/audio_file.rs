@@ -12,6 +12,7 @@ use crate::{
     util::OffsetFile,
 };
 use std::{
+    fs::{self, File},
     io,
     io::{BufReader, Seek, SeekFrom},
     path::PathBuf,
@@ -44,28 +45,30 @@ pub enum AudioFile {
 impl AudioFile {
     pub fn compatible_audio_formats(preferred_bitrate: usize) -> &'static [Format] {
         match preferred_bitrate {
-            96 => &[
+            0..=96 => &[
                 Format::OGG_VORBIS_96,
                 Format::OGG_VORBIS_160,
                 Format::OGG_VORBIS_320,
             ],
-            160 => &[
+            97..=160 => &[
                 Format::OGG_VORBIS_160,
                 Format::OGG_VORBIS_320,
                 Format::OGG_VORBIS_96,
             ],
-            320 => &[
+            _ => &[
                 Format::OGG_VORBIS_320,
                 Format::OGG_VORBIS_160,
                 Format::OGG_VORBIS_96,
             ],
-            _ => unreachable!(),
         }
     }
 
     pub fn open(path: AudioPath, cdn: CdnHandle, cache: CacheHandle) -> Result<Self, Error> {
         let cached_file = cache.audio_file_path(path.file_id);
         if cached_file.exists() {
+            if let Err(err) = cache.touch_audio_file(path.file_id) {
+                log::warn!("failed to touch cached audio file: {:?}", err);
+            }
             let cached_file = CachedFile::open(path, cached_file)?;
             Ok(Self::Cached { cached_file })
         } else {
@@ -85,6 +88,47 @@ impl AudioFile {
         }
     }
 
+    /// Download the complete file into the cache, without decrypting it.  `progress`
+    /// is called with the number of downloaded and total bytes after every chunk.
+    pub fn download(
+        path: AudioPath,
+        cdn: &CdnHandle,
+        cache: &CacheHandle,
+        mut progress: impl FnMut(u64, u64),
+    ) -> Result<(), Error> {
+        // How many bytes we request in one go.
+        const CHUNK_LENGTH: u64 = 1024 * 512;
+
+        let cached_path = cache.audio_file_path(path.file_id);
+        let part_path = cached_path.with_extension("part");
+        let mut part_file = File::create(&part_path)?;
+
+        let mut url = cdn.resolve_audio_file_url(path.file_id)?;
+        let mut offset = 0;
+        loop {
+            if url.is_expired() {
+                url = cdn.resolve_audio_file_url(path.file_id)?;
+            }
+            let (total_length, mut reader) =
+                cdn.fetch_file_range(&url.url, offset, CHUNK_LENGTH)?;
+            let written = io::copy(&mut reader, &mut part_file)?;
+            if written == 0 {
+                return Err(Error::UnexpectedResponse);
+            }
+            offset += written;
+            progress(offset, total_length);
+            if offset >= total_length {
+                break;
+            }
+        }
+
+        // Only move the file into the cache after it's complete, so a partial download
+        // is never mistaken for a cached file.
+        fs::rename(part_path, cached_path)?;
+        cache.evict_audio_files()?;
+        Ok(())
+    }
+
     pub fn path(&self) -> AudioPath {
         match self {
             Self::Streamed { streamed_file, .. } => streamed_file.path,
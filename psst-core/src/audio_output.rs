@@ -1,6 +1,10 @@
-use crate::error::Error;
+use crate::{
+    audio_cast::{CastSession, Renderer},
+    audio_spectrum::SpectrumTap,
+    error::Error,
+};
 use crossbeam_channel::{unbounded, Receiver, Sender};
-use miniaudio::{Context, Device, DeviceConfig, DeviceType, Format};
+use miniaudio::{Context, Device, DeviceConfig, DeviceId, DeviceType, Format};
 use std::sync::{Arc, Mutex};
 
 pub type AudioSample = f32;
@@ -8,9 +12,9 @@ pub type AudioSample = f32;
 pub trait AudioSource: Iterator<Item = AudioSample> {
     fn channels(&self) -> u8;
     fn sample_rate(&self) -> u32;
-    fn normalization_factor(&self) -> Option<f32>;
 }
 
+#[derive(Clone)]
 pub struct AudioOutputRemote {
     event_sender: Sender<InternalEvent>,
 }
@@ -28,6 +32,18 @@ impl AudioOutputRemote {
         self.send(InternalEvent::Resume);
     }
 
+    /// Switch to a playback device with given name, or to the system default if
+    /// `None`.
+    pub fn set_device(&self, name: Option<String>) {
+        self.send(InternalEvent::SetDevice(name));
+    }
+
+    /// Play on a media renderer on the local network instead of the playback
+    /// device, or go back to the device if `None`.
+    pub fn cast(&self, renderer: Option<Renderer>) {
+        self.send(InternalEvent::Cast(renderer));
+    }
+
     fn send(&self, event: InternalEvent) {
         self.event_sender.send(event).expect("Audio output died");
     }
@@ -35,6 +51,8 @@ impl AudioOutputRemote {
 
 pub struct AudioOutput {
     context: Context,
+    device_name: Option<String>,
+    spectrum_tap: Option<SpectrumTap>,
     event_sender: Sender<InternalEvent>,
     event_receiver: Receiver<InternalEvent>,
 }
@@ -50,11 +68,36 @@ impl AudioOutput {
 
         Ok(Self {
             context,
+            device_name: None,
+            spectrum_tap: None,
             event_sender,
             event_receiver,
         })
     }
 
+    /// Names of all available playback devices.
+    pub fn devices() -> Result<Vec<String>, Error> {
+        let context = Context::new(&[], None)?;
+        let mut names = Vec::new();
+        context.with_devices(|playback_devices, _capture_devices| {
+            names = playback_devices
+                .iter()
+                .map(|device| device.name().to_string())
+                .collect();
+        })?;
+        Ok(names)
+    }
+
+    /// Use a playback device with given name, instead of the system default.
+    pub fn set_device(&mut self, name: Option<String>) {
+        self.device_name = name;
+    }
+
+    /// Feed all played samples also into given spectrum tap.
+    pub fn set_spectrum_tap(&mut self, tap: SpectrumTap) {
+        self.spectrum_tap = Some(tap);
+    }
+
     pub fn remote(&self) -> AudioOutputRemote {
         AudioOutputRemote {
             event_sender: self.event_sender.clone(),
@@ -67,6 +110,7 @@ impl AudioOutput {
     {
         // Create a device config that describes the kind of device we want to use.
         let mut config = DeviceConfig::new(DeviceType::Playback);
+        let mut spectrum_tap = self.spectrum_tap.clone();
 
         {
             // Setup the device config for playback with the channel count and sample rate
@@ -75,46 +119,123 @@ impl AudioOutput {
             config.playback_mut().set_format(Format::F32);
             config.playback_mut().set_channels(source.channels().into());
             config.set_sample_rate(source.sample_rate());
+            if let Some(tap) = &mut spectrum_tap {
+                tap.set_format(source.channels(), source.sample_rate());
+            }
         };
 
+        // The tap is only ever touched from the data callback, so the lock is never
+        // contended.
+        let spectrum_tap = Arc::new(Mutex::new(spectrum_tap));
+
+        // Let us know when the device stops, so we can detect it getting disconnected.
+        config.set_stop_callback({
+            let event_sender = self.event_sender.clone();
+            move |_device| {
+                event_sender.send(InternalEvent::Stopped).ok();
+            }
+        });
+
+        // Casting reads the samples on its own, without the device.
+        let cast_source = source.clone();
+
         // Move the source into the config's data callback.  Callback will get cloned
         // for each device we create.
         config.set_data_callback(move |_device, output, _frames| {
             let mut source = source.lock().expect("Failed to acquire audio source lock");
-            // Get the audio normalization factor.
-            let norm_factor = source.normalization_factor().unwrap_or(1.0);
+            let mut spectrum_tap = spectrum_tap.lock().expect("Failed to acquire tap lock");
             // Fill the buffer with audio samples from the source.
             for sample in output.as_samples_mut() {
                 let s = source.next().unwrap_or(0.0); // Use silence in case the
                                                       // source has finished.
-                *sample = s * norm_factor;
+                *sample = s;
+                if let Some(tap) = spectrum_tap.as_mut() {
+                    tap.push(s);
+                }
             }
         });
 
-        let device = {
-            let context = self.context.clone();
-            Device::new(Some(context), &config)?
-        };
+        // Errors of the device are logged and we fall back to the default one, the
+        // output keeps running even without a device, so the player can carry on.
+        let mut device_name = self.device_name.clone();
+        let mut device = self.open_device_or_default(&mut config, device_name.as_deref());
+        // Whether the output should be running, regardless of the device state.
+        let mut is_playing = false;
+        // Renderer we are casting to instead of playing on the device, if any.
+        let mut cast: Option<CastSession> = None;
 
         for event in self.event_receiver.iter() {
             match event {
                 InternalEvent::Close => {
                     log::debug!("closing audio output");
-                    if device.is_started() {
-                        device.stop()?;
-                    }
+                    cast.take();
+                    stop_device(&device);
                     break;
                 }
                 InternalEvent::Pause => {
                     log::debug!("pausing audio output");
-                    if device.is_started() {
-                        device.stop()?;
+                    is_playing = false;
+                    if let Some(cast) = &cast {
+                        if let Err(err) = cast.pause() {
+                            log::warn!("failed to pause renderer: {}", err);
+                        }
+                    } else {
+                        stop_device(&device);
                     }
                 }
                 InternalEvent::Resume => {
                     log::debug!("resuming audio output");
-                    if !device.is_started() {
-                        device.start()?;
+                    is_playing = true;
+                    if let Some(cast) = &cast {
+                        if let Err(err) = cast.play() {
+                            log::warn!("failed to resume renderer: {}", err);
+                        }
+                    } else if !start_device(&device) {
+                        log::warn!("audio device {:?} failed, falling back", device_name);
+                        device_name = None;
+                        device = self.open_device_or_default(&mut config, None);
+                        start_device(&device);
+                    }
+                }
+                InternalEvent::SetDevice(name) => {
+                    log::debug!("switching audio output to {:?}", name);
+                    stop_device(&device);
+                    device_name = name;
+                    device = self.open_device_or_default(&mut config, device_name.as_deref());
+                    if is_playing && cast.is_none() {
+                        start_device(&device);
+                    }
+                }
+                InternalEvent::Cast(renderer) => {
+                    log::debug!("casting to {:?}", renderer);
+                    cast.take();
+                    if let Some(renderer) = renderer {
+                        stop_device(&device);
+                        match CastSession::start(renderer, cast_source.clone()) {
+                            Ok(session) => {
+                                if !is_playing {
+                                    session.pause().ok();
+                                }
+                                cast.replace(session);
+                            }
+                            Err(err) => {
+                                log::error!("failed to start casting: {}", err);
+                            }
+                        }
+                    }
+                    if cast.is_none() && is_playing {
+                        start_device(&device);
+                    }
+                }
+                InternalEvent::Stopped => {
+                    let is_started = device.as_ref().map_or(false, Device::is_started);
+                    if is_playing && cast.is_none() && !is_started {
+                        // Device stopped on its own, most probably because it got
+                        // disconnected.  Fall back to the default device.
+                        log::warn!("audio device {:?} stopped, falling back", device_name);
+                        device_name = None;
+                        device = self.open_device_or_default(&mut config, None);
+                        start_device(&device);
                     }
                 }
             }
@@ -122,12 +243,89 @@ impl AudioOutput {
 
         Ok(())
     }
+
+    /// Opens the device with given name, or the default device if that fails.
+    fn open_device_or_default(
+        &self,
+        config: &mut DeviceConfig,
+        name: Option<&str>,
+    ) -> Option<Device> {
+        match self.open_device(config, name) {
+            Ok(device) => return Some(device),
+            Err(err) => log::error!("failed to open audio device {:?}: {}", name, err),
+        }
+        if name.is_some() {
+            match self.open_device(config, None) {
+                Ok(device) => return Some(device),
+                Err(err) => log::error!("failed to open default audio device: {}", err),
+            }
+        }
+        None
+    }
+
+    fn open_device(&self, config: &mut DeviceConfig, name: Option<&str>) -> Result<Device, Error> {
+        let device_id = match name {
+            Some(name) => {
+                let device_id = self.find_device_id(name)?;
+                if device_id.is_none() {
+                    log::warn!("audio device {:?} not found, using default", name);
+                }
+                device_id
+            }
+            None => None,
+        };
+        config.playback_mut().set_device_id(device_id);
+        let context = self.context.clone();
+        let device = Device::new(Some(context), config)?;
+        Ok(device)
+    }
+
+    fn find_device_id(&self, name: &str) -> Result<Option<DeviceId>, Error> {
+        let mut device_id = None;
+        self.context
+            .with_devices(|playback_devices, _capture_devices| {
+                device_id = playback_devices
+                    .iter()
+                    .find(|device| device.name() == name)
+                    .map(|device| device.id().clone());
+            })?;
+        Ok(device_id)
+    }
+}
+
+/// Starts the device unless it's running already, returns false if there's no
+/// device or it failed to start.
+fn start_device(device: &Option<Device>) -> bool {
+    match device {
+        Some(device) if device.is_started() => true,
+        Some(device) => match device.start() {
+            Ok(()) => true,
+            Err(err) => {
+                log::error!("failed to start audio device: {}", Error::from(err));
+                false
+            }
+        },
+        None => false,
+    }
+}
+
+fn stop_device(device: &Option<Device>) {
+    if let Some(device) = device {
+        if device.is_started() {
+            if let Err(err) = device.stop() {
+                log::error!("failed to stop audio device: {}", Error::from(err));
+            }
+        }
+    }
 }
 
 enum InternalEvent {
     Close,
     Pause,
     Resume,
+    SetDevice(Option<String>),
+    Cast(Option<Renderer>),
+    Stopped,
 }
 
 impl From<miniaudio::Error> for Error {
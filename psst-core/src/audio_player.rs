@@ -1,15 +1,17 @@
 use crate::{
+    audio_equalizer::{Equalizer, EqualizerGains, EQUALIZER_BANDS},
     audio_file::{AudioFile, AudioPath, FileAudioSource},
     audio_key::AudioKey,
     audio_normalize::NormalizationLevel,
     audio_output::{AudioOutputRemote, AudioSample, AudioSource},
-    audio_queue::{Queue, QueueBehavior},
+    audio_queue::{Queue, QueueBehavior, RepeatMode},
+    audio_resample::Resampler,
     cache::CacheHandle,
     cdn::CdnHandle,
     error::Error,
     item_id::{ItemId, ItemIdType},
     metadata::{Fetch, ToAudioPath},
-    protocol::metadata::Track,
+    protocol::metadata::{Episode, Track},
     session::SessionHandle,
 };
 use crossbeam_channel::{unbounded, Receiver, Sender};
@@ -23,10 +25,16 @@ use std::{
 
 const PREVIOUS_TRACK_THRESHOLD: Duration = Duration::from_secs(3);
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct PlaybackConfig {
     pub bitrate: usize,
     pub pregain: f32,
+    pub crossfade: Duration,
+    pub fade: Duration,
+    pub equalizer: EqualizerGains,
+    pub mono: bool,
+    /// Output volume, between 0.0 and 1.0.
+    pub volume: f32,
 }
 
 impl Default for PlaybackConfig {
@@ -34,6 +42,11 @@ impl Default for PlaybackConfig {
         Self {
             bitrate: 320,
             pregain: 3.0,
+            crossfade: Duration::default(),
+            fade: Duration::from_millis(50),
+            equalizer: [0.0; EQUALIZER_BANDS],
+            mono: false,
+            volume: 1.0,
         }
     }
 }
@@ -65,7 +78,7 @@ impl PlaybackItem {
     }
 }
 
-fn load_audio_path(
+pub(crate) fn load_audio_path(
     item_id: ItemId,
     session: &SessionHandle,
     cache: &CacheHandle,
@@ -75,7 +88,7 @@ fn load_audio_path(
         ItemIdType::Track => {
             load_audio_path_from_track_or_alternative(item_id, session, cache, config)
         }
-        ItemIdType::Podcast => unimplemented!(),
+        ItemIdType::Podcast => load_audio_path_from_episode(item_id, session, cache, config),
         ItemIdType::Unknown => unimplemented!(),
     }
 }
@@ -96,8 +109,7 @@ fn load_audio_path_from_track_or_alternative(
                 .find_allowed_alternative(&user_country)
                 .ok_or(Error::AudioFileNotFound)?;
             let alt_track = load_track(alt_id, session, cache)?;
-            let alt_path = alt_track
-                .to_audio_path(config.bitrate)
+            let alt_path = preferred_audio_path(&alt_track, item_id, cache, config)
                 .ok_or(Error::AudioFileNotFound)?;
             // We've found an alternative track with a fitting audio file.  Let's cheat a
             // little and pretend we've obtained it from the requested track.
@@ -110,14 +122,50 @@ fn load_audio_path_from_track_or_alternative(
         _ => {
             // Either we do not have a country code loaded or the track is available, return
             // it.
-            track
-                .to_audio_path(config.bitrate)
-                .ok_or(Error::AudioFileNotFound)?
+            preferred_audio_path(&track, item_id, cache, config).ok_or(Error::AudioFileNotFound)?
         }
     };
     Ok(path)
 }
 
+fn load_audio_path_from_episode(
+    item_id: ItemId,
+    session: &SessionHandle,
+    cache: &CacheHandle,
+    config: &PlaybackConfig,
+) -> Result<AudioPath, Error> {
+    let episode = Episode::fetch(session, item_id)?;
+    if let Some(country) = get_country_code(session, cache) {
+        if episode.is_restricted_in_region(&country) {
+            return Err(Error::AudioFileNotFound);
+        }
+    }
+    if episode.file.is_empty() {
+        // Some shows are not licensed for distribution through Spotify's CDN,
+        // and their episodes are streamed from the publisher's servers.  We
+        // can't play these.
+        if let Some(url) = &episode.external_url {
+            log::warn!("episode is hosted externally at {}", url);
+        }
+        return Err(Error::AudioFileNotFound);
+    }
+    preferred_audio_path(&episode, item_id, cache, config).ok_or(Error::AudioFileNotFound)
+}
+
+fn preferred_audio_path(
+    track: &impl ToAudioPath,
+    item_id: ItemId,
+    cache: &CacheHandle,
+    config: &PlaybackConfig,
+) -> Option<AudioPath> {
+    // Files pinned for offline playback take precedence, even if their bitrate does not
+    // match the configured one.
+    cache
+        .get_pinned_audio_file(item_id)
+        .and_then(|file_id| track.to_audio_path_for_file(file_id))
+        .or_else(|| track.to_audio_path(config.bitrate))
+}
+
 fn get_country_code(session: &SessionHandle, cache: &CacheHandle) -> Option<String> {
     if let Some(cached_country_code) = cache.get_country_code() {
         Some(cached_country_code)
@@ -146,7 +194,7 @@ fn load_track(
     }
 }
 
-fn load_audio_key(
+pub(crate) fn load_audio_key(
     path: &AudioPath,
     session: &SessionHandle,
     cache: &CacheHandle,
@@ -195,7 +243,9 @@ impl Player {
         let (event_sender, event_receiver) = unbounded();
         let audio_source = {
             let event_sender = event_sender.clone();
-            Arc::new(Mutex::new(PlayerAudioSource::new(event_sender)))
+            let mut source = PlayerAudioSource::new(event_sender);
+            source.configure(&config);
+            Arc::new(Mutex::new(source))
         };
         Self {
             session,
@@ -235,12 +285,18 @@ impl Player {
             PlayerEvent::Preloaded { item, result } => {
                 self.handle_preloaded(item, result);
             }
-            PlayerEvent::Progress { duration, path } => {
-                self.handle_progress(duration, path);
+            PlayerEvent::Progress { duration, .. } => {
+                self.handle_progress(duration);
             }
             PlayerEvent::Finished { .. } => {
                 self.handle_finished();
             }
+            PlayerEvent::Transitioned { path, duration } => {
+                self.handle_transitioned(path, duration);
+            }
+            PlayerEvent::FadedOut => {
+                self.handle_faded_out();
+            }
             PlayerEvent::Loading { .. }
             | PlayerEvent::Playing { .. }
             | PlayerEvent::Pausing { .. }
@@ -255,6 +311,21 @@ impl Player {
             PlayerCommand::LoadQueue { items, position } => self.load_queue(items, position),
             PlayerCommand::LoadAndPlay { item } => self.load_and_play(item),
             PlayerCommand::Preload { item } => self.preload(item),
+            PlayerCommand::InsertIntoQueue { index, item } => {
+                self.reclaim_following();
+                self.queue.insert(index, item);
+                self.preload_following();
+            }
+            PlayerCommand::RemoveFromQueue { index } => {
+                self.reclaim_following();
+                self.queue.remove(index);
+                self.preload_following();
+            }
+            PlayerCommand::MoveInQueue { from, to } => {
+                self.reclaim_following();
+                self.queue.move_item(from, to);
+                self.preload_following();
+            }
             PlayerCommand::Pause => self.pause(),
             PlayerCommand::Resume => self.resume(),
             PlayerCommand::PauseOrResume => self.pause_or_resume(),
@@ -262,8 +333,18 @@ impl Player {
             PlayerCommand::Next => self.next(),
             PlayerCommand::Stop => self.stop(),
             PlayerCommand::Seek { position } => self.seek(position),
+            PlayerCommand::SetSpeed { speed } => self.set_speed(speed),
             PlayerCommand::Configure { config } => self.configure(config),
-            PlayerCommand::SetQueueBehavior { behavior } => self.queue.set_behaviour(behavior),
+            PlayerCommand::SetQueueBehavior { behavior } => {
+                self.reclaim_following();
+                self.queue.set_behaviour(behavior);
+                self.preload_following();
+            }
+            PlayerCommand::SetRepeatMode { repeat } => {
+                self.reclaim_following();
+                self.queue.set_repeat_mode(repeat);
+                self.preload_following();
+            }
         }
     }
 
@@ -296,6 +377,7 @@ impl Player {
                 Ok(loaded_item) => {
                     log::info!("preloaded audio file");
                     self.preload = PreloadState::Preloaded { item, loaded_item };
+                    self.hand_over_preloaded();
                 }
                 Err(err) => {
                     log::error!("failed to preload audio file, error while opening: {}", err);
@@ -308,7 +390,7 @@ impl Player {
         }
     }
 
-    fn handle_progress(&mut self, progress: Duration, path: AudioPath) {
+    fn handle_progress(&mut self, progress: Duration) {
         match &mut self.state {
             PlayerState::Playing { duration, .. } | PlayerState::Paused { duration, .. } => {
                 *duration = progress;
@@ -317,12 +399,26 @@ impl Player {
                 log::warn!("received unexpected progress report");
             }
         }
-        const PRELOAD_BEFORE_END_OF_TRACK: Duration = Duration::from_secs(30);
-        if let Some(&item_to_preload) = self.queue.get_following() {
-            let time_until_end_of_track = path.duration.checked_sub(progress).unwrap_or_default();
-            if time_until_end_of_track <= PRELOAD_BEFORE_END_OF_TRACK {
-                self.preload(item_to_preload);
-            }
+    }
+
+    fn handle_transitioned(&mut self, path: AudioPath, duration: Duration) {
+        // Audio source has already started playing the item we have handed over to it,
+        // without pausing the output.  Catch up with it, including the part played
+        // while crossfading.
+        self.queue.skip_to_following();
+        self.preload = PreloadState::None;
+        self.event_sender
+            .send(PlayerEvent::Playing { path, duration })
+            .expect("Failed to send PlayerEvent::Playing");
+        self.state = PlayerState::Playing { path, duration };
+        self.preload_following();
+    }
+
+    fn handle_faded_out(&mut self) {
+        // Audio source has faded out after we have requested a pause.  Unless we have
+        // resumed in the meantime, we can now pause the output.
+        if let PlayerState::Paused { .. } = self.state {
+            self.audio_output_remote.pause();
         }
     }
 
@@ -345,6 +441,9 @@ impl Player {
     }
 
     fn load_and_play(&mut self, item: PlaybackItem) {
+        // If the following item has already been handed over to the audio source, take
+        // it back, we are going to be starting the playback explicitly.
+        self.reclaim_following();
         // Check if the item is already preloaded, and if so, take it out of the
         // preloader state, and start the playback.
         match mem::replace(&mut self.preload, PreloadState::None) {
@@ -409,6 +508,61 @@ impl Player {
         };
     }
 
+    fn preload_following(&mut self) {
+        // Start preloading the following item as soon as we know about it, so skipping
+        // to it or transitioning into it is instant.  Preloading fetches just the
+        // metadata, the audio key and the beginning of the file.
+        let is_playing = matches!(
+            self.state,
+            PlayerState::Playing { .. } | PlayerState::Paused { .. }
+        );
+        if let Some(&item) = self.queue.get_following() {
+            if is_playing {
+                self.preload(item);
+                self.hand_over_preloaded();
+            }
+        }
+    }
+
+    fn hand_over_preloaded(&mut self) {
+        // To play the following item without any gap, hand it over to the audio source
+        // as soon as it's preloaded.  Audio source is going to continue with it right
+        // after the current item ends.
+        let is_playing = matches!(
+            self.state,
+            PlayerState::Playing { .. } | PlayerState::Paused { .. }
+        );
+        let following = self.queue.get_following().copied();
+        match mem::replace(&mut self.preload, PreloadState::None) {
+            PreloadState::Preloaded { item, loaded_item }
+                if is_playing && following == Some(item) =>
+            {
+                self.audio_source
+                    .lock()
+                    .expect("Failed to acquire audio source lock")
+                    .play_following(loaded_item);
+                self.preload = PreloadState::HandedOver { item };
+            }
+            other => {
+                self.preload = other;
+            }
+        }
+    }
+
+    fn reclaim_following(&mut self) {
+        if let PreloadState::HandedOver { item } = self.preload {
+            let following = self
+                .audio_source
+                .lock()
+                .expect("Failed to acquire audio source lock")
+                .take_following();
+            self.preload = match following {
+                Some(loaded_item) => PreloadState::Preloaded { item, loaded_item },
+                None => PreloadState::None,
+            };
+        }
+    }
+
     fn play_loaded(&mut self, loaded_item: LoadedPlaybackItem) {
         log::info!("starting playback");
         let path = loaded_item.file.path();
@@ -425,6 +579,7 @@ impl Player {
                     .expect("Failed to send PlayerEvent::Playing");
                 self.state = PlayerState::Playing { path, duration };
                 self.audio_output_remote.resume();
+                self.preload_following();
             }
             Err(err) => {
                 log::error!("error while creating audio source: {}", err);
@@ -441,7 +596,12 @@ impl Player {
                     .send(PlayerEvent::Pausing { path, duration })
                     .expect("Failed to send PlayerEvent::Paused");
                 self.state = PlayerState::Paused { path, duration };
-                self.audio_output_remote.pause();
+                // Output gets paused after the audio source fades out, see
+                // `handle_faded_out`.
+                self.audio_source
+                    .lock()
+                    .expect("Failed to acquire audio source lock")
+                    .fade_out();
             }
             _ => {
                 log::warn!("invalid state transition");
@@ -457,6 +617,10 @@ impl Player {
                     .send(PlayerEvent::Resuming { path, duration })
                     .expect("Failed to send PlayerEvent::Resuming");
                 self.state = PlayerState::Playing { path, duration };
+                self.audio_source
+                    .lock()
+                    .expect("Failed to acquire audio source lock")
+                    .fade_in();
                 self.audio_output_remote.resume();
             }
             _ => {
@@ -498,6 +662,7 @@ impl Player {
     }
 
     fn stop(&mut self) {
+        self.reclaim_following();
         self.event_sender
             .send(PlayerEvent::Stopped)
             .expect("Failed to send PlayerEvent::Stopped");
@@ -507,13 +672,32 @@ impl Player {
     }
 
     fn seek(&mut self, position: Duration) {
+        let mut source = self
+            .audio_source
+            .lock()
+            .expect("Failed to acquire audio source lock");
+        if let PlayerState::Playing { .. } = self.state {
+            // Avoid an audible click by fading out before seeking and fading back in
+            // afterwards.
+            source.fade_out_and_seek(position);
+        } else {
+            // Audio source is not getting polled, seek right away.
+            source.seek(position);
+        }
+    }
+
+    fn set_speed(&mut self, speed: f64) {
         self.audio_source
             .lock()
             .expect("Failed to acquire audio source lock")
-            .seek(position);
+            .set_speed(speed);
     }
 
     fn configure(&mut self, config: PlaybackConfig) {
+        self.audio_source
+            .lock()
+            .expect("Failed to acquire audio source lock")
+            .configure(&config);
         self.config = config;
     }
 
@@ -529,7 +713,8 @@ impl Player {
     fn is_in_preload(&self, item: PlaybackItem) -> bool {
         match self.preload {
             PreloadState::Preloading { item: p_item, .. }
-            | PreloadState::Preloaded { item: p_item, .. } => p_item == item,
+            | PreloadState::Preloaded { item: p_item, .. }
+            | PreloadState::HandedOver { item: p_item } => p_item == item,
             _ => false,
         }
     }
@@ -546,6 +731,23 @@ pub enum PlayerCommand {
     Preload {
         item: PlaybackItem,
     },
+    /// Insert `item` into the queue at `index`, without interrupting the
+    /// current item.
+    InsertIntoQueue {
+        index: usize,
+        item: PlaybackItem,
+    },
+    /// Remove the item at `index` from the queue.  It shouldn't be the current
+    /// item.
+    RemoveFromQueue {
+        index: usize,
+    },
+    /// Move the item at `from` in the queue to `to`.  It shouldn't be the
+    /// current item.
+    MoveInQueue {
+        from: usize,
+        to: usize,
+    },
     Pause,
     Resume,
     PauseOrResume,
@@ -555,12 +757,18 @@ pub enum PlayerCommand {
     Seek {
         position: Duration,
     },
+    SetSpeed {
+        speed: f64,
+    },
     Configure {
         config: PlaybackConfig,
     },
     SetQueueBehavior {
         behavior: QueueBehavior,
     },
+    SetRepeatMode {
+        repeat: RepeatMode,
+    },
 }
 
 pub enum PlayerEvent {
@@ -607,6 +815,15 @@ pub enum PlayerEvent {
     /// Player has finished playing a track.  `Loading` or `Playing` might
     /// follow if the queue is not empty, `Stopped` will follow if it is.
     Finished,
+    /// Audio source has finished playing a track and continued seamlessly with
+    /// the following one, `duration` into it.  `Playing` follows.
+    Transitioned {
+        path: AudioPath,
+        duration: Duration,
+    },
+    /// Audio source has faded out after a pause was requested, and it's safe
+    /// to pause the output.
+    FadedOut,
     /// The queue is empty.
     Stopped,
 }
@@ -637,6 +854,11 @@ enum PreloadState {
         item: PlaybackItem,
         loaded_item: LoadedPlaybackItem,
     },
+    /// Preloaded item has been handed over to the audio source, which is going to
+    /// play it right after the current one.
+    HandedOver {
+        item: PlaybackItem,
+    },
     None,
 }
 
@@ -650,10 +872,44 @@ struct CurrentPlaybackItem {
     norm_factor: f32,
 }
 
+impl CurrentPlaybackItem {
+    fn total_samples(&self) -> u64 {
+        duration_to_samples(self.file.path().duration)
+    }
+}
+
+fn duration_to_samples(duration: Duration) -> u64 {
+    let frames = (duration.as_secs_f64() * OUTPUT_SAMPLE_RATE as f64) as u64;
+    frames * OUTPUT_CHANNELS as u64
+}
+
+fn samples_to_duration(samples: u64) -> Duration {
+    Duration::from_secs_f64(samples as f64 / OUTPUT_SAMPLE_RATE as f64 / OUTPUT_CHANNELS as f64)
+}
+
+enum AfterFade {
+    Hold,
+    Seek(Duration),
+}
+
 struct PlayerAudioSource {
     current: Option<CurrentPlaybackItem>,
+    following: Option<CurrentPlaybackItem>,
     event_sender: Sender<PlayerEvent>,
     samples: u64,
+    following_samples: u64,
+    crossfade_samples: u64,
+    equalizer: Equalizer,
+    resampler: Resampler,
+    mono: bool,
+    // Second half of a down-mixed frame, waiting to be played.
+    mono_pending: Option<AudioSample>,
+    volume: f32,
+    gain: f32,
+    gain_target: f32,
+    gain_step: f32,
+    after_fade: Option<AfterFade>,
+    is_held: bool,
 }
 
 impl PlayerAudioSource {
@@ -661,10 +917,85 @@ impl PlayerAudioSource {
         Self {
             event_sender,
             current: None,
+            following: None,
             samples: 0,
+            following_samples: 0,
+            crossfade_samples: 0,
+            equalizer: Equalizer::new(OUTPUT_CHANNELS, OUTPUT_SAMPLE_RATE),
+            resampler: Resampler::new(OUTPUT_CHANNELS),
+            mono: false,
+            mono_pending: None,
+            volume: 1.0,
+            gain: 1.0,
+            gain_target: 1.0,
+            gain_step: 1.0,
+            after_fade: None,
+            is_held: false,
         }
     }
 
+    fn configure(&mut self, config: &PlaybackConfig) {
+        self.crossfade_samples = duration_to_samples(config.crossfade);
+        self.equalizer.set_gains(&config.equalizer);
+        self.mono = config.mono;
+        self.volume = config.volume.max(0.0).min(1.0);
+        self.gain_step = match duration_to_samples(config.fade) {
+            0 => 1.0,
+            fade_samples => 1.0 / fade_samples as f32,
+        };
+    }
+
+    fn fade_out(&mut self) {
+        if let Some(AfterFade::Seek(position)) = self.after_fade.take() {
+            self.seek(position);
+        }
+        self.gain_target = 0.0;
+        self.after_fade.replace(AfterFade::Hold);
+    }
+
+    fn fade_out_and_seek(&mut self, position: Duration) {
+        self.gain_target = 0.0;
+        self.after_fade.replace(AfterFade::Seek(position));
+    }
+
+    fn fade_in(&mut self) {
+        if let Some(AfterFade::Seek(position)) = self.after_fade.take() {
+            self.seek(position);
+        }
+        self.is_held = false;
+        self.gain_target = 1.0;
+    }
+
+    fn next_gain(&mut self) -> f32 {
+        if self.gain < self.gain_target {
+            self.gain = (self.gain + self.gain_step).min(self.gain_target);
+        } else if self.gain > self.gain_target {
+            self.gain = (self.gain - self.gain_step).max(self.gain_target);
+            if self.gain <= self.gain_target {
+                match self.after_fade.take() {
+                    Some(AfterFade::Hold) => {
+                        // Stop consuming the sources and let the player know it can
+                        // pause the output.
+                        self.is_held = true;
+                        self.event_sender
+                            .send(PlayerEvent::FadedOut)
+                            .expect("Failed to send PlayerEvent::FadedOut");
+                    }
+                    Some(AfterFade::Seek(position)) => {
+                        self.seek(position);
+                        self.gain_target = 1.0;
+                    }
+                    None => {}
+                }
+            }
+        }
+        self.gain
+    }
+
+    fn set_speed(&mut self, speed: f64) {
+        self.resampler.set_speed(speed);
+    }
+
     fn seek(&mut self, position: Duration) {
         if let Some(current) = &mut self.current {
             let seconds = position.as_secs_f64();
@@ -672,10 +1003,23 @@ impl PlayerAudioSource {
             let samples = frames * OUTPUT_CHANNELS as f64;
             current.source.seek(frames as u64);
             self.samples = samples as u64;
+            self.resampler.reset();
+            self.rewind_following();
             self.report_audio_position();
         }
     }
 
+    fn rewind_following(&mut self) {
+        // In case we've already started mixing in the following item, rewind it back to
+        // the beginning.
+        if let Some(following) = &mut self.following {
+            if self.following_samples > 0 {
+                following.source.seek(0);
+                self.following_samples = 0;
+            }
+        }
+    }
+
     fn play_now(&mut self, item: LoadedPlaybackItem) -> Result<(), Error> {
         self.current.replace(CurrentPlaybackItem {
             norm_factor: item.norm_factor,
@@ -683,28 +1027,114 @@ impl PlayerAudioSource {
             file: item.file,
         });
         self.samples = 0;
+        self.resampler.reset();
+        self.gain = 1.0;
+        self.gain_target = 1.0;
+        self.after_fade = None;
+        self.is_held = false;
         Ok(())
     }
 
+    fn play_following(&mut self, item: LoadedPlaybackItem) {
+        self.following.replace(CurrentPlaybackItem {
+            norm_factor: item.norm_factor,
+            source: item.source,
+            file: item.file,
+        });
+    }
+
+    fn take_following(&mut self) -> Option<LoadedPlaybackItem> {
+        self.rewind_following();
+        self.following.take().map(|following| LoadedPlaybackItem {
+            norm_factor: following.norm_factor,
+            source: following.source,
+            file: following.file,
+        })
+    }
+
     fn next_sample(&mut self) -> Option<AudioSample> {
-        if let Some(current) = self.current.as_mut() {
-            let sample = current.source.next();
-            if sample.is_some() {
-                self.samples += 1;
-            } else {
-                self.samples = 0;
+        let current = self.current.as_mut()?;
+        let sample = current.source.next()? * current.norm_factor;
+        self.samples += 1;
+
+        // If we are close enough to the end of the current item, start mixing in the
+        // beginning of the following one, linearly fading between the two.
+        if let Some(following) = self.following.as_mut() {
+            let remaining = current.total_samples().saturating_sub(self.samples);
+            if remaining < self.crossfade_samples {
+                let fade = remaining as f32 / self.crossfade_samples as f32;
+                let following_sample = following
+                    .source
+                    .next()
+                    .map_or(0.0, |s| s * following.norm_factor);
+                self.following_samples += 1;
+                return Some(sample * fade + following_sample * (1.0 - fade));
             }
-            sample
+        }
+
+        Some(sample)
+    }
+
+    fn next_source_sample(&mut self) -> Option<AudioSample> {
+        let sample = self.next_sample();
+        if sample.is_some() {
+            // Report audio progress.
+            if self.samples % PROGRESS_PRECISION_SAMPLES == 0 {
+                self.report_audio_position()
+            }
+        } else if let Some(following) = self.following.take() {
+            // We're at the end of track, but the following item is ready.  Continue with
+            // it right away, so there's no gap between the tracks, and let the player
+            // know.
+            self.current.replace(following);
+            self.samples = self.following_samples;
+            self.following_samples = 0;
+            self.report_audio_transition();
+            return self.next_source_sample();
         } else {
-            None
+            // We're at the end of track.  If we still have the source, drop it and report.
+            // Player will pause the audio output and we will stop getting polled
+            // eventually.
+            if self.current.take().is_some() {
+                self.report_audio_end();
+            }
         }
+        sample.map(|s| self.equalizer.process(s))
+    }
+
+    fn next_resampled_sample(&mut self) -> Option<AudioSample> {
+        if self.resampler.is_bypassed() {
+            return self.next_source_sample();
+        }
+        // Keep feeding the resampler until it can produce an output sample.
+        loop {
+            if let Some(sample) = self.resampler.pull() {
+                return Some(sample);
+            }
+            let sample = self.next_source_sample()?;
+            self.resampler.push(sample);
+        }
+    }
+
+    fn next_mixed_sample(&mut self) -> Option<AudioSample> {
+        if let Some(sample) = self.mono_pending.take() {
+            return Some(sample);
+        }
+        if !self.mono {
+            return self.next_resampled_sample();
+        }
+        // Output is stereo, average both channels of the frame and play the result
+        // on each of them.
+        let left = self.next_resampled_sample()?;
+        let right = self.next_resampled_sample().unwrap_or(left);
+        let sample = (left + right) / 2.0;
+        self.mono_pending.replace(sample);
+        Some(sample)
     }
 
     fn report_audio_position(&self) {
         if let Some(current) = self.current.as_ref() {
-            let duration = Duration::from_secs_f64(
-                self.samples as f64 / OUTPUT_SAMPLE_RATE as f64 / OUTPUT_CHANNELS as f64,
-            );
+            let duration = samples_to_duration(self.samples);
             let path = current.file.path();
             self.event_sender
                 .send(PlayerEvent::Progress { duration, path })
@@ -712,6 +1142,17 @@ impl PlayerAudioSource {
         }
     }
 
+    fn report_audio_transition(&self) {
+        if let Some(current) = self.current.as_ref() {
+            // The following item has already been mixed in while crossfading.
+            let duration = samples_to_duration(self.samples);
+            let path = current.file.path();
+            self.event_sender
+                .send(PlayerEvent::Transitioned { path, duration })
+                .expect("Failed to send PlayerEvent::Transitioned");
+        }
+    }
+
     fn report_audio_end(&self) {
         self.event_sender
             .send(PlayerEvent::Finished)
@@ -727,30 +1168,17 @@ impl AudioSource for PlayerAudioSource {
     fn sample_rate(&self) -> u32 {
         OUTPUT_SAMPLE_RATE
     }
-
-    fn normalization_factor(&self) -> Option<f32> {
-        self.current.as_ref().map(|current| current.norm_factor)
-    }
 }
 
 impl Iterator for PlayerAudioSource {
     type Item = AudioSample;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let sample = self.next_sample();
-        if sample.is_some() {
-            // Report audio progress.
-            if self.samples % PROGRESS_PRECISION_SAMPLES == 0 {
-                self.report_audio_position()
-            }
-        } else {
-            // We're at the end of track.  If we still have the source, drop it and report.
-            // Player will pause the audio output and we will stop getting polled
-            // eventually.
-            if self.current.take().is_some() {
-                self.report_audio_end();
-            }
+        if self.is_held {
+            // We have faded out and are waiting for the output to get paused.
+            return Some(0.0);
         }
-        sample
+        let sample = self.next_mixed_sample()?;
+        Some(sample * self.next_gain() * self.volume)
     }
 }
@@ -6,8 +6,6 @@ use crate::audio_player::PlaybackItem;
 pub enum QueueBehavior {
     Sequential,
     Random,
-    LoopTrack,
-    LoopAll,
 }
 
 impl Default for QueueBehavior {
@@ -16,11 +14,25 @@ impl Default for QueueBehavior {
     }
 }
 
+#[derive(Debug)]
+pub enum RepeatMode {
+    Off,
+    Context,
+    Track,
+}
+
+impl Default for RepeatMode {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
 pub struct Queue {
     items: Vec<PlaybackItem>,
     position: usize,
     positions: Vec<usize>,
     behavior: QueueBehavior,
+    repeat: RepeatMode,
 }
 
 impl Queue {
@@ -30,6 +42,7 @@ impl Queue {
             position: 0,
             positions: Vec::new(),
             behavior: QueueBehavior::default(),
+            repeat: RepeatMode::default(),
         }
     }
 
@@ -45,11 +58,64 @@ impl Queue {
         self.compute_positions();
     }
 
+    /// Insert `item` at `index` of the items, keeping the current item.  When
+    /// shuffling, it plays right after the current item.
+    pub fn insert(&mut self, index: usize, item: PlaybackItem) {
+        let index = index.min(self.items.len());
+        for position in &mut self.positions {
+            if *position >= index {
+                *position += 1;
+            }
+        }
+        let slot = match self.behavior {
+            QueueBehavior::Sequential => index,
+            QueueBehavior::Random => (self.position + 1).min(self.positions.len()),
+        };
+        if slot <= self.position && !self.items.is_empty() {
+            self.position += 1;
+        }
+        self.positions.insert(slot, index);
+        self.items.insert(index, item);
+    }
+
+    /// Remove the item at `index`, keeping the current item, unless it is the
+    /// one removed.
+    pub fn remove(&mut self, index: usize) {
+        if index >= self.items.len() {
+            return;
+        }
+        self.items.remove(index);
+        if let Some(slot) = self.positions.iter().position(|&p| p == index) {
+            self.positions.remove(slot);
+            if slot < self.position {
+                self.position -= 1;
+            }
+        }
+        for position in &mut self.positions {
+            if *position > index {
+                *position -= 1;
+            }
+        }
+    }
+
+    /// Move the item at `from` to `to`.  Moving the current item is not
+    /// supported.
+    pub fn move_item(&mut self, from: usize, to: usize) {
+        if let Some(&item) = self.items.get(from) {
+            self.remove(from);
+            self.insert(to, item);
+        }
+    }
+
     pub fn set_behaviour(&mut self, behavior: QueueBehavior) {
         self.behavior = behavior;
         self.compute_positions();
     }
 
+    pub fn set_repeat_mode(&mut self, repeat: RepeatMode) {
+        self.repeat = repeat;
+    }
+
     fn compute_positions(&mut self) {
         // Start with an ordered 1:1 mapping.
         self.positions = (0..self.items.len()).collect();
@@ -89,28 +155,38 @@ impl Queue {
     }
 
     fn previous_position(&self) -> usize {
-        match self.behavior {
-            QueueBehavior::Sequential
-            | QueueBehavior::Random
-            | QueueBehavior::LoopTrack
-            | QueueBehavior::LoopAll => self.position.saturating_sub(1),
+        match self.repeat {
+            RepeatMode::Off => self.position.saturating_sub(1),
+            // Skipping back from the first item wraps around to the last one.
+            RepeatMode::Context | RepeatMode::Track => match self.position {
+                0 => self.positions.len().saturating_sub(1),
+                position => position - 1,
+            },
         }
     }
 
     fn next_position(&self) -> usize {
-        match self.behavior {
-            QueueBehavior::Sequential | QueueBehavior::Random | QueueBehavior::LoopTrack => {
-                self.position + 1
-            }
-            QueueBehavior::LoopAll => (self.position + 1) % self.items.len(),
+        match self.repeat {
+            RepeatMode::Off => self.position + 1,
+            // Explicitly skipping forward moves to the next item even when repeating a
+            // single track.
+            RepeatMode::Context | RepeatMode::Track => self.wrapped_position(self.position + 1),
         }
     }
 
     fn following_position(&self) -> usize {
-        match self.behavior {
-            QueueBehavior::Sequential | QueueBehavior::Random => self.position + 1,
-            QueueBehavior::LoopTrack => self.position,
-            QueueBehavior::LoopAll => (self.position + 1) % self.items.len(),
+        match self.repeat {
+            RepeatMode::Off => self.position + 1,
+            RepeatMode::Context => self.wrapped_position(self.position + 1),
+            RepeatMode::Track => self.position,
+        }
+    }
+
+    fn wrapped_position(&self, position: usize) -> usize {
+        if self.positions.is_empty() {
+            position
+        } else {
+            position % self.positions.len()
         }
     }
 }
@@ -0,0 +1,81 @@
+use crate::audio_output::AudioSample;
+use std::mem;
+
+/// Changes the playback speed of an interleaved stream by linearly interpolating
+/// between consecutive frames.  Samples are pushed into the resampler and pulled
+/// out of it, until it runs out of input frames.
+pub struct Resampler {
+    channels: usize,
+    speed: f64,
+    // Fractional position between the `prev` and `next` frames.
+    position: f64,
+    prev: Vec<AudioSample>,
+    next: Vec<AudioSample>,
+    incoming: Vec<AudioSample>,
+    frame: Vec<AudioSample>,
+    // Channel of the next sample pulled from `frame`.
+    channel: usize,
+}
+
+impl Resampler {
+    pub fn new(channels: u8) -> Self {
+        let channels = channels.into();
+        Self {
+            channels,
+            speed: 1.0,
+            position: 2.0, // Wait for two input frames before producing output.
+            prev: vec![0.0; channels],
+            next: vec![0.0; channels],
+            incoming: Vec::with_capacity(channels),
+            frame: vec![0.0; channels],
+            channel: 0,
+        }
+    }
+
+    pub fn set_speed(&mut self, speed: f64) {
+        if (self.speed - speed).abs() > f64::EPSILON {
+            self.speed = speed;
+            self.reset();
+        }
+    }
+
+    /// Returns true if the resampler is at a frame boundary and does not change
+    /// the speed, meaning it can be skipped entirely.
+    pub fn is_bypassed(&self) -> bool {
+        (self.speed - 1.0).abs() <= f64::EPSILON && self.channel == 0
+    }
+
+    pub fn reset(&mut self) {
+        self.position = 2.0;
+        self.incoming.clear();
+        self.channel = 0;
+    }
+
+    pub fn push(&mut self, sample: AudioSample) {
+        self.incoming.push(sample);
+        if self.incoming.len() == self.channels {
+            // We have received a full frame, shift it in.
+            mem::swap(&mut self.prev, &mut self.next);
+            mem::swap(&mut self.next, &mut self.incoming);
+            self.incoming.clear();
+            self.position -= 1.0;
+        }
+    }
+
+    pub fn pull(&mut self) -> Option<AudioSample> {
+        if self.channel == 0 {
+            if self.position >= 1.0 {
+                // We need more input.
+                return None;
+            }
+            let t = self.position as AudioSample;
+            for ((out, prev), next) in self.frame.iter_mut().zip(&self.prev).zip(&self.next) {
+                *out = prev + (next - prev) * t;
+            }
+            self.position += self.speed;
+        }
+        let sample = self.frame[self.channel];
+        self.channel = (self.channel + 1) % self.channels;
+        Some(sample)
+    }
+}
@@ -0,0 +1,277 @@
+use crate::audio_output::AudioSample;
+use crossbeam_channel::{bounded, Receiver, Sender};
+use std::{
+    f32::consts::PI,
+    mem,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+};
+
+// Number of samples in one FFT window, needs to be a power of two.
+const WINDOW_SIZE: usize = 2048;
+
+// Number of mono samples in one block sent from the audio thread.
+const BLOCK_SIZE: usize = 512;
+
+// Number of blocks that can be in flight between the audio thread and the
+// analyzer.
+const BLOCK_COUNT: usize = 8;
+
+// Range of displayed frequencies, in Hz.
+const MIN_FREQUENCY: f32 = 40.0;
+const MAX_FREQUENCY: f32 = 16000.0;
+
+// Range of displayed magnitudes, in decibels.
+const MIN_DB: f32 = -70.0;
+const MAX_DB: f32 = 0.0;
+
+/// Audio thread side of the spectrum analyzer.  Down-mixes the played samples
+/// into mono blocks and hands them over to `Spectrum`.
+///
+/// The tap never blocks or takes a lock: blocks travel through a bounded
+/// channel that is only ever polled with `try_recv` on the other side, and
+/// emptied blocks are sent back to be re-used, so the audio thread does not
+/// allocate in the steady state.  If the analyzer is not keeping up, or
+/// nobody is looking at the spectrum at all, the samples are dropped.
+pub struct SpectrumTap {
+    block_sender: Sender<Vec<AudioSample>>,
+    recycled_receiver: Receiver<Vec<AudioSample>>,
+    sample_rate: Arc<AtomicU32>,
+    block: Vec<AudioSample>,
+    channels: usize,
+    channel: usize,
+    frame_sum: AudioSample,
+}
+
+impl SpectrumTap {
+    pub fn set_format(&mut self, channels: u8, sample_rate: u32) {
+        self.channels = channels.max(1).into();
+        self.channel = 0;
+        self.frame_sum = 0.0;
+        self.sample_rate.store(sample_rate, Ordering::Relaxed);
+    }
+
+    /// Push an interleaved output sample.
+    pub fn push(&mut self, sample: AudioSample) {
+        self.frame_sum += sample;
+        self.channel += 1;
+        if self.channel < self.channels {
+            return;
+        }
+        self.block
+            .push(self.frame_sum / self.channels as AudioSample);
+        self.channel = 0;
+        self.frame_sum = 0.0;
+
+        if self.block.len() >= BLOCK_SIZE {
+            if self.block_sender.is_full() {
+                self.block.clear();
+            } else {
+                let next = self
+                    .recycled_receiver
+                    .try_recv()
+                    .unwrap_or_else(|_| Vec::with_capacity(BLOCK_SIZE));
+                let block = mem::replace(&mut self.block, next);
+                self.block_sender.try_send(block).ok();
+            }
+        }
+    }
+}
+
+impl Clone for SpectrumTap {
+    fn clone(&self) -> Self {
+        Self {
+            block_sender: self.block_sender.clone(),
+            recycled_receiver: self.recycled_receiver.clone(),
+            sample_rate: self.sample_rate.clone(),
+            block: Vec::with_capacity(BLOCK_SIZE),
+            channels: self.channels,
+            channel: 0,
+            frame_sum: 0.0,
+        }
+    }
+}
+
+/// UI side of the spectrum analyzer.  Keeps a window of the most recently
+/// played samples and computes their magnitude spectrum on demand.
+#[derive(Clone)]
+pub struct Spectrum {
+    tap: SpectrumTap,
+    analyzer: Arc<Mutex<Analyzer>>,
+}
+
+impl Spectrum {
+    pub fn new() -> Self {
+        let (block_sender, block_receiver) = bounded(BLOCK_COUNT);
+        let (recycled_sender, recycled_receiver) = bounded(BLOCK_COUNT * 2);
+        for _ in 0..BLOCK_COUNT {
+            recycled_sender
+                .try_send(Vec::with_capacity(BLOCK_SIZE))
+                .ok();
+        }
+        let sample_rate = Arc::new(AtomicU32::new(44100));
+        Self {
+            tap: SpectrumTap {
+                block_sender,
+                recycled_receiver,
+                sample_rate: sample_rate.clone(),
+                block: Vec::with_capacity(BLOCK_SIZE),
+                channels: 2,
+                channel: 0,
+                frame_sum: 0.0,
+            },
+            analyzer: Arc::new(Mutex::new(Analyzer::new(
+                block_receiver,
+                recycled_sender,
+                sample_rate,
+            ))),
+        }
+    }
+
+    /// Create a tap that should be fed with the output samples.
+    pub fn tap(&self) -> SpectrumTap {
+        self.tap.clone()
+    }
+
+    /// Magnitudes of `count` logarithmically spaced frequency bands, scaled
+    /// into the `0.0..=1.0` range.
+    pub fn bands(&self, count: usize) -> Vec<f32> {
+        let mut analyzer = self
+            .analyzer
+            .lock()
+            .expect("Failed to acquire spectrum lock");
+        analyzer.receive();
+        analyzer.compute_bands(count)
+    }
+}
+
+impl Default for Spectrum {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Analyzer {
+    block_receiver: Receiver<Vec<AudioSample>>,
+    recycled_sender: Sender<Vec<AudioSample>>,
+    sample_rate: Arc<AtomicU32>,
+    // Ring buffer of the last `WINDOW_SIZE` samples, `position` points to the
+    // oldest one.
+    history: Vec<f32>,
+    position: usize,
+    window: Vec<f32>,
+    real: Vec<f32>,
+    imag: Vec<f32>,
+}
+
+impl Analyzer {
+    fn new(
+        block_receiver: Receiver<Vec<AudioSample>>,
+        recycled_sender: Sender<Vec<AudioSample>>,
+        sample_rate: Arc<AtomicU32>,
+    ) -> Self {
+        // Hann window, to reduce the spectral leakage.
+        let window = (0..WINDOW_SIZE)
+            .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / (WINDOW_SIZE - 1) as f32).cos())
+            .collect();
+        Self {
+            block_receiver,
+            recycled_sender,
+            sample_rate,
+            history: vec![0.0; WINDOW_SIZE],
+            position: 0,
+            window,
+            real: vec![0.0; WINDOW_SIZE],
+            imag: vec![0.0; WINDOW_SIZE],
+        }
+    }
+
+    fn receive(&mut self) {
+        while let Ok(mut block) = self.block_receiver.try_recv() {
+            for &sample in &block {
+                self.history[self.position] = sample;
+                self.position = (self.position + 1) % WINDOW_SIZE;
+            }
+            block.clear();
+            self.recycled_sender.try_send(block).ok();
+        }
+    }
+
+    fn compute_bands(&mut self, count: usize) -> Vec<f32> {
+        for i in 0..WINDOW_SIZE {
+            let sample = self.history[(self.position + i) % WINDOW_SIZE];
+            self.real[i] = sample * self.window[i];
+            self.imag[i] = 0.0;
+        }
+        fft(&mut self.real, &mut self.imag);
+
+        // Hann window halves the amplitude, and we only look at one half of the
+        // symmetric spectrum.
+        let scale = 4.0 / WINDOW_SIZE as f32;
+        let bin_count = WINDOW_SIZE / 2;
+        let bin_width = self.sample_rate.load(Ordering::Relaxed) as f32 / WINDOW_SIZE as f32;
+        let ratio = MAX_FREQUENCY / MIN_FREQUENCY;
+
+        (0..count)
+            .map(|band| {
+                let low = MIN_FREQUENCY * ratio.powf(band as f32 / count as f32);
+                let high = MIN_FREQUENCY * ratio.powf((band + 1) as f32 / count as f32);
+                let low_bin = ((low / bin_width) as usize).min(bin_count - 1);
+                let high_bin = ((high / bin_width) as usize)
+                    .max(low_bin + 1)
+                    .min(bin_count);
+                let peak = (low_bin..high_bin)
+                    .map(|bin| self.real[bin].hypot(self.imag[bin]) * scale)
+                    .fold(0.0, f32::max);
+                let db = 20.0 * peak.max(f32::MIN_POSITIVE).log10();
+                ((db - MIN_DB) / (MAX_DB - MIN_DB)).max(0.0).min(1.0)
+            })
+            .collect()
+    }
+}
+
+// In-place iterative radix-2 FFT.  Length of the buffers needs to be a power of
+// two.
+fn fft(real: &mut [f32], imag: &mut [f32]) {
+    let n = real.len();
+
+    // Reorder the input in the bit-reversed order.
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            real.swap(i, j);
+            imag.swap(i, j);
+        }
+    }
+
+    // Combine the butterflies, doubling their length in each pass.
+    let mut len = 2;
+    while len <= n {
+        let (w_imag, w_real) = (-2.0 * PI / len as f32).sin_cos();
+        for start in (0..n).step_by(len) {
+            let (mut cur_real, mut cur_imag) = (1.0, 0.0);
+            for k in 0..len / 2 {
+                let a = start + k;
+                let b = a + len / 2;
+                let t_real = real[b] * cur_real - imag[b] * cur_imag;
+                let t_imag = real[b] * cur_imag + imag[b] * cur_real;
+                real[b] = real[a] - t_real;
+                imag[b] = imag[a] - t_imag;
+                real[a] += t_real;
+                imag[a] += t_imag;
+                let next_real = cur_real * w_real - cur_imag * w_imag;
+                cur_imag = cur_real * w_imag + cur_imag * w_real;
+                cur_real = next_real;
+            }
+        }
+        len <<= 1;
+    }
+}
@@ -1,20 +1,28 @@
 use crate::{
     audio_key::AudioKey,
     error::Error,
-    item_id::{FileId, ItemId},
+    item_id::{FileId, ItemId, ItemIdType},
     util::{deserialize_protobuf, serialize_protobuf},
 };
+use filetime::FileTime;
 use psst_protocol::metadata::Track;
 use std::{
+    collections::HashSet,
     fs, io,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::SystemTime,
 };
 
 pub type CacheHandle = Arc<Cache>;
 
 pub struct Cache {
     base: PathBuf,
+    // Maximum size of the audio file cache in bytes, zero if unlimited.
+    audio_limit: AtomicU64,
 }
 
 impl Cache {
@@ -26,8 +34,12 @@ impl Cache {
         mkdir_if_not_exists(&base.join("track"))?;
         mkdir_if_not_exists(&base.join("audio"))?;
         mkdir_if_not_exists(&base.join("key"))?;
+        mkdir_if_not_exists(&base.join("pinned"))?;
 
-        let cache = Self { base };
+        let cache = Self {
+            base,
+            audio_limit: AtomicU64::new(0),
+        };
         Ok(Arc::new(cache))
     }
 }
@@ -85,8 +97,145 @@ impl Cache {
     pub fn save_audio_file(&self, file_id: FileId, from_path: PathBuf) -> Result<(), Error> {
         log::debug!("saving audio file to cache: {:?}", file_id);
         fs::copy(from_path, self.audio_file_path(file_id))?;
+        self.evict_audio_files()?;
         Ok(())
     }
+
+    /// Mark the audio file as recently played, so it is evicted last.
+    pub fn touch_audio_file(&self, file_id: FileId) -> Result<(), Error> {
+        filetime::set_file_mtime(self.audio_file_path(file_id), FileTime::now())?;
+        Ok(())
+    }
+
+    pub fn set_audio_limit(&self, limit: Option<u64>) -> Result<(), Error> {
+        self.audio_limit
+            .store(limit.unwrap_or(0), Ordering::Relaxed);
+        self.evict_audio_files()
+    }
+
+    /// Remove all audio files, except the ones pinned for offline playback.
+    pub fn clear_audio_files(&self) -> Result<(), Error> {
+        for entry in self.evictable_audio_files()? {
+            fs::remove_file(entry.path)?;
+        }
+        Ok(())
+    }
+
+    /// Remove the least recently played audio files until the audio cache fits
+    /// into the configured limit.  Files pinned for offline playback are not
+    /// counted towards the limit and are never removed.
+    pub fn evict_audio_files(&self) -> Result<(), Error> {
+        let limit = self.audio_limit.load(Ordering::Relaxed);
+        if limit == 0 {
+            return Ok(());
+        }
+        let mut entries = self.evictable_audio_files()?;
+        let mut total_size: u64 = entries.iter().map(|entry| entry.size).sum();
+        entries.sort_by_key(|entry| entry.modified);
+        for entry in entries {
+            if total_size <= limit {
+                break;
+            }
+            log::debug!("evicting audio file from cache: {:?}", entry.path);
+            fs::remove_file(&entry.path)?;
+            total_size -= entry.size;
+        }
+        Ok(())
+    }
+
+    fn evictable_audio_files(&self) -> Result<Vec<AudioFileEntry>, Error> {
+        let pinned: HashSet<String> = self
+            .pinned_audio_files()?
+            .iter()
+            .map(|file_id| file_id.to_base16())
+            .collect();
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(self.base.join("audio"))? {
+            let entry = entry?;
+            let path = entry.path();
+            // Skip partially downloaded files, they have an extension.
+            if path.extension().is_some() {
+                continue;
+            }
+            let is_pinned = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map_or(false, |name| pinned.contains(name));
+            if is_pinned {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            entries.push(AudioFileEntry {
+                path,
+                size: metadata.len(),
+                modified: metadata.modified()?,
+            });
+        }
+        Ok(entries)
+    }
+}
+
+struct AudioFileEntry {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+// Audio files pinned for offline playback, indexed by the item they belong to.
+impl Cache {
+    pub fn get_pinned_audio_file(&self, item_id: ItemId) -> Option<FileId> {
+        let buf = fs::read(self.pinned_path(item_id)).ok()?;
+        FileId::from_raw(&buf)
+    }
+
+    pub fn pin_audio_file(&self, item_id: ItemId, file_id: FileId) -> Result<(), Error> {
+        log::debug!("pinning audio file: {:?}:{:?}", item_id, file_id);
+        fs::write(self.pinned_path(item_id), &*file_id)?;
+        Ok(())
+    }
+
+    pub fn unpin_audio_file(&self, item_id: ItemId) -> Result<(), Error> {
+        log::debug!("unpinning audio file: {:?}", item_id);
+        fs::remove_file(self.pinned_path(item_id)).or_else(|err| {
+            if err.kind() == io::ErrorKind::NotFound {
+                Ok(())
+            } else {
+                Err(err)
+            }
+        })?;
+        Ok(())
+    }
+
+    pub fn pinned_items(&self) -> Result<Vec<ItemId>, Error> {
+        let mut items = Vec::new();
+        for entry in fs::read_dir(self.base.join("pinned"))? {
+            let name = entry?.file_name();
+            if let Some(item_id) = name
+                .to_str()
+                .and_then(|name| ItemId::from_base62(name, ItemIdType::Track))
+            {
+                items.push(item_id);
+            }
+        }
+        Ok(items)
+    }
+
+    fn pinned_audio_files(&self) -> Result<Vec<FileId>, Error> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(self.base.join("pinned"))? {
+            if let Some(file_id) = fs::read(entry?.path())
+                .ok()
+                .and_then(|buf| FileId::from_raw(&buf))
+            {
+                files.push(file_id);
+            }
+        }
+        Ok(files)
+    }
+
+    fn pinned_path(&self, item_id: ItemId) -> PathBuf {
+        self.base.join("pinned").join(item_id.to_base62())
+    }
 }
 
 // Cache of user country code.
@@ -0,0 +1,195 @@
+use crate::{connection::Transport, error::Error};
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    io,
+    io::Read,
+    net::TcpStream,
+    time::{Duration, Instant},
+};
+use tungstenite::{handshake::HandshakeError, stream::MaybeTlsStream, Message, WebSocket};
+
+// The dealer drops connections that stay silent for too long, we need to ping
+// it more often than that.
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+// How long to block on reading, so we get a chance to send pings and notice
+// the shutdown requests.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Message pushed to us by the dealer.
+#[derive(Debug)]
+pub enum DealerMessage {
+    /// Notification about a state change, i.e. a new cluster state.
+    Message {
+        uri: String,
+        headers: HashMap<String, String>,
+        payloads: Vec<Vec<u8>>,
+    },
+    /// Command sent from another device, needs to be replied to.
+    Request {
+        key: String,
+        message_ident: String,
+        payload: Value,
+    },
+}
+
+/// WebSocket connection to the Spotify "dealer", the push service behind the
+/// Connect protocol.
+pub struct Dealer {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    last_ping: Instant,
+}
+
+impl Dealer {
+    pub fn connect(host: &str, access_token: &str, proxy_url: Option<&str>) -> Result<Self, Error> {
+        log::info!("connecting to dealer: {}", host);
+        let stream = Transport::open_stream(host, proxy_url)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let url = format!("wss://{}/?access_token={}", host, access_token);
+        let (socket, _) = tungstenite::client_tls(url, stream).map_err(|err| match err {
+            HandshakeError::Failure(err) => Error::from(err),
+            HandshakeError::Interrupted(_) => Error::IoError(io::ErrorKind::TimedOut.into()),
+        })?;
+        Ok(Self {
+            socket,
+            last_ping: Instant::now(),
+        })
+    }
+
+    /// Wait for the next message.  Returns `None` if nothing arrived in a
+    /// while, so the caller can do other work in between.
+    pub fn receive(&mut self) -> Result<Option<DealerMessage>, Error> {
+        if self.last_ping.elapsed() >= PING_INTERVAL {
+            self.send(json!({ "type": "ping" }))?;
+            self.last_ping = Instant::now();
+        }
+        let text = match self.socket.read_message() {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) => {
+                return Err(Error::IoError(io::ErrorKind::ConnectionAborted.into()));
+            }
+            Ok(_) => {
+                return Ok(None);
+            }
+            Err(tungstenite::Error::Io(err))
+                if err.kind() == io::ErrorKind::WouldBlock
+                    || err.kind() == io::ErrorKind::TimedOut =>
+            {
+                return Ok(None);
+            }
+            Err(err) => {
+                return Err(err.into());
+            }
+        };
+        let message = match serde_json::from_str(&text)? {
+            RawMessage::Message {
+                uri,
+                headers,
+                payloads,
+            } => {
+                let is_gzipped = headers
+                    .get("Transfer-Encoding")
+                    .map_or(false, |encoding| encoding == "gzip");
+                let payloads = payloads
+                    .into_iter()
+                    .map(|payload| match payload {
+                        Value::String(encoded) => {
+                            let decoded =
+                                base64::decode(encoded).map_err(|_| Error::UnexpectedResponse)?;
+                            if is_gzipped {
+                                gunzip(&decoded)
+                            } else {
+                                Ok(decoded)
+                            }
+                        }
+                        other => Ok(serde_json::to_vec(&other)?),
+                    })
+                    .collect::<Result<_, Error>>()?;
+                DealerMessage::Message {
+                    uri,
+                    headers,
+                    payloads,
+                }
+            }
+            RawMessage::Request {
+                key,
+                message_ident,
+                payload,
+            } => {
+                let compressed =
+                    base64::decode(payload.compressed).map_err(|_| Error::UnexpectedResponse)?;
+                DealerMessage::Request {
+                    key,
+                    message_ident,
+                    payload: serde_json::from_slice(&gunzip(&compressed)?)?,
+                }
+            }
+            RawMessage::Ping | RawMessage::Pong => {
+                return Ok(None);
+            }
+        };
+        Ok(Some(message))
+    }
+
+    /// Let the dealer know if we have processed the request with `key`.
+    pub fn reply(&mut self, key: &str, success: bool) -> Result<(), Error> {
+        self.send(json!({
+            "type": "reply",
+            "key": key,
+            "payload": { "success": success },
+        }))
+    }
+
+    pub fn close(&mut self) {
+        self.socket.close(None).ok();
+        self.socket.write_pending().ok();
+    }
+
+    fn send(&mut self, message: Value) -> Result<(), Error> {
+        self.socket
+            .write_message(Message::Text(message.to_string()))?;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum RawMessage {
+    Ping,
+    Pong,
+    Message {
+        uri: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+        #[serde(default)]
+        payloads: Vec<Value>,
+    },
+    Request {
+        key: String,
+        message_ident: String,
+        payload: CompressedPayload,
+    },
+}
+
+#[derive(Deserialize)]
+struct CompressedPayload {
+    compressed: String,
+}
+
+fn gunzip(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut decoded = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut decoded)?;
+    Ok(decoded)
+}
+
+impl From<tungstenite::Error> for Error {
+    fn from(err: tungstenite::Error) -> Self {
+        match err {
+            tungstenite::Error::Io(err) => Error::IoError(err),
+            err => Error::IoError(io::Error::new(io::ErrorKind::Other, err)),
+        }
+    }
+}
@@ -0,0 +1,542 @@
+pub mod dealer;
+
+use crate::{
+    access_token::TokenProvider,
+    connect::dealer::{Dealer, DealerMessage},
+    error::Error,
+    item_id::{ItemId, ItemIdType},
+    protocol::connect::{
+        Capabilities, ClusterUpdate, ContextPlayerOptions, ContextTrack, Device, DeviceInfo,
+        DeviceType, MemberType, PlayerState, ProvidedTrack, PutStateReason, PutStateRequest,
+        TransferState,
+    },
+    session::SessionHandle,
+    util::{default_ureq_agent_builder, deserialize_protobuf, serialize_protobuf},
+};
+use crossbeam_channel::Sender;
+use serde::Deserialize;
+use serde_json::Value;
+use sha1::{Digest, Sha1};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+// Resolves the hosts of the dealer and of the internal client API.
+const AP_RESOLVE_ENDPOINT: &str = "https://apresolve.spotify.com/?type=dealer&type=spclient";
+
+// How long to wait before reconnecting after the connection got lost.
+const RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+// Hosts used in case the resolving fails.
+const DEALER_FALLBACK: &str = "dealer.spotify.com:443";
+const SPCLIENT_FALLBACK: &str = "spclient.wg.spotify.com:443";
+
+#[derive(Clone)]
+pub struct ConnectConfig {
+    pub device_name: String,
+    pub proxy_url: Option<String>,
+}
+
+/// Command received from another Spotify Connect device.
+#[derive(Debug, Clone)]
+pub enum ConnectEvent {
+    /// Start playing a context, either on transfer or on explicit request.
+    Load(ConnectLoad),
+    Pause,
+    Resume,
+    SkipNext,
+    SkipPrevious,
+    SeekTo(Duration),
+    SetShuffle(bool),
+    SetRepeat {
+        context: bool,
+        track: bool,
+    },
+    /// Playback has been transferred to another device.
+    Deactivated,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConnectLoad {
+    pub context_uri: String,
+    pub tracks: Vec<ItemId>,
+    pub position: usize,
+    pub progress: Duration,
+    pub paused: bool,
+}
+
+/// Playback state of this device, as shown to the other devices.
+#[derive(Debug, Clone)]
+pub struct ConnectPlayback {
+    pub context_uri: Option<String>,
+    pub track: ItemId,
+    pub progress: Duration,
+    pub duration: Duration,
+    pub is_playing: bool,
+    pub shuffle: bool,
+    pub repeat_context: bool,
+    pub repeat_track: bool,
+}
+
+/// Makes psst available as a Spotify Connect device, so the playback can be
+/// transferred to it and controlled from the official apps.
+pub struct ConnectDevice {
+    session: SessionHandle,
+    config: ConnectConfig,
+    device_id: String,
+    agent: ureq::Agent,
+    token_provider: TokenProvider,
+    state: Mutex<DeviceState>,
+    shutdown: AtomicBool,
+}
+
+#[derive(Default)]
+struct DeviceState {
+    spclient: Option<String>,
+    connection_id: Option<String>,
+    is_active: bool,
+    playback: Option<ConnectPlayback>,
+    message_id: u32,
+    last_command: Option<(String, u32)>,
+}
+
+impl ConnectDevice {
+    pub fn new(session: SessionHandle, config: ConnectConfig) -> Result<Self, Error> {
+        let agent = default_ureq_agent_builder(config.proxy_url.as_deref())?.build();
+        // Keep the ID stable between the runs, so the other devices see us as the
+        // same device.
+        let device_id = Sha1::digest(config.device_name.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        Ok(Self {
+            session,
+            config,
+            device_id,
+            agent,
+            token_provider: TokenProvider::new(),
+            state: Mutex::new(DeviceState::default()),
+            shutdown: AtomicBool::new(false),
+        })
+    }
+
+    pub fn device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    /// Connect to the dealer, announce the device and dispatch incoming
+    /// commands as `ConnectEvent`s.  Blocks until `shutdown` is called,
+    /// reconnecting if the connection gets lost.
+    pub fn run(&self, events: Sender<ConnectEvent>) {
+        while !self.shutdown.load(Ordering::SeqCst) {
+            if let Err(err) = self.connect_and_service(&events) {
+                log::error!("connect device error: {:?}", err);
+                {
+                    let mut state = self.state.lock().unwrap();
+                    state.connection_id.take();
+                    if state.is_active {
+                        state.is_active = false;
+                        events.send(ConnectEvent::Deactivated).ok();
+                    }
+                }
+                let deadline = Instant::now() + RECONNECT_DELAY;
+                while Instant::now() < deadline && !self.shutdown.load(Ordering::SeqCst) {
+                    thread::sleep(Duration::from_millis(250));
+                }
+            }
+        }
+    }
+
+    fn connect_and_service(&self, events: &Sender<ConnectEvent>) -> Result<(), Error> {
+        // Fails early if the session is not connected yet.
+        let token = self.token_provider.get(&self.session)?;
+
+        let (dealer_host, spclient_host) = self.resolve_hosts();
+        self.state.lock().unwrap().spclient.replace(spclient_host);
+
+        let mut dealer =
+            Dealer::connect(&dealer_host, &token.token, self.config.proxy_url.as_deref())?;
+
+        while !self.shutdown.load(Ordering::SeqCst) {
+            match dealer.receive()? {
+                Some(DealerMessage::Message {
+                    uri,
+                    headers,
+                    payloads,
+                }) => {
+                    self.handle_message(&uri, &headers, &payloads, events)?;
+                }
+                Some(DealerMessage::Request {
+                    key,
+                    message_ident,
+                    payload,
+                }) => {
+                    log::debug!("connect request: {}", message_ident);
+                    let success = self.handle_request(payload, events);
+                    dealer.reply(&key, success)?;
+                    self.put_state(PutStateReason::PLAYER_STATE_CHANGED)?;
+                }
+                None => {}
+            }
+        }
+
+        self.put_state(PutStateReason::BECAME_INACTIVE).ok();
+        dealer.close();
+        Ok(())
+    }
+
+    /// Stop the running `run` loop, within a couple of seconds.  The device
+    /// cannot be started again afterwards.
+    pub fn shutdown(&self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+    }
+
+    /// Report the local playback to the other devices.  Only has an effect
+    /// while the playback has been transferred to us.
+    pub fn update_playback(&self, playback: Option<ConnectPlayback>) -> Result<(), Error> {
+        let is_active = {
+            let mut state = self.state.lock().unwrap();
+            state.playback = playback;
+            state.is_active
+        };
+        if is_active {
+            self.put_state(PutStateReason::PLAYER_STATE_CHANGED)?;
+        }
+        Ok(())
+    }
+
+    fn resolve_hosts(&self) -> (String, String) {
+        #[derive(Deserialize)]
+        struct ResolveData {
+            #[serde(default)]
+            dealer: Vec<String>,
+            #[serde(default)]
+            spclient: Vec<String>,
+        }
+
+        let result: Result<ResolveData, Error> = self
+            .agent
+            .get(AP_RESOLVE_ENDPOINT)
+            .call()
+            .map_err(Error::from)
+            .and_then(|response| Ok(response.into_json()?));
+        match result {
+            Ok(data) => (
+                data.dealer
+                    .into_iter()
+                    .next()
+                    .unwrap_or_else(|| DEALER_FALLBACK.into()),
+                data.spclient
+                    .into_iter()
+                    .next()
+                    .unwrap_or_else(|| SPCLIENT_FALLBACK.into()),
+            ),
+            Err(err) => {
+                log::error!("using dealer fallback, error while resolving: {:?}", err);
+                (DEALER_FALLBACK.into(), SPCLIENT_FALLBACK.into())
+            }
+        }
+    }
+
+    fn handle_message(
+        &self,
+        uri: &str,
+        headers: &HashMap<String, String>,
+        payloads: &[Vec<u8>],
+        events: &Sender<ConnectEvent>,
+    ) -> Result<(), Error> {
+        if uri.starts_with("hm://pusher/v1/connections/") {
+            // First message after connecting, we need the connection ID to
+            // register the device.
+            let connection_id = headers
+                .get("Spotify-Connection-Id")
+                .cloned()
+                .ok_or(Error::UnexpectedResponse)?;
+            self.state
+                .lock()
+                .unwrap()
+                .connection_id
+                .replace(connection_id);
+            self.put_state(PutStateReason::NEW_DEVICE)?;
+        } else if uri.starts_with("hm://connect-state/v1/cluster") {
+            let update: ClusterUpdate = match payloads.first() {
+                Some(payload) => deserialize_protobuf(payload)?,
+                None => return Ok(()),
+            };
+            let active_device_id = update.cluster.and_then(|cluster| cluster.active_device_id);
+            let mut state = self.state.lock().unwrap();
+            if state.is_active && active_device_id.as_deref() != Some(self.device_id.as_str()) {
+                state.is_active = false;
+                events.send(ConnectEvent::Deactivated).ok();
+            }
+        } else {
+            log::debug!("ignoring connect message: {}", uri);
+        }
+        Ok(())
+    }
+
+    fn handle_request(&self, payload: Value, events: &Sender<ConnectEvent>) -> bool {
+        #[derive(Deserialize)]
+        struct Request {
+            message_id: u32,
+            sent_by_device_id: String,
+            command: Value,
+        }
+
+        let request: Request = match serde_json::from_value(payload) {
+            Ok(request) => request,
+            Err(err) => {
+                log::error!("malformed connect request: {:?}", err);
+                return false;
+            }
+        };
+        let event = match parse_command(&request.command) {
+            Some(event) => event,
+            None => {
+                log::warn!("unsupported connect command: {}", request.command);
+                return false;
+            }
+        };
+
+        let mut state = self.state.lock().unwrap();
+        state.last_command = Some((request.sent_by_device_id, request.message_id));
+        if let ConnectEvent::Load(_) = &event {
+            state.is_active = true;
+        }
+        events.send(event).is_ok()
+    }
+
+    fn put_state(&self, reason: PutStateReason) -> Result<(), Error> {
+        let (spclient, connection_id, body) = {
+            let mut state = self.state.lock().unwrap();
+            let (spclient, connection_id) = match (&state.spclient, &state.connection_id) {
+                (Some(spclient), Some(connection_id)) => (spclient.clone(), connection_id.clone()),
+                // Not connected yet, we will put the state after we are.
+                _ => return Ok(()),
+            };
+            state.message_id += 1;
+            let request = self.put_state_request(&state, reason);
+            (spclient, connection_id, serialize_protobuf(&request)?)
+        };
+        let token = self.token_provider.get(&self.session)?;
+        self.agent
+            .put(&format!(
+                "https://{}/connect-state/v1/devices/{}",
+                spclient, self.device_id
+            ))
+            .set("Authorization", &format!("Bearer {}", token.token))
+            .set("X-Spotify-Connection-Id", &connection_id)
+            .set("Content-Type", "application/x-protobuf")
+            .send_bytes(&body)?;
+        Ok(())
+    }
+
+    fn put_state_request(&self, state: &DeviceState, reason: PutStateReason) -> PutStateRequest {
+        let now = unix_time_millis();
+        let capabilities = Capabilities {
+            can_be_player: Some(true),
+            gaia_eq_connect_id: Some(true),
+            supports_logout: Some(false),
+            is_observable: Some(true),
+            // We don't have a volume control to drive.
+            disable_volume: Some(true),
+            supported_types: vec!["audio/track".into()],
+            command_acks: Some(true),
+            is_controllable: Some(true),
+            supports_transfer_command: Some(true),
+            supports_command_request: Some(true),
+            supports_gzip_pushes: Some(true),
+            ..Capabilities::default()
+        };
+        let device_info = DeviceInfo {
+            can_play: Some(true),
+            volume: Some(u16::MAX.into()),
+            name: Some(self.config.device_name.clone()),
+            capabilities: Some(capabilities),
+            device_software_version: Some(format!("psst {}", env!("CARGO_PKG_VERSION"))),
+            device_type: Some(DeviceType::COMPUTER),
+            spirc_version: Some("3.2.6".into()),
+            device_id: Some(self.device_id.clone()),
+            ..DeviceInfo::default()
+        };
+        let player_state = match (&state.playback, state.is_active) {
+            (Some(playback), true) => player_state(playback, now),
+            _ => PlayerState::default(),
+        };
+        let (last_command_sent_by_device_id, last_command_message_id) = state
+            .last_command
+            .clone()
+            .map_or((None, None), |(device, id)| (Some(device), Some(id)));
+        PutStateRequest {
+            device: Some(Device {
+                device_info: Some(device_info),
+                player_state: Some(player_state),
+            }),
+            member_type: Some(MemberType::CONNECT_STATE),
+            is_active: Some(state.is_active),
+            put_state_reason: Some(reason),
+            message_id: Some(state.message_id),
+            last_command_sent_by_device_id,
+            last_command_message_id,
+            client_side_timestamp: Some(now as u64),
+            ..PutStateRequest::default()
+        }
+    }
+}
+
+fn player_state(playback: &ConnectPlayback, now: i64) -> PlayerState {
+    PlayerState {
+        timestamp: Some(now),
+        context_uri: playback.context_uri.clone(),
+        track: Some(ProvidedTrack {
+            uri: Some(format!("spotify:track:{}", playback.track.to_base62())),
+            provider: Some("context".into()),
+            ..ProvidedTrack::default()
+        }),
+        playback_speed: Some(1.0),
+        position_as_of_timestamp: Some(playback.progress.as_millis() as i64),
+        duration: Some(playback.duration.as_millis() as i64),
+        // `is_playing` means there is something loaded in the player, pausing
+        // is reported separately.
+        is_playing: Some(true),
+        is_paused: Some(!playback.is_playing),
+        is_buffering: Some(false),
+        options: Some(ContextPlayerOptions {
+            shuffling_context: Some(playback.shuffle),
+            repeating_context: Some(playback.repeat_context),
+            repeating_track: Some(playback.repeat_track),
+        }),
+        ..PlayerState::default()
+    }
+}
+
+fn parse_command(command: &Value) -> Option<ConnectEvent> {
+    let event = match command["endpoint"].as_str()? {
+        "transfer" => {
+            let data = base64::decode(command["data"].as_str()?).ok()?;
+            let transfer: TransferState = deserialize_protobuf(&data).ok()?;
+            ConnectEvent::Load(parse_transfer(transfer))
+        }
+        "play" => ConnectEvent::Load(parse_play(command)?),
+        "pause" => ConnectEvent::Pause,
+        "resume" => ConnectEvent::Resume,
+        "skip_next" => ConnectEvent::SkipNext,
+        "skip_prev" => ConnectEvent::SkipPrevious,
+        "seek_to" => ConnectEvent::SeekTo(Duration::from_millis(command["value"].as_u64()?)),
+        "set_shuffling_context" => ConnectEvent::SetShuffle(command["value"].as_bool()?),
+        "set_repeating_context" => ConnectEvent::SetRepeat {
+            context: command["value"].as_bool()?,
+            track: false,
+        },
+        "set_repeating_track" => ConnectEvent::SetRepeat {
+            context: false,
+            track: command["value"].as_bool()?,
+        },
+        _ => return None,
+    };
+    Some(event)
+}
+
+fn parse_transfer(transfer: TransferState) -> ConnectLoad {
+    let playback = transfer.playback.unwrap_or_default();
+    let session = transfer.current_session.unwrap_or_default();
+    let context = session.context.unwrap_or_default();
+    let current = playback.current_track.unwrap_or_default();
+
+    let mut tracks = Vec::new();
+    let mut position = None;
+    for track in context.pages.iter().flat_map(|page| &page.tracks) {
+        if let Some(id) = context_track_id(track) {
+            let is_current = match (&track.uid, &session.current_uid) {
+                (Some(uid), Some(current_uid)) => uid == current_uid,
+                _ => track.uri.is_some() && track.uri == current.uri,
+            };
+            if is_current && position.is_none() {
+                position = Some(tracks.len());
+            }
+            tracks.push(id);
+        }
+    }
+    // The context is not always sent in full, fall back to the current track.
+    let position = match (position, context_track_id(&current)) {
+        (Some(position), _) => position,
+        (None, Some(id)) => {
+            tracks = vec![id];
+            0
+        }
+        (None, None) => 0,
+    };
+
+    let paused = playback.is_paused.unwrap_or(false);
+    let mut progress = i64::from(playback.position_as_of_timestamp.unwrap_or(0).max(0));
+    if !paused {
+        // The position is only valid at the time of the timestamp, account for
+        // the time it took the command to arrive.
+        let elapsed = unix_time_millis() - playback.timestamp.unwrap_or_else(unix_time_millis);
+        progress += elapsed.max(0);
+    }
+
+    ConnectLoad {
+        context_uri: context.uri.unwrap_or_default(),
+        tracks,
+        position,
+        progress: Duration::from_millis(progress as u64),
+        paused,
+    }
+}
+
+fn parse_play(command: &Value) -> Option<ConnectLoad> {
+    let context = &command["context"];
+    let tracks: Vec<ItemId> = context["pages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|page| page["tracks"].as_array().into_iter().flatten())
+        .filter_map(|track| track["uri"].as_str())
+        .filter_map(track_id_from_uri)
+        .collect();
+    let skip_to = &command["options"]["skip_to"];
+    let position = skip_to["track_index"]
+        .as_u64()
+        .map(|index| index as usize)
+        .or_else(|| {
+            let uri = skip_to["track_uri"].as_str()?;
+            let id = track_id_from_uri(uri)?;
+            tracks.iter().position(|&track| track == id)
+        })
+        .unwrap_or(0);
+    Some(ConnectLoad {
+        context_uri: context["uri"].as_str()?.to_string(),
+        tracks,
+        position,
+        progress: Duration::from_millis(command["options"]["seek_to"].as_u64().unwrap_or(0)),
+        paused: command["options"]["initially_paused"]
+            .as_bool()
+            .unwrap_or(false),
+    })
+}
+
+fn context_track_id(track: &ContextTrack) -> Option<ItemId> {
+    match (&track.uri, &track.gid) {
+        (Some(uri), _) if !uri.is_empty() => track_id_from_uri(uri),
+        (_, Some(gid)) => ItemId::from_raw(gid, ItemIdType::Track),
+        _ => None,
+    }
+}
+
+fn track_id_from_uri(uri: &str) -> Option<ItemId> {
+    ItemId::from_uri(uri).filter(|id| id.id_type == ItemIdType::Track)
+}
+
+fn unix_time_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
@@ -111,15 +111,21 @@ impl Transport {
 
     pub fn connect(ap: &str, proxy_url: Option<&str>) -> Result<Self, Error> {
         log::trace!("connecting to: {:?} with proxy: {:?}", ap, proxy_url);
-        let stream = if let Some(url) = proxy_url {
-            Self::connect_with_proxy(ap, url)?
-        } else {
-            TcpStream::connect(ap)?
-        };
+        let stream = Self::open_stream(ap, proxy_url)?;
         log::trace!("connected");
         Self::exchange_keys(stream)
     }
 
+    /// Open a TCP connection to `addr`, going through the proxy if one is
+    /// configured.
+    pub fn open_stream(addr: &str, proxy_url: Option<&str>) -> Result<TcpStream, Error> {
+        if let Some(url) = proxy_url {
+            Self::connect_with_proxy(addr, url)
+        } else {
+            Ok(TcpStream::connect(addr)?)
+        }
+    }
+
     fn connect_with_proxy(ap: &str, url: &str) -> Result<TcpStream, Error> {
         match Url::parse(url) {
             Ok(url) if url.scheme() == "socks" || url.scheme() == "socks5" => {
@@ -0,0 +1,253 @@
+//! Control channel of a running psst instance.  Messages are JSON objects, one
+//! per line, exchanged over a Unix socket (or a loopback TCP port on Windows).
+//!
+//! Any local user can connect to the TCP port, so on Windows the first message
+//! of a client has to carry the token the running instance wrote to a file
+//! only readable by its user.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum IpcRequest {
+    Status,
+    /// Bring the main window to the front.
+    Show,
+    /// Show the page of the item behind a `spotify:` URI or an
+    /// `open.spotify.com` link.
+    Open {
+        uri: String,
+    },
+    /// Start playing the item behind a `spotify:` URI, or resume the current
+    /// playback if no URI is given.
+    Play {
+        #[serde(default)]
+        uri: Option<String>,
+    },
+    Pause,
+    Toggle,
+    Next,
+    Previous,
+    Stop,
+    Seek {
+        position_ms: u64,
+    },
+    /// Set the volume, in percent.
+    Volume {
+        level: u32,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum IpcResponse {
+    /// The request was handed over to an instance of the given kind.
+    Ok {
+        instance: IpcInstance,
+    },
+    Status(IpcStatus),
+    Error {
+        message: String,
+    },
+}
+
+/// Kind of the running instance that answers the requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IpcInstance {
+    /// The app with its windows.
+    Gui,
+    /// Only the player, started with `--headless`.
+    Headless,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcStatus {
+    /// One of `loading`, `playing`, `paused` or `stopped`.
+    pub state: String,
+    pub track: Option<IpcTrack>,
+    pub progress_ms: u64,
+    pub volume: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcTrack {
+    pub uri: String,
+    pub name: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub duration_ms: Option<u64>,
+}
+
+#[cfg(unix)]
+pub type IpcStream = std::os::unix::net::UnixStream;
+
+#[cfg(windows)]
+pub type IpcStream = std::net::TcpStream;
+
+#[cfg(windows)]
+const IPC_PORT: u16 = 8845;
+
+/// First message of a client, proving it can read the token file.
+#[cfg(windows)]
+#[derive(Serialize, Deserialize)]
+struct IpcHello {
+    token: String,
+}
+
+#[cfg(windows)]
+fn token_path() -> std::path::PathBuf {
+    // The temp directory lives in the user's profile, other users can't read it.
+    std::env::temp_dir().join("psst-ipc.token")
+}
+
+/// Random hex string, new for every run.
+#[cfg(windows)]
+fn generate_token() -> String {
+    use rand::Rng;
+
+    let mut rng = rand::thread_rng();
+    (0..16)
+        .map(|_| format!("{:02x}", rng.gen::<u8>()))
+        .collect()
+}
+
+#[cfg(unix)]
+fn socket_path() -> std::path::PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("psst.sock")
+}
+
+pub struct IpcListener {
+    #[cfg(unix)]
+    listener: std::os::unix::net::UnixListener,
+    #[cfg(windows)]
+    listener: std::net::TcpListener,
+    token: IpcToken,
+}
+
+/// Checks that a client is allowed to control this instance.  On Unix the
+/// permissions of the socket keep the other users out, so it lets everyone in.
+#[derive(Clone)]
+pub struct IpcToken {
+    #[cfg(windows)]
+    token: String,
+}
+
+impl IpcToken {
+    /// Read the first message of the client and check it carries the token.
+    #[cfg(windows)]
+    pub fn verify(&self, reader: &mut impl BufRead) -> io::Result<()> {
+        match read_message::<IpcHello>(reader)? {
+            Some(hello) if hello.token == self.token => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "missing or invalid token",
+            )),
+        }
+    }
+
+    #[cfg(unix)]
+    pub fn verify(&self, _reader: &mut impl BufRead) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl IpcListener {
+    #[cfg(unix)]
+    pub fn bind() -> io::Result<Self> {
+        use std::{fs, os::unix::net::UnixListener};
+
+        let path = socket_path();
+        if path.exists() {
+            if IpcStream::connect(&path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    "another instance is already running",
+                ));
+            }
+            // Left behind by an instance that didn't shut down cleanly.
+            fs::remove_file(&path)?;
+        }
+        Ok(Self {
+            listener: UnixListener::bind(path)?,
+            token: IpcToken {},
+        })
+    }
+
+    #[cfg(windows)]
+    pub fn bind() -> io::Result<Self> {
+        use std::{
+            fs,
+            net::{Ipv4Addr, TcpListener},
+        };
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, IPC_PORT))?;
+        // Only written once we own the port, so a second instance failing to
+        // start doesn't lock the clients out of the first one.
+        let token = generate_token();
+        fs::write(token_path(), &token)?;
+        Ok(Self {
+            listener,
+            token: IpcToken { token },
+        })
+    }
+
+    pub fn token(&self) -> IpcToken {
+        self.token.clone()
+    }
+
+    pub fn incoming(&self) -> impl Iterator<Item = io::Result<IpcStream>> + '_ {
+        self.listener.incoming()
+    }
+}
+
+#[cfg(unix)]
+impl Drop for IpcListener {
+    fn drop(&mut self) {
+        std::fs::remove_file(socket_path()).ok();
+    }
+}
+
+#[cfg(windows)]
+impl Drop for IpcListener {
+    fn drop(&mut self) {
+        std::fs::remove_file(token_path()).ok();
+    }
+}
+
+/// Connect to the control channel of a running instance.
+#[cfg(unix)]
+pub fn connect() -> io::Result<IpcStream> {
+    IpcStream::connect(socket_path())
+}
+
+/// Connect to the control channel of a running instance.
+#[cfg(windows)]
+pub fn connect() -> io::Result<IpcStream> {
+    let mut stream = IpcStream::connect((std::net::Ipv4Addr::LOCALHOST, IPC_PORT))?;
+    let token = std::fs::read_to_string(token_path())?;
+    write_message(&mut stream, &IpcHello { token })?;
+    Ok(stream)
+}
+
+/// Read the next message, returns `None` if the other side has hung up.
+pub fn read_message<T: DeserializeOwned>(reader: &mut impl BufRead) -> io::Result<Option<T>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    let message = serde_json::from_str(&line)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    Ok(Some(message))
+}
+
+pub fn write_message<T: Serialize>(writer: &mut impl Write, message: &T) -> io::Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    writer.flush()
+}
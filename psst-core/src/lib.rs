@@ -1,16 +1,23 @@
 pub mod access_token;
+pub mod audio_cast;
 pub mod audio_decode;
 pub mod audio_decrypt;
+pub mod audio_download;
+pub mod audio_equalizer;
 pub mod audio_file;
 pub mod audio_key;
 pub mod audio_normalize;
 pub mod audio_output;
 pub mod audio_player;
 pub mod audio_queue;
+pub mod audio_resample;
+pub mod audio_spectrum;
 pub mod cache;
 pub mod cdn;
+pub mod connect;
 pub mod connection;
 pub mod error;
+pub mod ipc;
 pub mod item_id;
 pub mod mercury;
 pub mod metadata;
@@ -2,7 +2,7 @@ use crate::{
     audio_file::{AudioFile, AudioPath},
     error::Error,
     item_id::{FileId, ItemId, ItemIdType},
-    protocol::metadata::{Restriction, Track},
+    protocol::metadata::{AudioFile as ProtoAudioFile, Episode, Restriction, Track},
     session::SessionHandle,
 };
 use quick_protobuf::MessageRead;
@@ -21,10 +21,17 @@ impl Fetch for Track {
     }
 }
 
+impl Fetch for Episode {
+    fn uri(id: ItemId) -> String {
+        format!("hm://metadata/3/episode/{}", id.to_base16())
+    }
+}
+
 pub trait ToAudioPath {
     fn is_restricted_in_region(&self, country: &str) -> bool;
     fn find_allowed_alternative(&self, country: &str) -> Option<ItemId>;
     fn to_audio_path(&self, preferred_bitrate: usize) -> Option<AudioPath>;
+    fn to_audio_path_for_file(&self, file_id: FileId) -> Option<AudioPath>;
 }
 
 impl ToAudioPath for Track {
@@ -43,26 +50,74 @@ impl ToAudioPath for Track {
     }
 
     fn to_audio_path(&self, preferred_bitrate: usize) -> Option<AudioPath> {
-        let file = AudioFile::compatible_audio_formats(preferred_bitrate)
-            .iter()
-            .find_map(|&preferred_format| {
-                self.file
-                    .iter()
-                    .find(|file| file.format == Some(preferred_format))
-            })?;
-        let file_format = file.format?;
+        let file = preferred_audio_file(&self.file, preferred_bitrate)?;
         let item_id = ItemId::from_raw(self.gid.as_ref()?, ItemIdType::Track)?;
-        let file_id = FileId::from_raw(file.file_id.as_ref()?)?;
-        let duration = Duration::from_millis(self.duration? as u64);
-        Some(AudioPath {
-            item_id,
-            file_id,
-            file_format,
-            duration,
-        })
+        to_audio_path(item_id, self.duration?, file)
+    }
+
+    fn to_audio_path_for_file(&self, file_id: FileId) -> Option<AudioPath> {
+        let file = audio_file_with_id(&self.file, file_id)?;
+        let item_id = ItemId::from_raw(self.gid.as_ref()?, ItemIdType::Track)?;
+        to_audio_path(item_id, self.duration?, file)
+    }
+}
+
+impl ToAudioPath for Episode {
+    fn is_restricted_in_region(&self, country: &str) -> bool {
+        self.restriction
+            .iter()
+            .any(|rest| is_restricted_in_region(rest, country))
+    }
+
+    fn find_allowed_alternative(&self, _country: &str) -> Option<ItemId> {
+        // Episodes do not have any alternatives.
+        None
+    }
+
+    fn to_audio_path(&self, preferred_bitrate: usize) -> Option<AudioPath> {
+        let file = preferred_audio_file(&self.file, preferred_bitrate)?;
+        let item_id = ItemId::from_raw(self.gid.as_ref()?, ItemIdType::Podcast)?;
+        to_audio_path(item_id, self.duration?, file)
+    }
+
+    fn to_audio_path_for_file(&self, file_id: FileId) -> Option<AudioPath> {
+        let file = audio_file_with_id(&self.file, file_id)?;
+        let item_id = ItemId::from_raw(self.gid.as_ref()?, ItemIdType::Podcast)?;
+        to_audio_path(item_id, self.duration?, file)
     }
 }
 
+fn preferred_audio_file(
+    files: &[ProtoAudioFile],
+    preferred_bitrate: usize,
+) -> Option<&ProtoAudioFile> {
+    AudioFile::compatible_audio_formats(preferred_bitrate)
+        .iter()
+        .find_map(|&preferred_format| {
+            files
+                .iter()
+                .find(|file| file.format == Some(preferred_format))
+        })
+}
+
+fn audio_file_with_id(files: &[ProtoAudioFile], file_id: FileId) -> Option<&ProtoAudioFile> {
+    files
+        .iter()
+        .find(|file| file.file_id.as_deref() == Some(&*file_id))
+}
+
+fn to_audio_path(item_id: ItemId, duration_ms: i32, file: &ProtoAudioFile) -> Option<AudioPath> {
+    let file_format = file.format?;
+    let file_id = FileId::from_raw(file.file_id.as_ref()?)?;
+    let duration = Duration::from_millis(duration_ms as u64);
+    Some(AudioPath {
+        item_id,
+        file_id,
+        file_format,
+        duration,
+    })
+}
+
 fn is_restricted_in_region(restriction: &Restriction, country: &str) -> bool {
     if let Some(allowed) = &restriction.countries_allowed {
         if allowed.is_empty() {
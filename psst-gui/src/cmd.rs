@@ -1,53 +1,161 @@
 use crate::{
     data::{
-        Album, AlbumLink, Artist, ArtistAlbums, ArtistLink, AudioAnalysis, Cached, Nav,
-        PlaybackPayload, PlaylistLink, QueueBehavior, SearchResults, Track, TrackId,
+        Album, AlbumLink, Artist, ArtistAlbums, ArtistInfo, ArtistLink, AudioAnalysis,
+        AudioFeatures, Cached, CastRenderer, CursorMove, Device, Episode, FeaturedPlaylists,
+        FollowedArtists, Lyrics, Nav, PaletteEntry, PlaybackPayload, Playlist, PlaylistLink,
+        PlaylistTracks, QueueBehavior, QueuedTrack, RadioSeed, RecentlyPlayed, RemoteCommand,
+        RemotePlayback, RepeatMode, SavedTracks, SearchResults, SearchSuggestion, SearchTopic,
+        SeekDirection, Show, ShowLink, SpotifyUrl, TopRange, Track, TrackColumn, TrackId,
+        TrackView, UserProfile,
     },
     error::Error,
 };
-use druid::{im::Vector, Selector, WidgetId};
+use druid::{im::Vector, Modifiers, Selector, WidgetId};
+use psst_core::connect::ConnectEvent;
 use std::{sync::Arc, time::Duration};
 
 // Widget IDs
 
 pub const WIDGET_SEARCH_INPUT: WidgetId = WidgetId::reserved(1);
+pub const WIDGET_PLAYLIST_NAME_INPUT: WidgetId = WidgetId::reserved(2);
+pub const WIDGET_PALETTE_INPUT: WidgetId = WidgetId::reserved(3);
 
 // Common
 
 pub const SHOW_MAIN: Selector = Selector::new("app.show-main");
+pub const TOGGLE_MINI_PLAYER: Selector = Selector::new("app.toggle-mini-player");
+pub const TOGGLE_QUEUE_PANEL: Selector = Selector::new("app.toggle-queue-panel");
 pub const SET_FOCUS: Selector = Selector::new("app.set-focus");
 pub const COPY: Selector<String> = Selector::new("app.copy-to-clipboard");
+// Opens a URL in the default browser, or a URI in the app registered for it.
+pub const OPEN_EXTERNAL: Selector<String> = Selector::new("app.open-external");
+pub const UPDATE_RETRYING: Selector<bool> = Selector::new("app.update-retrying");
+// Requests that failed for good, for the delegate to react to.
+pub const WEBAPI_ERROR: Selector<Error> = Selector::new("app.webapi-error");
+pub const UPDATE_METERED: Selector<bool> = Selector::new("app.update-metered");
 
 // Session
 
 pub const SESSION_CONNECT: Selector = Selector::new("app.session-connect");
 pub const SESSION_CONNECTED: Selector = Selector::new("app.session-connected");
 pub const SESSION_DISCONNECTED: Selector = Selector::new("app.session-disconnected");
+pub const UPDATE_USER_PROFILE: Selector<Result<UserProfile, Error>> =
+    Selector::new("app.update-user-profile");
 
 // Navigation
 
 pub const NAVIGATE: Selector<Nav> = Selector::new("app.navigates");
 pub const NAVIGATE_BACK: Selector<usize> = Selector::new("app.navigate-back");
+pub const OPEN_LINK: Selector<SpotifyUrl> = Selector::new("app.open-link");
+pub const PLAY_LINK: Selector<SpotifyUrl> = Selector::new("app.play-link");
+pub const SHUFFLE_LINK: Selector<SpotifyUrl> = Selector::new("app.shuffle-link");
+pub const QUEUE_LINK: Selector<SpotifyUrl> = Selector::new("app.queue-link");
+pub const REFRESH: Selector = Selector::new("app.refresh");
+
+// Command palette
+
+pub const TOGGLE_COMMAND_PALETTE: Selector = Selector::new("app.toggle-command-palette");
+pub const SHOW_COMMAND_PALETTE: Selector<bool> = Selector::new("app.show-command-palette");
+pub const FILTER_COMMAND_PALETTE: Selector = Selector::new("app.filter-command-palette");
+pub const MOVE_PALETTE_SELECTION: Selector<isize> = Selector::new("app.move-palette-selection");
+pub const SUBMIT_COMMAND_PALETTE: Selector = Selector::new("app.submit-command-palette");
+pub const RUN_PALETTE_ENTRY: Selector<PaletteEntry> = Selector::new("app.run-palette-entry");
 
 // Search
 
 pub const LOAD_SEARCH_RESULTS: Selector<String> = Selector::new("app.load-search-results");
-pub const UPDATE_SEARCH_RESULTS: Selector<Result<SearchResults, Error>> =
+pub const UPDATE_SEARCH_RESULTS: Selector<(String, Result<SearchResults, Error>)> =
     Selector::new("app.update-search-results");
+pub const SUBMIT_SEARCH: Selector<String> = Selector::new("app.submit-search");
+pub const LIVE_SEARCH: Selector<String> = Selector::new("app.live-search");
+pub const LOAD_SEARCH_SUGGESTIONS: Selector<String> = Selector::new("app.load-search-suggestions");
+pub const UPDATE_SEARCH_SUGGESTIONS: Selector<(String, Result<SearchResults, Error>)> =
+    Selector::new("app.update-search-suggestions");
+pub const SHOW_SEARCH_SUGGESTIONS: Selector<bool> = Selector::new("app.show-search-suggestions");
+pub const MOVE_SEARCH_SELECTION: Selector<isize> = Selector::new("app.move-search-selection");
+pub const OPEN_SEARCH_SUGGESTION: Selector<SearchSuggestion> =
+    Selector::new("app.open-search-suggestion");
+pub const CLEAR_SEARCH_HISTORY: Selector = Selector::new("app.clear-search-history");
+pub const LOAD_SEARCH_TOPIC: Selector<(String, SearchTopic)> =
+    Selector::new("app.load-search-topic");
+pub const LOAD_MORE_SEARCH_TOPIC: Selector = Selector::new("app.load-more-search-topic");
+pub const UPDATE_SEARCH_TOPIC: Selector<(
+    (String, SearchTopic),
+    usize,
+    Result<SearchResults, Error>,
+)> = Selector::new("app.update-search-topic");
+
+// Home
+
+pub const LOAD_HOME: Selector = Selector::new("app.load-home");
+pub const UPDATE_FEATURED_PLAYLISTS: Selector<Result<FeaturedPlaylists, Error>> =
+    Selector::new("app.update-featured-playlists");
+pub const UPDATE_NEW_RELEASES: Selector<Result<Vector<Album>, Error>> =
+    Selector::new("app.update-new-releases");
+pub const UPDATE_HOME_RECENTLY_PLAYED: Selector<Result<RecentlyPlayed, Error>> =
+    Selector::new("app.update-home-recently-played");
 
 // Library
 
 pub const LOAD_SAVED_TRACKS: Selector = Selector::new("app.load-saved-tracks");
 pub const LOAD_SAVED_ALBUMS: Selector = Selector::new("app.load-saved-albums");
-pub const UPDATE_SAVED_ALBUMS: Selector<Result<Vector<Album>, Error>> =
+pub const UPDATE_SAVED_ALBUMS: Selector<(usize, Result<Vector<Album>, Error>)> =
     Selector::new("app.update-saved-albums");
-pub const UPDATE_SAVED_TRACKS: Selector<Result<Vector<Arc<Track>>, Error>> =
+pub const LOAD_MORE_SAVED_TRACKS: Selector = Selector::new("app.load-more-saved-tracks");
+pub const UPDATE_SAVED_TRACKS: Selector<(usize, Result<SavedTracks, Error>)> =
     Selector::new("app.update-saved-tracks");
+pub const LOAD_PLAYLISTS: Selector = Selector::new("app.load-playlists");
+pub const UPDATE_PLAYLISTS: Selector<(usize, Result<Vector<Playlist>, Error>)> =
+    Selector::new("app.update-playlists");
+pub const REFRESH_LIBRARY: Selector = Selector::new("app.refresh-library");
+pub const REFRESHED_PLAYLISTS: Selector<Result<Vector<Playlist>, Error>> =
+    Selector::new("app.refreshed-playlists");
+pub const REFRESHED_SAVED_ALBUMS: Selector<Result<Vector<Album>, Error>> =
+    Selector::new("app.refreshed-saved-albums");
+pub const REFRESHED_SAVED_TRACKS: Selector<Result<SavedTracks, Error>> =
+    Selector::new("app.refreshed-saved-tracks");
 pub const SAVE_TRACK: Selector<Arc<Track>> = Selector::new("app.save-track");
 pub const UNSAVE_TRACK: Selector<TrackId> = Selector::new("app.unsave-track");
+pub const SAVE_TRACKS: Selector<Vector<Arc<Track>>> = Selector::new("app.save-tracks");
+pub const UNSAVE_TRACKS: Selector<Vector<TrackId>> = Selector::new("app.unsave-tracks");
+pub const TOGGLE_SAVE_NOW_PLAYING: Selector = Selector::new("app.toggle-save-now-playing");
+pub const CHECK_SAVED_TRACKS: Selector<Vector<TrackId>> = Selector::new("app.check-saved-tracks");
+pub const UPDATE_CHECKED_TRACKS: Selector<(Vector<TrackId>, Result<Vector<bool>, Error>)> =
+    Selector::new("app.update-checked-tracks");
+pub const CHECK_SAVED_ALBUMS: Selector<Vector<Arc<str>>> = Selector::new("app.check-saved-albums");
+pub const UPDATE_CHECKED_ALBUMS: Selector<(Vector<Arc<str>>, Result<Vector<bool>, Error>)> =
+    Selector::new("app.update-checked-albums");
+pub const LOAD_FOLLOWED_ARTISTS: Selector = Selector::new("app.load-followed-artists");
+pub const LOAD_MORE_FOLLOWED_ARTISTS: Selector = Selector::new("app.load-more-followed-artists");
+pub const UPDATE_FOLLOWED_ARTISTS: Selector<(Option<Arc<str>>, Result<FollowedArtists, Error>)> =
+    Selector::new("app.update-followed-artists");
+pub const LOAD_RECENTLY_PLAYED: Selector = Selector::new("app.load-recently-played");
+pub const LOAD_MORE_RECENTLY_PLAYED: Selector = Selector::new("app.load-more-recently-played");
+pub const UPDATE_RECENTLY_PLAYED: Selector<(Option<Arc<str>>, Result<RecentlyPlayed, Error>)> =
+    Selector::new("app.update-recently-played");
+pub const LOAD_SAVED_SHOWS: Selector = Selector::new("app.load-saved-shows");
+pub const UPDATE_SAVED_SHOWS: Selector<(usize, Result<Vector<Show>, Error>)> =
+    Selector::new("app.update-saved-shows");
+pub const SAVE_SHOW: Selector<Show> = Selector::new("app.save-show");
+pub const UNSAVE_SHOW: Selector<ShowLink> = Selector::new("app.unsave-show");
 pub const SAVE_ALBUM: Selector<Album> = Selector::new("app.save-album");
 pub const UNSAVE_ALBUM: Selector<AlbumLink> = Selector::new("app.unsave-album");
 
+// Top items
+
+pub const LOAD_TOP_ITEMS: Selector<TopRange> = Selector::new("app.load-top-items");
+pub const UPDATE_TOP_TRACKS: Selector<(TopRange, Result<Vector<Arc<Track>>, Error>)> =
+    Selector::new("app.update-top-tracks");
+pub const UPDATE_TOP_ARTISTS: Selector<(TopRange, Result<Vector<Artist>, Error>)> =
+    Selector::new("app.update-top-artists");
+
+// Radio
+
+pub const LOAD_RADIO: Selector<RadioSeed> = Selector::new("app.load-radio");
+pub const UPDATE_RADIO: Selector<(RadioSeed, Result<Vector<Arc<Track>>, Error>)> =
+    Selector::new("app.update-radio");
+pub const SAVE_RADIO_AS_PLAYLIST: Selector = Selector::new("app.save-radio-as-playlist");
+
 // Album detail
 
 pub const LOAD_ALBUM_DETAIL: Selector<AlbumLink> = Selector::new("app.load-album-detail");
@@ -65,12 +173,60 @@ pub const UPDATE_ARTIST_TOP_TRACKS: Selector<(ArtistLink, Result<Vector<Arc<Trac
     Selector::new("app.update-artist-top_tracks");
 pub const UPDATE_ARTIST_RELATED: Selector<(ArtistLink, Result<Cached<Vector<Artist>>, Error>)> =
     Selector::new("app.update-artist-related");
+pub const UPDATE_ARTIST_INFO: Selector<(ArtistLink, Result<ArtistInfo, Error>)> =
+    Selector::new("app.update-artist-info");
+pub const UPDATE_ARTIST_FOLLOWED: Selector<(ArtistLink, Result<bool, Error>)> =
+    Selector::new("app.update-artist-followed");
+pub const FOLLOW_ARTIST: Selector<ArtistLink> = Selector::new("app.follow-artist");
+pub const UNFOLLOW_ARTIST: Selector<ArtistLink> = Selector::new("app.unfollow-artist");
 
 // Playlist detail
 
 pub const LOAD_PLAYLIST_DETAIL: Selector<PlaylistLink> = Selector::new("app.load-playlist-detail");
-pub const UPDATE_PLAYLIST_TRACKS: Selector<(PlaylistLink, Result<Vector<Arc<Track>>, Error>)> =
+pub const LOAD_MORE_PLAYLIST_TRACKS: Selector = Selector::new("app.load-more-playlist-tracks");
+pub const UPDATE_PLAYLIST_TRACKS: Selector<(PlaylistLink, usize, Result<PlaylistTracks, Error>)> =
     Selector::new("app.update-playlist-tracks");
+pub const SAVE_QUEUE_AS_PLAYLIST: Selector = Selector::new("app.save-queue-as-playlist");
+pub const PLAYLIST_CREATED: Selector<Result<Playlist, Error>> =
+    Selector::new("app.playlist-created");
+pub const CREATE_PLAYLIST: Selector = Selector::new("app.create-playlist");
+pub const RENAME_PLAYLIST: Selector<String> = Selector::new("app.rename-playlist");
+pub const EDIT_PLAYLIST: Selector<PlaylistLink> = Selector::new("app.edit-playlist");
+pub const PLAYLIST_RENAMED: Selector<(PlaylistLink, Result<(), Error>)> =
+    Selector::new("app.playlist-renamed");
+pub const ADD_TO_PLAYLIST: Selector<(PlaylistLink, Vector<Arc<Track>>)> =
+    Selector::new("app.add-to-playlist");
+// Tracks dragged out of a track list, empty once they are dropped.
+pub const DRAG_TRACKS: Selector<Vector<Arc<Track>>> = Selector::new("app.drag-tracks");
+pub const MOVE_PLAYLIST_TRACK: Selector<(PlaylistLink, usize, usize)> =
+    Selector::new("app.move-playlist-track");
+pub const CONFIRM_UNFOLLOW_PLAYLIST: Selector<PlaylistLink> =
+    Selector::new("app.confirm-unfollow-playlist");
+pub const UNFOLLOW_PLAYLIST: Selector<PlaylistLink> = Selector::new("app.unfollow-playlist");
+pub const FOLLOW_PLAYLIST: Selector<PlaylistLink> = Selector::new("app.follow-playlist");
+pub const PLAYLIST_FOLLOWED: Selector<Result<Playlist, Error>> =
+    Selector::new("app.playlist-followed");
+pub const PLAYLIST_CHANGED: Selector<(PlaylistLink, Result<Arc<str>, Error>)> =
+    Selector::new("app.playlist-changed");
+
+// Show detail
+
+pub const LOAD_SHOW_DETAIL: Selector<ShowLink> = Selector::new("app.load-show-detail");
+pub const UPDATE_SHOW_DETAIL: Selector<(ShowLink, Result<Show, Error>)> =
+    Selector::new("app.update-show-detail");
+pub const UPDATE_SHOW_EPISODES: Selector<(ShowLink, Result<Vector<Arc<Episode>>, Error>)> =
+    Selector::new("app.update-show-episodes");
+
+// Track info
+
+pub const SHOW_TRACK_INFO: Selector<Arc<Track>> = Selector::new("app.show-track-info");
+pub const UPDATE_TRACK_INFO: Selector<(Arc<Track>, Result<AudioFeatures, Error>)> =
+    Selector::new("app.update-track-info");
+pub const LOAD_AUDIO_FEATURES: Selector<Vector<TrackId>> = Selector::new("app.load-audio-features");
+pub const UPDATE_AUDIO_FEATURES: Selector<Result<Vector<AudioFeatures>, Error>> =
+    Selector::new("app.update-audio-features");
+pub const SET_TRACK_COLUMNS: Selector<(TrackView, Vector<TrackColumn>)> =
+    Selector::new("app.set-track-columns");
 
 // Playback state
 
@@ -81,17 +237,66 @@ pub const PLAYBACK_PAUSING: Selector = Selector::new("app.playback-pausing");
 pub const PLAYBACK_RESUMING: Selector = Selector::new("app.playback-resuming");
 pub const PLAYBACK_BLOCKED: Selector = Selector::new("app.playback-blocked");
 pub const PLAYBACK_STOPPED: Selector = Selector::new("app.playback-stopped");
+pub const PLAYBACK_ENDED: Selector = Selector::new("app.playback-ended");
 pub const UPDATE_AUDIO_ANALYSIS: Selector<(TrackId, Result<AudioAnalysis, Error>)> =
     Selector::new("app.update-audio-analysis");
+pub const UPDATE_LYRICS: Selector<(TrackId, Result<Lyrics, Error>)> =
+    Selector::new("app.update-lyrics");
 
 // Playback control
 
 pub const PLAY_TRACK_AT: Selector<usize> = Selector::new("app.play-index");
+pub const SELECT_TRACK_AT: Selector<(usize, Modifiers)> = Selector::new("app.select-index");
+pub const SHOW_TRACK_MENU: Selector = Selector::new("app.show-track-menu");
+pub const MOVE_TRACK_CURSOR: Selector<CursorMove> = Selector::new("app.move-track-cursor");
+pub const PLAY_TRACK_CURSOR: Selector = Selector::new("app.play-track-cursor");
 pub const PLAY_TRACKS: Selector<PlaybackPayload> = Selector::new("app.play-tracks");
+// Same as `PLAY_TRACKS`, but starts at the given progress, optionally paused.
+pub const PLAY_TRACKS_FROM: Selector<(PlaybackPayload, Duration, bool)> =
+    Selector::new("app.play-tracks-from");
+pub const ADD_TO_QUEUE: Selector<QueuedTrack> = Selector::new("app.add-to-queue");
+pub const ADD_ALL_TO_QUEUE: Selector<Vector<QueuedTrack>> = Selector::new("app.add-all-to-queue");
+pub const REMOVE_FROM_QUEUE: Selector<usize> = Selector::new("app.remove-from-queue");
+// Moves the track at the queue index by a number of rows of the same kind in the
+// queue panel.
+pub const MOVE_IN_QUEUE: Selector<(usize, isize)> = Selector::new("app.move-in-queue");
 pub const PLAY_PREVIOUS: Selector = Selector::new("app.play-previous");
 pub const PLAY_PAUSE: Selector = Selector::new("app.play-pause");
 pub const PLAY_RESUME: Selector = Selector::new("app.play-resume");
+pub const PLAY_PAUSE_OR_RESUME: Selector = Selector::new("app.play-pause-or-resume");
 pub const PLAY_NEXT: Selector = Selector::new("app.play-next");
 pub const PLAY_STOP: Selector = Selector::new("app.play-stop");
 pub const PLAY_QUEUE_BEHAVIOR: Selector<QueueBehavior> = Selector::new("app.play-queue-behavior");
+pub const PLAY_REPEAT_MODE: Selector<RepeatMode> = Selector::new("app.play-repeat-mode");
+pub const PLAY_CYCLE_REPEAT_MODE: Selector = Selector::new("app.play-cycle-repeat-mode");
+pub const PLAY_SPEED: Selector<f64> = Selector::new("app.play-speed");
+pub const PLAY_VOLUME: Selector<f64> = Selector::new("app.play-volume");
 pub const PLAY_SEEK: Selector<f64> = Selector::new("app.play-seek");
+pub const PLAY_SEEK_TO: Selector<Duration> = Selector::new("app.play-seek-to");
+pub const SEEK_RELATIVE: Selector<SeekDirection> = Selector::new("app.seek-relative");
+
+// Connect
+
+pub const LOAD_CONNECT_DEVICES: Selector = Selector::new("app.load-connect-devices");
+pub const UPDATE_CONNECT_DEVICES: Selector<Result<Vector<Device>, Error>> =
+    Selector::new("app.update-connect-devices");
+pub const TRANSFER_PLAYBACK: Selector<Device> = Selector::new("app.transfer-playback");
+pub const DISCONNECT_REMOTE: Selector = Selector::new("app.disconnect-remote");
+pub const REMOTE_CONTROL: Selector<RemoteCommand> = Selector::new("app.remote-control");
+pub const LOAD_CAST_RENDERERS: Selector = Selector::new("app.load-cast-renderers");
+pub const UPDATE_CAST_RENDERERS: Selector<Result<Vector<CastRenderer>, Error>> =
+    Selector::new("app.update-cast-renderers");
+pub const CAST_TO: Selector<CastRenderer> = Selector::new("app.cast-to");
+pub const LOAD_REMOTE_PLAYBACK: Selector = Selector::new("app.load-remote-playback");
+pub const UPDATE_REMOTE_PLAYBACK: Selector<Result<Option<RemotePlayback>, Error>> =
+    Selector::new("app.update-remote-playback");
+pub const CONNECT_EVENT: Selector<ConnectEvent> = Selector::new("app.connect-event");
+
+// Downloads
+
+pub const DOWNLOAD_TRACKS: Selector<Vector<Arc<Track>>> = Selector::new("app.download-tracks");
+pub const REMOVE_DOWNLOADS: Selector<Vector<Arc<Track>>> = Selector::new("app.remove-downloads");
+pub const UPDATE_PINNED_TRACKS: Selector<Vector<TrackId>> =
+    Selector::new("app.update-pinned-tracks");
+pub const DOWNLOAD_PROGRESS: Selector<(TrackId, f64)> = Selector::new("app.download-progress");
+pub const DOWNLOAD_FINISHED: Selector<(TrackId, bool)> = Selector::new("app.download-finished");
@@ -0,0 +1,182 @@
+use std::{
+    sync::Arc,
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use druid::{
+    widget::{prelude::*, Controller},
+    ExtEventSink, Target,
+};
+use psst_core::connect::{ConnectDevice, ConnectPlayback};
+
+use crate::{
+    cmd,
+    data::{Playback, PlaybackOrigin, PlaybackState, QueueBehavior, RepeatMode, State},
+};
+
+// Progress jumping by more than this between two updates means we have seeked.
+const SEEK_THRESHOLD: Duration = Duration::from_secs(2);
+
+/// Runs the Spotify Connect device while it's enabled in the config, forwards
+/// the commands from other devices and reports our playback state back.
+pub struct ConnectController {
+    device: Option<Arc<ConnectDevice>>,
+    sender: Option<Sender<Option<ConnectPlayback>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ConnectController {
+    pub fn new() -> Self {
+        Self {
+            device: None,
+            sender: None,
+            thread: None,
+        }
+    }
+
+    fn start_device(&mut self, data: &State, event_sink: ExtEventSink) {
+        let device = match ConnectDevice::new(data.session.clone(), data.config.connect()) {
+            Ok(device) => Arc::new(device),
+            Err(err) => {
+                log::error!("failed to create connect device: {:?}", err);
+                return;
+            }
+        };
+        let (event_sender, event_receiver) = unbounded();
+        let (sender, receiver) = unbounded();
+
+        thread::spawn({
+            let device = device.clone();
+            move || device.run(event_sender)
+        });
+        thread::spawn(move || {
+            for event in event_receiver {
+                event_sink
+                    .submit_command(cmd::CONNECT_EVENT, event, Target::Auto)
+                    .unwrap();
+            }
+        });
+        let thread = thread::spawn({
+            let device = device.clone();
+            move || Self::service_updates(&device, receiver)
+        });
+
+        self.device.replace(device);
+        self.sender.replace(sender);
+        self.thread.replace(thread);
+    }
+
+    fn stop_device(&mut self) {
+        if let Some(device) = self.device.take() {
+            device.shutdown();
+        }
+        // Dropping the sender ends the update thread.
+        self.sender.take();
+        self.thread.take();
+    }
+
+    fn service_updates(device: &ConnectDevice, receiver: Receiver<Option<ConnectPlayback>>) {
+        for playback in receiver {
+            if let Err(err) = device.update_playback(playback) {
+                log::warn!("failed to update connect state: {:?}", err);
+            }
+        }
+    }
+
+    fn send(&mut self, playback: &Playback) {
+        if let Some(sender) = &self.sender {
+            sender.send(connect_playback(playback)).unwrap();
+        }
+    }
+}
+
+impl<W> Controller<State, W> for ConnectController
+where
+    W: Widget<State>,
+{
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &State,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            if data.config.connect_device {
+                self.start_device(data, ctx.get_external_handle());
+            }
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &State,
+        data: &State,
+        env: &Env,
+    ) {
+        let was_enabled = old_data.config.connect_device;
+        let is_enabled = data.config.connect_device;
+
+        if was_enabled && !is_enabled {
+            self.stop_device();
+        } else if is_enabled && !was_enabled {
+            self.start_device(data, ctx.get_external_handle());
+            self.send(&data.playback);
+        } else if is_enabled && has_playback_changed(&old_data.playback, &data.playback) {
+            self.send(&data.playback);
+        }
+        child.update(ctx, old_data, data, env);
+    }
+}
+
+fn connect_playback(playback: &Playback) -> Option<ConnectPlayback> {
+    let now_playing = playback.now_playing.as_ref()?;
+    Some(ConnectPlayback {
+        context_uri: context_uri(&now_playing.origin),
+        track: *now_playing.item.id,
+        progress: now_playing.progress,
+        duration: now_playing.item.duration,
+        is_playing: playback.state == PlaybackState::Playing,
+        shuffle: playback.queue_behavior == QueueBehavior::Random,
+        repeat_context: playback.repeat_mode == RepeatMode::Context,
+        repeat_track: playback.repeat_mode == RepeatMode::Track,
+    })
+}
+
+fn context_uri(origin: &PlaybackOrigin) -> Option<String> {
+    match origin {
+        PlaybackOrigin::Album(album) => Some(format!("spotify:album:{}", album.id)),
+        PlaybackOrigin::Artist(artist) => Some(format!("spotify:artist:{}", artist.id)),
+        PlaybackOrigin::Playlist(playlist) => Some(format!("spotify:playlist:{}", playlist.id)),
+        PlaybackOrigin::Show(show) => Some(format!("spotify:show:{}", show.id)),
+        _ => None,
+    }
+}
+
+fn has_playback_changed(old: &Playback, new: &Playback) -> bool {
+    if old.state != new.state
+        || old.queue_behavior != new.queue_behavior
+        || old.repeat_mode != new.repeat_mode
+    {
+        return true;
+    }
+    match (&old.now_playing, &new.now_playing) {
+        (Some(old), Some(new)) => {
+            if !old.item.id.same(&new.item.id) {
+                return true;
+            }
+            // The other devices extrapolate the progress on their own, only report
+            // it after seeking.
+            let expected = old.progress..old.progress + SEEK_THRESHOLD;
+            !expected.contains(&new.progress)
+        }
+        (None, None) => false,
+        _ => true,
+    }
+}
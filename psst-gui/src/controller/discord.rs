@@ -0,0 +1,318 @@
+use std::{
+    io::{self, Read, Write},
+    process,
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use druid::widget::{prelude::*, Controller};
+use serde_json::{json, Value};
+
+use crate::data::{Config, Playback, PlaybackState, State};
+
+// Discord RPC opcodes.
+const OP_HANDSHAKE: u32 = 0;
+const OP_FRAME: u32 = 1;
+const OP_CLOSE: u32 = 2;
+
+// Size of the artwork we ask for, Discord shows it at most at 300x300 pixels.
+const ARTWORK_SIZE: f64 = 300.0;
+
+// Progress jumping by more than this between two updates means we have seeked.
+const SEEK_THRESHOLD: Duration = Duration::from_secs(2);
+
+#[derive(Clone)]
+struct Presence {
+    title: String,
+    artist: String,
+    album: String,
+    artwork_url: Option<String>,
+    duration: Duration,
+    progress: Duration,
+    is_playing: bool,
+}
+
+impl Presence {
+    fn new(playback: &Playback) -> Option<Self> {
+        let now_playing = playback.now_playing.as_ref()?;
+        let track = &now_playing.item;
+        Some(Self {
+            title: track.name.to_string(),
+            artist: track.artist_name(),
+            album: track.album_name(),
+            artwork_url: track
+                .album
+                .as_ref()
+                .and_then(|album| album.image(ARTWORK_SIZE, ARTWORK_SIZE))
+                .map(|image| image.url.to_string()),
+            duration: track.duration,
+            progress: now_playing.progress,
+            is_playing: playback.state == PlaybackState::Playing,
+        })
+    }
+
+    fn to_activity(&self) -> Value {
+        let mut activity = json!({
+            "details": self.title,
+            "state": format!("by {}", self.artist),
+            "assets": {
+                "large_text": self.album,
+            },
+        });
+        if let Some(url) = &self.artwork_url {
+            activity["assets"]["large_image"] = url.as_str().into();
+        }
+        if self.is_playing {
+            // Let Discord count the elapsed time on its own.
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            let start = now.checked_sub(self.progress).unwrap_or_default();
+            let end = start + self.duration;
+            activity["timestamps"] = json!({
+                "start": start.as_millis() as u64,
+                "end": end.as_millis() as u64,
+            });
+        } else {
+            activity["state"] = format!("by {} (paused)", self.artist).into();
+        }
+        activity
+    }
+}
+
+enum DiscordRequest {
+    Update(Option<Presence>),
+    Disconnect,
+}
+
+pub struct DiscordController {
+    sender: Option<Sender<DiscordRequest>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl DiscordController {
+    pub fn new() -> Self {
+        Self {
+            sender: None,
+            thread: None,
+        }
+    }
+
+    fn start_presence_thread(&mut self) {
+        let (sender, receiver) = unbounded();
+        let thread = thread::spawn(move || {
+            Self::service_requests(receiver);
+        });
+        self.sender.replace(sender);
+        self.thread.replace(thread);
+    }
+
+    fn service_requests(receiver: Receiver<DiscordRequest>) {
+        let mut client: Option<DiscordClient> = None;
+
+        for request in receiver {
+            match request {
+                DiscordRequest::Update(presence) => {
+                    if client.is_none() {
+                        client = match DiscordClient::connect() {
+                            Ok(client) => Some(client),
+                            Err(err) => {
+                                log::warn!("failed to connect to discord: {:?}", err);
+                                None
+                            }
+                        };
+                    }
+                    if let Some(c) = client.as_mut() {
+                        if let Err(err) = c.set_activity(presence.as_ref()) {
+                            // Discord has most probably quit, try to reconnect on the
+                            // next update.
+                            log::warn!("failed to update discord presence: {:?}", err);
+                            client = None;
+                        }
+                    }
+                }
+                DiscordRequest::Disconnect => {
+                    if let Some(mut client) = client.take() {
+                        client.close();
+                    }
+                }
+            }
+        }
+    }
+
+    fn send(&mut self, request: DiscordRequest) {
+        if let Some(sender) = &self.sender {
+            sender.send(request).unwrap();
+        }
+    }
+}
+
+impl<W> Controller<State, W> for DiscordController
+where
+    W: Widget<State>,
+{
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &State,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.start_presence_thread();
+            if data.config.discord_presence {
+                self.send(DiscordRequest::Update(Presence::new(&data.playback)));
+            }
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &State,
+        data: &State,
+        env: &Env,
+    ) {
+        let was_enabled = old_data.config.discord_presence;
+        let is_enabled = data.config.discord_presence;
+
+        if was_enabled && !is_enabled {
+            self.send(DiscordRequest::Disconnect);
+        } else if is_enabled && (!was_enabled || has_presence_changed(old_data, data)) {
+            self.send(DiscordRequest::Update(Presence::new(&data.playback)));
+        }
+        child.update(ctx, old_data, data, env);
+    }
+}
+
+fn has_presence_changed(old_data: &State, data: &State) -> bool {
+    let old = &old_data.playback;
+    let new = &data.playback;
+    if old.state != new.state {
+        return true;
+    }
+    match (&old.now_playing, &new.now_playing) {
+        (Some(old), Some(new)) => {
+            if !old.item.id.same(&new.item.id) {
+                return true;
+            }
+            // Only report the progress after seeking, Discord counts the time by
+            // itself otherwise.
+            let expected = old.progress..old.progress + SEEK_THRESHOLD;
+            !expected.contains(&new.progress)
+        }
+        (None, None) => false,
+        _ => true,
+    }
+}
+
+trait Socket: Read + Write + Send {}
+
+impl<T: Read + Write + Send> Socket for T {}
+
+struct DiscordClient {
+    socket: Box<dyn Socket>,
+    nonce: u64,
+}
+
+impl DiscordClient {
+    fn connect() -> io::Result<Self> {
+        let client_id = Config::discord_client_id()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "missing discord client id"))?;
+        let mut client = Self {
+            socket: Self::open_socket()?,
+            nonce: 0,
+        };
+        client.send(OP_HANDSHAKE, &json!({ "v": 1, "client_id": client_id }))?;
+        client.receive()?;
+        Ok(client)
+    }
+
+    #[cfg(unix)]
+    fn open_socket() -> io::Result<Box<dyn Socket>> {
+        use std::{env, os::unix::net::UnixStream};
+
+        let dir = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+            .iter()
+            .find_map(|var| env::var(var).ok())
+            .unwrap_or_else(|| "/tmp".to_string());
+        let mut last_err = io::Error::from(io::ErrorKind::NotFound);
+        for i in 0..10 {
+            match UnixStream::connect(format!("{}/discord-ipc-{}", dir, i)) {
+                Ok(stream) => return Ok(Box::new(stream)),
+                Err(err) => last_err = err,
+            }
+        }
+        Err(last_err)
+    }
+
+    #[cfg(windows)]
+    fn open_socket() -> io::Result<Box<dyn Socket>> {
+        use std::fs::OpenOptions;
+
+        let mut last_err = io::Error::from(io::ErrorKind::NotFound);
+        for i in 0..10 {
+            match OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(format!(r"\\?\pipe\discord-ipc-{}", i))
+            {
+                Ok(pipe) => return Ok(Box::new(pipe)),
+                Err(err) => last_err = err,
+            }
+        }
+        Err(last_err)
+    }
+
+    fn set_activity(&mut self, presence: Option<&Presence>) -> io::Result<()> {
+        self.nonce += 1;
+        let payload = json!({
+            "cmd": "SET_ACTIVITY",
+            "args": {
+                "pid": process::id(),
+                "activity": presence.map(Presence::to_activity),
+            },
+            "nonce": self.nonce.to_string(),
+        });
+        self.send(OP_FRAME, &payload)?;
+        self.receive()?;
+        Ok(())
+    }
+
+    fn close(&mut self) {
+        // Clear the activity first, Discord can keep showing it for a while after
+        // the connection is gone.
+        self.set_activity(None).ok();
+        self.send(OP_CLOSE, &json!({})).ok();
+    }
+
+    fn send(&mut self, opcode: u32, payload: &Value) -> io::Result<()> {
+        let payload = payload.to_string();
+        let mut frame = Vec::with_capacity(8 + payload.len());
+        frame.extend_from_slice(&opcode.to_le_bytes());
+        frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        frame.extend_from_slice(payload.as_bytes());
+        self.socket.write_all(&frame)?;
+        self.socket.flush()
+    }
+
+    fn receive(&mut self) -> io::Result<Value> {
+        let mut header = [0_u8; 8];
+        self.socket.read_exact(&mut header)?;
+        let opcode = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        let mut payload = vec![0_u8; len as usize];
+        self.socket.read_exact(&mut payload)?;
+        if opcode == OP_CLOSE {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                String::from_utf8_lossy(&payload).into_owned(),
+            ));
+        }
+        Ok(serde_json::from_slice(&payload)?)
+    }
+}
@@ -0,0 +1,200 @@
+use std::thread::{self, JoinHandle};
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use druid::{
+    im::Vector,
+    widget::{prelude::*, Controller},
+    ExtEventSink,
+};
+use psst_core::{
+    audio_download::Downloader, audio_player::PlaybackConfig, cache::Cache, cdn::Cdn,
+    session::SessionHandle,
+};
+
+use crate::{
+    cmd,
+    data::{Config, State, TrackId},
+};
+
+enum DownloadRequest {
+    Download(TrackId),
+    Remove(TrackId),
+    Configure(PlaybackConfig),
+}
+
+pub struct DownloadController {
+    sender: Option<Sender<DownloadRequest>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl DownloadController {
+    pub fn new() -> Self {
+        Self {
+            sender: None,
+            thread: None,
+        }
+    }
+
+    fn start_download_thread(
+        &mut self,
+        session: SessionHandle,
+        config: PlaybackConfig,
+        event_sink: ExtEventSink,
+        widget_id: WidgetId,
+    ) {
+        let cache_dir = Config::cache_dir().unwrap();
+        let proxy_url = Config::proxy();
+        let downloader = Downloader::new(
+            session.clone(),
+            Cdn::new(session, proxy_url.as_deref()).unwrap(),
+            Cache::new(cache_dir).unwrap(),
+            config,
+        );
+        let (sender, receiver) = unbounded();
+
+        let thread = thread::spawn(move || {
+            Self::service_requests(downloader, receiver, event_sink, widget_id);
+        });
+
+        self.sender.replace(sender);
+        self.thread.replace(thread);
+    }
+
+    fn service_requests(
+        mut downloader: Downloader,
+        receiver: Receiver<DownloadRequest>,
+        event_sink: ExtEventSink,
+        widget_id: WidgetId,
+    ) {
+        match downloader.pinned_items() {
+            Ok(items) => {
+                let pinned: Vector<TrackId> = items.into_iter().map(TrackId::from).collect();
+                event_sink
+                    .submit_command(cmd::UPDATE_PINNED_TRACKS, pinned, widget_id)
+                    .unwrap();
+            }
+            Err(err) => {
+                log::error!("failed to list pinned tracks: {:?}", err);
+            }
+        }
+
+        for request in receiver {
+            match request {
+                DownloadRequest::Download(track_id) => {
+                    let result = downloader.download(*track_id, |downloaded, total| {
+                        let progress = downloaded as f64 / total.max(1) as f64;
+                        event_sink
+                            .submit_command(cmd::DOWNLOAD_PROGRESS, (track_id, progress), widget_id)
+                            .unwrap();
+                    });
+                    if let Err(err) = &result {
+                        log::error!("failed to download track: {:?}", err);
+                    }
+                    event_sink
+                        .submit_command(
+                            cmd::DOWNLOAD_FINISHED,
+                            (track_id, result.is_ok()),
+                            widget_id,
+                        )
+                        .unwrap();
+                }
+                DownloadRequest::Remove(track_id) => {
+                    if let Err(err) = downloader.remove(*track_id) {
+                        log::error!("failed to remove download: {:?}", err);
+                    }
+                }
+                DownloadRequest::Configure(config) => {
+                    downloader.configure(config);
+                }
+            }
+        }
+    }
+
+    fn send(&mut self, request: DownloadRequest) {
+        self.sender.as_mut().unwrap().send(request).unwrap();
+    }
+}
+
+impl<W> Controller<State, W> for DownloadController
+where
+    W: Widget<State>,
+{
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut State,
+        env: &Env,
+    ) {
+        match event {
+            Event::Command(cmd) if cmd.is(cmd::DOWNLOAD_TRACKS) => {
+                let tracks = cmd.get_unchecked(cmd::DOWNLOAD_TRACKS);
+                for track in data.enqueue_downloads(tracks) {
+                    self.send(DownloadRequest::Download(track.id));
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(cmd::REMOVE_DOWNLOADS) => {
+                let tracks = cmd.get_unchecked(cmd::REMOVE_DOWNLOADS);
+                data.remove_downloads(tracks);
+                for track in tracks {
+                    self.send(DownloadRequest::Remove(track.id));
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(cmd::UPDATE_PINNED_TRACKS) => {
+                let pinned = cmd.get_unchecked(cmd::UPDATE_PINNED_TRACKS);
+                data.common_ctx.pinned_tracks = pinned.iter().cloned().collect();
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(cmd::DOWNLOAD_PROGRESS) => {
+                let (track_id, progress) = cmd.get_unchecked(cmd::DOWNLOAD_PROGRESS);
+                data.progress_download(track_id, *progress);
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(cmd::DOWNLOAD_FINISHED) => {
+                let (track_id, is_pinned) = cmd.get_unchecked(cmd::DOWNLOAD_FINISHED);
+                data.finish_download(track_id, *is_pinned);
+                ctx.set_handled();
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &State,
+        env: &Env,
+    ) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                self.start_download_thread(
+                    data.session.clone(),
+                    data.config.playback(),
+                    ctx.get_external_handle(),
+                    ctx.widget_id(),
+                );
+            }
+            _ => {}
+        }
+        child.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &State,
+        data: &State,
+        env: &Env,
+    ) {
+        if !old_data.config.same(&data.config) {
+            self.send(DownloadRequest::Configure(data.config.playback()));
+        }
+        child.update(ctx, old_data, data, env);
+    }
+}
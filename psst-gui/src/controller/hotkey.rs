@@ -0,0 +1,94 @@
+use druid::{
+    widget::{prelude::*, Controller},
+    ExtEventSink, Selector, Target,
+};
+use tauri_hotkey::{parse_hotkey, HotkeyManager};
+
+use crate::{
+    cmd,
+    data::{Hotkeys, State},
+};
+
+/// Registers the system-wide hotkeys from the config and routes them through
+/// the same commands as the UI controls.
+pub struct HotkeyController {
+    manager: HotkeyManager,
+}
+
+impl HotkeyController {
+    pub fn new() -> Self {
+        Self {
+            manager: HotkeyManager::new(),
+        }
+    }
+
+    fn register_hotkeys(&mut self, hotkeys: &Hotkeys, event_sink: ExtEventSink) {
+        if let Err(err) = self.manager.unregister_all() {
+            log::warn!("failed to unregister hotkeys: {}", err);
+        }
+        if !hotkeys.enabled {
+            return;
+        }
+        let bindings = [
+            (&hotkeys.play_pause, cmd::PLAY_PAUSE_OR_RESUME),
+            (&hotkeys.next, cmd::PLAY_NEXT),
+            (&hotkeys.previous, cmd::PLAY_PREVIOUS),
+            (&hotkeys.like, cmd::TOGGLE_SAVE_NOW_PLAYING),
+        ];
+        for (binding, command) in bindings.iter() {
+            if binding.trim().is_empty() {
+                continue;
+            }
+            let hotkey = match parse_hotkey(binding) {
+                Ok(hotkey) => hotkey,
+                Err(err) => {
+                    log::warn!("invalid hotkey {:?}: {}", binding, err);
+                    continue;
+                }
+            };
+            let command: Selector = *command;
+            let event_sink = event_sink.clone();
+            let result = self.manager.register(hotkey, move || {
+                event_sink
+                    .submit_command(command, (), Target::Auto)
+                    .unwrap();
+            });
+            if let Err(err) = result {
+                log::warn!("failed to register hotkey {:?}: {}", binding, err);
+            }
+        }
+    }
+}
+
+impl<W> Controller<State, W> for HotkeyController
+where
+    W: Widget<State>,
+{
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &State,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.register_hotkeys(&data.config.hotkeys, ctx.get_external_handle());
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &State,
+        data: &State,
+        env: &Env,
+    ) {
+        if !old_data.config.hotkeys.same(&data.config.hotkeys) {
+            self.register_hotkeys(&data.config.hotkeys, ctx.get_external_handle());
+        }
+        child.update(ctx, old_data, data, env);
+    }
+}
@@ -2,16 +2,29 @@ use crate::cmd;
 use druid::{
     commands,
     widget::{prelude::*, Controller, TextBox},
-    HotKey, KbKey, SysMods,
+    HotKey, KbKey, KeyEvent, SysMods, TimerToken,
 };
+use std::time::Duration;
+
+type InputCallback = Box<dyn Fn(&mut EventCtx, &mut String, &Env)>;
 
 pub struct InputController {
-    on_submit: Option<Box<dyn Fn(&mut EventCtx, &mut String, &Env)>>,
+    on_submit: Option<InputCallback>,
+    on_change: Option<(Duration, InputCallback)>,
+    on_focus_changed: Option<Box<dyn Fn(&mut LifeCycleCtx, bool)>>,
+    on_key: Option<Box<dyn Fn(&mut EventCtx, &KeyEvent) -> bool>>,
+    change_timer: TimerToken,
 }
 
 impl InputController {
     pub fn new() -> Self {
-        Self { on_submit: None }
+        Self {
+            on_submit: None,
+            on_change: None,
+            on_focus_changed: None,
+            on_key: None,
+            change_timer: TimerToken::INVALID,
+        }
     }
 
     pub fn on_submit(
@@ -21,6 +34,31 @@ impl InputController {
         self.on_submit = Some(Box::new(on_submit));
         self
     }
+
+    /// Call `on_change` once the text stops changing for `delay`.
+    pub fn on_change(
+        mut self,
+        delay: Duration,
+        on_change: impl Fn(&mut EventCtx, &mut String, &Env) + 'static,
+    ) -> Self {
+        self.on_change = Some((delay, Box::new(on_change)));
+        self
+    }
+
+    pub fn on_focus_changed(
+        mut self,
+        on_focus_changed: impl Fn(&mut LifeCycleCtx, bool) + 'static,
+    ) -> Self {
+        self.on_focus_changed = Some(Box::new(on_focus_changed));
+        self
+    }
+
+    /// Let `on_key` handle the key presses before the text box does, it
+    /// returns true if it handled the key.
+    pub fn on_key(mut self, on_key: impl Fn(&mut EventCtx, &KeyEvent) -> bool + 'static) -> Self {
+        self.on_key = Some(Box::new(on_key));
+        self
+    }
 }
 
 impl Controller<String, TextBox<String>> for InputController {
@@ -38,10 +76,20 @@ impl Controller<String, TextBox<String>> for InputController {
                 ctx.request_paint();
                 ctx.set_handled();
             }
+            Event::KeyDown(k_e)
+                if self
+                    .on_key
+                    .as_ref()
+                    .map_or(false, |on_key| on_key(ctx, k_e)) =>
+            {
+                ctx.set_handled();
+            }
             Event::KeyDown(k_e) if HotKey::new(None, KbKey::Enter).matches(k_e) => {
                 ctx.resign_focus();
                 ctx.request_paint();
                 ctx.set_handled();
+                // Submitting makes the pending change moot.
+                self.change_timer = TimerToken::INVALID;
                 if let Some(on_submit) = &self.on_submit {
                     on_submit(ctx, data, env);
                 }
@@ -58,9 +106,39 @@ impl Controller<String, TextBox<String>> for InputController {
                 ctx.submit_command(commands::PASTE);
                 ctx.set_handled();
             }
+            Event::Timer(token) if *token == self.change_timer => {
+                self.change_timer = TimerToken::INVALID;
+                if let Some((_, on_change)) = &self.on_change {
+                    on_change(ctx, data, env);
+                }
+                ctx.set_handled();
+            }
             _ => {
+                let old = data.clone();
                 child.event(ctx, event, data, env);
+                if let Some((delay, _)) = &self.on_change {
+                    if *data != old {
+                        // Starting over makes the previous timer stale.
+                        self.change_timer = ctx.request_timer(*delay);
+                    }
+                }
+            }
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut TextBox<String>,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &String,
+        env: &Env,
+    ) {
+        if let LifeCycle::FocusChanged(focused) = event {
+            if let Some(on_focus_changed) = &self.on_focus_changed {
+                on_focus_changed(ctx, *focused);
             }
         }
+        child.lifecycle(ctx, event, data, env)
     }
 }
@@ -0,0 +1,227 @@
+use std::{
+    io::BufReader,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use druid::{
+    widget::{prelude::*, Controller},
+    ExtEventSink, Target,
+};
+use psst_core::ipc::{
+    self, IpcInstance, IpcListener, IpcRequest, IpcResponse, IpcStatus, IpcStream, IpcToken,
+    IpcTrack,
+};
+
+use crate::{
+    cmd,
+    data::{PlaybackState, SpotifyUrl, State},
+};
+
+/// Serves the IPC control channel, so the running app can be controlled from
+/// the command line.
+pub struct IpcController {
+    status: Arc<Mutex<IpcStatus>>,
+}
+
+impl IpcController {
+    pub fn new() -> Self {
+        Self {
+            status: Arc::new(Mutex::new(IpcStatus {
+                state: "stopped".to_string(),
+                track: None,
+                progress_ms: 0,
+                volume: 100,
+            })),
+        }
+    }
+
+    fn start_listener_thread(&mut self, event_sink: ExtEventSink) {
+        let listener = match IpcListener::bind() {
+            Ok(listener) => listener,
+            Err(err) => {
+                log::warn!("failed to open the control channel: {}", err);
+                return;
+            }
+        };
+        let status = self.status.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let token = listener.token();
+                        let status = status.clone();
+                        let event_sink = event_sink.clone();
+                        thread::spawn(move || {
+                            Self::serve_client(stream, &token, &status, &event_sink)
+                        });
+                    }
+                    Err(err) => {
+                        log::warn!("failed to accept control connection: {}", err);
+                    }
+                }
+            }
+        });
+    }
+
+    fn serve_client(
+        stream: IpcStream,
+        token: &IpcToken,
+        status: &Mutex<IpcStatus>,
+        event_sink: &ExtEventSink,
+    ) {
+        let mut writer = match stream.try_clone() {
+            Ok(writer) => writer,
+            Err(err) => {
+                log::warn!("failed to open control connection: {}", err);
+                return;
+            }
+        };
+        let mut reader = BufReader::new(stream);
+        if let Err(err) = token.verify(&mut reader) {
+            log::warn!("refused control connection: {}", err);
+            return;
+        }
+        loop {
+            let response = match ipc::read_message(&mut reader) {
+                Ok(Some(request)) => Self::handle_request(request, status, event_sink),
+                Ok(None) => break,
+                Err(err) => {
+                    // Whoever is on the other side doesn't speak the protocol,
+                    // tell them and hang up.
+                    let response = IpcResponse::Error {
+                        message: err.to_string(),
+                    };
+                    ipc::write_message(&mut writer, &response).ok();
+                    break;
+                }
+            };
+            if let Err(err) = ipc::write_message(&mut writer, &response) {
+                log::warn!("failed to respond to control request: {}", err);
+                break;
+            }
+        }
+    }
+
+    fn handle_request(
+        request: IpcRequest,
+        status: &Mutex<IpcStatus>,
+        event_sink: &ExtEventSink,
+    ) -> IpcResponse {
+        let result = match request {
+            IpcRequest::Status => {
+                return IpcResponse::Status(status.lock().unwrap().clone());
+            }
+            IpcRequest::Show => event_sink.submit_command(cmd::SHOW_MAIN, (), Target::Auto),
+            IpcRequest::Open { uri } => match SpotifyUrl::parse(&uri) {
+                Some(url) => event_sink.submit_command(cmd::OPEN_LINK, url, Target::Auto),
+                None => {
+                    return IpcResponse::Error {
+                        message: format!("Unsupported URI: {}", uri),
+                    };
+                }
+            },
+            // The delegate loads the tracks, there's no runtime here to wait for them on.
+            IpcRequest::Play { uri: Some(uri) } => match SpotifyUrl::parse(&uri) {
+                Some(url) => event_sink.submit_command(cmd::PLAY_LINK, url, Target::Auto),
+                None => {
+                    return IpcResponse::Error {
+                        message: format!("Unsupported URI: {}", uri),
+                    };
+                }
+            },
+            IpcRequest::Play { uri: None } => {
+                event_sink.submit_command(cmd::PLAY_RESUME, (), Target::Auto)
+            }
+            IpcRequest::Pause => event_sink.submit_command(cmd::PLAY_PAUSE, (), Target::Auto),
+            IpcRequest::Toggle => {
+                event_sink.submit_command(cmd::PLAY_PAUSE_OR_RESUME, (), Target::Auto)
+            }
+            IpcRequest::Next => event_sink.submit_command(cmd::PLAY_NEXT, (), Target::Auto),
+            IpcRequest::Previous => event_sink.submit_command(cmd::PLAY_PREVIOUS, (), Target::Auto),
+            IpcRequest::Stop => event_sink.submit_command(cmd::PLAY_STOP, (), Target::Auto),
+            IpcRequest::Seek { position_ms } => event_sink.submit_command(
+                cmd::PLAY_SEEK_TO,
+                Duration::from_millis(position_ms),
+                Target::Auto,
+            ),
+            IpcRequest::Volume { level } => event_sink.submit_command(
+                cmd::PLAY_VOLUME,
+                level.min(100) as f64 / 100.0,
+                Target::Auto,
+            ),
+        };
+        match result {
+            Ok(_) => IpcResponse::Ok {
+                instance: IpcInstance::Gui,
+            },
+            Err(err) => IpcResponse::Error {
+                message: err.to_string(),
+            },
+        }
+    }
+
+    fn update_status(&mut self, data: &State) {
+        let playback = &data.playback;
+        let track = playback.now_playing.as_ref().map(|now_playing| {
+            let track = &now_playing.item;
+            IpcTrack {
+                uri: format!("spotify:track:{}", track.id.to_base62()),
+                name: Some(track.name.to_string()),
+                artist: Some(track.artist_name()),
+                album: Some(track.album_name()),
+                duration_ms: Some(track.duration.as_millis() as u64),
+            }
+        });
+        *self.status.lock().unwrap() = IpcStatus {
+            state: match playback.state {
+                PlaybackState::Loading => "loading",
+                PlaybackState::Playing => "playing",
+                PlaybackState::Paused => "paused",
+                PlaybackState::Stopped => "stopped",
+            }
+            .to_string(),
+            track,
+            progress_ms: playback
+                .now_playing
+                .as_ref()
+                .map_or(0, |now_playing| now_playing.progress.as_millis() as u64),
+            volume: (data.config.volume * 100.0).round() as u32,
+        };
+    }
+}
+
+impl<W> Controller<State, W> for IpcController
+where
+    W: Widget<State>,
+{
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &State,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.update_status(data);
+            self.start_listener_thread(ctx.get_external_handle());
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &State,
+        data: &State,
+        env: &Env,
+    ) {
+        if !old_data.playback.same(&data.playback) || old_data.config.volume != data.config.volume {
+            self.update_status(data);
+        }
+        child.update(ctx, old_data, data, env);
+    }
+}
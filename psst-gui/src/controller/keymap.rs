@@ -0,0 +1,246 @@
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use druid::{
+    widget::{prelude::*, Controller},
+    KbKey, KeyEvent,
+};
+
+use crate::{
+    cmd,
+    data::{CursorMove, KeyAction, Keymap, SeekDirection, State},
+};
+
+/// Longest pause between the keys of a sequence.
+const SEQUENCE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Key with its modifiers, as written in a binding.
+#[derive(Clone, Debug, PartialEq)]
+struct Chord {
+    key: KbKey,
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+    meta: bool,
+}
+
+impl Chord {
+    fn parse(input: &str) -> Option<Self> {
+        let mut chord = Chord {
+            key: KbKey::Unidentified,
+            ctrl: false,
+            alt: false,
+            shift: false,
+            meta: false,
+        };
+        let mut parts: Vec<&str> = input.split('+').collect();
+        let key = parts.pop()?;
+        for modifier in parts {
+            match modifier.to_uppercase().as_str() {
+                "CTRL" | "CONTROL" => chord.ctrl = true,
+                "ALT" | "OPTION" => chord.alt = true,
+                "SHIFT" => chord.shift = true,
+                "META" | "SUPER" | "WIN" => chord.meta = true,
+                "CMD" if cfg!(target_os = "macos") => chord.meta = true,
+                "CMD" => chord.ctrl = true,
+                _ => return None,
+            }
+        }
+        chord.key = match key {
+            _ if key.eq_ignore_ascii_case("SPACE") => KbKey::Character(" ".to_string()),
+            _ if key.chars().count() == 1 => KbKey::Character(key.to_string()),
+            _ => match KbKey::from_str(key) {
+                Ok(KbKey::Unidentified) | Err(_) => return None,
+                Ok(key) => key,
+            },
+        };
+        Some(chord)
+    }
+
+    fn matches(&self, event: &KeyEvent) -> bool {
+        let mods = &event.mods;
+        if self.ctrl != mods.ctrl() || self.alt != mods.alt() || self.meta != mods.meta() {
+            return false;
+        }
+        match (&self.key, &event.key) {
+            // Shift is already part of the character, except when combined
+            // with other modifiers.
+            (KbKey::Character(a), KbKey::Character(b)) if self.ctrl || self.alt || self.meta => {
+                a.eq_ignore_ascii_case(b)
+            }
+            (KbKey::Character(a), KbKey::Character(b)) => a == b,
+            (a, b) => a == b && self.shift == mods.shift(),
+        }
+    }
+}
+
+/// Runs the actions bound to keys in the keymap of the config, once none of
+/// the focused widgets want the key.
+pub struct KeymapController {
+    bindings: Vec<(KeyAction, Vec<Chord>)>,
+    /// Keys pressed so far of a sequence.
+    pending: Vec<KeyEvent>,
+    last_key: Instant,
+}
+
+impl KeymapController {
+    pub fn new() -> Self {
+        Self {
+            bindings: Vec::new(),
+            pending: Vec::new(),
+            last_key: Instant::now(),
+        }
+    }
+
+    fn load_bindings(&mut self, keymap: &Keymap) {
+        self.pending.clear();
+        self.bindings = keymap
+            .bindings()
+            .into_iter()
+            .filter_map(|(action, keys)| {
+                let chords: Option<Vec<Chord>> =
+                    keys.split_whitespace().map(Chord::parse).collect();
+                match chords {
+                    Some(chords) if !chords.is_empty() => Some((action, chords)),
+                    _ => {
+                        log::warn!("invalid key binding {:?} for {:?}", keys, action);
+                        None
+                    }
+                }
+            })
+            .collect();
+    }
+
+    /// Returns the action the pending keys complete, and whether they could
+    /// still become one.
+    fn lookup(&self) -> (Option<KeyAction>, bool) {
+        let mut is_prefix = false;
+        for (action, chords) in &self.bindings {
+            if chords.len() < self.pending.len() {
+                continue;
+            }
+            let matching = chords
+                .iter()
+                .zip(&self.pending)
+                .all(|(chord, key)| chord.matches(key));
+            if matching && chords.len() == self.pending.len() {
+                return (Some(*action), false);
+            }
+            is_prefix |= matching;
+        }
+        (None, is_prefix)
+    }
+
+    fn key_down(&mut self, key: &KeyEvent) -> Option<KeyAction> {
+        if self.last_key.elapsed() > SEQUENCE_TIMEOUT {
+            self.pending.clear();
+        }
+        self.last_key = Instant::now();
+        self.pending.push(key.to_owned());
+        match self.lookup() {
+            (Some(action), _) => {
+                self.pending.clear();
+                Some(action)
+            }
+            (None, true) => None,
+            (None, false) if self.pending.len() > 1 => {
+                // Start over from the last key.
+                self.pending.clear();
+                self.key_down(key)
+            }
+            (None, false) => {
+                self.pending.clear();
+                None
+            }
+        }
+    }
+
+    fn perform(ctx: &mut EventCtx, action: KeyAction) {
+        match action {
+            KeyAction::PlayPause => ctx.submit_command(cmd::PLAY_PAUSE_OR_RESUME),
+            KeyAction::Next => ctx.submit_command(cmd::PLAY_NEXT),
+            KeyAction::Previous => ctx.submit_command(cmd::PLAY_PREVIOUS),
+            KeyAction::SeekForward => {
+                ctx.submit_command(cmd::SEEK_RELATIVE.with(SeekDirection::Forward))
+            }
+            KeyAction::SeekBackward => {
+                ctx.submit_command(cmd::SEEK_RELATIVE.with(SeekDirection::Backward))
+            }
+            KeyAction::ToggleSave => ctx.submit_command(cmd::TOGGLE_SAVE_NOW_PLAYING),
+            KeyAction::Search => ctx.submit_command(cmd::SET_FOCUS.to(cmd::WIDGET_SEARCH_INPUT)),
+            KeyAction::GoBack => ctx.submit_command(cmd::NAVIGATE_BACK.with(1)),
+            KeyAction::Refresh => ctx.submit_command(cmd::REFRESH),
+            KeyAction::CursorDown => {
+                ctx.submit_command(cmd::MOVE_TRACK_CURSOR.with(CursorMove::Next))
+            }
+            KeyAction::CursorUp => {
+                ctx.submit_command(cmd::MOVE_TRACK_CURSOR.with(CursorMove::Previous))
+            }
+            KeyAction::CursorFirst => {
+                ctx.submit_command(cmd::MOVE_TRACK_CURSOR.with(CursorMove::First))
+            }
+            KeyAction::CursorLast => {
+                ctx.submit_command(cmd::MOVE_TRACK_CURSOR.with(CursorMove::Last))
+            }
+            KeyAction::PlayCursor => ctx.submit_command(cmd::PLAY_TRACK_CURSOR),
+            KeyAction::CursorMenu => ctx.submit_command(cmd::SHOW_TRACK_MENU),
+            KeyAction::CommandPalette => ctx.submit_command(cmd::TOGGLE_COMMAND_PALETTE),
+        }
+    }
+}
+
+impl<W> Controller<State, W> for KeymapController
+where
+    W: Widget<State>,
+{
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut State,
+        env: &Env,
+    ) {
+        child.event(ctx, event, data, env);
+        if let Event::KeyDown(key) = event {
+            // Let the focused widget, i.e. a text box, handle the key first.
+            if ctx.is_handled() {
+                return;
+            }
+            if let Some(action) = self.key_down(key) {
+                Self::perform(ctx, action);
+                ctx.set_handled();
+            }
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &State,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.load_bindings(&data.config.keymap);
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &State,
+        data: &State,
+        env: &Env,
+    ) {
+        if !old_data.config.keymap.same(&data.config.keymap) {
+            self.load_bindings(&data.config.keymap);
+        }
+        child.update(ctx, old_data, data, env);
+    }
+}
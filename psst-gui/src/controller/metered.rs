@@ -0,0 +1,125 @@
+use std::{
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use druid::{
+    widget::{prelude::*, Controller},
+    ExtEventSink, Target,
+};
+
+use crate::{cmd, data::State};
+
+// How often to ask the system about the network connection.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Keeps `Config::metered` up to date with what the system reports about the
+/// network connection, so the data saver can kick in.
+pub struct MeteredController {
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MeteredController {
+    pub fn new() -> Self {
+        Self { thread: None }
+    }
+
+    fn start_polling_thread(&mut self, event_sink: ExtEventSink, widget_id: WidgetId) {
+        self.thread.replace(thread::spawn(move || {
+            let mut last = None;
+            // Stop once the system can't tell, or the window is gone.
+            while let Some(metered) = is_metered() {
+                if last != Some(metered) {
+                    last = Some(metered);
+                    let sent = event_sink.submit_command(
+                        cmd::UPDATE_METERED,
+                        metered,
+                        Target::Widget(widget_id),
+                    );
+                    if sent.is_err() {
+                        break;
+                    }
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+        }));
+    }
+}
+
+impl<W> Controller<State, W> for MeteredController
+where
+    W: Widget<State>,
+{
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut State,
+        env: &Env,
+    ) {
+        match event {
+            Event::Command(cmd) if cmd.is(cmd::UPDATE_METERED) => {
+                let metered = cmd.get_unchecked(cmd::UPDATE_METERED);
+                log::info!("metered connection: {}", metered);
+                data.config.metered = *metered;
+                ctx.set_handled();
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &State,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.start_polling_thread(ctx.get_external_handle(), ctx.widget_id());
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+}
+
+/// Asks NetworkManager whether the primary connection is metered.
+#[cfg(target_os = "linux")]
+fn is_metered() -> Option<bool> {
+    // Values of `NMMetered`.
+    const NM_METERED_YES: u32 = 1;
+    const NM_METERED_GUESS_YES: u32 = 3;
+
+    let output = std::process::Command::new("busctl")
+        .args(&[
+            "--system",
+            "get-property",
+            "org.freedesktop.NetworkManager",
+            "/org/freedesktop/NetworkManager",
+            "org.freedesktop.NetworkManager",
+            "Metered",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // Prints the D-Bus type followed by the value, i.e. `u 4`.
+    let value: u32 = String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .last()?
+        .parse()
+        .ok()?;
+    Some(value == NM_METERED_YES || value == NM_METERED_GUESS_YES)
+}
+
+#[cfg(target_os = "windows")]
+fn is_metered() -> Option<bool> {
+    super::metered_windows::is_metered()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn is_metered() -> Option<bool> {
+    None
+}
@@ -0,0 +1,95 @@
+//! Cost of the network connection, through `INetworkCostManager` of the
+//! Network List Manager.  winapi doesn't define the interface, so we declare
+//! the part of it we call.
+
+#![allow(non_snake_case)]
+
+use std::{ffi::c_void, ptr};
+
+use winapi::{
+    shared::{
+        guiddef::GUID,
+        minwindef::DWORD,
+        winerror::{HRESULT, SUCCEEDED},
+        wtypesbase::CLSCTX_INPROC_SERVER,
+    },
+    um::{
+        combaseapi::{CoCreateInstance, CoInitializeEx},
+        objbase::COINIT_MULTITHREADED,
+        unknwnbase::{IUnknown, IUnknownVtbl},
+    },
+};
+
+// DCB00C01-570F-4A9B-8D69-199FDBA5723B
+const CLSID_NETWORK_LIST_MANAGER: GUID = GUID {
+    Data1: 0xDCB00C01,
+    Data2: 0x570F,
+    Data3: 0x4A9B,
+    Data4: [0x8D, 0x69, 0x19, 0x9F, 0xDB, 0xA5, 0x72, 0x3B],
+};
+
+// DCB00008-570F-4A9B-8D69-199FDBA5723B
+const IID_INETWORK_COST_MANAGER: GUID = GUID {
+    Data1: 0xDCB00008,
+    Data2: 0x570F,
+    Data3: 0x4A9B,
+    Data4: [0x8D, 0x69, 0x19, 0x9F, 0xDB, 0xA5, 0x72, 0x3B],
+};
+
+// Flags of `NLM_CONNECTION_COST` meaning the data is paid for.
+const NLM_CONNECTION_COST_FIXED: DWORD = 0x2;
+const NLM_CONNECTION_COST_VARIABLE: DWORD = 0x4;
+const NLM_CONNECTION_COST_OVERDATALIMIT: DWORD = 0x10000;
+const NLM_CONNECTION_COST_ROAMING: DWORD = 0x40000;
+
+#[repr(C)]
+struct INetworkCostManager {
+    lpVtbl: *const INetworkCostManagerVtbl,
+}
+
+// Only the methods up to the ones we call, in the order of the vtable.
+#[repr(C)]
+struct INetworkCostManagerVtbl {
+    parent: IUnknownVtbl,
+    GetCost: unsafe extern "system" fn(
+        This: *mut INetworkCostManager,
+        pCost: *mut DWORD,
+        pDestIPAddr: *mut c_void,
+    ) -> HRESULT,
+}
+
+/// Whether the machine-wide connection cost is anything but unrestricted.
+pub fn is_metered() -> Option<bool> {
+    unsafe {
+        // Fails harmlessly if this thread has already been initialized.
+        CoInitializeEx(ptr::null_mut(), COINIT_MULTITHREADED);
+
+        let mut manager: *mut INetworkCostManager = ptr::null_mut();
+        let hr = CoCreateInstance(
+            &CLSID_NETWORK_LIST_MANAGER,
+            ptr::null_mut(),
+            CLSCTX_INPROC_SERVER,
+            &IID_INETWORK_COST_MANAGER,
+            &mut manager as *mut _ as *mut _,
+        );
+        if !SUCCEEDED(hr) || manager.is_null() {
+            log::warn!("failed to create network cost manager: {:#x}", hr);
+            return None;
+        }
+
+        let mut cost: DWORD = 0;
+        // No destination address, we want the cost of the machine-wide connection.
+        let hr = ((*(*manager).lpVtbl).GetCost)(manager, &mut cost, ptr::null_mut());
+        ((*(*manager).lpVtbl).parent.Release)(manager as *mut IUnknown);
+        if !SUCCEEDED(hr) {
+            log::warn!("failed to get network cost: {:#x}", hr);
+            return None;
+        }
+
+        let metered = NLM_CONNECTION_COST_FIXED
+            | NLM_CONNECTION_COST_VARIABLE
+            | NLM_CONNECTION_COST_OVERDATALIMIT
+            | NLM_CONNECTION_COST_ROAMING;
+        Some(cost & metered != 0)
+    }
+}
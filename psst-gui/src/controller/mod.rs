@@ -1,9 +1,31 @@
+mod connect;
+mod discord;
+mod download;
+mod hotkey;
 mod input;
+mod ipc;
+mod keymap;
+mod metered;
+#[cfg(target_os = "windows")]
+mod metered_windows;
 mod nav;
+#[cfg(target_os = "macos")]
+mod now_playing_macos;
 mod playback;
+mod remote_api;
 mod session;
+#[cfg(target_os = "windows")]
+mod taskbar_windows;
 
+pub use connect::ConnectController;
+pub use discord::DiscordController;
+pub use download::DownloadController;
+pub use hotkey::HotkeyController;
 pub use input::InputController;
+pub use ipc::IpcController;
+pub use keymap::KeymapController;
+pub use metered::MeteredController;
 pub use nav::NavController;
 pub use playback::PlaybackController;
+pub use remote_api::RemoteApiController;
 pub use session::SessionController;
@@ -10,16 +10,35 @@ pub struct NavController;
 impl NavController {
     fn load_route_data(&self, ctx: &mut EventCtx, data: &mut State) {
         match &data.route {
-            Nav::Home => {}
+            Nav::Home => {
+                ctx.submit_command(cmd::LOAD_HOME);
+            }
+            Nav::NowPlaying => {}
+            Nav::History => {}
             Nav::SavedTracks => {
                 ctx.submit_command(cmd::LOAD_SAVED_TRACKS);
             }
             Nav::SavedAlbums => {
                 ctx.submit_command(cmd::LOAD_SAVED_ALBUMS);
             }
+            Nav::FollowedArtists => {
+                ctx.submit_command(cmd::LOAD_FOLLOWED_ARTISTS);
+            }
+            Nav::RecentlyPlayed => {
+                ctx.submit_command(cmd::LOAD_RECENTLY_PLAYED);
+            }
+            Nav::TopItems => {
+                ctx.submit_command(cmd::LOAD_TOP_ITEMS.with(data.top.range));
+            }
+            Nav::SavedShows => {
+                ctx.submit_command(cmd::LOAD_SAVED_SHOWS);
+            }
             Nav::SearchResults(query) => {
                 ctx.submit_command(cmd::LOAD_SEARCH_RESULTS.with(query.to_owned()));
             }
+            Nav::SearchTopicResults(query, topic) => {
+                ctx.submit_command(cmd::LOAD_SEARCH_TOPIC.with((query.to_owned(), *topic)));
+            }
             Nav::AlbumDetail(link) => {
                 ctx.submit_command(cmd::LOAD_ALBUM_DETAIL.with(link.to_owned()));
             }
@@ -29,6 +48,13 @@ impl NavController {
             Nav::PlaylistDetail(link) => {
                 ctx.submit_command(cmd::LOAD_PLAYLIST_DETAIL.with(link.to_owned()));
             }
+            Nav::ShowDetail(link) => {
+                ctx.submit_command(cmd::LOAD_SHOW_DETAIL.with(link.to_owned()));
+                ctx.submit_command(cmd::LOAD_SAVED_SHOWS);
+            }
+            Nav::Radio(seed) => {
+                ctx.submit_command(cmd::LOAD_RADIO.with(seed.to_owned()));
+            }
         }
     }
 }
@@ -0,0 +1,86 @@
+//! Timing information and scrubbing for the macOS Now Playing widget.  The
+//! media controls only publish the track metadata and the basic remote
+//! commands, so we fill in the rest of `MPNowPlayingInfoCenter` here.
+
+use std::time::Duration;
+
+use block::ConcreteBlock;
+use cocoa::{
+    base::{id, nil},
+    foundation::{NSInteger, NSString},
+};
+use druid::{ExtEventSink, WidgetId};
+use objc::{class, msg_send, sel, sel_impl};
+
+use crate::cmd;
+
+#[link(name = "MediaPlayer", kind = "framework")]
+extern "C" {}
+
+// Values of the `MPMediaItemPropertyPlaybackDuration`,
+// `MPNowPlayingInfoPropertyElapsedPlaybackTime` and
+// `MPNowPlayingInfoPropertyPlaybackRate` constants.
+const DURATION_KEY: &str = "playbackDuration";
+const ELAPSED_KEY: &str = "MPNowPlayingInfoPropertyElapsedPlaybackTime";
+const RATE_KEY: &str = "MPNowPlayingInfoPropertyPlaybackRate";
+
+// `MPRemoteCommandHandlerStatusSuccess`.
+const HANDLER_STATUS_SUCCESS: NSInteger = 0;
+
+/// Publish the duration, the elapsed time and the playback rate of the current
+/// item.  The system extrapolates the elapsed time from the rate, so this only
+/// needs to be called when the playback state changes or on seek.
+pub fn set_playback_position(duration: Duration, elapsed: Duration, rate: f64) {
+    update_now_playing_info(&[
+        (DURATION_KEY, duration.as_secs_f64()),
+        (ELAPSED_KEY, elapsed.as_secs_f64()),
+        (RATE_KEY, rate),
+    ]);
+}
+
+/// Publish a new elapsed time, i.e. after seeking.
+pub fn set_elapsed(elapsed: Duration) {
+    update_now_playing_info(&[(ELAPSED_KEY, elapsed.as_secs_f64())]);
+}
+
+/// Let the user scrub through the current item from the Now Playing widget.
+pub fn attach_seek_handler(event_sink: ExtEventSink, widget_id: WidgetId) {
+    let handler = ConcreteBlock::new(move |event: id| -> NSInteger {
+        let position: f64 = unsafe { msg_send![event, positionTime] };
+        event_sink
+            .submit_command(
+                cmd::PLAY_SEEK_TO,
+                Duration::from_secs_f64(position.max(0.0)),
+                widget_id,
+            )
+            .ok();
+        HANDLER_STATUS_SUCCESS
+    })
+    .copy();
+    unsafe {
+        let command_center: id = msg_send![class!(MPRemoteCommandCenter), sharedCommandCenter];
+        let command: id = msg_send![command_center, changePlaybackPositionCommand];
+        let _: () = msg_send![command, setEnabled: true];
+        let _: id = msg_send![command, addTargetWithHandler: &*handler];
+    }
+}
+
+fn update_now_playing_info(values: &[(&str, f64)]) {
+    unsafe {
+        let center: id = msg_send![class!(MPNowPlayingInfoCenter), defaultCenter];
+        let current: id = msg_send![center, nowPlayingInfo];
+        let info: id = if current == nil {
+            msg_send![class!(NSMutableDictionary), dictionary]
+        } else {
+            let copy: id = msg_send![current, mutableCopy];
+            msg_send![copy, autorelease]
+        };
+        for &(key, value) in values {
+            let key = NSString::alloc(nil).init_str(key);
+            let _: id = msg_send![key, autorelease];
+            let number: id = msg_send![class!(NSNumber), numberWithDouble: value];
+            let _: () = msg_send![info, setObject: number forKey: key];
+        }
+        let _: () = msg_send![center, setNowPlayingInfo: info];
+    }
+}
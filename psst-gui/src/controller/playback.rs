@@ -7,13 +7,14 @@ use crossbeam_channel::Sender;
 use druid::{
     im::Vector,
     widget::{prelude::*, Controller},
-    ExtEventSink, WindowHandle,
+    ExtEventSink, KbKey, Target, WindowHandle,
 };
 use psst_core::{
     audio_normalize::NormalizationLevel,
-    audio_output::AudioOutput,
+    audio_output::{AudioOutput, AudioOutputRemote},
     audio_player::{PlaybackConfig, PlaybackItem, Player, PlayerCommand, PlayerEvent},
-    cache::Cache,
+    audio_spectrum::SpectrumTap,
+    cache::{Cache, CacheHandle},
     cdn::Cdn,
     session::SessionHandle,
 };
@@ -22,24 +23,43 @@ use souvlaki::{MediaControlEvent, MediaControls, MediaMetadata, MediaPlayback};
 use crate::{
     cmd,
     data::{
-        Config, Playback, PlaybackOrigin, PlaybackState, QueueBehavior, QueuedTrack, State, TrackId,
+        Config, Normalization, Playback, PlaybackOrigin, PlaybackPayload, PlaybackState,
+        QueueBehavior, QueuedTrack, RepeatMode, SavedPlayback, SeekDirection, State, TrackId,
     },
 };
 
 pub struct PlaybackController {
     sender: Option<Sender<PlayerEvent>>,
+    output_remote: Option<AudioOutputRemote>,
+    cache: Option<CacheHandle>,
     thread: Option<JoinHandle<()>>,
     output_thread: Option<JoinHandle<()>>,
     media_controls: Option<MediaControls>,
+    #[cfg(target_os = "windows")]
+    taskbar: Option<Box<super::taskbar_windows::Taskbar>>,
+    // Queue position and progress of playback restored from the previous run.  The
+    // player stays empty until the user resumes.
+    restored: Option<(usize, Duration)>,
+    seek_after_playing: Option<Duration>,
+    pause_after_playing: bool,
+    window_title: String,
 }
 
 impl PlaybackController {
     pub fn new() -> Self {
         Self {
             sender: None,
+            output_remote: None,
+            cache: None,
             thread: None,
             output_thread: None,
             media_controls: None,
+            #[cfg(target_os = "windows")]
+            taskbar: None,
+            restored: None,
+            seek_after_playing: None,
+            pause_after_playing: false,
+            window_title: String::new(),
         }
     }
 
@@ -47,27 +67,40 @@ impl PlaybackController {
         &mut self,
         session: SessionHandle,
         config: PlaybackConfig,
+        device: Option<String>,
+        cache_limit: Option<u64>,
+        spectrum_tap: SpectrumTap,
         event_sink: ExtEventSink,
         widget_id: WidgetId,
         #[allow(unused_variables)] window: &WindowHandle,
     ) {
-        let output = AudioOutput::open().unwrap();
+        let mut output = AudioOutput::open().unwrap();
+        output.set_device(device);
+        output.set_spectrum_tap(spectrum_tap);
         let remote = output.remote();
+        let output_remote = remote.clone();
 
         let cache_dir = Config::cache_dir().unwrap();
+        let cache = Cache::new(cache_dir).unwrap();
+        if let Err(err) = cache.set_audio_limit(cache_limit) {
+            log::error!("failed to limit audio cache: {:?}", err);
+        }
         let proxy_url = Config::proxy();
         let player = Player::new(
             session.clone(),
             Cdn::new(session, proxy_url.as_deref()).unwrap(),
-            Cache::new(cache_dir).unwrap(),
+            cache.clone(),
             config,
             remote,
         );
         let sender = player.event_sender();
         let source = player.audio_source();
 
-        let thread = thread::spawn(move || {
-            Self::service_events(player, event_sink, widget_id);
+        let thread = thread::spawn({
+            let event_sink = event_sink.clone();
+            move || {
+                Self::service_events(player, event_sink, widget_id);
+            }
         });
         let output_thread = thread::spawn(move || {
             output.start_playback(source).expect("Playback failed");
@@ -81,27 +114,45 @@ impl PlaybackController {
                 RawWindowHandle::Windows(h) => h,
                 _ => panic!("Cannot get window handle"),
             };
+            match super::taskbar_windows::Taskbar::attach(
+                window_handle.hwnd as _,
+                event_sink.clone(),
+                widget_id,
+            ) {
+                Ok(taskbar) => {
+                    self.taskbar.replace(taskbar);
+                }
+                Err(err) => {
+                    log::error!("failed to attach to the taskbar: {}", err);
+                }
+            }
             MediaControls::for_window(window_handle).unwrap()
         };
         #[cfg(not(target_os = "windows"))]
         let mut media_controls = MediaControls::new();
 
+        #[cfg(target_os = "macos")]
+        super::now_playing_macos::attach_seek_handler(event_sink.clone(), widget_id);
+
         media_controls
-            .attach({
-                let sender = sender.clone();
-                move |event| {
-                    Self::handle_media_control_event(event, &sender);
-                }
+            .attach(move |event| {
+                Self::handle_media_control_event(event, &event_sink, widget_id);
             })
             .unwrap();
 
         self.sender.replace(sender);
+        self.output_remote.replace(output_remote);
+        self.cache.replace(cache);
         self.thread.replace(thread);
         self.output_thread.replace(output_thread);
         self.media_controls.replace(media_controls);
     }
 
     fn service_events(mut player: Player, event_sink: ExtEventSink, widget_id: WidgetId) {
+        // Set when the last track has finished, so we can tell the queue running
+        // out apart from an explicit stop.
+        let mut is_finished = false;
+
         for event in player.event_receiver() {
             // Forward events that affect the UI state to the UI thread.
             match &event {
@@ -143,24 +194,36 @@ impl PlaybackController {
                     event_sink
                         .submit_command(cmd::PLAYBACK_STOPPED, (), widget_id)
                         .unwrap();
+                    if is_finished {
+                        event_sink
+                            .submit_command(cmd::PLAYBACK_ENDED, (), Target::Auto)
+                            .unwrap();
+                    }
                 }
                 _ => {}
             }
+            is_finished = matches!(event, PlayerEvent::Finished);
 
             // Let the player react to its internal events.
             player.handle(event);
         }
     }
 
-    fn handle_media_control_event(event: MediaControlEvent, sender: &Sender<PlayerEvent>) {
+    fn handle_media_control_event(
+        event: MediaControlEvent,
+        event_sink: &ExtEventSink,
+        widget_id: WidgetId,
+    ) {
+        // Route the media keys through the same commands as the UI controls, so
+        // the app state stays in sync.
         let cmd = match event {
-            MediaControlEvent::Play => PlayerEvent::Command(PlayerCommand::Resume),
-            MediaControlEvent::Pause => PlayerEvent::Command(PlayerCommand::Pause),
-            MediaControlEvent::Toggle => PlayerEvent::Command(PlayerCommand::PauseOrResume),
-            MediaControlEvent::Next => PlayerEvent::Command(PlayerCommand::Next),
-            MediaControlEvent::Previous => PlayerEvent::Command(PlayerCommand::Previous),
+            MediaControlEvent::Play => cmd::PLAY_RESUME,
+            MediaControlEvent::Pause => cmd::PLAY_PAUSE,
+            MediaControlEvent::Toggle => cmd::PLAY_PAUSE_OR_RESUME,
+            MediaControlEvent::Next => cmd::PLAY_NEXT,
+            MediaControlEvent::Previous => cmd::PLAY_PREVIOUS,
         };
-        sender.send(cmd).unwrap();
+        event_sink.submit_command(cmd, (), widget_id).unwrap();
     }
 
     fn update_media_controls(&mut self, playback: &Playback) {
@@ -182,6 +245,55 @@ impl PlaybackController {
                     artist: artist.as_deref(),
                 })
                 .unwrap();
+            #[cfg(target_os = "macos")]
+            Self::update_media_timing(playback);
+        }
+        self.update_taskbar(playback);
+    }
+
+    fn update_taskbar(&mut self, #[allow(unused_variables)] playback: &Playback) {
+        #[cfg(target_os = "windows")]
+        if let Some(taskbar) = self.taskbar.as_mut() {
+            use super::taskbar_windows::TaskbarState;
+
+            taskbar.set_state(match playback.state {
+                PlaybackState::Loading | PlaybackState::Stopped => TaskbarState::Stopped,
+                PlaybackState::Playing => TaskbarState::Playing,
+                PlaybackState::Paused => TaskbarState::Paused,
+            });
+            if let Some(now_playing) = &playback.now_playing {
+                taskbar.set_progress(now_playing.progress, now_playing.item.duration);
+            }
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    fn update_media_timing(playback: &Playback) {
+        if let Some(now_playing) = &playback.now_playing {
+            let rate = match playback.state {
+                PlaybackState::Playing => playback.speed,
+                _ => 0.0,
+            };
+            super::now_playing_macos::set_playback_position(
+                now_playing.item.duration,
+                now_playing.progress,
+                rate,
+            );
+        }
+    }
+
+    fn update_media_position(&mut self, #[allow(unused_variables)] position: Duration) {
+        #[cfg(target_os = "macos")]
+        super::now_playing_macos::set_elapsed(position);
+    }
+
+    fn update_window_title(&mut self, window: &WindowHandle, data: &State) {
+        let title = data.playback.window_title(&data.config.window_title);
+        // Progress gets updated often, only touch the window when the title
+        // actually changes.
+        if title != self.window_title {
+            window.set_title(&title);
+            self.window_title = title;
         }
     }
 
@@ -189,16 +301,23 @@ impl PlaybackController {
         self.sender.as_mut().unwrap().send(event).unwrap();
     }
 
-    fn play(&mut self, items: &Vector<QueuedTrack>, position: usize) {
+    fn playback_item(queued: &QueuedTrack, normalization: Normalization) -> PlaybackItem {
+        PlaybackItem {
+            item_id: *queued.track.id,
+            norm_level: match (normalization, &queued.origin) {
+                (Normalization::Off, _) => NormalizationLevel::None,
+                (Normalization::Track, _) => NormalizationLevel::Track,
+                (Normalization::Album, _) => NormalizationLevel::Album,
+                (Normalization::Automatic, PlaybackOrigin::Album(_)) => NormalizationLevel::Album,
+                (Normalization::Automatic, _) => NormalizationLevel::Track,
+            },
+        }
+    }
+
+    fn play(&mut self, items: &Vector<QueuedTrack>, position: usize, normalization: Normalization) {
         let items = items
             .iter()
-            .map(|queued| PlaybackItem {
-                item_id: *queued.track.id,
-                norm_level: match queued.origin {
-                    PlaybackOrigin::Album(_) => NormalizationLevel::Album,
-                    _ => NormalizationLevel::Track,
-                },
-            })
+            .map(|queued| Self::playback_item(queued, normalization))
             .collect();
         self.send(PlayerEvent::Command(PlayerCommand::LoadQueue {
             items,
@@ -206,6 +325,85 @@ impl PlaybackController {
         }));
     }
 
+    fn add_to_queue(&mut self, data: &mut State, queued: &QueuedTrack) {
+        if !queued.track.is_playable() {
+            log::info!("not queueing unplayable track");
+            return;
+        }
+        if data.config.skip_explicit && queued.track.explicit {
+            log::info!("not queueing explicit track");
+            return;
+        }
+        let index = data.playback.added_index();
+        data.playback.queue.insert(index, queued.to_owned());
+        let item = Self::playback_item(queued, data.config.normalization);
+        self.update_queue(data, PlayerCommand::InsertIntoQueue { index, item });
+    }
+
+    fn remove_from_queue(&mut self, data: &mut State, index: usize) {
+        if index >= data.playback.queue.len() || data.playback.current_index() == Some(index) {
+            return;
+        }
+        data.playback.queue.remove(index);
+        self.update_queue(data, PlayerCommand::RemoveFromQueue { index });
+    }
+
+    fn move_in_queue(&mut self, data: &mut State, from: usize, offset: isize) {
+        let to = match data.playback.upcoming_target(from, offset) {
+            Some(to) if to != from => to,
+            _ => return,
+        };
+        let queued = data.playback.queue.remove(from);
+        data.playback.queue.insert(to, queued);
+        self.update_queue(data, PlayerCommand::MoveInQueue { from, to });
+    }
+
+    fn update_queue(&mut self, data: &State, command: PlayerCommand) {
+        match self.restored.as_mut() {
+            // Restored playback loads the whole queue once it gets resumed, just
+            // follow the playing track.
+            Some((position, _)) => {
+                if let Some(current) = data.playback.current_index() {
+                    *position = current;
+                }
+            }
+            None => self.send(PlayerEvent::Command(command)),
+        }
+    }
+
+    fn play_tracks(&mut self, data: &mut State, payload: &PlaybackPayload) {
+        let skip_explicit = data.config.skip_explicit;
+        let payload =
+            &payload.without(|track| !track.is_playable() || (skip_explicit && track.explicit));
+        if payload.tracks.is_empty() {
+            log::info!("nothing to play, all tracks were skipped");
+            return;
+        }
+        self.restored.take();
+        self.pause_after_playing = false;
+        data.playback.queue = payload
+            .tracks
+            .iter()
+            .map(|track| QueuedTrack {
+                origin: payload.origin.to_owned(),
+                track: track.to_owned(),
+                added: false,
+            })
+            .collect();
+        self.play(
+            &data.playback.queue,
+            payload.position,
+            data.config.normalization,
+        );
+    }
+
+    fn play_restored(&mut self, data: &State, position: usize, progress: Duration) {
+        self.play(&data.playback.queue, position, data.config.normalization);
+        if progress > Duration::default() {
+            self.seek_after_playing.replace(progress);
+        }
+    }
+
     fn pause(&mut self) {
         self.send(PlayerEvent::Command(PlayerCommand::Pause));
     }
@@ -230,13 +428,29 @@ impl PlaybackController {
         self.send(PlayerEvent::Command(PlayerCommand::Seek { position }));
     }
 
+    fn set_speed(&mut self, speed: f64) {
+        self.send(PlayerEvent::Command(PlayerCommand::SetSpeed { speed }));
+    }
+
+    fn configure(&mut self, config: PlaybackConfig) {
+        self.send(PlayerEvent::Command(PlayerCommand::Configure { config }));
+    }
+
     fn set_queue_behavior(&mut self, behavior: QueueBehavior) {
         self.send(PlayerEvent::Command(PlayerCommand::SetQueueBehavior {
             behavior: match behavior {
                 QueueBehavior::Sequential => psst_core::audio_queue::QueueBehavior::Sequential,
                 QueueBehavior::Random => psst_core::audio_queue::QueueBehavior::Random,
-                QueueBehavior::LoopTrack => psst_core::audio_queue::QueueBehavior::LoopTrack,
-                QueueBehavior::LoopAll => psst_core::audio_queue::QueueBehavior::LoopAll,
+            },
+        }));
+    }
+
+    fn set_repeat_mode(&mut self, repeat: RepeatMode) {
+        self.send(PlayerEvent::Command(PlayerCommand::SetRepeatMode {
+            repeat: match repeat {
+                RepeatMode::Off => psst_core::audio_queue::RepeatMode::Off,
+                RepeatMode::Context => psst_core::audio_queue::RepeatMode::Context,
+                RepeatMode::Track => psst_core::audio_queue::RepeatMode::Track,
             },
         }));
     }
@@ -273,6 +487,13 @@ where
                 if let Some(queued) = data.queued_track(item) {
                     data.start_playback(queued.track, queued.origin, progress.to_owned());
                     self.update_media_controls(&data.playback);
+                    if let Some(position) = self.seek_after_playing.take() {
+                        self.seek(position);
+                    }
+                    if self.pause_after_playing {
+                        self.pause_after_playing = false;
+                        self.pause();
+                    }
                 } else {
                     log::warn!("played item not found in playback queue");
                 }
@@ -281,6 +502,7 @@ where
             Event::Command(cmd) if cmd.is(cmd::PLAYBACK_PROGRESS) => {
                 let progress = cmd.get_unchecked(cmd::PLAYBACK_PROGRESS);
                 data.progress_playback(progress.to_owned());
+                self.update_taskbar(&data.playback);
                 ctx.set_handled();
             }
             Event::Command(cmd) if cmd.is(cmd::PLAYBACK_PAUSING) => {
@@ -311,37 +533,89 @@ where
                 });
                 ctx.set_handled();
             }
+            Event::Command(cmd) if cmd.is(cmd::UPDATE_LYRICS) => {
+                let (track_id, result) = cmd.get_unchecked(cmd::UPDATE_LYRICS);
+                data.playback.now_playing.as_mut().map(|current| {
+                    if current.lyrics.is_deferred(track_id) {
+                        current.lyrics.resolve_or_reject(result.to_owned());
+                    }
+                });
+                ctx.set_handled();
+            }
             //
             Event::Command(cmd) if cmd.is(cmd::PLAY_TRACKS) => {
                 let payload = cmd.get_unchecked(cmd::PLAY_TRACKS);
-                data.playback.queue = payload
-                    .tracks
-                    .iter()
-                    .map(|track| QueuedTrack {
-                        origin: payload.origin.to_owned(),
-                        track: track.to_owned(),
-                    })
-                    .collect();
-                self.play(&data.playback.queue, payload.position);
+                self.play_tracks(data, payload);
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(cmd::PLAY_TRACKS_FROM) => {
+                let (payload, progress, paused) = cmd.get_unchecked(cmd::PLAY_TRACKS_FROM);
+                self.play_tracks(data, payload);
+                if *progress > Duration::default() {
+                    self.seek_after_playing.replace(*progress);
+                }
+                self.pause_after_playing = *paused;
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(cmd::ADD_TO_QUEUE) => {
+                let queued = cmd.get_unchecked(cmd::ADD_TO_QUEUE);
+                self.add_to_queue(data, queued);
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(cmd::ADD_ALL_TO_QUEUE) => {
+                for queued in cmd.get_unchecked(cmd::ADD_ALL_TO_QUEUE) {
+                    self.add_to_queue(data, queued);
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(cmd::REMOVE_FROM_QUEUE) => {
+                let index = cmd.get_unchecked(cmd::REMOVE_FROM_QUEUE);
+                self.remove_from_queue(data, *index);
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(cmd::MOVE_IN_QUEUE) => {
+                let (from, offset) = cmd.get_unchecked(cmd::MOVE_IN_QUEUE);
+                self.move_in_queue(data, *from, *offset);
                 ctx.set_handled();
             }
             Event::Command(cmd) if cmd.is(cmd::PLAY_PAUSE) => {
                 self.pause();
                 ctx.set_handled();
             }
+            Event::Command(cmd) if cmd.is(cmd::PLAY_PAUSE_OR_RESUME) => {
+                match data.playback.state {
+                    PlaybackState::Playing => ctx.submit_command(cmd::PLAY_PAUSE),
+                    PlaybackState::Paused => ctx.submit_command(cmd::PLAY_RESUME),
+                    PlaybackState::Loading | PlaybackState::Stopped => {}
+                }
+                ctx.set_handled();
+            }
             Event::Command(cmd) if cmd.is(cmd::PLAY_RESUME) => {
-                self.resume();
+                if let Some((position, progress)) = self.restored.take() {
+                    self.play_restored(data, position, progress);
+                } else {
+                    self.resume();
+                }
                 ctx.set_handled();
             }
             Event::Command(cmd) if cmd.is(cmd::PLAY_PREVIOUS) => {
-                self.previous();
+                if let Some((position, _)) = self.restored.take() {
+                    self.play_restored(data, position.saturating_sub(1), Duration::default());
+                } else {
+                    self.previous();
+                }
                 ctx.set_handled();
             }
             Event::Command(cmd) if cmd.is(cmd::PLAY_NEXT) => {
-                self.next();
+                if let Some((position, _)) = self.restored.take() {
+                    self.play_restored(data, position + 1, Duration::default());
+                } else {
+                    self.next();
+                }
                 ctx.set_handled();
             }
             Event::Command(cmd) if cmd.is(cmd::PLAY_STOP) => {
+                self.restored.take();
                 self.stop();
                 ctx.set_handled();
             }
@@ -351,15 +625,100 @@ where
                 self.set_queue_behavior(behavior.to_owned());
                 ctx.set_handled();
             }
+            Event::Command(cmd) if cmd.is(cmd::PLAY_REPEAT_MODE) => {
+                let repeat = cmd.get_unchecked(cmd::PLAY_REPEAT_MODE);
+                data.playback.repeat_mode = repeat.to_owned();
+                self.set_repeat_mode(repeat.to_owned());
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(cmd::PLAY_CYCLE_REPEAT_MODE) => {
+                let repeat = data.playback.repeat_mode.cycle();
+                data.playback.repeat_mode = repeat;
+                self.set_repeat_mode(repeat);
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(cmd::PLAY_SPEED) => {
+                let speed = cmd.get_unchecked(cmd::PLAY_SPEED);
+                data.playback.speed = *speed;
+                self.set_speed(*speed);
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(cmd::PLAY_VOLUME) => {
+                let volume = cmd.get_unchecked(cmd::PLAY_VOLUME);
+                // Gets applied to the player together with the rest of the config.
+                data.config.volume = volume.max(0.0).min(1.0);
+                ctx.set_handled();
+            }
             Event::Command(cmd) if cmd.is(cmd::PLAY_SEEK) => {
                 let fraction = cmd.get_unchecked(cmd::PLAY_SEEK);
-                data.playback.now_playing.as_ref().map(|current| {
+                data.playback.now_playing.as_mut().map(|current| {
                     let position =
                         Duration::from_secs_f64(current.item.duration.as_secs_f64() * fraction);
-                    self.seek(position);
+                    if let Some((_, progress)) = self.restored.as_mut() {
+                        // Nothing is loaded yet, remember the position for later.
+                        *progress = position;
+                        current.progress = position;
+                    } else {
+                        self.seek(position);
+                        self.update_media_position(position);
+                    }
                 });
                 ctx.set_handled();
             }
+            Event::Command(cmd) if cmd.is(cmd::PLAY_SEEK_TO) => {
+                let position = cmd.get_unchecked(cmd::PLAY_SEEK_TO);
+                if let Some(current) = &data.playback.now_playing {
+                    let fraction = position.as_secs_f64() / current.item.duration.as_secs_f64();
+                    ctx.submit_command(cmd::PLAY_SEEK.with(fraction.min(1.0)));
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(cmd::SEEK_RELATIVE) => {
+                let direction = cmd.get_unchecked(cmd::SEEK_RELATIVE);
+                let step = data.config.seek_step();
+                data.playback.now_playing.as_mut().map(|current| {
+                    let position = match direction {
+                        SeekDirection::Forward => {
+                            (current.progress + step).min(current.item.duration)
+                        }
+                        SeekDirection::Backward => {
+                            current.progress.checked_sub(step).unwrap_or_default()
+                        }
+                    };
+                    if let Some((_, progress)) = self.restored.as_mut() {
+                        // Nothing is loaded yet, remember the position for later.
+                        *progress = position;
+                        current.progress = position;
+                    } else {
+                        self.seek(position);
+                        self.update_media_position(position);
+                    }
+                });
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) => {
+                // Let the focused widget, i.e. a text box, handle the key first.
+                child.event(ctx, event, data, env);
+                if ctx.is_handled() {
+                    return;
+                }
+                // Media keys, in case the system delivers them to the focused
+                // window instead of the media controls.  The rest of the keys
+                // are up to the keymap.
+                let media_cmd = match &key.key {
+                    KbKey::MediaPlayPause => Some(cmd::PLAY_PAUSE_OR_RESUME),
+                    KbKey::MediaPlay => Some(cmd::PLAY_RESUME),
+                    KbKey::MediaPause => Some(cmd::PLAY_PAUSE),
+                    KbKey::MediaStop => Some(cmd::PLAY_STOP),
+                    KbKey::MediaTrackNext => Some(cmd::PLAY_NEXT),
+                    KbKey::MediaTrackPrevious => Some(cmd::PLAY_PREVIOUS),
+                    _ => None,
+                };
+                if let Some(media_cmd) = media_cmd {
+                    ctx.submit_command(media_cmd);
+                    ctx.set_handled();
+                }
+            }
             //
             _ => child.event(ctx, event, data, env),
         }
@@ -378,13 +737,63 @@ where
                 self.open_audio_output_and_start_threads(
                     data.session.clone(),
                     data.config.playback(),
+                    data.config.audio_device.clone(),
+                    data.config.audio_cache_limit(),
+                    data.spectrum.tap(),
                     ctx.get_external_handle(),
                     ctx.widget_id(),
                     ctx.window(),
                 );
+                if let Some(saved) = SavedPlayback::new(&data.playback) {
+                    self.restored.replace((saved.position, saved.progress));
+                    self.update_media_controls(&data.playback);
+                }
+                self.update_window_title(ctx.window(), data);
             }
             _ => {}
         }
         child.lifecycle(ctx, event, data, env);
     }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &State,
+        data: &State,
+        env: &Env,
+    ) {
+        let playback_config = data.config.playback();
+        if old_data.config.playback() != playback_config {
+            self.configure(playback_config);
+        }
+        if old_data.config.audio_device != data.config.audio_device {
+            if let Some(remote) = &self.output_remote {
+                remote.set_device(data.config.audio_device.clone());
+            }
+        }
+        if old_data.config.audio_cache_limit_gb != data.config.audio_cache_limit_gb {
+            if let Some(cache) = &self.cache {
+                if let Err(err) = cache.set_audio_limit(data.config.audio_cache_limit()) {
+                    log::error!("failed to limit audio cache: {:?}", err);
+                }
+            }
+        }
+        if old_data.connect.cast_renderer != data.connect.cast_renderer {
+            if let Some(remote) = &self.output_remote {
+                remote.cast(
+                    data.connect
+                        .cast_renderer
+                        .as_ref()
+                        .map(|renderer| renderer.renderer()),
+                );
+            }
+        }
+        if !old_data.playback.same(&data.playback)
+            || old_data.config.window_title != data.config.window_title
+        {
+            self.update_window_title(ctx.window(), data);
+        }
+        child.update(ctx, old_data, data, env);
+    }
 }
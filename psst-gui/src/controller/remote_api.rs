@@ -0,0 +1,271 @@
+use std::{
+    io::{Cursor, Read},
+    net::{Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+};
+
+use druid::{
+    widget::{prelude::*, Controller},
+    ExtEventError, ExtEventSink, Target,
+};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::{
+    cmd,
+    data::{PlaybackState, QueueBehavior, RepeatMode, State},
+};
+
+/// Local HTTP server letting scripts and home-automation tools control the
+/// playback.  Only listens on the loopback interface, and only answers
+/// requests carrying the token from the config, so web pages the user visits
+/// can't drive it.
+pub struct RemoteApiController {
+    server: Option<Arc<Server>>,
+    status: Arc<Mutex<Value>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl RemoteApiController {
+    pub fn new() -> Self {
+        Self {
+            server: None,
+            status: Arc::new(Mutex::new(Value::Null)),
+            thread: None,
+        }
+    }
+
+    fn start_server(&mut self, port: u16, token: String, event_sink: ExtEventSink) {
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let server = match Server::http(addr) {
+            Ok(server) => Arc::new(server),
+            Err(err) => {
+                log::error!("failed to start remote api on {}: {}", addr, err);
+                return;
+            }
+        };
+        log::info!("remote api listening on {}", addr);
+        let thread = thread::spawn({
+            let server = server.clone();
+            let status = self.status.clone();
+            move || Self::service_requests(&server, port, &token, &status, &event_sink)
+        });
+        self.server.replace(server);
+        self.thread.replace(thread);
+    }
+
+    fn stop_server(&mut self) {
+        if let Some(server) = self.server.take() {
+            // Wakes up the server thread, which then quits.
+            server.unblock();
+        }
+        self.thread.take();
+    }
+
+    fn service_requests(
+        server: &Server,
+        port: u16,
+        token: &str,
+        status: &Mutex<Value>,
+        event_sink: &ExtEventSink,
+    ) {
+        for mut request in server.incoming_requests() {
+            let response = if let Err(err) = authorize(&request, port, token) {
+                log::warn!("rejected remote api request: {}", err);
+                json_response(403, json!({ "error": err }))
+            } else {
+                Self::handle_request(&mut request, status, event_sink)
+            };
+            if let Err(err) = request.respond(response) {
+                log::warn!("failed to respond to remote api request: {}", err);
+            }
+        }
+    }
+
+    fn handle_request(
+        request: &mut Request,
+        status: &Mutex<Value>,
+        event_sink: &ExtEventSink,
+    ) -> Response<Cursor<Vec<u8>>> {
+        let method = request.method().clone();
+        let url = request.url().to_string();
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+        let command = match path {
+            "/play" => Some(cmd::PLAY_RESUME),
+            "/pause" => Some(cmd::PLAY_PAUSE),
+            "/toggle" => Some(cmd::PLAY_PAUSE_OR_RESUME),
+            "/next" => Some(cmd::PLAY_NEXT),
+            "/previous" => Some(cmd::PLAY_PREVIOUS),
+            _ => None,
+        };
+        match (&method, path, command) {
+            (Method::Get, "/status", _) => json_response(200, status.lock().unwrap().clone()),
+            (Method::Post, _, Some(command)) => {
+                submit_response(event_sink.submit_command(command, (), Target::Auto))
+            }
+            (Method::Get, "/volume", _) => {
+                let volume = status.lock().unwrap()["volume"].clone();
+                json_response(200, json!({ "volume": volume }))
+            }
+            (Method::Post, "/volume", _) | (Method::Put, "/volume", _) => {
+                // Accept the level in percent, either in the query (`/volume?level=50`)
+                // or as the request body.
+                let mut body = String::new();
+                request.as_reader().read_to_string(&mut body).ok();
+                let level = query
+                    .split('&')
+                    .find_map(|pair| pair.strip_prefix("level="))
+                    .unwrap_or_else(|| body.trim())
+                    .parse::<f64>();
+                match level {
+                    Ok(level) if (0.0..=100.0).contains(&level) => submit_response(
+                        event_sink.submit_command(cmd::PLAY_VOLUME, level / 100.0, Target::Auto),
+                    ),
+                    _ => json_response(
+                        400,
+                        json!({ "error": "expected a volume level between 0 and 100" }),
+                    ),
+                }
+            }
+            (_, "/status", _) | (_, "/volume", _) | (_, _, Some(_)) => {
+                json_response(405, json!({ "error": "method not allowed" }))
+            }
+            _ => json_response(404, json!({ "error": "not found" })),
+        }
+    }
+
+    fn update_status(&mut self, data: &State) {
+        *self.status.lock().unwrap() = status(data);
+    }
+}
+
+impl<W> Controller<State, W> for RemoteApiController
+where
+    W: Widget<State>,
+{
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &State,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.update_status(data);
+            if data.config.remote_api {
+                self.start_server(
+                    data.config.remote_api_port,
+                    data.config.remote_api_token.clone(),
+                    ctx.get_external_handle(),
+                );
+            }
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &State,
+        data: &State,
+        env: &Env,
+    ) {
+        let was_enabled = old_data.config.remote_api;
+        let is_enabled = data.config.remote_api;
+        let port_changed = old_data.config.remote_api_port != data.config.remote_api_port
+            || old_data.config.remote_api_token != data.config.remote_api_token;
+
+        if was_enabled && (!is_enabled || port_changed) {
+            self.stop_server();
+        }
+        if is_enabled && (!was_enabled || port_changed) {
+            self.start_server(
+                data.config.remote_api_port,
+                data.config.remote_api_token.clone(),
+                ctx.get_external_handle(),
+            );
+        }
+        if !old_data.playback.same(&data.playback) || old_data.config.volume != data.config.volume {
+            self.update_status(data);
+        }
+        child.update(ctx, old_data, data, env);
+    }
+}
+
+/// Name of the header the clients have to send the token in.
+pub const TOKEN_HEADER: &str = "X-Psst-Token";
+
+/// Turns away requests that don't come straight from a local client.  Browsers
+/// always send `Origin` with cross-site requests and can be made to resolve a
+/// foreign `Host` to the loopback (DNS rebinding), neither of which is the case
+/// for scripts talking to us directly.
+fn authorize(request: &Request, port: u16, token: &str) -> Result<(), &'static str> {
+    let header = |name: &'static str| {
+        request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv(name))
+            .map(|header| header.value.as_str())
+    };
+    if header("Origin").is_some() {
+        return Err("cross-origin requests are not allowed");
+    }
+    let host_allowed = header("Host").map_or(false, |host| {
+        host == format!("127.0.0.1:{}", port) || host == format!("localhost:{}", port)
+    });
+    if !host_allowed {
+        return Err("unexpected host");
+    }
+    if header(TOKEN_HEADER) != Some(token) {
+        return Err("missing or invalid token");
+    }
+    Ok(())
+}
+
+fn status(data: &State) -> Value {
+    let playback = &data.playback;
+    let track = playback.now_playing.as_ref().map(|now_playing| {
+        let track = &now_playing.item;
+        json!({
+            "id": track.id.to_base62(),
+            "name": track.name,
+            "artist": track.artist_name(),
+            "album": track.album_name(),
+            "duration_ms": track.duration.as_millis() as u64,
+            "progress_ms": now_playing.progress.as_millis() as u64,
+        })
+    });
+    json!({
+        "state": match playback.state {
+            PlaybackState::Loading => "loading",
+            PlaybackState::Playing => "playing",
+            PlaybackState::Paused => "paused",
+            PlaybackState::Stopped => "stopped",
+        },
+        "track": track,
+        "shuffle": playback.queue_behavior == QueueBehavior::Random,
+        "repeat": match playback.repeat_mode {
+            RepeatMode::Off => "off",
+            RepeatMode::Context => "context",
+            RepeatMode::Track => "track",
+        },
+        "volume": (data.config.volume * 100.0).round() as u32,
+    })
+}
+
+fn submit_response(result: Result<(), ExtEventError>) -> Response<Cursor<Vec<u8>>> {
+    match result {
+        Ok(_) => json_response(200, json!({ "ok": true })),
+        Err(_) => json_response(503, json!({ "error": "psst is shutting down" })),
+    }
+}
+
+fn json_response(status_code: u16, body: Value) -> Response<Cursor<Vec<u8>>> {
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+    Response::from_string(body.to_string())
+        .with_status_code(status_code)
+        .with_header(content_type)
+}
@@ -1,4 +1,11 @@
-use std::thread::{self, JoinHandle};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
 
 use druid::{
     widget::{prelude::*, Controller},
@@ -8,13 +15,24 @@ use psst_core::session::{SessionConfig, SessionHandle};
 
 use crate::{cmd, data::State};
 
+// Lost connections are retried after a delay, doubled after every failed
+// attempt up to the maximum.
+const RECONNECT_MIN_DELAY: Duration = Duration::from_secs(2);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(60);
+
 pub struct SessionController {
     thread: Option<JoinHandle<()>>,
+    /// Incremented with every new connection thread, so the previous ones
+    /// know to stop reconnecting.
+    generation: Arc<AtomicUsize>,
 }
 
 impl SessionController {
     pub fn new() -> Self {
-        Self { thread: None }
+        Self {
+            thread: None,
+            generation: Arc::new(AtomicUsize::new(0)),
+        }
     }
 
     fn start_connection_thread(
@@ -23,30 +41,52 @@ impl SessionController {
         config: SessionConfig,
         event_sink: ExtEventSink,
     ) {
+        let generation = self.generation.clone();
+        let current = generation.fetch_add(1, Ordering::SeqCst) + 1;
         self.thread.replace(thread::spawn(move || {
-            Self::connect_and_service(handle, config, event_sink);
+            let is_current = || generation.load(Ordering::SeqCst) == current;
+            Self::connect_and_service(handle, config, event_sink, is_current);
         }));
     }
 
-    fn connect_and_service(handle: SessionHandle, config: SessionConfig, event_sink: ExtEventSink) {
-        let try_connect_and_service = || {
-            let session = handle.connect(config)?;
+    fn connect_and_service(
+        handle: SessionHandle,
+        config: SessionConfig,
+        event_sink: ExtEventSink,
+        is_current: impl Fn() -> bool,
+    ) {
+        let mut delay = RECONNECT_MIN_DELAY;
+        loop {
+            let mut try_connect_and_service = || {
+                let session = handle.connect(config.clone())?;
+                event_sink
+                    .submit_command(cmd::SESSION_CONNECTED, (), Target::Auto)
+                    .unwrap();
+                delay = RECONNECT_MIN_DELAY;
+                session.service()
+            };
+            match try_connect_and_service() {
+                Ok(_) => {
+                    log::info!("connection shutdown");
+                }
+                Err(err) => {
+                    log::error!("connection error: {:?}", err);
+                }
+            };
+            // A newer connection has replaced this one.
+            if !is_current() {
+                break;
+            }
             event_sink
-                .submit_command(cmd::SESSION_CONNECTED, (), Target::Auto)
+                .submit_command(cmd::SESSION_DISCONNECTED, (), Target::Auto)
                 .unwrap();
-            session.service()
-        };
-        match try_connect_and_service() {
-            Ok(_) => {
-                log::info!("connection shutdown");
-            }
-            Err(err) => {
-                log::error!("connection error: {:?}", err);
+            log::info!("reconnecting in {:?}", delay);
+            thread::sleep(delay);
+            delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+            if !is_current() {
+                break;
             }
-        };
-        event_sink
-            .submit_command(cmd::SESSION_DISCONNECTED, (), Target::Auto)
-            .unwrap();
+        }
     }
 }
 
@@ -0,0 +1,337 @@
+//! Playback progress on the taskbar button and previous/play-pause/next
+//! buttons in the thumbnail toolbar, through `ITaskbarList3`.  Clicks on the
+//! toolbar buttons arrive as `WM_COMMAND` messages to the main window, so we
+//! subclass it to catch them before druid does.
+
+use std::{mem, ptr, time::Duration};
+
+use druid::{ExtEventSink, Selector, WidgetId};
+use winapi::{
+    shared::{
+        basetsd::{DWORD_PTR, UINT_PTR},
+        minwindef::{HIWORD, LOWORD, LPARAM, LRESULT, UINT, WPARAM},
+        windef::{HICON, HWND},
+        winerror::SUCCEEDED,
+        wtypesbase::CLSCTX_INPROC_SERVER,
+    },
+    um::{
+        combaseapi::{CoCreateInstance, CoInitializeEx},
+        commctrl::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass},
+        objbase::COINIT_APARTMENTTHREADED,
+        shobjidl_core::{
+            CLSID_TaskbarList, ITaskbarList3, TBPF_NOPROGRESS, TBPF_NORMAL, TBPF_PAUSED,
+            THBF_ENABLED, THB_FLAGS, THB_ICON, THB_TOOLTIP, THUMBBUTTON,
+        },
+        wingdi::{CreateBitmap, DeleteObject},
+        winuser::{
+            CreateIconIndirect, DestroyIcon, RegisterWindowMessageW, ICONINFO, WM_COMMAND,
+            WM_NCDESTROY,
+        },
+    },
+    Interface,
+};
+
+use crate::cmd;
+
+// Notification code of a clicked thumbnail toolbar button, `THBN_CLICKED`.
+const THBN_CLICKED: u16 = 0x1800;
+
+const SUBCLASS_ID: UINT_PTR = 0x7073_7374;
+
+const BUTTON_PREVIOUS: UINT = 0;
+const BUTTON_PLAY_PAUSE: UINT = 1;
+const BUTTON_NEXT: UINT = 2;
+
+const ICON_SIZE: usize = 16;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TaskbarState {
+    Stopped,
+    Playing,
+    Paused,
+}
+
+pub struct Taskbar {
+    hwnd: HWND,
+    taskbar: *mut ITaskbarList3,
+    icons: Icons,
+    state: TaskbarState,
+    buttons_added: bool,
+    subclass_data: *mut SubclassData,
+    // Message sent to the window once its taskbar button exists.  Toolbar
+    // buttons can't be added before that.
+    button_created_msg: UINT,
+}
+
+struct Icons {
+    previous: HICON,
+    play: HICON,
+    pause: HICON,
+    next: HICON,
+}
+
+struct SubclassData {
+    event_sink: ExtEventSink,
+    widget_id: WidgetId,
+    taskbar: *mut Taskbar,
+    button_created_msg: UINT,
+}
+
+impl Taskbar {
+    /// Attach to the taskbar button of `hwnd`.  Clicks on the toolbar buttons
+    /// are submitted as playback commands to `widget_id`.
+    pub fn attach(
+        hwnd: HWND,
+        event_sink: ExtEventSink,
+        widget_id: WidgetId,
+    ) -> Result<Box<Self>, String> {
+        unsafe {
+            // Fails harmlessly if COM is already initialized on this thread.
+            CoInitializeEx(ptr::null_mut(), COINIT_APARTMENTTHREADED);
+
+            let mut taskbar: *mut ITaskbarList3 = ptr::null_mut();
+            let hr = CoCreateInstance(
+                &CLSID_TaskbarList,
+                ptr::null_mut(),
+                CLSCTX_INPROC_SERVER,
+                &ITaskbarList3::uuidof(),
+                &mut taskbar as *mut _ as *mut _,
+            );
+            if !SUCCEEDED(hr) {
+                return Err(format!("failed to create taskbar list: {:#x}", hr));
+            }
+            let hr = (*taskbar).HrInit();
+            if !SUCCEEDED(hr) {
+                (*taskbar).Release();
+                return Err(format!("failed to initialize taskbar list: {:#x}", hr));
+            }
+
+            let button_created_msg = RegisterWindowMessageW(wide("TaskbarButtonCreated").as_ptr());
+            let mut this = Box::new(Self {
+                hwnd,
+                taskbar,
+                icons: Icons::new(),
+                state: TaskbarState::Stopped,
+                buttons_added: false,
+                subclass_data: ptr::null_mut(),
+                button_created_msg,
+            });
+            this.subclass_data = Box::into_raw(Box::new(SubclassData {
+                event_sink,
+                widget_id,
+                taskbar: &mut *this,
+                button_created_msg,
+            }));
+            SetWindowSubclass(
+                hwnd,
+                Some(subclass_proc),
+                SUBCLASS_ID,
+                this.subclass_data as DWORD_PTR,
+            );
+            // The button might already exist if the window is visible.
+            this.add_buttons();
+            Ok(this)
+        }
+    }
+
+    pub fn set_state(&mut self, state: TaskbarState) {
+        if self.state == state {
+            return;
+        }
+        self.state = state;
+        let flag = match state {
+            TaskbarState::Stopped => TBPF_NOPROGRESS,
+            TaskbarState::Playing => TBPF_NORMAL,
+            TaskbarState::Paused => TBPF_PAUSED,
+        };
+        unsafe {
+            (*self.taskbar).SetProgressState(self.hwnd, flag);
+        }
+        self.update_buttons();
+    }
+
+    pub fn set_progress(&mut self, progress: Duration, duration: Duration) {
+        if self.state == TaskbarState::Stopped {
+            return;
+        }
+        unsafe {
+            (*self.taskbar).SetProgressValue(
+                self.hwnd,
+                progress.as_millis() as u64,
+                duration.as_millis().max(1) as u64,
+            );
+        }
+    }
+
+    fn add_buttons(&mut self) {
+        let mut buttons = self.buttons();
+        let hr = unsafe {
+            (*self.taskbar).ThumbBarAddButtons(
+                self.hwnd,
+                buttons.len() as UINT,
+                buttons.as_mut_ptr(),
+            )
+        };
+        self.buttons_added = SUCCEEDED(hr);
+    }
+
+    fn update_buttons(&mut self) {
+        if !self.buttons_added {
+            return;
+        }
+        let mut buttons = self.buttons();
+        unsafe {
+            (*self.taskbar).ThumbBarUpdateButtons(
+                self.hwnd,
+                buttons.len() as UINT,
+                buttons.as_mut_ptr(),
+            );
+        }
+    }
+
+    fn buttons(&self) -> [THUMBBUTTON; 3] {
+        let (play_pause_icon, play_pause_tip) = match self.state {
+            TaskbarState::Playing => (self.icons.pause, "Pause"),
+            TaskbarState::Paused | TaskbarState::Stopped => (self.icons.play, "Play"),
+        };
+        [
+            button(BUTTON_PREVIOUS, self.icons.previous, "Previous"),
+            button(BUTTON_PLAY_PAUSE, play_pause_icon, play_pause_tip),
+            button(BUTTON_NEXT, self.icons.next, "Next"),
+        ]
+    }
+}
+
+impl Drop for Taskbar {
+    fn drop(&mut self) {
+        unsafe {
+            RemoveWindowSubclass(self.hwnd, Some(subclass_proc), SUBCLASS_ID);
+            drop(Box::from_raw(self.subclass_data));
+            (*self.taskbar).Release();
+        }
+    }
+}
+
+impl Icons {
+    fn new() -> Self {
+        // Right-pointing triangle, 6 pixels wide, starting at column `from`.
+        let triangle = |x: i32, y: i32, from: i32| {
+            let dx = x - from;
+            let dy = (y - 8).abs();
+            (0..6).contains(&dx) && dy * 6 <= (6 - dx) * 5
+        };
+        Self {
+            previous: glyph_icon(|x, y| (3..5).contains(&x) || triangle(15 - x, y, 3)),
+            play: glyph_icon(|x, y| triangle(x, y, 5)),
+            pause: glyph_icon(|x, _| (4..7).contains(&x) || (9..12).contains(&x)),
+            next: glyph_icon(|x, y| triangle(x, y, 5) || (11..13).contains(&x)),
+        }
+    }
+}
+
+impl Drop for Icons {
+    fn drop(&mut self) {
+        unsafe {
+            DestroyIcon(self.previous);
+            DestroyIcon(self.play);
+            DestroyIcon(self.pause);
+            DestroyIcon(self.next);
+        }
+    }
+}
+
+unsafe extern "system" fn subclass_proc(
+    hwnd: HWND,
+    msg: UINT,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    _id: UINT_PTR,
+    ref_data: DWORD_PTR,
+) -> LRESULT {
+    let data = ref_data as *mut SubclassData;
+    match msg {
+        WM_COMMAND if HIWORD(wparam as u32) == THBN_CLICKED => {
+            let command: Selector = match LOWORD(wparam as u32) as UINT {
+                BUTTON_PREVIOUS => cmd::PLAY_PREVIOUS,
+                BUTTON_PLAY_PAUSE => cmd::PLAY_PAUSE_OR_RESUME,
+                BUTTON_NEXT => cmd::PLAY_NEXT,
+                _ => return DefSubclassProc(hwnd, msg, wparam, lparam),
+            };
+            let data = &*data;
+            data.event_sink
+                .submit_command(command, (), data.widget_id)
+                .ok();
+            0
+        }
+        WM_NCDESTROY => {
+            // The data is owned by `Taskbar`, just unhook from the window.
+            RemoveWindowSubclass(hwnd, Some(subclass_proc), SUBCLASS_ID);
+            DefSubclassProc(hwnd, msg, wparam, lparam)
+        }
+        _ if msg == (*data).button_created_msg => {
+            let taskbar = &mut *(*data).taskbar;
+            if !taskbar.buttons_added {
+                taskbar.add_buttons();
+            }
+            DefSubclassProc(hwnd, msg, wparam, lparam)
+        }
+        _ => DefSubclassProc(hwnd, msg, wparam, lparam),
+    }
+}
+
+fn button(id: UINT, icon: HICON, tip: &str) -> THUMBBUTTON {
+    let mut button: THUMBBUTTON = unsafe { mem::zeroed() };
+    button.dwMask = THB_ICON | THB_TOOLTIP | THB_FLAGS;
+    button.iId = id;
+    button.hIcon = icon;
+    button.dwFlags = THBF_ENABLED;
+    for (dst, src) in button.szTip.iter_mut().zip(wide(tip)) {
+        *dst = src;
+    }
+    button
+}
+
+/// Render a white 16x16 icon, with pixels set where `is_set` returns true.
+fn glyph_icon(is_set: impl Fn(i32, i32) -> bool) -> HICON {
+    let mut pixels = [0_u32; ICON_SIZE * ICON_SIZE];
+    for y in 0..ICON_SIZE {
+        for x in 0..ICON_SIZE {
+            // Keep a margin at the top and the bottom.
+            if (3..ICON_SIZE - 2).contains(&y) && is_set(x as i32, y as i32) {
+                pixels[y * ICON_SIZE + x] = 0xffff_ffff;
+            }
+        }
+    }
+    let mask = [0_u8; ICON_SIZE * ICON_SIZE / 8];
+    unsafe {
+        let color = CreateBitmap(
+            ICON_SIZE as i32,
+            ICON_SIZE as i32,
+            1,
+            32,
+            pixels.as_ptr() as *const _,
+        );
+        let mask = CreateBitmap(
+            ICON_SIZE as i32,
+            ICON_SIZE as i32,
+            1,
+            1,
+            mask.as_ptr() as *const _,
+        );
+        let mut info = ICONINFO {
+            fIcon: 1,
+            xHotspot: 0,
+            yHotspot: 0,
+            hbmMask: mask,
+            hbmColor: color,
+        };
+        let icon = CreateIconIndirect(&mut info);
+        DeleteObject(color as *mut _);
+        DeleteObject(mask as *mut _);
+        icon
+    }
+}
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(Some(0)).collect()
+}
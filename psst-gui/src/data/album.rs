@@ -2,7 +2,7 @@ use crate::data::{ArtistLink, Cached, Image, Promise, Track};
 use chrono::NaiveDate;
 use druid::{im::Vector, Data, Lens};
 use itertools::Itertools;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 #[derive(Clone, Data, Lens)]
@@ -58,29 +58,38 @@ impl Album {
     }
 
     pub fn image(&self, width: f64, height: f64) -> Option<&Image> {
-        self.images
-            .iter()
-            .rev()
-            .find(|img| !img.fits(width, height))
-            .or_else(|| self.images.back())
+        Image::select(&self.images, width, height)
     }
 
     pub fn url(&self) -> String {
         format!("https://open.spotify.com/album/{id}", id = self.id)
     }
 
+    pub fn uri(&self) -> String {
+        format!("spotify:album:{id}", id = self.id)
+    }
+
     pub fn link(&self) -> AlbumLink {
         AlbumLink {
             id: self.id.clone(),
             name: self.name.clone(),
+            images: self.images.clone(),
         }
     }
 }
 
-#[derive(Clone, Debug, Data, Lens, Eq, PartialEq, Hash, Deserialize)]
+#[derive(Clone, Debug, Data, Lens, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct AlbumLink {
     pub id: Arc<str>,
     pub name: Arc<str>,
+    #[serde(default)]
+    pub images: Vector<Image>,
+}
+
+impl AlbumLink {
+    pub fn image(&self, width: f64, height: f64) -> Option<&Image> {
+        Image::select(&self.images, width, height)
+    }
 }
 
 #[derive(Clone, Debug, Data, Eq, PartialEq, Hash, Deserialize)]
@@ -113,6 +122,21 @@ pub struct Copyright {
     pub kind: CopyrightType,
 }
 
+impl Copyright {
+    /// Text of the notice, starting with the symbol of its type.
+    pub fn notice(&self) -> String {
+        let (symbol, abbreviation) = match self.kind {
+            CopyrightType::Copyright => ("©", "(C)"),
+            CopyrightType::Performance => ("℗", "(P)"),
+        };
+        if self.text.starts_with(symbol) || self.text.starts_with(abbreviation) {
+            self.text.to_string()
+        } else {
+            format!("{} {}", symbol, self.text)
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Data, Deserialize)]
 pub enum CopyrightType {
     #[serde(rename = "C")]
@@ -1,6 +1,6 @@
 use crate::data::{Album, Cached, Image, Promise, Track};
 use druid::{im::Vector, Data, Lens};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 #[derive(Clone, Data, Lens)]
@@ -9,6 +9,7 @@ pub struct ArtistDetail {
     pub albums: Promise<ArtistAlbums, ArtistLink>,
     pub top_tracks: Promise<ArtistTracks, ArtistLink>,
     pub related_artists: Promise<Cached<Vector<Artist>>, ArtistLink>,
+    pub info: Promise<ArtistInfo, ArtistLink>,
 }
 
 #[derive(Clone, Data, Lens, Deserialize)]
@@ -20,11 +21,7 @@ pub struct Artist {
 
 impl Artist {
     pub fn image(&self, width: f64, height: f64) -> Option<&Image> {
-        self.images
-            .iter()
-            .rev()
-            .find(|img| !img.fits(width, height))
-            .or_else(|| self.images.back())
+        Image::select(&self.images, width, height)
     }
 
     pub fn link(&self) -> ArtistLink {
@@ -59,8 +56,28 @@ impl ArtistTracks {
     }
 }
 
-#[derive(Clone, Debug, Data, Lens, Eq, PartialEq, Hash, Deserialize)]
+/// Biography and listener statistics, shown in the "About" section of the
+/// artist page.
+#[derive(Clone, Data, Lens)]
+pub struct ArtistInfo {
+    /// Plain text of the biography, empty if the artist doesn't have one.
+    pub biography: Arc<str>,
+    pub monthly_listeners: Option<u64>,
+    pub followers: Option<u64>,
+}
+
+#[derive(Clone, Debug, Data, Lens, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct ArtistLink {
     pub id: Arc<str>,
     pub name: Arc<str>,
 }
+
+impl ArtistLink {
+    pub fn url(&self) -> String {
+        format!("https://open.spotify.com/artist/{id}", id = self.id)
+    }
+
+    pub fn uri(&self) -> String {
+        format!("spotify:artist:{id}", id = self.id)
+    }
+}
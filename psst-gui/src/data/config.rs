@@ -1,38 +1,93 @@
-use druid::{Data, Lens};
+use druid::{im::Vector, Data, Lens};
 use env::VarError;
 use platform_dirs::AppDirs;
 use psst_core::{
+    audio_equalizer::{EQUALIZER_BANDS, EQUALIZER_FREQUENCIES},
+    audio_output::AudioOutput,
     audio_player::PlaybackConfig,
-    cache::mkdir_if_not_exists,
+    cache::{mkdir_if_not_exists, Cache},
+    connect::ConnectConfig,
     connection::Credentials,
     session::{Session, SessionConfig},
 };
+use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::{env, fs::File, path::PathBuf};
+use std::{collections::HashMap, env, fs::File, path::PathBuf, sync::Arc, time::Duration};
 
-use super::Promise;
+use super::{Promise, TrackColumn, TrackView};
+use crate::webapi::WebApi;
 
 #[derive(Clone, Debug, Data, Lens)]
 pub struct Preferences {
     pub active: PreferencesTab,
-    pub cache_size: Promise<u64, (), ()>,
+    pub cache_usage: Promise<CacheUsage, (), ()>,
+    pub output_devices: Promise<Vector<String>, (), ()>,
     pub auth: Authentication,
 }
 
 impl Preferences {
     pub fn reset(&mut self) {
-        self.cache_size.clear();
+        self.cache_usage.clear();
+        self.output_devices.clear();
         self.auth.result.clear();
     }
 
-    pub fn measure_cache_usage() -> Option<u64> {
-        Config::cache_dir().and_then(|path| fs_extra::dir::get_size(&path).ok())
+    pub fn measure_cache_usage() -> Option<CacheUsage> {
+        let cache_dir = Config::cache_dir()?;
+        let total = fs_extra::dir::get_size(&cache_dir).ok()?;
+        let mut buckets = Vector::new();
+        buckets.push_back(CacheBucket {
+            id: AUDIO_CACHE_BUCKET.into(),
+            name: "Audio".into(),
+            size: fs_extra::dir::get_size(cache_dir.join("audio")).unwrap_or(0),
+        });
+        buckets.append(WebApi::global().cache_usage());
+        Some(CacheUsage { total, buckets })
+    }
+
+    pub fn clear_cache(bucket: &str) {
+        if bucket == AUDIO_CACHE_BUCKET {
+            Self::clear_audio_cache();
+        } else {
+            WebApi::global().clear_cache(bucket);
+        }
+    }
+
+    pub fn clear_audio_cache() {
+        if let Some(cache_dir) = Config::cache_dir() {
+            if let Err(err) = Cache::new(cache_dir).and_then(|cache| cache.clear_audio_files()) {
+                log::error!("failed to clear audio cache: {}", err);
+            }
+        }
+    }
+
+    pub fn list_output_devices() -> Option<Vector<String>> {
+        AudioOutput::devices()
+            .map_err(|err| log::error!("failed to list audio devices: {}", err))
+            .ok()
+            .map(Vector::from)
     }
 }
 
+const AUDIO_CACHE_BUCKET: &str = "audio";
+
+#[derive(Clone, Debug, Data, Lens)]
+pub struct CacheUsage {
+    pub total: u64,
+    pub buckets: Vector<CacheBucket>,
+}
+
+#[derive(Clone, Debug, Data, Lens)]
+pub struct CacheBucket {
+    pub id: Arc<str>,
+    pub name: Arc<str>,
+    pub size: u64,
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Data)]
 pub enum PreferencesTab {
     General,
+    Playback,
     Cache,
 }
 
@@ -66,17 +121,96 @@ impl Authentication {
 const APP_NAME: &str = "Psst";
 const CONFIG_FILENAME: &str = "config.json";
 const PROXY_ENV_VAR: &str = "SOCKS_PROXY";
+const DISCORD_CLIENT_ID_ENV_VAR: &str = "PSST_DISCORD_CLIENT_ID";
 
-#[derive(Clone, Debug, Default, Data, Lens, Serialize, Deserialize)]
+#[derive(Clone, Debug, Data, Lens, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Config {
     #[data(ignore)]
     credentials: Option<Credentials>,
     pub audio_quality: AudioQuality,
+    pub data_saver: bool,
+    pub data_saver_on_metered: bool,
+    /// True while the system reports the network connection as metered.
+    #[serde(skip)]
+    pub metered: bool,
+    pub audio_device: Option<String>,
+    pub crossfade_secs: f64,
+    pub fade_millis: u64,
+    pub seek_step_secs: u64,
+    pub autoplay: bool,
+    pub skip_explicit: bool,
+    pub audio_cache_limit_gb: Option<u64>,
+    pub image_cache_limit_mb: Option<u64>,
+    /// Multiplier of the artwork resolution, for HiDPI displays.
+    pub image_scale: f64,
+    pub normalization: Normalization,
+    pub pregain_db: f64,
+    pub equalizer: Equalizer,
+    pub mono_audio: bool,
+    pub volume: f64,
+    pub discord_presence: bool,
+    pub connect_device: bool,
+    pub remote_api: bool,
+    pub remote_api_port: u16,
+    /// Secret the remote API clients have to present, generated per install.
+    pub remote_api_token: String,
+    pub hotkeys: Hotkeys,
+    pub keymap: Keymap,
+    pub window_title: String,
     pub theme: Theme,
+    /// Columns of the track lists, for the views the user changed them in.
+    #[data(same_fn = "PartialEq::eq")]
+    pub track_columns: HashMap<TrackView, Vec<TrackColumn>>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            credentials: None,
+            audio_quality: AudioQuality::default(),
+            data_saver: false,
+            data_saver_on_metered: true,
+            metered: false,
+            audio_device: None,
+            crossfade_secs: 0.0,
+            fade_millis: PlaybackConfig::default().fade.as_millis() as u64,
+            seek_step_secs: 10,
+            autoplay: false,
+            skip_explicit: false,
+            audio_cache_limit_gb: Some(5),
+            image_cache_limit_mb: Some(250),
+            image_scale: 1.0,
+            normalization: Normalization::default(),
+            pregain_db: PlaybackConfig::default().pregain.into(),
+            equalizer: Equalizer::default(),
+            mono_audio: false,
+            volume: PlaybackConfig::default().volume.into(),
+            discord_presence: false,
+            connect_device: false,
+            remote_api: false,
+            remote_api_port: 8844,
+            remote_api_token: generate_token(),
+            hotkeys: Hotkeys::default(),
+            keymap: Keymap::default(),
+            window_title: "{artist} – {title}".to_string(),
+            theme: Theme::default(),
+            track_columns: HashMap::new(),
+        }
+    }
+}
+
+/// Random hex string, for the secrets we hand out to local clients.
+fn generate_token() -> String {
+    let mut rng = rand::thread_rng();
+    (0..16)
+        .map(|_| format!("{:02x}", rng.gen::<u8>()))
+        .collect()
 }
 
 impl Config {
+    pub const MAX_CROSSFADE_SECS: f64 = 12.0;
+
     fn app_dirs() -> Option<AppDirs> {
         const USE_XDG_ON_MACOS: bool = false;
 
@@ -128,13 +262,51 @@ impl Config {
         }
     }
 
+    pub fn connect(&self) -> ConnectConfig {
+        ConnectConfig {
+            device_name: "Psst".to_string(),
+            proxy_url: Config::proxy(),
+        }
+    }
+
     pub fn playback(&self) -> PlaybackConfig {
         PlaybackConfig {
-            bitrate: self.audio_quality.as_bitrate(),
-            ..PlaybackConfig::default()
+            bitrate: self.effective_audio_quality().as_bitrate(),
+            pregain: self.pregain_db.round() as f32,
+            // The config file can hold anything, `max` also turns NaN into 0.
+            crossfade: Duration::from_secs_f64(
+                self.crossfade_secs
+                    .max(0.0)
+                    .min(Self::MAX_CROSSFADE_SECS)
+                    .round(),
+            ),
+            fade: Duration::from_millis(self.fade_millis),
+            equalizer: self.equalizer.gains_db(),
+            mono: self.mono_audio,
+            volume: self.volume as f32,
+        }
+    }
+
+    pub fn effective_audio_quality(&self) -> AudioQuality {
+        if self.data_saver || (self.data_saver_on_metered && self.metered) {
+            AudioQuality::Low
+        } else {
+            self.audio_quality
         }
     }
 
+    pub fn audio_cache_limit(&self) -> Option<u64> {
+        self.audio_cache_limit_gb.map(|gb| gb * 1_000_000_000)
+    }
+
+    pub fn image_cache_limit(&self) -> Option<u64> {
+        self.image_cache_limit_mb.map(|mb| mb * 1_000_000)
+    }
+
+    pub fn seek_step(&self) -> Duration {
+        Duration::from_secs(self.seek_step_secs)
+    }
+
     pub fn proxy() -> Option<String> {
         env::var(PROXY_ENV_VAR).map_or_else(
             |err| match err {
@@ -147,6 +319,11 @@ impl Config {
             |url| Some(url),
         )
     }
+
+    /// ID of the Discord application the Rich Presence is published under.
+    pub fn discord_client_id() -> Option<String> {
+        env::var(DISCORD_CLIENT_ID_ENV_VAR).ok()
+    }
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Data, Serialize, Deserialize)]
@@ -172,6 +349,205 @@ impl Default for AudioQuality {
     }
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Data, Serialize, Deserialize)]
+pub enum Normalization {
+    Off,
+    Automatic,
+    Track,
+    Album,
+}
+
+impl Default for Normalization {
+    fn default() -> Self {
+        Self::Automatic
+    }
+}
+
+/// System-wide shortcuts, in the `CTRL+ALT+P` notation.  Empty ones are not
+/// registered.
+#[derive(Clone, Debug, Data, Lens, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hotkeys {
+    pub enabled: bool,
+    pub play_pause: String,
+    pub next: String,
+    pub previous: String,
+    pub like: String,
+}
+
+impl Default for Hotkeys {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            play_pause: "CTRL+ALT+P".to_string(),
+            next: "CTRL+ALT+N".to_string(),
+            previous: "CTRL+ALT+B".to_string(),
+            like: "CTRL+ALT+L".to_string(),
+        }
+    }
+}
+
+/// Shortcuts of the app window, in the same notation as the global hotkeys,
+/// with spaces between the keys of a sequence, like `g g`.
+#[derive(Clone, Debug, Default, Data, Lens, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keymap {
+    pub preset: KeymapPreset,
+    /// Bindings replacing the ones of the preset, empty ones unbind the
+    /// action.
+    #[data(same_fn = "PartialEq::eq")]
+    #[lens(ignore)]
+    pub bindings: HashMap<KeyAction, String>,
+}
+
+impl Keymap {
+    /// Bindings of the preset, with the configured ones in place of them.
+    pub fn bindings(&self) -> Vec<(KeyAction, String)> {
+        let mut bindings: Vec<(KeyAction, String)> = self
+            .preset
+            .bindings()
+            .iter()
+            .filter(|(action, _)| !self.bindings.contains_key(action))
+            .map(|&(action, keys)| (action, keys.to_string()))
+            .collect();
+        bindings.extend(
+            self.bindings
+                .iter()
+                .filter(|(_, keys)| !keys.trim().is_empty())
+                .map(|(&action, keys)| (action, keys.to_owned())),
+        );
+        bindings
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Data, Serialize, Deserialize)]
+pub enum KeymapPreset {
+    Default,
+    Vim,
+}
+
+impl KeymapPreset {
+    fn bindings(self) -> &'static [(KeyAction, &'static str)] {
+        match self {
+            Self::Default => &[
+                (KeyAction::PlayPause, "SPACE"),
+                (KeyAction::SeekForward, "ArrowRight"),
+                (KeyAction::SeekBackward, "ArrowLeft"),
+                (KeyAction::CommandPalette, "CMD+k"),
+            ],
+            Self::Vim => &[
+                (KeyAction::PlayPause, "SPACE"),
+                (KeyAction::SeekForward, "l"),
+                (KeyAction::SeekBackward, "h"),
+                (KeyAction::Next, "n"),
+                (KeyAction::Previous, "N"),
+                (KeyAction::CursorDown, "j"),
+                (KeyAction::CursorUp, "k"),
+                (KeyAction::CursorFirst, "g g"),
+                (KeyAction::CursorLast, "G"),
+                (KeyAction::PlayCursor, "o"),
+                (KeyAction::CursorMenu, "m"),
+                (KeyAction::Search, "/"),
+                (KeyAction::GoBack, "CTRL+o"),
+                (KeyAction::Refresh, "CTRL+r"),
+                (KeyAction::CommandPalette, "CMD+k"),
+            ],
+        }
+    }
+}
+
+impl Default for KeymapPreset {
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Data, Serialize, Deserialize)]
+pub enum KeyAction {
+    PlayPause,
+    Next,
+    Previous,
+    SeekForward,
+    SeekBackward,
+    ToggleSave,
+    Search,
+    GoBack,
+    Refresh,
+    CursorDown,
+    CursorUp,
+    CursorFirst,
+    CursorLast,
+    PlayCursor,
+    CursorMenu,
+    CommandPalette,
+}
+
+#[derive(Clone, Debug, Default, Data, Lens, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Equalizer {
+    pub preset: EqualizerPreset,
+    pub gains: [f64; EQUALIZER_BANDS],
+}
+
+impl Equalizer {
+    pub fn frequencies() -> impl Iterator<Item = f32> {
+        EQUALIZER_FREQUENCIES.iter().copied()
+    }
+
+    pub fn set_preset(&mut self, preset: EqualizerPreset) {
+        if let Some(gains) = preset.gains() {
+            self.gains = gains;
+        }
+        self.preset = preset;
+    }
+
+    pub fn set_gain(&mut self, band: usize, gain: f64) {
+        self.gains[band] = gain;
+        self.preset = EqualizerPreset::Custom;
+    }
+
+    fn gains_db(&self) -> [f32; EQUALIZER_BANDS] {
+        let mut gains = [0.0; EQUALIZER_BANDS];
+        for (gain, &db) in gains.iter_mut().zip(self.gains.iter()) {
+            *gain = db.round() as f32;
+        }
+        gains
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Data, Serialize, Deserialize)]
+pub enum EqualizerPreset {
+    Flat,
+    BassBoost,
+    TrebleBoost,
+    Vocal,
+    Rock,
+    Pop,
+    Electronic,
+    Custom,
+}
+
+impl EqualizerPreset {
+    fn gains(self) -> Option<[f64; EQUALIZER_BANDS]> {
+        match self {
+            Self::Flat => Some([0.0; EQUALIZER_BANDS]),
+            Self::BassBoost => Some([6.0, 5.0, 4.0, 2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+            Self::TrebleBoost => Some([0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 4.0, 5.0, 6.0]),
+            Self::Vocal => Some([-2.0, -2.0, -1.0, 0.0, 2.0, 4.0, 4.0, 2.0, 0.0, -1.0]),
+            Self::Rock => Some([4.0, 3.0, 2.0, 0.0, -1.0, -1.0, 0.0, 2.0, 3.0, 4.0]),
+            Self::Pop => Some([-1.0, 0.0, 2.0, 3.0, 4.0, 3.0, 2.0, 0.0, -1.0, -1.0]),
+            Self::Electronic => Some([4.0, 3.0, 1.0, 0.0, -2.0, 1.0, 0.0, 1.0, 3.0, 4.0]),
+            Self::Custom => None,
+        }
+    }
+}
+
+impl Default for EqualizerPreset {
+    fn default() -> Self {
+        Self::Flat
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Data, Serialize, Deserialize)]
 pub enum Theme {
     Light,
@@ -0,0 +1,94 @@
+use crate::data::{Promise, Track};
+use druid::{im::Vector, Data, Lens};
+use psst_core::audio_cast::Renderer;
+use serde::Deserialize;
+use std::{sync::Arc, time::Duration};
+
+/// Remote control of other Spotify Connect devices.
+#[derive(Clone, Debug, Data, Lens)]
+pub struct Connect {
+    pub devices: Promise<Vector<Device>>,
+    /// Device we are acting as a remote for, if any.
+    pub active_device: Option<Arc<str>>,
+    pub remote: Option<RemotePlayback>,
+    /// Volume of the remote device, in the `0.0..=1.0` range.
+    pub volume: f64,
+    /// DLNA renderers found on the local network.
+    pub renderers: Promise<Vector<CastRenderer>>,
+    /// Renderer the local playback is cast to, if any.
+    pub cast_renderer: Option<CastRenderer>,
+}
+
+impl Connect {
+    pub fn is_remote(&self) -> bool {
+        self.active_device.is_some()
+    }
+
+    pub fn set_remote(&mut self, remote: Option<RemotePlayback>) {
+        if let Some(volume) = remote
+            .as_ref()
+            .and_then(|remote| remote.device.volume_percent)
+        {
+            self.volume = f64::from(volume) / 100.0;
+        }
+        self.remote = remote;
+    }
+}
+
+#[derive(Clone, Debug, Data, Lens, Deserialize)]
+pub struct Device {
+    pub id: Option<Arc<str>>,
+    pub name: Arc<str>,
+    #[serde(rename = "type")]
+    pub device_type: Arc<str>,
+    pub is_active: bool,
+    pub is_restricted: bool,
+    pub volume_percent: Option<u32>,
+}
+
+#[derive(Clone, Debug, Data, Lens, PartialEq)]
+pub struct CastRenderer {
+    pub name: Arc<str>,
+    pub control_url: Arc<str>,
+}
+
+impl CastRenderer {
+    pub fn renderer(&self) -> Renderer {
+        Renderer {
+            name: self.name.to_string(),
+            control_url: self.control_url.to_string(),
+        }
+    }
+}
+
+impl From<Renderer> for CastRenderer {
+    fn from(renderer: Renderer) -> Self {
+        Self {
+            name: renderer.name.into(),
+            control_url: renderer.control_url.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Data, Lens, Deserialize)]
+pub struct RemotePlayback {
+    pub device: Device,
+    pub is_playing: bool,
+    pub item: Option<Arc<Track>>,
+    progress_ms: Option<u64>,
+}
+
+impl RemotePlayback {
+    pub fn progress(&self) -> Duration {
+        Duration::from_millis(self.progress_ms.unwrap_or_default())
+    }
+}
+
+#[derive(Copy, Clone, Debug, Data, Eq, PartialEq)]
+pub enum RemoteCommand {
+    Play,
+    Pause,
+    Next,
+    Previous,
+    SetVolume(u32),
+}
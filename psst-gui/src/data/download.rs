@@ -0,0 +1,20 @@
+use crate::data::Track;
+use druid::{im::Vector, Data, Lens};
+use std::sync::Arc;
+
+#[derive(Clone, Data, Lens)]
+pub struct Downloads {
+    pub queue: Vector<Download>,
+}
+
+impl Downloads {
+    pub fn current(&self) -> Option<&Download> {
+        self.queue.front()
+    }
+}
+
+#[derive(Clone, Data, Lens)]
+pub struct Download {
+    pub track: Arc<Track>,
+    pub progress: f64,
+}
@@ -0,0 +1,19 @@
+use crate::data::{Album, Playlist, Promise, RecentlyPlayed};
+use druid::{im::Vector, Data, Lens};
+use std::sync::Arc;
+
+/// Shelves of the home page.  Each of them is loaded separately, so a failing
+/// one doesn't take the others down with it.
+#[derive(Clone, Data, Lens)]
+pub struct Home {
+    pub featured_playlists: Promise<FeaturedPlaylists>,
+    pub new_releases: Promise<Vector<Album>>,
+    pub recently_played: Promise<RecentlyPlayed>,
+}
+
+#[derive(Clone, Data, Lens)]
+pub struct FeaturedPlaylists {
+    /// Headline given by Spotify, i.e. "Monday morning music".
+    pub message: Option<Arc<str>>,
+    pub playlists: Vector<Playlist>,
+}
@@ -0,0 +1,33 @@
+use druid::{im::Vector, Data, Lens};
+use serde::{Deserialize, Serialize};
+use std::{sync::Arc, time::Duration};
+
+#[derive(Clone, Debug, Default, Data, Lens, Serialize, Deserialize)]
+pub struct Lyrics {
+    pub lines: Vector<LyricsLine>,
+    /// True if the lines come with their start times.
+    pub synced: bool,
+    /// Name of the service providing the lyrics.
+    pub provider: Option<Arc<str>>,
+}
+
+impl Lyrics {
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Index of the line being sung at `progress`, if the lyrics are synced.
+    pub fn current_line(&self, progress: Duration) -> Option<usize> {
+        if self.synced {
+            self.lines.iter().rposition(|line| line.start <= progress)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, Data, Lens, Serialize, Deserialize)]
+pub struct LyricsLine {
+    pub start: Duration,
+    pub words: Arc<str>,
+}
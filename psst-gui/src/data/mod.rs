@@ -1,63 +1,105 @@
 mod album;
 mod artist;
 mod config;
+mod connect;
 mod ctx;
+mod download;
+mod home;
+mod lyrics;
 mod nav;
+mod palette;
 mod playback;
 mod playlist;
 mod promise;
+mod radio;
 mod search;
+mod show;
+mod top;
 mod track;
 mod user;
 mod utils;
 
 pub use crate::data::{
     album::{Album, AlbumDetail, AlbumLink, AlbumType, Copyright, CopyrightType},
-    artist::{Artist, ArtistAlbums, ArtistDetail, ArtistLink, ArtistTracks},
-    config::{AudioQuality, Authentication, Config, Preferences, PreferencesTab, Theme},
+    artist::{Artist, ArtistAlbums, ArtistDetail, ArtistInfo, ArtistLink, ArtistTracks},
+    config::{
+        AudioQuality, Authentication, CacheBucket, CacheUsage, Config, Equalizer, EqualizerPreset,
+        Hotkeys, KeyAction, Keymap, KeymapPreset, Normalization, Preferences, PreferencesTab,
+        Theme,
+    },
+    connect::{CastRenderer, Connect, Device, RemoteCommand, RemotePlayback},
     ctx::Ctx,
-    nav::Nav,
+    download::{Download, Downloads},
+    home::{FeaturedPlaylists, Home},
+    lyrics::{Lyrics, LyricsLine},
+    nav::{Nav, SpotifyUrl},
+    palette::{Palette, PaletteCommand, PaletteEntry, PaletteRow},
     playback::{
-        NowPlaying, Playback, PlaybackOrigin, PlaybackPayload, PlaybackState, QueueBehavior,
-        QueuedTrack,
+        NowPlaying, PlayContext, Playback, PlaybackOrigin, PlaybackPayload, PlaybackState,
+        PlayedTrack, QueueBehavior, QueueEntry, QueuedTrack, RecentlyPlayed, RecentlyPlayedItem,
+        RepeatMode, SavedPlayback, SeekDirection,
     },
-    playlist::{Playlist, PlaylistDetail, PlaylistLink, PlaylistTracks},
+    playlist::{Playlist, PlaylistDetail, PlaylistLink, PlaylistOwner, PlaylistTracks},
     promise::{Promise, PromiseState},
-    search::{Search, SearchResults},
-    track::{AudioAnalysis, AudioSegment, TimeInterval, Track, TrackId},
+    radio::{RadioDetail, RadioSeed, RadioTracks},
+    search::{Search, SearchResults, SearchSuggestion, SearchTopic, SuggestionRow},
+    show::{Episode, EpisodePositions, ResumePoint, Show, ShowDetail, ShowEpisodes, ShowLink},
+    top::{TopItems, TopRange, TopTracks},
+    track::{
+        AudioAnalysis, AudioFeatures, AudioSegment, CursorMove, LinkedTrack, ShownKey, ShownTracks,
+        TimeInterval, Track, TrackColumn, TrackId, TrackInfo, TrackSelection, TrackSort, TrackView,
+    },
     user::UserProfile,
     utils::{Cached, Image, Page},
 };
+use chrono::{DateTime, Utc};
 use druid::{
-    im::{HashSet, Vector},
+    im::{HashMap, HashSet, Vector},
     Data, Lens,
 };
-use psst_core::session::SessionHandle;
+use psst_core::{audio_spectrum::Spectrum, item_id::ItemIdType, session::SessionHandle};
 use std::{sync::Arc, time::Duration};
 
 #[derive(Clone, Data, Lens)]
 pub struct State {
     #[data(ignore)]
     pub session: SessionHandle,
+    #[data(ignore)]
+    pub spectrum: Spectrum,
 
     pub route: Nav,
     pub history: Vector<Nav>,
     pub config: Config,
     pub preferences: Preferences,
     pub playback: Playback,
+    pub downloads: Downloads,
+    pub connect: Connect,
     pub search: Search,
+    pub palette: Palette,
+    pub home: Home,
     pub album: AlbumDetail,
     pub artist: ArtistDetail,
     pub playlist: PlaylistDetail,
+    pub show: ShowDetail,
+    pub radio: RadioDetail,
+    pub top: TopItems,
+    pub track_info: Promise<TrackInfo, TrackId>,
     pub library: Arc<Library>,
     pub common_ctx: CommonCtx,
     pub user_profile: Promise<UserProfile>,
+    /// True while some of the Web API requests are being retried.
+    pub retrying: bool,
+    /// True while the session is disconnected.
+    pub offline: bool,
+    /// True while the queue panel is shown next to the page.
+    pub show_queue: bool,
 }
 
 impl Default for State {
     fn default() -> Self {
         Self {
             session: SessionHandle::new(),
+            spectrum: Spectrum::new(),
             route: Nav::Home,
             history: Vector::new(),
             config: Config::default(),
@@ -68,17 +110,49 @@ impl Default for State {
                     password: String::new(),
                     result: Promise::Empty,
                 },
-                cache_size: Promise::Empty,
+                cache_usage: Promise::Empty,
+                output_devices: Promise::Empty,
             },
             playback: Playback {
                 state: PlaybackState::Stopped,
                 now_playing: None,
                 queue_behavior: QueueBehavior::Sequential,
+                repeat_mode: RepeatMode::Off,
+                speed: 1.0,
                 queue: Vector::new(),
+                history: Vector::new(),
+            },
+            downloads: Downloads {
+                queue: Vector::new(),
+            },
+            connect: Connect {
+                devices: Promise::Empty,
+                active_device: None,
+                remote: None,
+                volume: 1.0,
+                renderers: Promise::Empty,
+                cast_renderer: None,
             },
             search: Search {
                 input: "".into(),
                 results: Promise::Empty,
+                topic_results: Promise::Empty,
+                history: Vector::new(),
+                suggesting: false,
+                top_results: None,
+                suggestions: Vector::new(),
+                selected: None,
+            },
+            palette: Palette {
+                open: false,
+                input: String::new(),
+                entries: Vector::new(),
+                selected: 0,
+            },
+            home: Home {
+                featured_playlists: Promise::Empty,
+                new_releases: Promise::Empty,
+                recently_played: Promise::Empty,
             },
             album: AlbumDetail {
                 album: Promise::Empty,
@@ -88,22 +162,55 @@ impl Default for State {
                 albums: Promise::Empty,
                 top_tracks: Promise::Empty,
                 related_artists: Promise::Empty,
+                info: Promise::Empty,
             },
             playlist: PlaylistDetail {
                 playlist: Promise::Empty,
                 tracks: Promise::Empty,
+                name_input: String::new(),
+            },
+            show: ShowDetail {
+                show: Promise::Empty,
+                episodes: Promise::Empty,
+            },
+            radio: RadioDetail {
+                tracks: Promise::Empty,
             },
+            top: TopItems {
+                range: TopRange::MediumTerm,
+                tracks: Promise::Empty,
+                artists: Promise::Empty,
+            },
+            track_info: Promise::Empty,
             library: Arc::new(Library {
                 saved_albums: Promise::Empty,
                 saved_tracks: Promise::Empty,
                 playlists: Promise::Empty,
+                followed_artists: Promise::Empty,
+                saved_shows: Promise::Empty,
+                recently_played: Promise::Empty,
             }),
             common_ctx: CommonCtx {
                 playback_item: None,
                 saved_tracks: HashSet::new(),
                 saved_albums: HashSet::new(),
+                pinned_tracks: HashSet::new(),
+                followed_artists: HashSet::new(),
+                saved_shows: HashSet::new(),
+                editable_playlists: Vector::new(),
+                episode_positions: EpisodePositions::default(),
+                audio_features: HashMap::new(),
+                track_filter: String::new(),
+                track_sort: None,
+                track_columns: HashMap::new(),
+                track_selection: TrackSelection::default(),
+                shown_tracks: ShownTracks::default(),
+                dragged_tracks: Vector::new(),
             },
             user_profile: Promise::Empty,
+            retrying: false,
+            offline: false,
+            show_queue: false,
         }
     }
 }
@@ -140,17 +247,26 @@ impl State {
             origin,
             progress: Duration::default(),
             analysis: Promise::default(),
+            lyrics: Promise::default(),
         });
     }
 
     pub fn start_playback(&mut self, item: Arc<Track>, origin: PlaybackOrigin, progress: Duration) {
+        self.remember_episode_position();
         self.common_ctx.playback_item.replace(item.clone());
+        self.playback.add_to_history(item.clone(), origin.clone());
         self.playback.state = PlaybackState::Playing;
+        // Keep whatever has already been requested for the loaded item.
+        let (analysis, lyrics) = match self.playback.now_playing.take() {
+            Some(loaded) if loaded.item.id.same(&item.id) => (loaded.analysis, loaded.lyrics),
+            _ => (Promise::default(), Promise::default()),
+        };
         self.playback.now_playing.replace(NowPlaying {
             item,
             origin,
             progress,
-            analysis: Promise::default(),
+            analysis,
+            lyrics,
         });
     }
 
@@ -161,6 +277,7 @@ impl State {
     }
 
     pub fn pause_playback(&mut self) {
+        self.remember_episode_position();
         self.playback.state = PlaybackState::Paused;
     }
 
@@ -172,44 +289,165 @@ impl State {
         // TODO: Figure out how to signal blocked playback properly.
     }
 
+    pub fn restore_playback(&mut self, saved: SavedPlayback) {
+        if let Some(queued) = saved.queue.get(saved.position).cloned() {
+            self.common_ctx.playback_item.replace(queued.track.clone());
+            self.playback.queue = saved.queue;
+            self.playback.state = PlaybackState::Paused;
+            self.playback.now_playing.replace(NowPlaying {
+                item: queued.track,
+                origin: queued.origin,
+                progress: saved.progress,
+                analysis: Promise::default(),
+                lyrics: Promise::default(),
+            });
+        }
+    }
+
     pub fn stop_playback(&mut self) {
+        self.remember_episode_position();
         self.playback.state = PlaybackState::Stopped;
         self.playback.now_playing.take();
         self.common_ctx.playback_item.take();
     }
+
+    /// Remember how far we got in the playing episode, so it can be resumed
+    /// later.
+    pub fn remember_episode_position(&mut self) {
+        if let Some(now_playing) = &self.playback.now_playing {
+            if now_playing.item.id.id_type == ItemIdType::Podcast {
+                self.common_ctx
+                    .episode_positions
+                    .set(now_playing.item.id.to_base62().into(), now_playing.progress);
+            }
+        }
+    }
 }
 
 impl State {
     pub fn save_track(&mut self, track: Arc<Track>) {
+        self.common_ctx.saved_tracks.insert(track.id);
         if let Promise::Resolved(saved) = &mut self.library_mut().saved_tracks {
+            saved.added_at.insert(track.id, Utc::now());
             saved.tracks.push_front(track);
         }
-        if let Promise::Resolved(saved) = &self.library.saved_tracks {
-            self.common_ctx.set_saved_tracks(&saved.tracks);
-        }
     }
 
     pub fn unsave_track(&mut self, track_id: &TrackId) {
+        self.common_ctx.saved_tracks.remove(track_id);
         if let Promise::Resolved(saved) = &mut self.library_mut().saved_tracks {
             saved.tracks.retain(|track| &track.id != track_id);
-        }
-        if let Promise::Resolved(saved) = &self.library.saved_tracks {
-            self.common_ctx.set_saved_tracks(&saved.tracks);
+            saved.added_at.remove(track_id);
         }
     }
 
     pub fn save_album(&mut self, album: Album) {
+        self.common_ctx.saved_albums.insert(album.id.clone());
         if let Promise::Resolved(albums) = &mut self.library_mut().saved_albums {
             albums.push_front(album);
         }
     }
 
     pub fn unsave_album(&mut self, album_id: &Arc<str>) {
+        self.common_ctx.saved_albums.remove(album_id);
         if let Promise::Resolved(albums) = &mut self.library_mut().saved_albums {
             albums.retain(|album| &album.id != album_id)
         }
     }
 
+    pub fn save_show(&mut self, show: Show) {
+        self.common_ctx.saved_shows.insert(show.id.clone());
+        if let Promise::Resolved(shows) = &mut self.library_mut().saved_shows {
+            shows.push_front(show);
+        }
+    }
+
+    pub fn unsave_show(&mut self, show_id: &Arc<str>) {
+        self.common_ctx.saved_shows.remove(show_id);
+        if let Promise::Resolved(shows) = &mut self.library_mut().saved_shows {
+            shows.retain(|show| &show.id != show_id)
+        }
+    }
+
+    pub fn enqueue_downloads(&mut self, tracks: &Vector<Arc<Track>>) -> Vector<Arc<Track>> {
+        let enqueued: Vector<Arc<Track>> = tracks
+            .iter()
+            .filter(|track| {
+                !self.common_ctx.is_track_pinned(track)
+                    && !self
+                        .downloads
+                        .queue
+                        .iter()
+                        .any(|download| download.track.id.same(&track.id))
+            })
+            .cloned()
+            .collect();
+        self.downloads
+            .queue
+            .extend(enqueued.iter().map(|track| Download {
+                track: track.clone(),
+                progress: 0.0,
+            }));
+        enqueued
+    }
+
+    pub fn progress_download(&mut self, track_id: &TrackId, progress: f64) {
+        for download in self.downloads.queue.iter_mut() {
+            if download.track.id.same(track_id) {
+                download.progress = progress;
+            }
+        }
+    }
+
+    pub fn finish_download(&mut self, track_id: &TrackId, is_pinned: bool) {
+        self.downloads
+            .queue
+            .retain(|download| !download.track.id.same(track_id));
+        if is_pinned {
+            self.common_ctx.pinned_tracks.insert(track_id.clone());
+        }
+    }
+
+    pub fn remove_downloads(&mut self, tracks: &Vector<Arc<Track>>) {
+        for track in tracks {
+            self.downloads
+                .queue
+                .retain(|download| !download.track.id.same(&track.id));
+            self.common_ctx.pinned_tracks.remove(&track.id);
+        }
+    }
+
+    pub fn is_playlist_followed(&self, id: &str) -> bool {
+        match &self.library.playlists {
+            Promise::Resolved(playlists) => {
+                playlists.iter().any(|playlist| playlist.id.as_ref() == id)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn update_editable_playlists(&mut self) {
+        self.common_ctx.editable_playlists = match (&self.library.playlists, &self.user_profile) {
+            (Promise::Resolved(playlists), Promise::Resolved(user)) => playlists
+                .iter()
+                .filter(|playlist| playlist.is_editable_by(&user.id))
+                .map(Playlist::link)
+                .collect(),
+            _ => Vector::new(),
+        };
+    }
+
+    /// Snapshot of the playlist `id` as last seen in the library.
+    pub fn playlist_snapshot(&self, id: &str) -> Option<Arc<str>> {
+        match &self.library.playlists {
+            Promise::Resolved(playlists) => playlists
+                .iter()
+                .find(|playlist| &*playlist.id == id)
+                .map(|playlist| playlist.snapshot_id.clone()),
+            _ => None,
+        }
+    }
+
     pub fn library_mut(&mut self) -> &mut Library {
         Arc::make_mut(&mut self.library)
     }
@@ -220,18 +458,74 @@ pub struct Library {
     pub playlists: Promise<Vector<Playlist>>,
     pub saved_albums: Promise<Vector<Album>>,
     pub saved_tracks: Promise<SavedTracks>,
+    pub followed_artists: Promise<FollowedArtists>,
+    pub saved_shows: Promise<Vector<Show>>,
+    pub recently_played: Promise<RecentlyPlayed>,
 }
 
 #[derive(Clone, Data, Lens)]
 pub struct SavedTracks {
     pub tracks: Vector<Arc<Track>>,
+    #[data(same_fn = "HashMap::ptr_eq")]
+    pub added_at: HashMap<TrackId, DateTime<Utc>>,
+    /// Offset of the next page of tracks, `None` if all of them are loaded.
+    pub next_offset: Option<usize>,
 }
 
-#[derive(Clone, Data)]
+impl SavedTracks {
+    /// Add the next page of tracks.
+    pub fn append(&mut self, page: SavedTracks) {
+        for track in page.tracks {
+            // Tracks saved in the meantime shift the pages, skip the ones we
+            // already have.
+            if let Some(added_at) = page.added_at.get(&track.id) {
+                if self.added_at.insert(track.id, *added_at).is_none() {
+                    self.tracks.push_back(track);
+                }
+            }
+        }
+        self.next_offset = page.next_offset;
+    }
+}
+
+#[derive(Clone, Data, Lens)]
+pub struct FollowedArtists {
+    pub artists: Vector<Artist>,
+    /// Cursor of the next page, `None` if all of the artists are loaded.
+    pub next_cursor: Option<Arc<str>>,
+}
+
+#[derive(Clone, Data, Lens)]
 pub struct CommonCtx {
     pub playback_item: Option<Arc<Track>>,
+    /// Tracks known to be saved, either from the loaded library or from
+    /// checking the tracks shown on screen.
     pub saved_tracks: HashSet<TrackId>,
     pub saved_albums: HashSet<Arc<str>>,
+    pub pinned_tracks: HashSet<TrackId>,
+    pub followed_artists: HashSet<Arc<str>>,
+    pub saved_shows: HashSet<Arc<str>>,
+    /// Playlists the user can add tracks to.
+    pub editable_playlists: Vector<PlaylistLink>,
+    pub episode_positions: EpisodePositions,
+    /// Audio features of the tracks shown with their tempo and key.
+    #[data(same_fn = "HashMap::ptr_eq")]
+    pub audio_features: HashMap<TrackId, AudioFeatures>,
+    /// Text the track lists of the current page are narrowed down by.
+    pub track_filter: String,
+    /// Column the track lists of the current page are ordered by, `None` to
+    /// keep the order of their origin.
+    pub track_sort: Option<TrackSort>,
+    /// Columns the user picked for the track lists of each view, mirrored
+    /// from the config.
+    pub track_columns: HashMap<TrackView, Vector<TrackColumn>>,
+    pub track_selection: TrackSelection,
+    /// Order the tracks of the current page are shown in, derived from the
+    /// fields above.
+    #[data(ignore)]
+    pub shown_tracks: ShownTracks,
+    /// Tracks being dragged out of a track list, empty if there are none.
+    pub dragged_tracks: Vector<Arc<Track>>,
 }
 
 impl CommonCtx {
@@ -242,19 +536,86 @@ impl CommonCtx {
             .unwrap_or(false)
     }
 
+    pub fn is_playlist_editable(&self, id: &str) -> bool {
+        self.editable_playlists
+            .iter()
+            .any(|playlist| &*playlist.id == id)
+    }
+
+    pub fn is_filtering_tracks(&self) -> bool {
+        !self.track_filter.trim().is_empty()
+    }
+
+    /// True if all the words of the filter appear in the title, the artists
+    /// or the album of the track.
+    pub fn track_matches_filter(&self, track: &Track) -> bool {
+        if !self.is_filtering_tracks() {
+            return true;
+        }
+        let haystack = format!(
+            "{} {} {}",
+            track.name,
+            track.artist_name(),
+            track.album_name()
+        )
+        .to_lowercase();
+        self.track_filter
+            .to_lowercase()
+            .split_whitespace()
+            .all(|word| haystack.contains(word))
+    }
+
     pub fn is_track_saved(&self, track: &Track) -> bool {
         self.saved_tracks.contains(&track.id)
     }
 
-    pub fn set_saved_tracks(&mut self, tracks: &Vector<Arc<Track>>) {
-        self.saved_tracks = tracks.iter().map(|track| track.id.clone()).collect();
+    pub fn add_saved_tracks(&mut self, tracks: &Vector<Arc<Track>>) {
+        self.saved_tracks
+            .extend(tracks.iter().map(|track| track.id));
+    }
+
+    pub fn set_track_saved(&mut self, track_id: TrackId, saved: bool) {
+        if saved {
+            self.saved_tracks.insert(track_id);
+        } else {
+            self.saved_tracks.remove(&track_id);
+        }
     }
 
     pub fn is_album_saved(&self, album: &Album) -> bool {
         self.saved_albums.contains(&album.id)
     }
 
-    pub fn set_saved_albums(&mut self, albums: &Vector<Album>) {
-        self.saved_albums = albums.iter().map(|album| album.id.clone()).collect();
+    pub fn add_saved_albums(&mut self, albums: &Vector<Album>) {
+        self.saved_albums
+            .extend(albums.iter().map(|album| album.id.clone()));
+    }
+
+    pub fn set_album_saved(&mut self, album_id: Arc<str>, saved: bool) {
+        if saved {
+            self.saved_albums.insert(album_id);
+        } else {
+            self.saved_albums.remove(&album_id);
+        }
+    }
+
+    pub fn is_show_saved(&self, id: &str) -> bool {
+        self.saved_shows.contains(id)
+    }
+
+    pub fn set_saved_shows(&mut self, shows: &Vector<Show>) {
+        self.saved_shows = shows.iter().map(|show| show.id.clone()).collect();
+    }
+
+    pub fn is_artist_followed(&self, id: &str) -> bool {
+        self.followed_artists.contains(id)
+    }
+
+    pub fn is_track_pinned(&self, track: &Track) -> bool {
+        self.pinned_tracks.contains(&track.id)
+    }
+
+    pub fn are_tracks_pinned(&self, tracks: &Vector<Arc<Track>>) -> bool {
+        !tracks.is_empty() && tracks.iter().all(|track| self.is_track_pinned(track))
     }
 }
@@ -1,39 +1,121 @@
-use crate::data::{AlbumLink, ArtistLink, PlaylistLink};
+use crate::data::{AlbumLink, ArtistLink, PlaylistLink, RadioSeed, SearchTopic, ShowLink};
 use druid::Data;
+use std::sync::Arc;
 
 #[derive(Clone, Debug, Data, Eq, PartialEq, Hash)]
 pub enum Nav {
     Home,
+    NowPlaying,
+    History,
+    RecentlyPlayed,
+    TopItems,
     SavedTracks,
     SavedAlbums,
+    FollowedArtists,
+    SavedShows,
     SearchResults(String),
+    SearchTopicResults(String, SearchTopic),
     ArtistDetail(ArtistLink),
     AlbumDetail(AlbumLink),
     PlaylistDetail(PlaylistLink),
+    ShowDetail(ShowLink),
+    Radio(RadioSeed),
 }
 
 impl Nav {
     pub fn to_title(&self) -> String {
         match self {
             Nav::Home => "Home".to_string(),
+            Nav::NowPlaying => "Now Playing".to_string(),
+            Nav::History => "History".to_string(),
+            Nav::RecentlyPlayed => "Recently Played".to_string(),
+            Nav::TopItems => "Top Tracks & Artists".to_string(),
             Nav::SavedTracks => "Saved Tracks".to_string(),
             Nav::SavedAlbums => "Saved Albums".to_string(),
+            Nav::FollowedArtists => "Followed Artists".to_string(),
+            Nav::SavedShows => "Podcasts".to_string(),
             Nav::SearchResults(query) => query.to_owned(),
+            Nav::SearchTopicResults(query, topic) => format!("{} – {}", query, topic.title()),
             Nav::AlbumDetail(link) => link.name.to_string(),
             Nav::ArtistDetail(link) => link.name.to_string(),
             Nav::PlaylistDetail(link) => link.name.to_string(),
+            Nav::ShowDetail(link) => link.name.to_string(),
+            Nav::Radio(seed) => seed.to_title(),
         }
     }
 
     pub fn to_full_title(&self) -> String {
         match self {
             Nav::Home => "Home".to_string(),
+            Nav::NowPlaying => "Now Playing".to_string(),
+            Nav::History => "History".to_string(),
+            Nav::RecentlyPlayed => "Recently Played".to_string(),
+            Nav::TopItems => "Top Tracks & Artists".to_string(),
             Nav::SavedTracks => "Saved Tracks".to_string(),
             Nav::SavedAlbums => "Saved Albums".to_string(),
+            Nav::FollowedArtists => "Followed Artists".to_string(),
+            Nav::SavedShows => "Podcasts".to_string(),
             Nav::SearchResults(query) => format!("Search “{}”", query),
+            Nav::SearchTopicResults(query, topic) => {
+                format!("Search “{}” – {}", query, topic.title())
+            }
             Nav::AlbumDetail(link) => format!("Album “{}”", link.name),
             Nav::ArtistDetail(link) => format!("Artist “{}”", link.name),
             Nav::PlaylistDetail(link) => format!("Playlist “{}”", link.name),
+            Nav::ShowDetail(link) => format!("Show “{}”", link.name),
+            Nav::Radio(seed) => seed.to_full_title(),
+        }
+    }
+}
+
+/// Item referenced by a `spotify:` URI or an `open.spotify.com` link.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SpotifyUrl {
+    Track(Arc<str>),
+    Album(Arc<str>),
+    Artist(Arc<str>),
+    Playlist(Arc<str>),
+}
+
+impl SpotifyUrl {
+    /// Parse either `spotify:album:<id>` or
+    /// `https://open.spotify.com/album/<id>?si=...`.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let mut parts: Vec<&str> = if let Some(uri) = input.strip_prefix("spotify:") {
+            uri.split(':').collect()
+        } else {
+            let path = input
+                .strip_prefix("https://")
+                .or_else(|| input.strip_prefix("http://"))?
+                .strip_prefix("open.spotify.com/")?;
+            let path = path.split(|c| c == '?' || c == '#').next()?;
+            path.split('/').filter(|part| !part.is_empty()).collect()
+        };
+        // Links can be localized, i.e. `open.spotify.com/intl-de/album/<id>`,
+        // and old playlist URIs contain the owner, i.e.
+        // `spotify:user:<name>:playlist:<id>`.
+        if parts
+            .first()
+            .map_or(false, |part| part.starts_with("intl-"))
+        {
+            parts.remove(0);
+        }
+        if parts.first() == Some(&"user") && parts.get(2) == Some(&"playlist") {
+            parts.drain(..2);
+        }
+        match parts.as_slice() {
+            [kind, id] if !id.is_empty() => {
+                let id = Arc::from(*id);
+                match *kind {
+                    "track" => Some(Self::Track(id)),
+                    "album" => Some(Self::Album(id)),
+                    "artist" => Some(Self::Artist(id)),
+                    "playlist" => Some(Self::Playlist(id)),
+                    _ => None,
+                }
+            }
+            _ => None,
         }
     }
 }
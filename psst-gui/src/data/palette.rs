@@ -0,0 +1,202 @@
+use crate::data::Nav;
+use druid::{im::Vector, Data, Lens};
+
+const PALETTE_LENGTH: usize = 10;
+const PALETTE_RECENT_LENGTH: usize = 5;
+
+const PALETTE_COMMANDS: [PaletteCommand; 12] = [
+    PaletteCommand::PlayPause,
+    PaletteCommand::Next,
+    PaletteCommand::Previous,
+    PaletteCommand::ToggleShuffle,
+    PaletteCommand::CycleRepeat,
+    PaletteCommand::ToggleSave,
+    PaletteCommand::Search,
+    PaletteCommand::GoBack,
+    PaletteCommand::Refresh,
+    PaletteCommand::ToggleMiniPlayer,
+    PaletteCommand::ToggleQueue,
+    PaletteCommand::OpenPreferences,
+];
+
+const PALETTE_PAGES: [Nav; 9] = [
+    Nav::Home,
+    Nav::NowPlaying,
+    Nav::History,
+    Nav::RecentlyPlayed,
+    Nav::TopItems,
+    Nav::SavedTracks,
+    Nav::SavedAlbums,
+    Nav::FollowedArtists,
+    Nav::SavedShows,
+];
+
+#[derive(Clone, Data, Lens)]
+pub struct Palette {
+    pub open: bool,
+    pub input: String,
+    pub entries: Vector<PaletteRow>,
+    pub selected: usize,
+}
+
+impl Palette {
+    /// Show the commands, pages and recently visited pages matching the
+    /// input, the best match first.
+    pub fn update_entries(&mut self, history: &Vector<Nav>) {
+        let mut recent: Vec<Nav> = Vec::new();
+        for nav in history.iter().rev() {
+            if !PALETTE_PAGES.contains(nav) && !recent.contains(nav) {
+                recent.push(nav.clone());
+            }
+        }
+        recent.truncate(PALETTE_RECENT_LENGTH);
+
+        let candidates = recent
+            .into_iter()
+            .map(PaletteEntry::Recent)
+            .chain(PALETTE_PAGES.iter().cloned().map(PaletteEntry::Page))
+            .chain(PALETTE_COMMANDS.iter().copied().map(PaletteEntry::Command));
+        let mut matches: Vec<(i64, PaletteEntry)> = candidates
+            .filter_map(|entry| Some((entry.score(&self.input)?, entry)))
+            .collect();
+        // Stable, so equal scores keep the order above.
+        matches.sort_by_key(|(score, _)| -score);
+
+        self.selected = self.selected.min(matches.len().saturating_sub(1));
+        self.entries = matches
+            .into_iter()
+            .take(PALETTE_LENGTH)
+            .enumerate()
+            .map(|(index, (_, entry))| PaletteRow {
+                entry,
+                selected: self.selected == index,
+            })
+            .collect();
+    }
+
+    pub fn move_selection(&mut self, delta: isize, history: &Vector<Nav>) {
+        let last = self.entries.len().saturating_sub(1) as isize;
+        self.selected = (self.selected as isize + delta).max(0).min(last) as usize;
+        self.update_entries(history);
+    }
+
+    pub fn selected_entry(&self) -> Option<PaletteEntry> {
+        self.entries.get(self.selected).map(|row| row.entry.clone())
+    }
+
+    pub fn reset(&mut self) {
+        self.input.clear();
+        self.entries.clear();
+        self.selected = 0;
+    }
+}
+
+#[derive(Clone, Data, Lens)]
+pub struct PaletteRow {
+    pub entry: PaletteEntry,
+    pub selected: bool,
+}
+
+#[derive(Clone, Debug, Data)]
+pub enum PaletteEntry {
+    Command(PaletteCommand),
+    Page(Nav),
+    Recent(Nav),
+}
+
+impl PaletteEntry {
+    pub fn title(&self) -> String {
+        match self {
+            Self::Command(command) => command.title().to_string(),
+            Self::Page(nav) => format!("Go to {}", nav.to_title()),
+            Self::Recent(nav) => nav.to_full_title(),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Command(_) => "Command",
+            Self::Page(_) => "Page",
+            Self::Recent(_) => "Recent",
+        }
+    }
+
+    /// Other names the entry is found by.
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            Self::Command(PaletteCommand::ToggleSave) => &["Like", "Heart"],
+            Self::Command(PaletteCommand::OpenPreferences) => &["Settings"],
+            Self::Page(Nav::SavedTracks) => &["Go to Liked Songs"],
+            Self::Page(Nav::SavedShows) => &["Go to Saved Shows"],
+            _ => &[],
+        }
+    }
+
+    fn score(&self, input: &str) -> Option<i64> {
+        let title = self.title();
+        let names = Some(title.as_str())
+            .into_iter()
+            .chain(self.aliases().iter().copied());
+        names.filter_map(|name| fuzzy_score(input, name)).max()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Data, Eq, PartialEq)]
+pub enum PaletteCommand {
+    PlayPause,
+    Next,
+    Previous,
+    ToggleShuffle,
+    CycleRepeat,
+    ToggleSave,
+    Search,
+    GoBack,
+    Refresh,
+    ToggleMiniPlayer,
+    ToggleQueue,
+    OpenPreferences,
+}
+
+impl PaletteCommand {
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::PlayPause => "Play / Pause",
+            Self::Next => "Next Track",
+            Self::Previous => "Previous Track",
+            Self::ToggleShuffle => "Toggle Shuffle",
+            Self::CycleRepeat => "Cycle Repeat Mode",
+            Self::ToggleSave => "Save / Remove Playing Track",
+            Self::Search => "Search",
+            Self::GoBack => "Go Back",
+            Self::Refresh => "Refresh",
+            Self::ToggleMiniPlayer => "Toggle Mini Player",
+            Self::ToggleQueue => "Toggle Queue",
+            Self::OpenPreferences => "Open Preferences",
+        }
+    }
+}
+
+/// Score of `text` containing the characters of `query` in order, higher is
+/// better, `None` if it doesn't contain them.
+fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous = None;
+    for c in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position + text[position..].iter().position(|&t| t == c)?;
+        score += 1;
+        if previous.map_or(false, |p: usize| p + 1 == found) {
+            // Consecutive characters.
+            score += 4;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            // Start of a word.
+            score += 3;
+        }
+        score -= (found - position).min(3) as i64;
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
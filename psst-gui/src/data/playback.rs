@@ -1,29 +1,200 @@
 use crate::data::{
-    AlbumLink, ArtistLink, AudioAnalysis, Nav, PlaylistLink, Promise, Track, TrackId,
+    AlbumLink, ArtistLink, AudioAnalysis, Config, Lyrics, Nav, PlaylistLink, Promise, RadioSeed,
+    ShowLink, Track, TrackId,
 };
+use chrono::{DateTime, Local, Utc};
 use druid::{im::Vector, Data, Lens};
-use std::{sync::Arc, time::Duration};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    sync::Arc,
+    time::Duration,
+};
 
 #[derive(Clone, Debug, Data, Lens)]
 pub struct Playback {
     pub state: PlaybackState,
     pub now_playing: Option<NowPlaying>,
     pub queue_behavior: QueueBehavior,
+    pub repeat_mode: RepeatMode,
+    pub speed: f64,
     pub queue: Vector<QueuedTrack>,
+    pub history: Vector<PlayedTrack>,
 }
 
-#[derive(Clone, Debug, Data, Lens)]
+impl Playback {
+    // Maximum number of entries kept in the play history.
+    const HISTORY_LIMIT: usize = 500;
+
+    pub fn add_to_history(&mut self, track: Arc<Track>, origin: PlaybackOrigin) {
+        // Seeking or resuming can report the same track as playing again, skip
+        // it if it's the last thing we played.
+        if let Some(last) = self.history.front() {
+            if last.track.id.same(&track.id) {
+                return;
+            }
+        }
+        self.history.push_front(PlayedTrack {
+            track,
+            origin,
+            played_at: Local::now(),
+        });
+        self.history.truncate(Self::HISTORY_LIMIT);
+    }
+
+    /// Title of the main window.  `format` can refer to the current track with
+    /// `{title}`, `{artist}`, `{album}`, `{elapsed}` and `{duration}`.
+    pub fn window_title(&self, format: &str) -> String {
+        const APP_TITLE: &str = "Psst";
+
+        let format_time =
+            |dur: Duration| format!("{}:{:02}", dur.as_secs() / 60, dur.as_secs() % 60);
+
+        match &self.now_playing {
+            Some(now_playing) if !format.trim().is_empty() => format
+                .replace("{title}", &now_playing.item.name)
+                .replace("{artist}", &now_playing.item.artist_name())
+                .replace("{album}", &now_playing.item.album_name())
+                .replace("{elapsed}", &format_time(now_playing.progress))
+                .replace("{duration}", &format_time(now_playing.item.duration)),
+            _ => APP_TITLE.to_string(),
+        }
+    }
+
+    /// Index of the playing track in the queue.
+    pub fn current_index(&self) -> Option<usize> {
+        let now_playing = self.now_playing.as_ref()?;
+        self.queue
+            .iter()
+            .position(|queued| queued.track.id.same(&now_playing.item.id))
+    }
+
+    /// Index the added tracks go to, after the playing track and the ones
+    /// added before.
+    pub fn added_index(&self) -> usize {
+        match self.current_index() {
+            Some(current) => {
+                let added = self
+                    .queue
+                    .iter()
+                    .skip(current + 1)
+                    .take_while(|queued| queued.added)
+                    .count();
+                current + 1 + added
+            }
+            None => self.queue.len(),
+        }
+    }
+
+    /// Tracks after the playing one, either the added ones or the ones from
+    /// the played context.
+    pub fn upcoming(&self, added: bool) -> Vector<QueueEntry> {
+        let start = self.current_index().map_or(0, |current| current + 1);
+        self.queue
+            .iter()
+            .enumerate()
+            .skip(start)
+            .filter(|(_, queued)| queued.added == added)
+            .map(|(index, queued)| QueueEntry {
+                queued: queued.to_owned(),
+                index,
+            })
+            .collect()
+    }
+
+    /// Queue index the upcoming track at `index` gets moved to when dragged by
+    /// `offset` rows of its kind.
+    pub fn upcoming_target(&self, index: usize, offset: isize) -> Option<usize> {
+        let added = self.queue.get(index)?.added;
+        let rows = self.upcoming(added);
+        let row = rows.iter().position(|entry| entry.index == index)?;
+        let target = (row as isize + offset).max(0).min(rows.len() as isize - 1) as usize;
+        Some(rows[target].index)
+    }
+}
+
+#[derive(Clone, Debug, Data, Lens, Serialize, Deserialize)]
 pub struct QueuedTrack {
     pub track: Arc<Track>,
     pub origin: PlaybackOrigin,
+    /// Added with "Add to Queue", instead of coming from the played context.
+    #[serde(default)]
+    pub added: bool,
+}
+
+/// Upcoming track in the queue panel.
+#[derive(Clone, Data, Lens)]
+pub struct QueueEntry {
+    pub queued: QueuedTrack,
+    /// Position in the playback queue.
+    pub index: usize,
+}
+
+#[derive(Clone, Debug, Data, Lens)]
+pub struct PlayedTrack {
+    pub track: Arc<Track>,
+    pub origin: PlaybackOrigin,
+    #[data(ignore)]
+    pub played_at: DateTime<Local>,
+}
+
+/// Page of the tracks played on any of the user's devices, as reported by
+/// Spotify.
+#[derive(Clone, Data, Lens)]
+pub struct RecentlyPlayed {
+    pub items: Vector<RecentlyPlayedItem>,
+    /// Cursor of the page with the older plays, if there is one.
+    pub next_cursor: Option<Arc<str>>,
+}
+
+impl RecentlyPlayed {
+    pub fn tracks(&self) -> Vector<Arc<Track>> {
+        self.items.iter().map(|item| item.track.clone()).collect()
+    }
+}
+
+#[derive(Clone, Data, Lens, Deserialize)]
+pub struct RecentlyPlayedItem {
+    pub track: Arc<Track>,
+    #[data(same_fn = "PartialEq::eq")]
+    pub played_at: DateTime<Utc>,
+    pub context: Option<PlayContext>,
+}
+
+#[derive(Clone, Data, Lens, Deserialize)]
+pub struct PlayContext {
+    #[serde(rename = "type")]
+    pub kind: Arc<str>,
+    pub uri: Arc<str>,
 }
 
 #[derive(Copy, Clone, Debug, Data, Eq, PartialEq)]
 pub enum QueueBehavior {
     Sequential,
     Random,
-    LoopTrack,
-    LoopAll,
+}
+
+#[derive(Copy, Clone, Debug, Data, Eq, PartialEq)]
+pub enum RepeatMode {
+    Off,
+    Context,
+    Track,
+}
+
+impl RepeatMode {
+    pub fn cycle(self) -> Self {
+        match self {
+            RepeatMode::Off => RepeatMode::Context,
+            RepeatMode::Context => RepeatMode::Track,
+            RepeatMode::Track => RepeatMode::Off,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Data, Eq, PartialEq)]
+pub enum SeekDirection {
+    Forward,
+    Backward,
 }
 
 #[derive(Copy, Clone, Debug, Data, Eq, PartialEq)]
@@ -40,15 +211,21 @@ pub struct NowPlaying {
     pub origin: PlaybackOrigin,
     pub progress: Duration,
     pub analysis: Promise<AudioAnalysis, TrackId>,
+    pub lyrics: Promise<Lyrics, TrackId>,
 }
 
-#[derive(Clone, Debug, Data)]
+#[derive(Clone, Debug, Data, Serialize, Deserialize)]
 pub enum PlaybackOrigin {
     Library,
     Album(AlbumLink),
     Artist(ArtistLink),
     Playlist(PlaylistLink),
     Search(String),
+    Recommendations,
+    Show(ShowLink),
+    RecentlyPlayed,
+    TopTracks,
+    Radio(RadioSeed),
 }
 
 impl PlaybackOrigin {
@@ -59,6 +236,11 @@ impl PlaybackOrigin {
             PlaybackOrigin::Artist(link) => Nav::ArtistDetail(link.clone()),
             PlaybackOrigin::Playlist(link) => Nav::PlaylistDetail(link.clone()),
             PlaybackOrigin::Search(query) => Nav::SearchResults(query.clone()),
+            PlaybackOrigin::Recommendations => Nav::Home,
+            PlaybackOrigin::Show(link) => Nav::ShowDetail(link.clone()),
+            PlaybackOrigin::RecentlyPlayed => Nav::RecentlyPlayed,
+            PlaybackOrigin::TopTracks => Nav::TopItems,
+            PlaybackOrigin::Radio(seed) => Nav::Radio(seed.clone()),
         }
     }
 
@@ -69,6 +251,11 @@ impl PlaybackOrigin {
             PlaybackOrigin::Artist(link) => link.name.to_string(),
             PlaybackOrigin::Playlist(link) => link.name.to_string(),
             PlaybackOrigin::Search(query) => query.clone(),
+            PlaybackOrigin::Recommendations => "Autoplay".to_string(),
+            PlaybackOrigin::Show(link) => link.name.to_string(),
+            PlaybackOrigin::RecentlyPlayed => "Recently Played".to_string(),
+            PlaybackOrigin::TopTracks => "Top Tracks".to_string(),
+            PlaybackOrigin::Radio(seed) => seed.to_title(),
         }
     }
 }
@@ -79,3 +266,77 @@ pub struct PlaybackPayload {
     pub tracks: Vector<Arc<Track>>,
     pub position: usize,
 }
+
+impl PlaybackPayload {
+    /// Leave out the tracks matching `skip`.  If the track at `position` is
+    /// skipped, the playback starts from the next one that isn't.
+    pub fn without(&self, skip: impl Fn(&Track) -> bool) -> Self {
+        let position = self
+            .tracks
+            .iter()
+            .take(self.position)
+            .filter(|track| !skip(track))
+            .count();
+        let tracks: Vector<_> = self
+            .tracks
+            .iter()
+            .filter(|track| !skip(track))
+            .cloned()
+            .collect();
+        Self {
+            origin: self.origin.clone(),
+            position: position.min(tracks.len().saturating_sub(1)),
+            tracks,
+        }
+    }
+}
+
+const SAVED_PLAYBACK_FILENAME: &str = "playback.json";
+
+/// Playback state persisted between the runs of the application.
+#[derive(Serialize, Deserialize)]
+pub struct SavedPlayback {
+    pub queue: Vector<QueuedTrack>,
+    pub position: usize,
+    pub progress: Duration,
+}
+
+impl SavedPlayback {
+    pub fn new(playback: &Playback) -> Option<Self> {
+        let now_playing = playback.now_playing.as_ref()?;
+        let position = playback.current_index()?;
+        Some(Self {
+            queue: playback.queue.clone(),
+            position,
+            progress: now_playing.progress,
+        })
+    }
+
+    pub fn load() -> Option<Self> {
+        let path = Config::config_dir()?.join(SAVED_PLAYBACK_FILENAME);
+        let file = File::open(&path).ok()?;
+        serde_json::from_reader(file)
+            .map_err(|err| log::error!("failed to read saved playback: {}", err))
+            .ok()
+    }
+
+    pub fn save(&self) {
+        let path = match Config::config_dir() {
+            Some(dir) => dir.join(SAVED_PLAYBACK_FILENAME),
+            None => return,
+        };
+        let result = File::create(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|file| serde_json::to_writer(file, self).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            log::error!("failed to save playback: {}", err);
+        }
+    }
+
+    pub fn clear() {
+        if let Some(dir) = Config::config_dir() {
+            // The file might not exist, there's nothing to do in that case.
+            let _ = fs::remove_file(dir.join(SAVED_PLAYBACK_FILENAME));
+        }
+    }
+}
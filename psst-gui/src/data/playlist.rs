@@ -1,12 +1,14 @@
 use crate::data::{Image, Promise, Track};
 use druid::{im::Vector, Data, Lens};
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::sync::Arc;
 
 #[derive(Clone, Debug, Data, Lens)]
 pub struct PlaylistDetail {
     pub playlist: Promise<Playlist, PlaylistLink>,
     pub tracks: Promise<PlaylistTracks, PlaylistLink>,
+    /// Contents of the name editor.
+    pub name_input: String,
 }
 
 #[derive(Clone, Debug, Data, Lens, Deserialize)]
@@ -18,6 +20,11 @@ pub struct Playlist {
     #[serde(rename = "tracks")]
     #[serde(deserialize_with = "deserialize_track_count")]
     pub track_count: usize,
+    pub owner: PlaylistOwner,
+    #[serde(default)]
+    pub collaborative: bool,
+    /// Version of the playlist contents, changes with every modification.
+    pub snapshot_id: Arc<str>,
 }
 
 impl Playlist {
@@ -27,6 +34,23 @@ impl Playlist {
             name: self.name.clone(),
         }
     }
+
+    pub fn url(&self) -> String {
+        format!("https://open.spotify.com/playlist/{id}", id = self.id)
+    }
+
+    pub fn uri(&self) -> String {
+        format!("spotify:playlist:{id}", id = self.id)
+    }
+
+    pub fn is_editable_by(&self, user_id: &str) -> bool {
+        self.collaborative || self.owner.id.as_ref() == user_id
+    }
+}
+
+#[derive(Clone, Debug, Data, Lens, Deserialize)]
+pub struct PlaylistOwner {
+    pub id: Arc<str>,
 }
 
 #[derive(Clone, Debug, Data, Lens)]
@@ -34,6 +58,8 @@ pub struct PlaylistTracks {
     pub id: Arc<str>,
     pub name: Arc<str>,
     pub tracks: Vector<Arc<Track>>,
+    /// Offset of the next page of tracks, `None` if all of them are loaded.
+    pub next_offset: Option<usize>,
 }
 
 impl PlaylistTracks {
@@ -45,7 +71,7 @@ impl PlaylistTracks {
     }
 }
 
-#[derive(Clone, Debug, Data, Lens, Eq, PartialEq, Hash)]
+#[derive(Clone, Debug, Data, Lens, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct PlaylistLink {
     pub id: Arc<str>,
     pub name: Arc<str>,
@@ -31,6 +31,10 @@ impl<T: Data, D: Data, E: Data> Promise<T, D, E> {
         matches!(self, Self::Empty)
     }
 
+    pub fn is_resolved(&self) -> bool {
+        matches!(self, Self::Resolved(_))
+    }
+
     pub fn is_rejected(&self) -> bool {
         matches!(self, Self::Rejected(_))
     }
@@ -0,0 +1,49 @@
+use crate::data::{ArtistLink, Promise, Track, TrackId};
+use druid::{im::Vector, Data, Lens};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Clone, Data, Lens)]
+pub struct RadioDetail {
+    pub tracks: Promise<RadioTracks, RadioSeed>,
+}
+
+#[derive(Clone, Data, Lens)]
+pub struct RadioTracks {
+    pub seed: RadioSeed,
+    pub tracks: Vector<Arc<Track>>,
+}
+
+/// Item the recommendations of a radio are generated from.
+#[derive(Clone, Debug, Data, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum RadioSeed {
+    Track { id: TrackId, name: Arc<str> },
+    Artist(ArtistLink),
+}
+
+impl RadioSeed {
+    pub fn track(track: &Track) -> Self {
+        RadioSeed::Track {
+            id: track.id,
+            name: track.name.clone(),
+        }
+    }
+
+    pub fn name(&self) -> &Arc<str> {
+        match self {
+            RadioSeed::Track { name, .. } => name,
+            RadioSeed::Artist(link) => &link.name,
+        }
+    }
+
+    pub fn to_title(&self) -> String {
+        format!("{} Radio", self.name())
+    }
+
+    pub fn to_full_title(&self) -> String {
+        match self {
+            RadioSeed::Track { name, .. } => format!("Song Radio “{}”", name),
+            RadioSeed::Artist(link) => format!("Artist Radio “{}”", link.name),
+        }
+    }
+}
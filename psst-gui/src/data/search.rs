@@ -1,11 +1,162 @@
-use crate::data::{Album, Artist, Playlist, Promise, Track};
-use druid::{im::Vector, Data, Lens};
-use std::sync::Arc;
+use crate::data::{Album, AlbumLink, Artist, ArtistLink, Config, Playlist, Promise, Show, Track};
+use druid::{
+    im::{HashSet, Vector},
+    Data, Lens,
+};
+use std::{fs::File, sync::Arc};
+
+const SEARCH_HISTORY_FILENAME: &str = "search-history.json";
+const SEARCH_HISTORY_LENGTH: usize = 20;
+const SUGGESTED_QUERIES: usize = 5;
+const SUGGESTED_RESULTS: usize = 3;
 
 #[derive(Clone, Data, Lens)]
 pub struct Search {
     pub input: String,
     pub results: Promise<SearchResults, String>,
+    /// All the results of a single type, loaded page by page.
+    pub topic_results: Promise<SearchResults, (String, SearchTopic)>,
+    /// Recent queries, the last one first.
+    pub history: Vector<String>,
+    /// True while the suggestions are shown under the search box.
+    pub suggesting: bool,
+    /// Top results for the text in the search box.
+    pub top_results: Option<SearchResults>,
+    pub suggestions: Vector<SuggestionRow>,
+    pub selected: Option<usize>,
+}
+
+impl Search {
+    pub fn remember(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+        self.history.retain(|q| q != query);
+        self.history.push_front(query.to_string());
+        self.history.truncate(SEARCH_HISTORY_LENGTH);
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.update_suggestions();
+    }
+
+    /// Show the recent queries matching the input, followed by the top
+    /// results.
+    pub fn update_suggestions(&mut self) {
+        let input = self.input.trim().to_lowercase();
+        let mut suggestions: Vector<SearchSuggestion> = self
+            .history
+            .iter()
+            .filter(|query| {
+                let query = query.to_lowercase();
+                query.starts_with(&input) && query != input
+            })
+            .take(SUGGESTED_QUERIES)
+            .map(|query| SearchSuggestion::Query(query.clone()))
+            .collect();
+        if let Some(top) = self.top_results.as_ref().filter(|_| !input.is_empty()) {
+            let artists = top
+                .artists
+                .iter()
+                .map(|a| SearchSuggestion::Artist(a.link()));
+            let albums = top.albums.iter().map(|a| SearchSuggestion::Album(a.link()));
+            let tracks = top.tracks.iter().cloned().map(SearchSuggestion::Track);
+            suggestions.extend(artists.take(SUGGESTED_RESULTS));
+            suggestions.extend(albums.take(SUGGESTED_RESULTS));
+            suggestions.extend(tracks.take(SUGGESTED_RESULTS));
+        }
+        self.selected = self.selected.filter(|&index| index < suggestions.len());
+        self.suggestions = suggestions
+            .into_iter()
+            .enumerate()
+            .map(|(index, suggestion)| SuggestionRow {
+                suggestion,
+                selected: self.selected == Some(index),
+            })
+            .collect();
+    }
+
+    /// Move the keyboard selection by `delta` rows, past the ends it goes
+    /// back to the input.
+    pub fn move_selection(&mut self, delta: isize) {
+        let len = self.suggestions.len() as isize;
+        let next = match self.selected {
+            Some(index) => index as isize + delta,
+            None if delta > 0 => 0,
+            None => len - 1,
+        };
+        self.selected = if next >= 0 && next < len {
+            Some(next as usize)
+        } else {
+            None
+        };
+        self.update_suggestions();
+    }
+
+    pub fn selected_suggestion(&self) -> Option<SearchSuggestion> {
+        self.selected
+            .and_then(|index| self.suggestions.get(index))
+            .map(|row| row.suggestion.clone())
+    }
+
+    pub fn load_history() -> Option<Vector<String>> {
+        let path = Config::config_dir()?.join(SEARCH_HISTORY_FILENAME);
+        let file = File::open(&path).ok()?;
+        serde_json::from_reader::<_, Vec<String>>(file)
+            .map(Vector::from)
+            .map_err(|err| log::error!("failed to read search history: {}", err))
+            .ok()
+    }
+
+    pub fn save_history(&self) {
+        let path = match Config::config_dir() {
+            Some(dir) => dir.join(SEARCH_HISTORY_FILENAME),
+            None => return,
+        };
+        let history: Vec<&String> = self.history.iter().collect();
+        let result = File::create(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|file| serde_json::to_writer(file, &history).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            log::error!("failed to save search history: {}", err);
+        }
+    }
+}
+
+#[derive(Clone, Debug, Data)]
+pub enum SearchSuggestion {
+    Query(String),
+    Artist(ArtistLink),
+    Album(AlbumLink),
+    Track(Arc<Track>),
+}
+
+impl SearchSuggestion {
+    pub fn title(&self) -> String {
+        match self {
+            Self::Query(query) => query.clone(),
+            Self::Artist(link) => link.name.to_string(),
+            Self::Album(link) => link.name.to_string(),
+            Self::Track(track) => format!("{} – {}", track.name, track.artist_name()),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Query(_) => "Recent",
+            Self::Artist(_) => "Artist",
+            Self::Album(_) => "Album",
+            Self::Track(_) => "Track",
+        }
+    }
+}
+
+#[derive(Clone, Data, Lens)]
+pub struct SuggestionRow {
+    pub suggestion: SearchSuggestion,
+    pub selected: bool,
 }
 
 #[derive(Clone, Data, Lens)]
@@ -15,4 +166,53 @@ pub struct SearchResults {
     pub albums: Vector<Album>,
     pub tracks: Vector<Arc<Track>>,
     pub playlists: Vector<Playlist>,
+    pub shows: Vector<Show>,
+    /// Types with more results than the ones shown.
+    pub more: HashSet<SearchTopic>,
+    /// Offset of the next page, when the results are of a single type.
+    pub next_offset: Option<usize>,
+}
+
+impl SearchResults {
+    /// Add the next page of results of a single type.
+    pub fn append(&mut self, page: SearchResults) {
+        self.artists.append(page.artists);
+        self.albums.append(page.albums);
+        self.tracks.append(page.tracks);
+        self.playlists.append(page.playlists);
+        self.shows.append(page.shows);
+        self.next_offset = page.next_offset;
+    }
+}
+
+#[derive(Copy, Clone, Debug, Data, Eq, PartialEq, Hash)]
+pub enum SearchTopic {
+    Artist,
+    Album,
+    Track,
+    Playlist,
+    Show,
+}
+
+impl SearchTopic {
+    /// Name of the type in the search API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Artist => "artist",
+            Self::Album => "album",
+            Self::Track => "track",
+            Self::Playlist => "playlist",
+            Self::Show => "show",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Artist => "Artists",
+            Self::Album => "Albums",
+            Self::Track => "Tracks",
+            Self::Playlist => "Playlists",
+            Self::Show => "Podcasts",
+        }
+    }
 }
@@ -0,0 +1,188 @@
+use crate::data::{Config, Image, Promise, Track, TrackId};
+use chrono::NaiveDate;
+use druid::{
+    im::{HashMap, Vector},
+    Data, Lens,
+};
+use psst_core::item_id::{ItemId, ItemIdType};
+use serde::{Deserialize, Serialize};
+use std::{fs::File, sync::Arc, time::Duration};
+
+// Episodes with less than this left to play count as played.
+const PLAYED_THRESHOLD: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Data, Lens)]
+pub struct ShowDetail {
+    pub show: Promise<Show, ShowLink>,
+    pub episodes: Promise<ShowEpisodes, ShowLink>,
+}
+
+#[derive(Clone, Data, Lens, Deserialize)]
+pub struct Show {
+    pub id: Arc<str>,
+    pub name: Arc<str>,
+    pub publisher: Arc<str>,
+    #[serde(default)]
+    pub images: Vector<Image>,
+    #[serde(default = "super::utils::default_str")]
+    pub description: Arc<str>,
+}
+
+impl Show {
+    pub fn image(&self, width: f64, height: f64) -> Option<&Image> {
+        Image::select(&self.images, width, height)
+    }
+
+    pub fn url(&self) -> String {
+        format!("https://open.spotify.com/show/{id}", id = self.id)
+    }
+
+    pub fn link(&self) -> ShowLink {
+        ShowLink {
+            id: self.id.clone(),
+            name: self.name.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Data, Lens, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct ShowLink {
+    pub id: Arc<str>,
+    pub name: Arc<str>,
+}
+
+#[derive(Clone, Data, Lens)]
+pub struct ShowEpisodes {
+    pub show: ShowLink,
+    pub episodes: Vector<Arc<Episode>>,
+}
+
+#[derive(Clone, Debug, Data, Lens, Deserialize)]
+pub struct Episode {
+    pub id: Arc<str>,
+    pub name: Arc<str>,
+    #[serde(default = "super::utils::default_str")]
+    pub description: Arc<str>,
+    #[serde(rename = "duration_ms")]
+    #[serde(deserialize_with = "super::utils::deserialize_millis")]
+    pub duration: Duration,
+    #[serde(default)]
+    pub images: Vector<Image>,
+    #[serde(default)]
+    pub explicit: bool,
+    pub is_playable: Option<bool>,
+    #[serde(deserialize_with = "super::utils::deserialize_date_option")]
+    #[data(same_fn = "PartialEq::eq")]
+    pub release_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub resume_point: Option<ResumePoint>,
+}
+
+#[derive(Clone, Debug, Data, Lens, Deserialize)]
+pub struct ResumePoint {
+    pub fully_played: bool,
+    #[serde(rename = "resume_position_ms")]
+    #[serde(deserialize_with = "super::utils::deserialize_millis")]
+    pub resume_position: Duration,
+}
+
+impl Episode {
+    pub fn track_id(&self) -> TrackId {
+        ItemId::from_base62(&self.id, ItemIdType::Podcast)
+            .map(TrackId::from)
+            .unwrap_or(TrackId::INVALID)
+    }
+
+    pub fn release(&self) -> String {
+        self.release_date
+            .as_ref()
+            .map(|date| date.format("%B %d, %Y").to_string())
+            .unwrap_or_else(|| '-'.to_string())
+    }
+
+    /// Episodes go through the same playback queue as tracks, so make a
+    /// track out of this episode.
+    pub fn to_track(&self) -> Arc<Track> {
+        Arc::new(Track {
+            id: self.track_id(),
+            name: self.name.clone(),
+            album: None,
+            artists: Vector::new(),
+            duration: self.duration,
+            disc_number: 0,
+            track_number: 0,
+            explicit: self.explicit,
+            is_local: false,
+            is_playable: self.is_playable,
+            is_available: None,
+            linked_from: None,
+            popularity: None,
+        })
+    }
+
+    /// How far the episode has been listened to, preferring the locally
+    /// remembered position over the one reported by Spotify.
+    pub fn progress(&self, positions: &EpisodePositions) -> Duration {
+        match (positions.get(&self.id), &self.resume_point) {
+            (Some(position), _) => position,
+            (None, Some(resume)) if resume.fully_played => self.duration,
+            (None, Some(resume)) => resume.resume_position,
+            (None, None) => Duration::default(),
+        }
+    }
+
+    pub fn is_played(&self, positions: &EpisodePositions) -> bool {
+        self.progress(positions) + PLAYED_THRESHOLD >= self.duration
+    }
+
+    /// Position to continue the playback from, `None` if the episode should
+    /// be played from the start.
+    pub fn resume_position(&self, positions: &EpisodePositions) -> Option<Duration> {
+        let progress = self.progress(positions);
+        if progress > Duration::default() && !self.is_played(positions) {
+            Some(progress)
+        } else {
+            None
+        }
+    }
+}
+
+const EPISODE_POSITIONS_FILENAME: &str = "episodes.json";
+
+/// Playback positions of the started episodes, persisted between the runs of
+/// the application.
+#[derive(Clone, Debug, Default, Data, Serialize, Deserialize)]
+pub struct EpisodePositions {
+    positions: HashMap<Arc<str>, Duration>,
+}
+
+impl EpisodePositions {
+    pub fn get(&self, episode_id: &str) -> Option<Duration> {
+        self.positions.get(episode_id).copied()
+    }
+
+    pub fn set(&mut self, episode_id: Arc<str>, position: Duration) {
+        self.positions.insert(episode_id, position);
+    }
+
+    pub fn load() -> Option<Self> {
+        let path = Config::config_dir()?.join(EPISODE_POSITIONS_FILENAME);
+        let file = File::open(&path).ok()?;
+        serde_json::from_reader(file)
+            .map_err(|err| log::error!("failed to read episode positions: {}", err))
+            .ok()
+    }
+
+    pub fn save(&self) {
+        let path = match Config::config_dir() {
+            Some(dir) => dir.join(EPISODE_POSITIONS_FILENAME),
+            None => return,
+        };
+        let result = File::create(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|file| serde_json::to_writer(file, self).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            log::error!("failed to save episode positions: {}", err);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::data::{Artist, Promise, Track};
+use druid::{im::Vector, Data, Lens};
+use std::sync::Arc;
+
+#[derive(Clone, Data, Lens)]
+pub struct TopItems {
+    pub range: TopRange,
+    pub tracks: Promise<TopTracks, TopRange>,
+    pub artists: Promise<Vector<Artist>, TopRange>,
+}
+
+#[derive(Clone, Data, Lens)]
+pub struct TopTracks {
+    pub range: TopRange,
+    pub tracks: Vector<Arc<Track>>,
+}
+
+/// Period of time the top items are computed over.
+#[derive(Copy, Clone, Debug, Data, Eq, PartialEq, Hash)]
+pub enum TopRange {
+    /// Approximately the last 4 weeks.
+    ShortTerm,
+    /// Approximately the last 6 months.
+    MediumTerm,
+    /// Several years of data.
+    LongTerm,
+}
+
+impl TopRange {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TopRange::ShortTerm => "short_term",
+            TopRange::MediumTerm => "medium_term",
+            TopRange::LongTerm => "long_term",
+        }
+    }
+
+    pub fn to_title(&self) -> &'static str {
+        match self {
+            TopRange::ShortTerm => "Last 4 Weeks",
+            TopRange::MediumTerm => "Last 6 Months",
+            TopRange::LongTerm => "All Time",
+        }
+    }
+}
@@ -1,10 +1,19 @@
 use crate::data::{AlbumLink, ArtistLink};
-use druid::{im::Vector, Data, Lens};
+use druid::{
+    im::{HashMap, Vector},
+    Data, Lens,
+};
 use psst_core::item_id::{ItemId, ItemIdType};
-use serde::Deserialize;
-use std::{convert::TryFrom, ops::Deref, str::FromStr, sync::Arc, time::Duration};
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::TryFrom,
+    ops::Deref,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-#[derive(Clone, Debug, Data, Lens, Deserialize)]
+#[derive(Clone, Debug, Data, Lens, Serialize, Deserialize)]
 pub struct Track {
     #[serde(default)]
     pub id: TrackId,
@@ -12,16 +21,189 @@ pub struct Track {
     pub album: Option<AlbumLink>,
     pub artists: Vector<ArtistLink>,
     #[serde(rename = "duration_ms")]
+    #[serde(serialize_with = "super::utils::serialize_millis")]
     #[serde(deserialize_with = "super::utils::deserialize_millis")]
     pub duration: Duration,
     pub disc_number: usize,
     pub track_number: usize,
     pub explicit: bool,
     pub is_local: bool,
+    // Shadowed by `Track::is_playable()`, which also looks at the availability.
+    #[lens(ignore)]
     pub is_playable: Option<bool>,
+    /// Only sent if we don't ask for a specific market, and we only need to
+    /// know if the track is available in any of them.
+    #[serde(default, rename = "available_markets")]
+    #[serde(deserialize_with = "super::utils::deserialize_non_empty")]
+    #[serde(skip_serializing)]
+    pub is_available: Option<bool>,
+    /// Track originally requested, if Spotify replaced it with a version
+    /// playable in the user's market.
+    #[serde(default)]
+    pub linked_from: Option<LinkedTrack>,
     pub popularity: Option<u32>,
 }
 
+#[derive(Clone, Debug, Data, Lens, Serialize, Deserialize)]
+pub struct LinkedTrack {
+    pub id: TrackId,
+}
+
+/// Column of a track list, the tracks can be ordered by any of them.
+#[derive(Copy, Clone, Debug, Data, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum TrackColumn {
+    Number,
+    Title,
+    Artist,
+    Album,
+    Duration,
+    Popularity,
+    DateAdded,
+    Bpm,
+    Key,
+}
+
+impl TrackColumn {
+    /// Columns the user can show or hide, in the order they are offered in.
+    pub const CONFIGURABLE: [TrackColumn; 8] = [
+        Self::Number,
+        Self::Artist,
+        Self::Album,
+        Self::Popularity,
+        Self::Duration,
+        Self::Bpm,
+        Self::Key,
+        Self::DateAdded,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Number => "#",
+            Self::Title => "Title",
+            Self::Artist => "Artist",
+            Self::Album => "Album",
+            Self::Duration => "Duration",
+            Self::Popularity => "Popularity",
+            Self::DateAdded => "Date Added",
+            Self::Bpm => "BPM",
+            Self::Key => "Key",
+        }
+    }
+}
+
+/// Kind of page a track list is shown on.  Each of them remembers the columns
+/// the user picked for it.
+#[derive(Copy, Clone, Debug, Data, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum TrackView {
+    Album,
+    Playlist,
+    SavedTracks,
+    ArtistTopTracks,
+    SearchResults,
+    Radio,
+    TopTracks,
+}
+
+#[derive(Copy, Clone, Debug, Data, Eq, PartialEq)]
+pub struct TrackSort {
+    pub column: TrackColumn,
+    pub descending: bool,
+}
+
+impl TrackSort {
+    /// Sort to switch to after clicking `column`: ascending first, then
+    /// descending, then back to the order of the origin.
+    pub fn toggle(sort: Option<TrackSort>, column: TrackColumn) -> Option<TrackSort> {
+        match sort {
+            Some(sort) if sort.column == column && sort.descending => None,
+            Some(sort) if sort.column == column => Some(TrackSort {
+                column,
+                descending: true,
+            }),
+            _ => Some(TrackSort {
+                column,
+                descending: false,
+            }),
+        }
+    }
+}
+
+/// Order the track list of the current page is shown in.  It is worked out
+/// once for a given set of tracks, filter and sort, and kept until one of
+/// them changes.
+#[derive(Clone, Default)]
+pub struct ShownTracks {
+    cached: Arc<Mutex<Option<(ShownKey, Arc<[usize]>)>>>,
+}
+
+/// Everything the shown order of a track list depends on.
+#[derive(Clone, Data)]
+pub struct ShownKey {
+    pub tracks: Vector<Arc<Track>>,
+    pub filter: String,
+    pub sort: Option<TrackSort>,
+    #[data(same_fn = "HashMap::ptr_eq")]
+    pub audio_features: HashMap<TrackId, AudioFeatures>,
+}
+
+impl ShownTracks {
+    /// Positions of the shown tracks in their list, computing them with
+    /// `compute` only if they are not cached for `key` yet.
+    pub fn positions(&self, key: ShownKey, compute: impl FnOnce() -> Vec<usize>) -> Arc<[usize]> {
+        let mut cached = self.cached.lock().unwrap();
+        match &*cached {
+            Some((cached_key, positions)) if cached_key.same(&key) => positions.clone(),
+            _ => {
+                let positions: Arc<[usize]> = compute().into();
+                *cached = Some((key, positions.clone()));
+                positions
+            }
+        }
+    }
+}
+
+/// Direction the cursor of a track list moves in.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CursorMove {
+    Previous,
+    Next,
+    First,
+    Last,
+}
+
+/// Tracks selected in the track list of the current page.
+#[derive(Clone, Debug, Default, Data, Lens)]
+pub struct TrackSelection {
+    /// Positions of the tracks in their list, in the order they are shown in.
+    pub positions: Vector<usize>,
+    pub tracks: Vector<Arc<Track>>,
+    /// Position a shift-click selects the tracks from.
+    pub anchor: Option<usize>,
+    /// Position of the row the arrow keys move from.
+    pub cursor: Option<usize>,
+}
+
+impl TrackSelection {
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn contains(&self, position: usize) -> bool {
+        self.positions.contains(&position)
+    }
+
+    pub fn clear(&mut self) {
+        self.positions.clear();
+        self.tracks.clear();
+        self.anchor = None;
+        self.cursor = None;
+    }
+}
+
 impl Track {
     pub fn artist_name(&self) -> String {
         self.artists
@@ -38,12 +220,34 @@ impl Track {
     }
 
     pub fn url(&self) -> String {
-        format!("https://open.spotify.com/track/{}", self.id.to_base62())
+        format!(
+            "https://open.spotify.com/track/{}",
+            self.original_id().to_base62()
+        )
+    }
+
+    pub fn uri(&self) -> String {
+        format!("spotify:track:{}", self.original_id().to_base62())
+    }
+
+    /// ID of the track as it appears in albums and playlists, before any
+    /// relinking.
+    pub fn original_id(&self) -> TrackId {
+        self.linked_from
+            .as_ref()
+            .map_or(self.id, |linked_from| linked_from.id)
+    }
+
+    /// Tracks restricted in the user's market, for which Spotify doesn't have
+    /// a playable replacement, can't be played.
+    pub fn is_playable(&self) -> bool {
+        !self.is_local && self.is_playable != Some(false) && self.is_available != Some(false)
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Serialize, Deserialize)]
 #[serde(try_from = "String")]
+#[serde(into = "String")]
 pub struct TrackId(ItemId);
 
 impl TrackId {
@@ -96,11 +300,97 @@ impl TryFrom<String> for TrackId {
     }
 }
 
+impl From<TrackId> for String {
+    fn from(id: TrackId) -> Self {
+        id.to_base62()
+    }
+}
+
+#[derive(Clone, Data, Lens)]
+pub struct TrackInfo {
+    pub track: Arc<Track>,
+    pub features: AudioFeatures,
+}
+
+#[derive(Clone, Data, Debug, Serialize, Deserialize)]
+pub struct AudioFeatures {
+    pub id: TrackId,
+    /// Estimated tempo in beats per minute.
+    pub tempo: f64,
+    /// Pitch class of the key, -1 if no key was detected.
+    pub key: i32,
+    /// 1 for major, 0 for minor.
+    pub mode: i32,
+    pub time_signature: i32,
+    pub energy: f64,
+    pub danceability: f64,
+    pub valence: f64,
+    pub acousticness: f64,
+    /// Average loudness in decibels.
+    pub loudness: f64,
+}
+
+impl AudioFeatures {
+    pub fn key_name(&self) -> Option<String> {
+        const PITCH_CLASSES: [&str; 12] = [
+            "C",
+            "C♯/D♭",
+            "D",
+            "D♯/E♭",
+            "E",
+            "F",
+            "F♯/G♭",
+            "G",
+            "G♯/A♭",
+            "A",
+            "A♯/B♭",
+            "B",
+        ];
+
+        let pitch = PITCH_CLASSES.get(usize::try_from(self.key).ok()?)?;
+        let mode = if self.mode == 1 { "major" } else { "minor" };
+        Some(format!("{} {}", pitch, mode))
+    }
+
+    /// Short name of the key, i.e. "F♯m" for F sharp minor.
+    pub fn key_abbreviation(&self) -> Option<String> {
+        const PITCH_CLASSES: [&str; 12] = [
+            "C", "C♯", "D", "E♭", "E", "F", "F♯", "G", "A♭", "A", "B♭", "B",
+        ];
+
+        let pitch = PITCH_CLASSES.get(usize::try_from(self.key).ok()?)?;
+        let mode = if self.mode == 1 { "" } else { "m" };
+        Some(format!("{}{}", pitch, mode))
+    }
+}
+
 #[derive(Clone, Data, Debug, Deserialize)]
 pub struct AudioAnalysis {
+    #[serde(default)]
+    pub beats: Vector<TimeInterval>,
     pub segments: Vector<AudioSegment>,
 }
 
+impl AudioAnalysis {
+    /// Find the beat closest to `position`, as long as it's no further than
+    /// `tolerance` away.
+    pub fn nearest_beat(&self, position: Duration, tolerance: Duration) -> Option<Duration> {
+        self.beats
+            .iter()
+            .map(|beat| beat.start)
+            .min_by_key(|&start| abs_diff(start, position))
+            .filter(|&start| abs_diff(start, position) <= tolerance)
+    }
+}
+
+fn abs_diff(a: Duration, b: Duration) -> Duration {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
 #[derive(Clone, Data, Debug, Deserialize)]
 pub struct AudioSegment {
     #[serde(flatten)]
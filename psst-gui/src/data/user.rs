@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 #[derive(Clone, Data, Lens, Deserialize)]
 pub struct UserProfile {
+    pub id: Arc<str>,
     pub display_name: Arc<str>,
     pub email: Arc<str>,
 }
@@ -1,6 +1,6 @@
 use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use druid::{im::Vector, Data, Lens};
-use serde::{Deserialize, Deserializer};
+use serde::{de::IgnoredAny, Deserialize, Deserializer, Serialize, Serializer};
 use std::{
     sync::Arc,
     time::{Duration, SystemTime},
@@ -49,7 +49,19 @@ pub struct Page<T: Clone> {
     pub total: usize,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Hash, Data, Deserialize)]
+impl<T: Clone> Page<T> {
+    /// Offset of the page following this one, `None` if this is the last one.
+    pub fn next_offset(&self) -> Option<usize> {
+        let next = self.offset + self.limit;
+        if next < self.total {
+            Some(next)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Data, Serialize, Deserialize)]
 pub struct Image {
     pub url: Arc<str>,
     pub width: Option<usize>,
@@ -64,12 +76,31 @@ impl Image {
             true // Unknown dimensions, treat as fitting.
         }
     }
+
+    /// Smallest of the `images` covering `width` x `height` pixels, or the
+    /// largest one if none of them does.
+    pub fn select(images: &Vector<Image>, width: f64, height: f64) -> Option<&Image> {
+        let area = |img: &&Image| img.width.unwrap_or(0) * img.height.unwrap_or(0);
+        images
+            .iter()
+            .filter(|img| !img.fits(width, height))
+            .min_by_key(area)
+            .or_else(|| images.iter().max_by_key(area))
+    }
 }
 
 pub fn default_str() -> Arc<str> {
     "".into()
 }
 
+pub fn deserialize_non_empty<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let items = Vec::<IgnoredAny>::deserialize(deserializer)?;
+    Ok(Some(!items.is_empty()))
+}
+
 pub fn deserialize_secs<'de, D>(deserializer: D) -> Result<Duration, D::Error>
 where
     D: Deserializer<'de>,
@@ -88,6 +119,13 @@ where
     Ok(duration)
 }
 
+pub fn serialize_millis<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_u64(duration.as_millis() as u64)
+}
+
 pub fn deserialize_date<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
 where
     D: Deserializer<'de>,
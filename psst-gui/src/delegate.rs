@@ -1,55 +1,260 @@
 use crate::{
     cmd,
-    data::{ArtistTracks, PlaylistTracks, SavedTracks, State},
+    data::{
+        ArtistTracks, CastRenderer, Nav, PaletteCommand, PaletteEntry, PlaybackOrigin,
+        PlaybackPayload, PlaybackState, PlaylistLink, PlaylistTracks, Promise, QueueBehavior,
+        QueuedTrack, RadioSeed, RadioTracks, RemoteCommand, RepeatMode, SavedPlayback,
+        SearchSuggestion, ShowEpisodes, SpotifyUrl, State, TopTracks, Track, TrackColumn, TrackId,
+        TrackInfo,
+    },
+    error::Error,
     ui,
-    webapi::WebApi,
+    webapi::SpotifyApi,
     widget::remote_image,
+    worker::{CancelGroup, CancelToken},
 };
 use druid::{
-    commands, im::Vector, image, AppDelegate, Application, Command, DelegateCtx, Env, Handled,
-    ImageBuf, Target, WindowId,
+    commands, im::Vector, image, AppDelegate, Application, Color, Command, DelegateCtx, Env,
+    ExtEventError, ExtEventSink, Handled, ImageBuf, Selector, Target, WindowId,
 };
 use lru_cache::LruCache;
-use std::{sync::Arc, thread};
+use psst_core::{
+    audio_cast,
+    connect::{ConnectEvent, ConnectLoad},
+    item_id::ItemIdType,
+};
+use rand::Rng;
+use std::{
+    any::Any,
+    collections::HashSet,
+    future::Future,
+    mem,
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::runtime::{self, Runtime};
+
+// How long to wait for the media renderers on the network to respond.
+const RENDERER_DISCOVERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Number of the recently played tracks shown on the home page.
+const HOME_RECENTLY_PLAYED_LENGTH: usize = 10;
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
 pub struct Delegate {
+    webapi: &'static dyn SpotifyApi,
+    /// Runs the background jobs.  The requests are async, so a page full of
+    /// covers shares a few threads and connections with everything else.
+    runtime: Runtime,
+    /// Jobs loading the data of the current page, cancelled when the user
+    /// navigates away.
+    route_jobs: CancelGroup,
     image_cache: LruCache<Arc<str>, ImageBuf>,
+    image_colors: LruCache<Arc<str>, Color>,
+    /// Tracks of the recently opened playlists, together with the snapshot
+    /// they belong to.
+    playlist_tracks: LruCache<Arc<str>, (Arc<str>, PlaylistTracks)>,
     main_window: Option<WindowId>,
     preferences_window: Option<WindowId>,
+    mini_player_window: Option<WindowId>,
+    track_info_window: Option<WindowId>,
+    /// Tracks and albums whose saved status was already asked for, so
+    /// scrolling back and forth doesn't repeat the checks.
+    checked_tracks: HashSet<TrackId>,
+    checked_albums: HashSet<Arc<str>>,
+    /// Tracks waiting for the running check to finish.  Rows ask one by one
+    /// as they scroll into view, so they get checked together.
+    pending_checked_tracks: Vector<TrackId>,
+    is_checking_tracks: bool,
+    /// True after a refused token, until the session logs in again.
+    is_reconnecting: bool,
+    /// The background refreshes wait until then after Spotify kept asking us
+    /// to slow down.
+    rate_limited_until: Option<Instant>,
 }
 
 impl Delegate {
-    pub fn new() -> Self {
+    pub fn new(webapi: &'static dyn SpotifyApi) -> Self {
+        const WORKER_COUNT: usize = 2;
         const IMAGE_CACHE_SIZE: usize = 256;
+        const IMAGE_COLOR_CACHE_SIZE: usize = 4096;
+        const PLAYLIST_TRACKS_CACHE_SIZE: usize = 32;
         let image_cache = LruCache::new(IMAGE_CACHE_SIZE);
+        let image_colors = LruCache::new(IMAGE_COLOR_CACHE_SIZE);
+        let runtime = runtime::Builder::new_multi_thread()
+            .worker_threads(WORKER_COUNT)
+            .thread_name("worker")
+            .enable_all()
+            .build()
+            .expect("failed to start the background runtime");
 
         Self {
+            webapi,
+            runtime,
+            route_jobs: CancelGroup::new(),
             image_cache,
+            image_colors,
+            playlist_tracks: LruCache::new(PLAYLIST_TRACKS_CACHE_SIZE),
             main_window: None,
             preferences_window: None,
+            mini_player_window: None,
+            track_info_window: None,
+            checked_tracks: HashSet::new(),
+            checked_albums: HashSet::new(),
+            pending_checked_tracks: Vector::new(),
+            is_checking_tracks: false,
+            is_reconnecting: false,
+            rate_limited_until: None,
         }
     }
 
-    pub fn with_main(main_window: WindowId) -> Self {
-        let mut this = Self::new();
+    pub fn with_main(webapi: &'static dyn SpotifyApi, main_window: WindowId) -> Self {
+        let mut this = Self::new(webapi);
         this.main_window.replace(main_window);
         this
     }
 
-    pub fn with_preferences(preferences_window: WindowId) -> Self {
-        let mut this = Self::new();
+    pub fn with_preferences(webapi: &'static dyn SpotifyApi, preferences_window: WindowId) -> Self {
+        let mut this = Self::new(webapi);
         this.preferences_window.replace(preferences_window);
         this
     }
 
-    fn spawn<F, T>(&self, f: F)
+    fn spawn<F>(&self, future: F)
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.runtime.spawn(future);
+    }
+
+    /// Like `spawn`, but for blocking calls that would hold up the other
+    /// jobs.
+    fn spawn_blocking<F, T>(&self, f: F)
     where
         F: FnOnce() -> T,
         F: Send + 'static,
         T: Send + 'static,
     {
-        // TODO: Use a thread pool.
-        thread::spawn(f);
+        self.runtime.spawn_blocking(f);
+    }
+
+    /// Like `spawn`, but for loading the data of the current page.  The job
+    /// doesn't run if the user navigates away before its turn comes, and its
+    /// results are dropped if they arrive after that.
+    fn spawn_for_route<F, Fut>(&self, ctx: &mut DelegateCtx, f: F)
+    where
+        F: FnOnce(RouteSink) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let sink = RouteSink {
+            sink: ctx.get_external_handle(),
+            token: self.route_jobs.token(),
+        };
+        self.spawn(async move {
+            if !sink.token.is_cancelled() {
+                f(sink).await;
+            }
+        });
+    }
+
+    /// Ask whether the pending tracks are saved, all in one go.
+    fn check_pending_tracks(&mut self, ctx: &mut DelegateCtx) {
+        if self.pending_checked_tracks.is_empty() {
+            return;
+        }
+        let unchecked = mem::take(&mut self.pending_checked_tracks);
+        self.is_checking_tracks = true;
+        let sink = ctx.get_external_handle();
+        let webapi = self.webapi;
+        self.spawn(async move {
+            let ids: Vec<TrackId> = unchecked.iter().copied().collect();
+            let result = webapi.contains_saved_tracks(&ids).await;
+            sink.submit_command(
+                cmd::UPDATE_CHECKED_TRACKS,
+                (unchecked, result),
+                Target::Auto,
+            )
+            .unwrap();
+        });
+    }
+
+    /// Load the tracks of the linked item in the background, and hand them
+    /// over to `on_loaded` unless there are none.
+    fn spawn_link_playback<F>(&self, ctx: &mut DelegateCtx, url: SpotifyUrl, on_loaded: F)
+    where
+        F: FnOnce(&ExtEventSink, PlaybackPayload) + Send + 'static,
+    {
+        let sink = ctx.get_external_handle();
+        let webapi = self.webapi;
+        self.spawn(async move {
+            match webapi.load_playback(&url).await {
+                Ok(payload) if !payload.tracks.is_empty() => on_loaded(&sink, payload),
+                Ok(_) => {
+                    log::info!("no tracks to play in {:?}", url);
+                }
+                Err(err) => {
+                    log::error!("failed to load {:?}: {:?}", url, err);
+                }
+            }
+        });
+    }
+
+    /// Load a paginated result set in the background, and submit every page
+    /// together with its offset as soon as it arrives, so the first results
+    /// can be shown right away.
+    fn spawn_pages<T, F>(
+        &self,
+        ctx: &mut DelegateCtx,
+        selector: Selector<(usize, Result<Vector<T>, Error>)>,
+        load: F,
+    ) where
+        T: Clone + Send + Sync + 'static,
+        F: for<'a> FnOnce(
+            &'static dyn SpotifyApi,
+            &'a mut (dyn FnMut(usize, Vector<T>) + Send),
+        ) -> BoxFuture<'a, Result<(), Error>>,
+        F: Send + 'static,
+    {
+        let sink = ctx.get_external_handle();
+        let webapi = self.webapi;
+        self.spawn(async move {
+            let mut next_offset = 0;
+            let mut on_page = |offset, items: Vector<T>| {
+                next_offset = offset + items.len();
+                sink.submit_command(selector, (offset, Ok(items)), Target::Auto)
+                    .unwrap();
+            };
+            let result = load(webapi, &mut on_page).await;
+            if let Err(err) = result {
+                sink.submit_command(selector, (next_offset, Err(err)), Target::Auto)
+                    .unwrap();
+            }
+        });
+    }
+}
+
+/// Event sink of a job loading the data of the current page.
+struct RouteSink {
+    sink: ExtEventSink,
+    token: CancelToken,
+}
+
+impl RouteSink {
+    /// Submit the command unless the user has navigated away in the meantime.
+    fn submit_command<T: Any + Send>(
+        &self,
+        selector: Selector<T>,
+        payload: impl Into<Box<T>>,
+        target: impl Into<Option<Target>>,
+    ) -> Result<(), ExtEventError> {
+        if self.token.is_cancelled() {
+            Ok(())
+        } else {
+            self.sink.submit_command(selector, payload, target)
+        }
     }
 }
 
@@ -86,23 +291,94 @@ impl AppDelegate<State> for Delegate {
                 }
             }
             Handled::Yes
+        } else if cmd.is(cmd::TOGGLE_MINI_PLAYER) {
+            match self.mini_player_window {
+                Some(id) => {
+                    ctx.submit_command(commands::CLOSE_WINDOW.to(id));
+                }
+                None => {
+                    let window = ui::mini_player_window();
+                    self.mini_player_window.replace(window.id);
+                    ctx.new_window(window);
+                }
+            }
+            Handled::Yes
+        } else if cmd.is(cmd::TOGGLE_QUEUE_PANEL) {
+            data.show_queue = !data.show_queue;
+            Handled::Yes
+        } else if let Handled::Yes = self.forward_from_mini_player(ctx, target, cmd) {
+            Handled::Yes
+        } else if cmd.is(cmd::NAVIGATE) || cmd.is(cmd::NAVIGATE_BACK) {
+            // Whatever the previous page was loading is of no use anymore.
+            self.route_jobs.cancel();
+            data.search.suggesting = false;
+            data.common_ctx.track_filter.clear();
+            data.common_ctx.track_sort = None;
+            data.common_ctx.track_selection.clear();
+            Handled::No
+        } else if cmd.is(cmd::REFRESH) {
+            // Load the current page again, bypassing the cache.
+            self.webapi.expire_cached(&data.route);
+            ctx.submit_command(cmd::NAVIGATE.with(data.route.clone()));
+            Handled::Yes
+        } else if let Some(&retrying) = cmd.get(cmd::UPDATE_RETRYING) {
+            data.retrying = retrying;
+            Handled::Yes
+        } else if let Some(err) = cmd.get(cmd::WEBAPI_ERROR) {
+            match err {
+                Error::Unauthorized if !self.is_reconnecting => {
+                    // Even a fresh token was refused, log in anew.
+                    log::warn!("access token refused, reconnecting the session");
+                    self.is_reconnecting = true;
+                    ctx.submit_command(cmd::SESSION_CONNECT);
+                }
+                Error::RateLimited { retry_after } => {
+                    self.rate_limited_until = Some(Instant::now() + *retry_after);
+                }
+                // The page that asked for the item tells it's gone, see
+                // `error_widget`.
+                _ => {}
+            }
+            Handled::Yes
         } else if let Some(text) = cmd.get(cmd::COPY) {
             Application::global().clipboard().put_string(&text);
             Handled::Yes
+        } else if let Some(url) = cmd.get(cmd::OPEN_EXTERNAL).cloned() {
+            // Waits for the opener to exit, keep it off the UI thread.
+            self.spawn_blocking(move || {
+                if let Err(err) = open::that(&url) {
+                    log::error!("failed to open {}: {:?}", url, err);
+                }
+            });
+            Handled::Yes
         } else if let Handled::Yes = self.command_image(ctx, target, cmd, data) {
             Handled::Yes
         } else if let Handled::Yes = self.command_playback(ctx, target, cmd, data) {
             Handled::Yes
         } else if let Handled::Yes = self.command_playlist(ctx, target, cmd, data) {
             Handled::Yes
+        } else if let Handled::Yes = self.command_connect(ctx, target, cmd, data) {
+            Handled::Yes
         } else if let Handled::Yes = self.command_library(ctx, target, cmd, data) {
             Handled::Yes
+        } else if let Handled::Yes = self.command_home(ctx, target, cmd, data) {
+            Handled::Yes
         } else if let Handled::Yes = self.command_album(ctx, target, cmd, data) {
             Handled::Yes
         } else if let Handled::Yes = self.command_artist(ctx, target, cmd, data) {
             Handled::Yes
+        } else if let Handled::Yes = self.command_show(ctx, target, cmd, data) {
+            Handled::Yes
+        } else if let Handled::Yes = self.command_top(ctx, target, cmd, data) {
+            Handled::Yes
+        } else if let Handled::Yes = self.command_radio(ctx, target, cmd, data) {
+            Handled::Yes
+        } else if let Handled::Yes = self.command_track_info(ctx, target, cmd, data) {
+            Handled::Yes
         } else if let Handled::Yes = self.command_search(ctx, target, cmd, data) {
             Handled::Yes
+        } else if let Handled::Yes = self.command_palette(ctx, target, cmd, data) {
+            Handled::Yes
         } else {
             Handled::No
         }
@@ -118,14 +394,54 @@ impl AppDelegate<State> for Delegate {
         if self.preferences_window == Some(id) {
             self.preferences_window.take();
             data.preferences.reset();
+            data.config.save();
+            self.webapi
+                .set_image_cache_limit(data.config.image_cache_limit());
+        }
+        if self.mini_player_window == Some(id) {
+            self.mini_player_window.take();
+        }
+        if self.track_info_window == Some(id) {
+            self.track_info_window.take();
+            data.track_info.clear();
         }
         if self.main_window == Some(id) {
             self.main_window.take();
+            match SavedPlayback::new(&data.playback) {
+                Some(saved) => saved.save(),
+                None => SavedPlayback::clear(),
+            }
+            data.remember_episode_position();
+            data.common_ctx.episode_positions.save();
+            data.search.save_history();
         }
     }
 }
 
 impl Delegate {
+    /// The playback is driven from the root widget of the main window, so
+    /// controls clicked in the mini player need to be delivered there.
+    fn forward_from_mini_player(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        target: Target,
+        cmd: &Command,
+    ) -> Handled {
+        let is_playback_command = cmd.is(cmd::PLAY_PREVIOUS)
+            || cmd.is(cmd::PLAY_PAUSE)
+            || cmd.is(cmd::PLAY_RESUME)
+            || cmd.is(cmd::PLAY_NEXT)
+            || cmd.is(cmd::PLAY_STOP)
+            || cmd.is(cmd::PLAY_SEEK);
+        match (self.mini_player_window, self.main_window) {
+            (Some(mini), Some(main)) if is_playback_command && target == Target::Window(mini) => {
+                ctx.submit_command(cmd.clone().to(main));
+                Handled::Yes
+            }
+            _ => Handled::No,
+        }
+    }
+
     fn command_image(
         &mut self,
         ctx: &mut DelegateCtx,
@@ -134,30 +450,37 @@ impl Delegate {
         _data: &mut State,
     ) -> Handled {
         if let Some(location) = cmd.get(remote_image::REQUEST_DATA).cloned() {
-            let sink = ctx.get_external_handle();
+            let color = self.image_color(&location);
             if let Some(image_buf) = self.image_cache.get_mut(&location).cloned() {
                 let payload = remote_image::ImagePayload {
                     location,
                     image_buf,
+                    color: color.unwrap_or(Color::TRANSPARENT),
                 };
-                sink.submit_command(remote_image::PROVIDE_DATA, payload, target)
-                    .unwrap();
+                ctx.submit_command(remote_image::PROVIDE_DATA.with(payload).to(target));
             } else {
-                self.spawn(move || {
-                    let dyn_image = WebApi::global()
-                        .get_image(&location, image::ImageFormat::Jpeg)
-                        .unwrap();
-                    let image_buf = ImageBuf::from_dynamic_image(dyn_image);
-                    let payload = remote_image::ImagePayload {
-                        location,
-                        image_buf,
+                if let Some(color) = color {
+                    let payload = remote_image::ColorPayload {
+                        location: location.clone(),
+                        color,
                     };
-                    sink.submit_command(remote_image::PROVIDE_DATA, payload, target)
-                        .unwrap();
-                });
+                    ctx.submit_command(remote_image::PROVIDE_COLOR.with(payload).to(target));
+                }
+                self.load_image(ctx, location, target);
+            }
+            Handled::Yes
+        } else if let Some(location) = cmd.get(remote_image::REQUEST_COLOR).cloned() {
+            if let Some(color) = self.image_color(&location) {
+                let payload = remote_image::ColorPayload { location, color };
+                ctx.submit_command(remote_image::PROVIDE_COLOR.with(payload).to(target));
+            } else {
+                // The color is only known after the image is loaded.
+                self.load_image(ctx, location, target);
             }
             Handled::Yes
         } else if let Some(payload) = cmd.get(remote_image::PROVIDE_DATA).cloned() {
+            self.image_colors
+                .insert(payload.location.clone(), payload.color);
             self.image_cache.insert(payload.location, payload.image_buf);
             Handled::No
         } else {
@@ -165,6 +488,89 @@ impl Delegate {
         }
     }
 
+    /// Dominant color of the image at `location`, if it was loaded before.
+    fn image_color(&mut self, location: &Arc<str>) -> Option<Color> {
+        if let Some(color) = self.image_colors.get_mut(location) {
+            return Some(color.clone());
+        }
+        let [r, g, b] = self.webapi.get_image_color(location)?;
+        let color = Color::rgb8(r, g, b);
+        self.image_colors.insert(location.clone(), color.clone());
+        Some(color)
+    }
+
+    /// Reload the parts of the library that are shown, in the background
+    /// and without deferring them, so nothing flickers.
+    fn refresh_library(&self, ctx: &mut DelegateCtx, data: &State) {
+        let playlists = data.library.playlists.is_resolved();
+        let saved_albums = data.library.saved_albums.is_resolved();
+        let saved_tracks = data.library.saved_tracks.is_resolved();
+        let sink = ctx.get_external_handle();
+        let webapi = self.webapi;
+        self.spawn(async move {
+            if playlists {
+                let mut all = Vector::new();
+                let result = webapi
+                    .get_playlists(&mut |_, page| all.append(page))
+                    .await
+                    .map(|_| all);
+                sink.submit_command(cmd::REFRESHED_PLAYLISTS, result, Target::Auto)
+                    .unwrap();
+            }
+            if saved_albums {
+                let mut all = Vector::new();
+                let result = webapi
+                    .get_saved_albums(&mut |_, page| all.append(page))
+                    .await
+                    .map(|_| all);
+                sink.submit_command(cmd::REFRESHED_SAVED_ALBUMS, result, Target::Auto)
+                    .unwrap();
+            }
+            if saved_tracks {
+                // Changes show up at the start of the list, the first page is
+                // enough to notice them.
+                let result = webapi.get_saved_tracks(0).await;
+                sink.submit_command(cmd::REFRESHED_SAVED_TRACKS, result, Target::Auto)
+                    .unwrap();
+            }
+        });
+    }
+
+    /// Keep the tracks of the open playlist, so they can be shown again
+    /// without a fetch until the playlist snapshot changes.
+    fn remember_playlist_tracks(&mut self, data: &State) {
+        if let Promise::Resolved(tracks) = &data.playlist.tracks {
+            if let Some(snapshot_id) = data.playlist_snapshot(&tracks.id) {
+                self.playlist_tracks
+                    .insert(tracks.id.clone(), (snapshot_id, tracks.clone()));
+            }
+        }
+    }
+
+    fn load_image(&self, ctx: &mut DelegateCtx, location: Arc<str>, target: Target) {
+        let sink = ctx.get_external_handle();
+        let webapi = self.webapi;
+        self.spawn(async move {
+            let dyn_image = match webapi.get_image(&location, image::ImageFormat::Jpeg).await {
+                Ok(dyn_image) => dyn_image,
+                Err(err) => {
+                    // The widget keeps showing its placeholder.
+                    log::warn!("failed to load image {}: {:?}", location, err);
+                    return;
+                }
+            };
+            let [r, g, b] = webapi.image_color(&location, &dyn_image);
+            let image_buf = ImageBuf::from_dynamic_image(dyn_image);
+            let payload = remote_image::ImagePayload {
+                location,
+                image_buf,
+                color: Color::rgb8(r, g, b),
+            };
+            sink.submit_command(remote_image::PROVIDE_DATA, payload, target)
+                .unwrap();
+        });
+    }
+
     fn command_playlist(
         &mut self,
         ctx: &mut DelegateCtx,
@@ -173,152 +579,373 @@ impl Delegate {
         data: &mut State,
     ) -> Handled {
         if cmd.is(cmd::SESSION_CONNECTED) {
-            data.library_mut().playlists.defer_default();
+            self.is_reconnecting = false;
+            if data.offline {
+                data.offline = false;
+                self.webapi.set_offline(false);
+                // Replace whatever was loaded from the cache.
+                ctx.submit_command(cmd::NAVIGATE.with(data.route.clone()));
+            }
+            ctx.submit_command(cmd::LOAD_PLAYLISTS);
+            ctx.submit_command(cmd::LOAD_HOME);
             data.user_profile.defer_default();
+            let sink = ctx.get_external_handle();
+            let webapi = self.webapi;
+            self.spawn(async move {
+                let result = webapi.get_user_profile().await;
+                sink.submit_command(cmd::UPDATE_USER_PROFILE, result, Target::Auto)
+                    .unwrap();
+            });
+            ctx.submit_command(cmd::LOAD_CONNECT_DEVICES);
+            Handled::Yes
+        } else if cmd.is(cmd::SESSION_DISCONNECTED) {
+            if !data.offline {
+                data.offline = true;
+                self.webapi.set_offline(true);
+                // Show what's available in the cache.
+                ctx.submit_command(cmd::LOAD_PLAYLISTS);
+                ctx.submit_command(cmd::NAVIGATE.with(data.route.clone()));
+            }
+            Handled::Yes
+        } else if cmd.is(cmd::LOAD_PLAYLISTS) {
+            data.library_mut().playlists.defer_default();
+            self.spawn_pages(ctx, cmd::UPDATE_PLAYLISTS, |webapi, on_page| {
+                webapi.get_playlists(on_page)
+            });
+            Handled::Yes
+        } else if let Some((offset, result)) = cmd.get(cmd::UPDATE_PLAYLISTS).cloned() {
+            match (offset, result) {
+                (0, result) => {
+                    if data.library.playlists.is_deferred(&()) {
+                        data.library_mut().playlists.resolve_or_reject(result);
+                    }
+                }
+                (_, Ok(page)) => {
+                    if let Promise::Resolved(playlists) = &mut data.library_mut().playlists {
+                        for playlist in page {
+                            if !playlists.iter().any(|p| p.id == playlist.id) {
+                                playlists.push_back(playlist);
+                            }
+                        }
+                    }
+                }
+                (_, Err(err)) => {
+                    log::error!("failed to load more playlists: {:?}", err);
+                }
+            }
+            data.update_editable_playlists();
+            Handled::Yes
+        } else if let Some(result) = cmd.get(cmd::UPDATE_USER_PROFILE).cloned() {
+            if data.user_profile.is_deferred(&()) {
+                data.user_profile.resolve_or_reject(result);
+                data.update_editable_playlists();
+            }
             Handled::Yes
         } else if let Some(link) = cmd.get(cmd::LOAD_PLAYLIST_DETAIL).cloned() {
-            let sink = ctx.get_external_handle();
             data.playlist.playlist.defer(link.clone());
             data.playlist.tracks.defer(link.clone());
-            self.spawn(move || {
-                let result = WebApi::global().get_playlist_tracks(&link.id);
-                sink.submit_command(cmd::UPDATE_PLAYLIST_TRACKS, (link, result), Target::Auto)
+            data.playlist.name_input = link.name.to_string();
+            let snapshot_id = data.playlist_snapshot(&link.id);
+            // Nothing to fetch if the playlist didn't change since we saw it.
+            if let (Some(snapshot_id), Some((loaded_snapshot_id, tracks))) =
+                (&snapshot_id, self.playlist_tracks.get_mut(&link.id))
+            {
+                if snapshot_id == loaded_snapshot_id {
+                    data.playlist.tracks.resolve_or_reject(Ok(tracks.clone()));
+                    return Handled::Yes;
+                }
+            }
+            let webapi = self.webapi;
+            self.spawn_for_route(ctx, move |sink| async move {
+                let result = webapi
+                    .get_playlist_tracks_page(&link, snapshot_id.as_deref(), 0)
+                    .await;
+                sink.submit_command(cmd::UPDATE_PLAYLIST_TRACKS, (link, 0, result), Target::Auto)
                     .unwrap();
             });
             Handled::Yes
-        } else if let Some((link, result)) = cmd.get(cmd::UPDATE_PLAYLIST_TRACKS).cloned() {
-            if data.playlist.tracks.is_deferred(&link) {
-                data.playlist
-                    .tracks
-                    .resolve_or_reject(result.map(|tracks| PlaylistTracks {
-                        id: link.id,
-                        name: link.name,
-                        tracks,
-                    }));
+        } else if cmd.is(cmd::LOAD_MORE_PLAYLIST_TRACKS) {
+            if let Promise::Resolved(tracks) = &data.playlist.tracks {
+                if let Some(offset) = tracks.next_offset {
+                    let link = tracks.link();
+                    let snapshot_id = data.playlist_snapshot(&link.id);
+                    let webapi = self.webapi;
+                    self.spawn_for_route(ctx, move |sink| async move {
+                        let result = webapi
+                            .get_playlist_tracks_page(&link, snapshot_id.as_deref(), offset)
+                            .await;
+                        sink.submit_command(
+                            cmd::UPDATE_PLAYLIST_TRACKS,
+                            (link, offset, result),
+                            Target::Auto,
+                        )
+                        .unwrap();
+                    });
+                }
             }
             Handled::Yes
-        } else {
-            Handled::No
-        }
-    }
-
-    fn command_library(
-        &mut self,
-        ctx: &mut DelegateCtx,
-        _target: Target,
-        cmd: &Command,
-        data: &mut State,
-    ) -> Handled {
-        if cmd.is(cmd::LOAD_SAVED_TRACKS) {
-            if data.library.saved_tracks.is_empty() || data.library.saved_tracks.is_rejected() {
-                data.library_mut().saved_tracks.defer_default();
-                let sink = ctx.get_external_handle();
-                self.spawn(move || {
-                    let result = WebApi::global().get_saved_tracks();
-                    sink.submit_command(cmd::UPDATE_SAVED_TRACKS, result, Target::Auto)
-                        .unwrap();
-                });
+        } else if let Some((link, offset, result)) = cmd.get(cmd::UPDATE_PLAYLIST_TRACKS).cloned() {
+            match (offset, result) {
+                (0, result) => {
+                    if data.playlist.tracks.is_deferred(&link) {
+                        data.playlist.tracks.resolve_or_reject(result);
+                        self.remember_playlist_tracks(data);
+                    }
+                }
+                (offset, Ok(page)) => {
+                    if let Promise::Resolved(tracks) = &mut data.playlist.tracks {
+                        // Ignore pages that don't continue the list we have.
+                        if tracks.id == link.id && tracks.next_offset == Some(offset) {
+                            tracks.tracks.append(page.tracks);
+                            tracks.next_offset = page.next_offset;
+                        }
+                    }
+                    self.remember_playlist_tracks(data);
+                }
+                (_, Err(err)) => {
+                    log::error!("failed to load more playlist tracks: {:?}", err);
+                }
             }
             Handled::Yes
-        } else if cmd.is(cmd::LOAD_SAVED_ALBUMS) {
-            if data.library.saved_albums.is_empty() || data.library.saved_albums.is_rejected() {
-                data.library_mut().saved_albums.defer_default();
+        } else if cmd.is(cmd::SAVE_QUEUE_AS_PLAYLIST) {
+            let track_ids: Vec<TrackId> = data
+                .playback
+                .queue
+                .iter()
+                .map(|queued| queued.track.id)
+                .collect();
+            if !track_ids.is_empty() {
+                let name = data
+                    .playback
+                    .now_playing
+                    .as_ref()
+                    .map(|now_playing| now_playing.origin.to_string())
+                    .unwrap_or_else(|| "Queue".to_string());
                 let sink = ctx.get_external_handle();
-                self.spawn(move || {
-                    let result = WebApi::global().get_saved_albums();
-                    sink.submit_command(cmd::UPDATE_SAVED_ALBUMS, result, Target::Auto)
+                let webapi = self.webapi;
+                self.spawn(async move {
+                    let result = async {
+                        let mut playlist = webapi
+                            .create_playlist(&name, "Saved from the play queue.")
+                            .await?;
+                        webapi
+                            .add_tracks_to_playlist(&playlist.id, &track_ids)
+                            .await?;
+                        playlist.track_count = track_ids.len();
+                        Ok(playlist)
+                    }
+                    .await;
+                    sink.submit_command(cmd::PLAYLIST_CREATED, result, Target::Auto)
                         .unwrap();
                 });
             }
             Handled::Yes
-        } else if let Some(result) = cmd.get(cmd::UPDATE_SAVED_TRACKS).cloned() {
+        } else if cmd.is(cmd::CREATE_PLAYLIST) {
+            let sink = ctx.get_external_handle();
+            let webapi = self.webapi;
+            self.spawn(async move {
+                let result = webapi.create_playlist("New Playlist", "").await;
+                sink.submit_command(cmd::PLAYLIST_CREATED, result, Target::Auto)
+                    .unwrap();
+            });
+            Handled::Yes
+        } else if let Some(result) = cmd.get(cmd::PLAYLIST_CREATED).cloned() {
             match result {
-                Ok(tracks) => {
-                    data.common_ctx.set_saved_tracks(&tracks);
-                    data.library_mut()
-                        .saved_tracks
-                        .resolve(SavedTracks { tracks });
+                Ok(playlist) => {
+                    let link = playlist.link();
+                    if let Promise::Resolved(playlists) = &mut data.library_mut().playlists {
+                        playlists.push_front(playlist);
+                    }
+                    data.update_editable_playlists();
+                    ctx.submit_command(cmd::NAVIGATE.with(Nav::PlaylistDetail(link)));
+                    // Let the user name the playlist right away.
+                    ctx.submit_command(cmd::SET_FOCUS.to(cmd::WIDGET_PLAYLIST_NAME_INPUT));
                 }
                 Err(err) => {
-                    data.common_ctx.set_saved_tracks(&Vector::new());
-                    data.library_mut().saved_tracks.reject(err);
+                    log::error!("failed to create playlist: {:?}", err);
                 }
-            };
+            }
+            Handled::Yes
+        } else if let Some(link) = cmd.get(cmd::EDIT_PLAYLIST).cloned() {
+            // The name gets edited in place on the playlist page.
+            ctx.submit_command(cmd::NAVIGATE.with(Nav::PlaylistDetail(link)));
+            ctx.submit_command(cmd::SET_FOCUS.to(cmd::WIDGET_PLAYLIST_NAME_INPUT));
             Handled::Yes
-        } else if let Some(result) = cmd.get(cmd::UPDATE_SAVED_ALBUMS).cloned() {
+        } else if let Some(name) = cmd.get(cmd::RENAME_PLAYLIST).cloned() {
+            if let Nav::PlaylistDetail(link) = &data.route {
+                let name = name.trim().to_string();
+                if !name.is_empty() && name != link.name.as_ref() {
+                    let link = link.clone();
+                    let sink = ctx.get_external_handle();
+                    let webapi = self.webapi;
+                    self.spawn(async move {
+                        let result = webapi.rename_playlist(&link.id, &name).await;
+                        let renamed = PlaylistLink {
+                            id: link.id,
+                            name: name.into(),
+                        };
+                        sink.submit_command(cmd::PLAYLIST_RENAMED, (renamed, result), Target::Auto)
+                            .unwrap();
+                    });
+                }
+            }
+            Handled::Yes
+        } else if let Some((renamed, result)) = cmd.get(cmd::PLAYLIST_RENAMED).cloned() {
             match result {
-                Ok(albums) => {
-                    data.common_ctx.set_saved_albums(&albums);
-                    data.library_mut().saved_albums.resolve(albums);
+                Ok(_) => {
+                    if let Promise::Resolved(playlists) = &mut data.library_mut().playlists {
+                        for playlist in playlists.iter_mut() {
+                            if playlist.id == renamed.id {
+                                playlist.name = renamed.name.clone();
+                            }
+                        }
+                    }
+                    data.update_editable_playlists();
+                    if let Nav::PlaylistDetail(link) = &mut data.route {
+                        if link.id == renamed.id {
+                            *link = renamed;
+                        }
+                    }
                 }
                 Err(err) => {
-                    data.common_ctx.set_saved_albums(&Vector::new());
-                    data.library_mut().saved_albums.reject(err);
+                    log::error!("failed to rename playlist: {:?}", err);
+                    if let Nav::PlaylistDetail(link) = &data.route {
+                        if link.id == renamed.id {
+                            data.playlist.name_input = link.name.to_string();
+                        }
+                    }
                 }
-            };
+            }
             Handled::Yes
-        } else if let Some(track) = cmd.get(cmd::SAVE_TRACK).cloned() {
-            let track_id = track.id.to_base62();
-            data.save_track(track);
-            self.spawn(move || {
-                let result = WebApi::global().save_track(&track_id);
-                if result.is_err() {
-                    // TODO: Refresh saved tracks.
+        } else if let Some((link, added)) = cmd.get(cmd::ADD_TO_PLAYLIST).cloned() {
+            // Show the tracks right away if the playlist is open.
+            if let Promise::Resolved(tracks) = &mut data.playlist.tracks {
+                // Otherwise the tracks come with the last page.
+                if tracks.id == link.id && tracks.next_offset.is_none() {
+                    tracks.tracks.append(added.clone());
                 }
-            });
-            Handled::Yes
-        } else if let Some(track_id) = cmd.get(cmd::UNSAVE_TRACK).cloned() {
-            data.unsave_track(&track_id);
-            self.spawn(move || {
-                let result = WebApi::global().unsave_track(&track_id.to_base62());
-                if result.is_err() {
-                    // TODO: Refresh saved tracks.
+            }
+            if let Some((_, tracks)) = self.playlist_tracks.get_mut(&link.id) {
+                if tracks.next_offset.is_none() {
+                    tracks.tracks.append(added.clone());
                 }
+            }
+            if let Promise::Resolved(playlists) = &mut data.library_mut().playlists {
+                for playlist in playlists.iter_mut() {
+                    if playlist.id == link.id {
+                        playlist.track_count += added.len();
+                    }
+                }
+            }
+            let track_ids: Vec<TrackId> = added.iter().map(|track| track.id).collect();
+            let sink = ctx.get_external_handle();
+            let webapi = self.webapi;
+            self.spawn(async move {
+                let result = webapi.add_tracks_to_playlist(&link.id, &track_ids).await;
+                sink.submit_command(cmd::PLAYLIST_CHANGED, (link, result), Target::Auto)
+                    .unwrap();
             });
             Handled::Yes
-        } else if let Some(album) = cmd.get(cmd::SAVE_ALBUM).cloned() {
-            let album_id = album.id.clone();
-            data.save_album(album);
-            self.spawn(move || {
-                let result = WebApi::global().save_album(&album_id);
-                if result.is_err() {
-                    // TODO: Refresh saved albums.
+        } else if let Some(tracks) = cmd.get(cmd::DRAG_TRACKS).cloned() {
+            data.common_ctx.dragged_tracks = tracks;
+            Handled::Yes
+        } else if let Some((link, from, to)) = cmd.get(cmd::MOVE_PLAYLIST_TRACK).cloned() {
+            let snapshot_id = data.playlist_snapshot(&link.id);
+            if let (Promise::Resolved(tracks), Some(snapshot_id)) =
+                (&mut data.playlist.tracks, snapshot_id)
+            {
+                let len = tracks.tracks.len();
+                let to = to.min(len.saturating_sub(1));
+                if tracks.id == link.id && from < len && from != to {
+                    let track = tracks.tracks.remove(from);
+                    tracks.tracks.insert(to, track);
+                    self.remember_playlist_tracks(data);
+                    // The API inserts before a position in the original order.
+                    let insert_before = if to > from { to + 1 } else { to };
+                    let sink = ctx.get_external_handle();
+                    let webapi = self.webapi;
+                    self.spawn(async move {
+                        let result = webapi
+                            .move_playlist_track(&link.id, from, insert_before, &snapshot_id)
+                            .await;
+                        sink.submit_command(cmd::PLAYLIST_CHANGED, (link, result), Target::Auto)
+                            .unwrap();
+                    });
                 }
-            });
+            }
             Handled::Yes
-        } else if let Some(link) = cmd.get(cmd::UNSAVE_ALBUM).cloned() {
-            data.unsave_album(&link.id);
-            self.spawn(move || {
-                let result = WebApi::global().unsave_album(&link.id);
-                if result.is_err() {
-                    // TODO: Refresh saved albums.
+        } else if let Some(link) = cmd.get(cmd::CONFIRM_UNFOLLOW_PLAYLIST).cloned() {
+            ctx.new_window(ui::unfollow_playlist_window(link));
+            Handled::Yes
+        } else if let Some(link) = cmd.get(cmd::UNFOLLOW_PLAYLIST).cloned() {
+            if let Promise::Resolved(playlists) = &mut data.library_mut().playlists {
+                playlists.retain(|playlist| playlist.id != link.id);
+            }
+            data.update_editable_playlists();
+            let sink = ctx.get_external_handle();
+            let webapi = self.webapi;
+            self.spawn(async move {
+                if let Err(err) = webapi.unfollow_playlist(&link.id).await {
+                    log::error!("failed to unfollow playlist: {:?}", err);
+                    // Bring the playlist back.
+                    sink.submit_command(cmd::LOAD_PLAYLISTS, (), Target::Auto)
+                        .unwrap();
                 }
             });
             Handled::Yes
-        } else {
-            Handled::No
-        }
-    }
-
-    fn command_album(
-        &mut self,
-        ctx: &mut DelegateCtx,
-        _target: Target,
-        cmd: &Command,
-        data: &mut State,
-    ) -> Handled {
-        if let Some(link) = cmd.get(cmd::LOAD_ALBUM_DETAIL).cloned() {
-            data.album.album.defer(link.clone());
+        } else if let Some(link) = cmd.get(cmd::FOLLOW_PLAYLIST).cloned() {
             let sink = ctx.get_external_handle();
-            self.spawn(move || {
-                let result = WebApi::global().get_album(&link.id);
-                sink.submit_command(cmd::UPDATE_ALBUM_DETAIL, (link, result), Target::Auto)
+            let webapi = self.webapi;
+            self.spawn(async move {
+                let result = async {
+                    webapi.follow_playlist(&link.id).await?;
+                    webapi.get_playlist(&link.id).await
+                }
+                .await;
+                sink.submit_command(cmd::PLAYLIST_FOLLOWED, result, Target::Auto)
                     .unwrap();
             });
             Handled::Yes
-        } else if let Some((link, result)) = cmd.get(cmd::UPDATE_ALBUM_DETAIL).cloned() {
-            if data.album.album.is_deferred(&link) {
-                data.album.album.resolve_or_reject(result);
+        } else if let Some(result) = cmd.get(cmd::PLAYLIST_FOLLOWED).cloned() {
+            match result {
+                Ok(playlist) => {
+                    if let Promise::Resolved(playlists) = &mut data.library_mut().playlists {
+                        if !playlists.iter().any(|p| p.id == playlist.id) {
+                            playlists.push_front(playlist);
+                        }
+                    }
+                    data.update_editable_playlists();
+                }
+                Err(err) => {
+                    log::error!("failed to follow playlist: {:?}", err);
+                }
+            }
+            Handled::Yes
+        } else if let Some((link, result)) = cmd.get(cmd::PLAYLIST_CHANGED).cloned() {
+            match result {
+                Ok(snapshot_id) => {
+                    if let Promise::Resolved(playlists) = &mut data.library_mut().playlists {
+                        for playlist in playlists.iter_mut() {
+                            if playlist.id == link.id {
+                                playlist.snapshot_id = snapshot_id.clone();
+                            }
+                        }
+                    }
+                    // The change is already applied to our copy of the tracks.
+                    if let Some((loaded_snapshot_id, _)) = self.playlist_tracks.get_mut(&link.id) {
+                        *loaded_snapshot_id = snapshot_id;
+                    }
+                }
+                Err(err) => {
+                    log::error!("failed to change playlist: {:?}", err);
+                    self.playlist_tracks.remove(&link.id);
+                    // Throw away the optimistic changes.
+                    if let Nav::PlaylistDetail(open) = &data.route {
+                        if open.id == link.id {
+                            ctx.submit_command(cmd::LOAD_PLAYLIST_DETAIL.with(open.clone()));
+                        }
+                    }
+                }
             }
             Handled::Yes
         } else {
@@ -326,50 +953,824 @@ impl Delegate {
         }
     }
 
-    fn command_artist(
+    fn command_connect(
         &mut self,
         ctx: &mut DelegateCtx,
         _target: Target,
         cmd: &Command,
         data: &mut State,
     ) -> Handled {
-        if let Some(album_link) = cmd.get(cmd::LOAD_ARTIST_DETAIL) {
-            // Load artist detail
-            data.artist.artist.defer(album_link.clone());
-            let link = album_link.clone();
-            let sink = ctx.get_external_handle();
-            self.spawn(move || {
-                let result = WebApi::global().get_artist(&link.id);
-                sink.submit_command(cmd::UPDATE_ARTIST_DETAIL, (link, result), Target::Auto)
-                    .unwrap();
-            });
-            // Load artist top tracks
-            data.artist.top_tracks.defer(album_link.clone());
-            let link = album_link.clone();
-            let sink = ctx.get_external_handle();
-            self.spawn(move || {
-                let result = WebApi::global().get_artist_top_tracks(&link.id);
-                sink.submit_command(cmd::UPDATE_ARTIST_TOP_TRACKS, (link, result), Target::Auto)
-                    .unwrap();
-            });
-            // Load artist's related artists
-            data.artist.related_artists.defer(album_link.clone());
-            let link = album_link.clone();
+        if cmd.is(cmd::LOAD_CONNECT_DEVICES) {
+            if !data.connect.devices.is_deferred(&()) {
+                data.connect.devices.defer_default();
+                let sink = ctx.get_external_handle();
+                let webapi = self.webapi;
+                self.spawn(async move {
+                    let result = webapi.get_devices().await;
+                    sink.submit_command(cmd::UPDATE_CONNECT_DEVICES, result, Target::Auto)
+                        .unwrap();
+                });
+            }
+            Handled::Yes
+        } else if let Some(result) = cmd.get(cmd::UPDATE_CONNECT_DEVICES).cloned() {
+            if data.connect.devices.is_deferred(&()) {
+                data.connect.devices.resolve_or_reject(result);
+            }
+            Handled::Yes
+        } else if let Some(device) = cmd.get(cmd::TRANSFER_PLAYBACK).cloned() {
+            if let Some(device_id) = device.id {
+                // Only one device can be playing at a time, stop playing locally.
+                if data.playback.state == PlaybackState::Playing {
+                    ctx.submit_command(cmd::PLAY_PAUSE);
+                }
+                data.connect.active_device.replace(device_id.clone());
+                data.connect.set_remote(None);
+                data.connect.cast_renderer.take();
+                let sink = ctx.get_external_handle();
+                let webapi = self.webapi;
+                self.spawn(async move {
+                    let result = async {
+                        webapi.transfer_playback(&device_id).await?;
+                        webapi.get_remote_playback().await
+                    }
+                    .await;
+                    sink.submit_command(cmd::UPDATE_REMOTE_PLAYBACK, result, Target::Auto)
+                        .unwrap();
+                });
+            }
+            Handled::Yes
+        } else if cmd.is(cmd::DISCONNECT_REMOTE) {
+            data.connect.active_device.take();
+            data.connect.set_remote(None);
+            data.connect.cast_renderer.take();
+            Handled::Yes
+        } else if cmd.is(cmd::LOAD_CAST_RENDERERS) {
+            if !data.connect.renderers.is_deferred(&()) {
+                data.connect.renderers.defer_default();
+                let sink = ctx.get_external_handle();
+                self.spawn_blocking(move || {
+                    let result = audio_cast::discover(RENDERER_DISCOVERY_TIMEOUT)
+                        .map(|renderers| renderers.into_iter().map(CastRenderer::from).collect())
+                        .map_err(|err| Error::WebApiError(err.to_string()));
+                    sink.submit_command(cmd::UPDATE_CAST_RENDERERS, result, Target::Auto)
+                        .unwrap();
+                });
+            }
+            Handled::Yes
+        } else if let Some(result) = cmd.get(cmd::UPDATE_CAST_RENDERERS).cloned() {
+            if data.connect.renderers.is_deferred(&()) {
+                data.connect.renderers.resolve_or_reject(result);
+            }
+            Handled::Yes
+        } else if let Some(renderer) = cmd.get(cmd::CAST_TO).cloned() {
+            // The playback controller picks up the change and switches the output.
+            data.connect.active_device.take();
+            data.connect.set_remote(None);
+            data.connect.cast_renderer.replace(renderer);
+            Handled::Yes
+        } else if let Some(&command) = cmd.get(cmd::REMOTE_CONTROL) {
+            if let Some(device_id) = data.connect.active_device.clone() {
+                // Reflect the change right away, the next update confirms it.
+                if let Some(remote) = &mut data.connect.remote {
+                    match command {
+                        RemoteCommand::Play => remote.is_playing = true,
+                        RemoteCommand::Pause => remote.is_playing = false,
+                        _ => {}
+                    }
+                }
+                let sink = ctx.get_external_handle();
+                let webapi = self.webapi;
+                self.spawn(async move {
+                    let result = async {
+                        match command {
+                            RemoteCommand::Play => webapi.remote_play(&device_id).await?,
+                            RemoteCommand::Pause => webapi.remote_pause(&device_id).await?,
+                            RemoteCommand::Next => webapi.remote_next(&device_id).await?,
+                            RemoteCommand::Previous => webapi.remote_previous(&device_id).await?,
+                            RemoteCommand::SetVolume(volume) => {
+                                webapi.set_remote_volume(&device_id, volume).await?
+                            }
+                        }
+                        webapi.get_remote_playback().await
+                    }
+                    .await;
+                    sink.submit_command(cmd::UPDATE_REMOTE_PLAYBACK, result, Target::Auto)
+                        .unwrap();
+                });
+            }
+            Handled::Yes
+        } else if cmd.is(cmd::LOAD_REMOTE_PLAYBACK) {
+            if data.connect.is_remote() {
+                let sink = ctx.get_external_handle();
+                let webapi = self.webapi;
+                self.spawn(async move {
+                    let result = webapi.get_remote_playback().await;
+                    sink.submit_command(cmd::UPDATE_REMOTE_PLAYBACK, result, Target::Auto)
+                        .unwrap();
+                });
+            }
+            Handled::Yes
+        } else if let Some(result) = cmd.get(cmd::UPDATE_REMOTE_PLAYBACK).cloned() {
+            // Ignore the late responses after we have stopped being a remote.
+            if data.connect.is_remote() {
+                match result {
+                    Ok(remote) => data.connect.set_remote(remote),
+                    Err(err) => log::error!("failed to update remote playback: {:?}", err),
+                }
+            }
+            Handled::Yes
+        } else if let Some(event) = cmd.get(cmd::CONNECT_EVENT).cloned() {
+            match event {
+                ConnectEvent::Load(load) => {
+                    // Playback got transferred to us, stop controlling the remote device.
+                    data.connect.active_device.take();
+                    data.connect.set_remote(None);
+                    let sink = ctx.get_external_handle();
+                    let webapi = self.webapi;
+                    self.spawn(async move {
+                        match load_connect_tracks(webapi, load).await {
+                            Ok(Some(payload)) => {
+                                sink.submit_command(cmd::PLAY_TRACKS_FROM, payload, Target::Auto)
+                                    .unwrap();
+                            }
+                            Ok(None) => {
+                                log::warn!("no playable tracks in the connect context");
+                            }
+                            Err(err) => {
+                                log::error!("failed to load connect tracks: {:?}", err);
+                            }
+                        }
+                    });
+                }
+                ConnectEvent::Pause | ConnectEvent::Deactivated => {
+                    ctx.submit_command(cmd::PLAY_PAUSE);
+                }
+                ConnectEvent::Resume => {
+                    ctx.submit_command(cmd::PLAY_RESUME);
+                }
+                ConnectEvent::SkipNext => {
+                    ctx.submit_command(cmd::PLAY_NEXT);
+                }
+                ConnectEvent::SkipPrevious => {
+                    ctx.submit_command(cmd::PLAY_PREVIOUS);
+                }
+                ConnectEvent::SeekTo(position) => {
+                    ctx.submit_command(cmd::PLAY_SEEK_TO.with(position));
+                }
+                ConnectEvent::SetShuffle(shuffle) => {
+                    let behavior = if shuffle {
+                        QueueBehavior::Random
+                    } else {
+                        QueueBehavior::Sequential
+                    };
+                    ctx.submit_command(cmd::PLAY_QUEUE_BEHAVIOR.with(behavior));
+                }
+                ConnectEvent::SetRepeat { context, track } => {
+                    let mode = if track {
+                        RepeatMode::Track
+                    } else if context {
+                        RepeatMode::Context
+                    } else {
+                        RepeatMode::Off
+                    };
+                    ctx.submit_command(cmd::PLAY_REPEAT_MODE.with(mode));
+                }
+            }
+            Handled::Yes
+        } else {
+            Handled::No
+        }
+    }
+
+    fn command_library(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        _target: Target,
+        cmd: &Command,
+        data: &mut State,
+    ) -> Handled {
+        if cmd.is(cmd::LOAD_SAVED_TRACKS) {
+            if data.library.saved_tracks.is_empty() || data.library.saved_tracks.is_rejected() {
+                data.library_mut().saved_tracks.defer_default();
+                let sink = ctx.get_external_handle();
+                let webapi = self.webapi;
+                self.spawn(async move {
+                    let result = webapi.get_saved_tracks(0).await;
+                    sink.submit_command(cmd::UPDATE_SAVED_TRACKS, (0, result), Target::Auto)
+                        .unwrap();
+                });
+            }
+            Handled::Yes
+        } else if cmd.is(cmd::REFRESH_LIBRARY) {
+            let is_rate_limited = self
+                .rate_limited_until
+                .map_or(false, |until| Instant::now() < until);
+            if !data.offline && !is_rate_limited {
+                self.refresh_library(ctx, data);
+            }
+            Handled::Yes
+        } else if let Some(result) = cmd.get(cmd::REFRESHED_PLAYLISTS).cloned() {
+            match result {
+                Ok(playlists) => {
+                    if data.library.playlists.is_resolved() {
+                        data.library_mut().playlists.resolve(playlists);
+                        data.update_editable_playlists();
+                    }
+                }
+                Err(err) => {
+                    log::warn!("failed to refresh playlists: {:?}", err);
+                }
+            }
+            Handled::Yes
+        } else if let Some(result) = cmd.get(cmd::REFRESHED_SAVED_ALBUMS).cloned() {
+            match result {
+                Ok(albums) => {
+                    if let Promise::Resolved(saved) = &data.library.saved_albums {
+                        for album in saved.iter() {
+                            if !albums.iter().any(|a| a.id == album.id) {
+                                data.common_ctx.set_album_saved(album.id.clone(), false);
+                            }
+                        }
+                        data.common_ctx.add_saved_albums(&albums);
+                        data.library_mut().saved_albums.resolve(albums);
+                    }
+                }
+                Err(err) => {
+                    log::warn!("failed to refresh saved albums: {:?}", err);
+                }
+            }
+            Handled::Yes
+        } else if let Some(result) = cmd.get(cmd::REFRESHED_SAVED_TRACKS).cloned() {
+            match result {
+                Ok(page) => {
+                    if let Promise::Resolved(saved) = &data.library.saved_tracks {
+                        // The page covers the tracks saved since its oldest one,
+                        // the ones we have from that time on should match it.
+                        let since = match page.next_offset {
+                            Some(_) => page.added_at.values().min().copied(),
+                            None => None,
+                        };
+                        let covered =
+                            |track: &Arc<Track>| match (since, saved.added_at.get(&track.id)) {
+                                (Some(since), Some(added_at)) => *added_at >= since,
+                                _ => true,
+                            };
+                        let removed: Vec<TrackId> = saved
+                            .tracks
+                            .iter()
+                            .filter(|track| {
+                                covered(track) && !page.added_at.contains_key(&track.id)
+                            })
+                            .map(|track| track.id)
+                            .collect();
+                        let added = page
+                            .tracks
+                            .iter()
+                            .any(|track| !saved.added_at.contains_key(&track.id));
+                        if added || !removed.is_empty() {
+                            for track_id in removed {
+                                data.common_ctx.set_track_saved(track_id, false);
+                            }
+                            data.common_ctx.add_saved_tracks(&page.tracks);
+                            data.library_mut().saved_tracks.resolve(page);
+                        }
+                    }
+                }
+                Err(err) => {
+                    log::warn!("failed to refresh saved tracks: {:?}", err);
+                }
+            }
+            Handled::Yes
+        } else if cmd.is(cmd::LOAD_MORE_SAVED_TRACKS) {
+            if let Promise::Resolved(saved) = &data.library.saved_tracks {
+                if let Some(offset) = saved.next_offset {
+                    let sink = ctx.get_external_handle();
+                    let webapi = self.webapi;
+                    self.spawn(async move {
+                        let result = webapi.get_saved_tracks(offset).await;
+                        sink.submit_command(
+                            cmd::UPDATE_SAVED_TRACKS,
+                            (offset, result),
+                            Target::Auto,
+                        )
+                        .unwrap();
+                    });
+                }
+            }
+            Handled::Yes
+        } else if cmd.is(cmd::LOAD_SAVED_ALBUMS) {
+            if data.library.saved_albums.is_empty() || data.library.saved_albums.is_rejected() {
+                data.library_mut().saved_albums.defer_default();
+                self.spawn_pages(ctx, cmd::UPDATE_SAVED_ALBUMS, |webapi, on_page| {
+                    webapi.get_saved_albums(on_page)
+                });
+            }
+            Handled::Yes
+        } else if cmd.is(cmd::LOAD_SAVED_SHOWS) {
+            if data.library.saved_shows.is_empty() || data.library.saved_shows.is_rejected() {
+                data.library_mut().saved_shows.defer_default();
+                self.spawn_pages(ctx, cmd::UPDATE_SAVED_SHOWS, |webapi, on_page| {
+                    webapi.get_saved_shows(on_page)
+                });
+            }
+            Handled::Yes
+        } else if let Some((offset, result)) = cmd.get(cmd::UPDATE_SAVED_SHOWS).cloned() {
+            match (offset, result) {
+                (0, Ok(shows)) => {
+                    data.common_ctx.set_saved_shows(&shows);
+                    data.library_mut().saved_shows.resolve(shows);
+                }
+                (0, Err(err)) => {
+                    data.common_ctx.set_saved_shows(&Vector::new());
+                    data.library_mut().saved_shows.reject(err);
+                }
+                (_, Ok(page)) => {
+                    data.common_ctx
+                        .saved_shows
+                        .extend(page.iter().map(|show| show.id.clone()));
+                    if let Promise::Resolved(shows) = &mut data.library_mut().saved_shows {
+                        for show in page {
+                            if !shows.iter().any(|s| s.id == show.id) {
+                                shows.push_back(show);
+                            }
+                        }
+                    }
+                }
+                (_, Err(err)) => {
+                    log::error!("failed to load more saved shows: {:?}", err);
+                }
+            };
+            Handled::Yes
+        } else if let Some(show) = cmd.get(cmd::SAVE_SHOW).cloned() {
+            let show_id = show.id.clone();
+            data.save_show(show);
+            self.spawn_pages(ctx, cmd::UPDATE_SAVED_SHOWS, move |webapi, on_page| {
+                Box::pin(async move {
+                    match webapi.save_show(&show_id).await {
+                        Ok(()) => Ok(()),
+                        Err(err) => {
+                            log::error!("failed to save show: {:?}", err);
+                            webapi.get_saved_shows(on_page).await
+                        }
+                    }
+                })
+            });
+            Handled::Yes
+        } else if let Some(link) = cmd.get(cmd::UNSAVE_SHOW).cloned() {
+            data.unsave_show(&link.id);
+            self.spawn_pages(ctx, cmd::UPDATE_SAVED_SHOWS, move |webapi, on_page| {
+                Box::pin(async move {
+                    match webapi.unsave_show(&link.id).await {
+                        Ok(()) => Ok(()),
+                        Err(err) => {
+                            log::error!("failed to remove show: {:?}", err);
+                            webapi.get_saved_shows(on_page).await
+                        }
+                    }
+                })
+            });
+            Handled::Yes
+        } else if cmd.is(cmd::LOAD_RECENTLY_PLAYED) {
+            // Always reload, the plays keep on coming.
+            data.library_mut().recently_played.defer_default();
             let sink = ctx.get_external_handle();
-            self.spawn(move || {
-                let result = WebApi::global().get_related_artists(&link.id);
+            let webapi = self.webapi;
+            self.spawn(async move {
+                let result = webapi.get_recently_played(None).await;
+                sink.submit_command(cmd::UPDATE_RECENTLY_PLAYED, (None, result), Target::Auto)
+                    .unwrap();
+            });
+            Handled::Yes
+        } else if cmd.is(cmd::LOAD_MORE_RECENTLY_PLAYED) {
+            if let Promise::Resolved(recent) = &data.library.recently_played {
+                if let Some(before) = recent.next_cursor.clone() {
+                    let sink = ctx.get_external_handle();
+                    let webapi = self.webapi;
+                    self.spawn(async move {
+                        let result = webapi.get_recently_played(Some(&before)).await;
+                        sink.submit_command(
+                            cmd::UPDATE_RECENTLY_PLAYED,
+                            (Some(before), result),
+                            Target::Auto,
+                        )
+                        .unwrap();
+                    });
+                }
+            }
+            Handled::Yes
+        } else if let Some((before, result)) = cmd.get(cmd::UPDATE_RECENTLY_PLAYED).cloned() {
+            match (before, result) {
+                (None, result) => {
+                    data.library_mut().recently_played.resolve_or_reject(result);
+                }
+                (Some(before), Ok(page)) => {
+                    if let Promise::Resolved(recent) = &mut data.library_mut().recently_played {
+                        // Ignore pages that don't continue the list we have.
+                        if recent.next_cursor.as_ref() == Some(&before) {
+                            recent.items.extend(page.items);
+                            recent.next_cursor = page.next_cursor;
+                        }
+                    }
+                }
+                (Some(_), Err(err)) => {
+                    log::error!("failed to load more recently played tracks: {:?}", err);
+                }
+            }
+            Handled::Yes
+        } else if cmd.is(cmd::LOAD_FOLLOWED_ARTISTS) {
+            if data.library.followed_artists.is_empty()
+                || data.library.followed_artists.is_rejected()
+            {
+                data.library_mut().followed_artists.defer_default();
+                let sink = ctx.get_external_handle();
+                let webapi = self.webapi;
+                self.spawn(async move {
+                    let result = webapi.get_followed_artists(None).await;
+                    sink.submit_command(cmd::UPDATE_FOLLOWED_ARTISTS, (None, result), Target::Auto)
+                        .unwrap();
+                });
+            }
+            Handled::Yes
+        } else if cmd.is(cmd::LOAD_MORE_FOLLOWED_ARTISTS) {
+            if let Promise::Resolved(followed) = &data.library.followed_artists {
+                if let Some(after) = followed.next_cursor.clone() {
+                    let sink = ctx.get_external_handle();
+                    let webapi = self.webapi;
+                    self.spawn(async move {
+                        let result = webapi.get_followed_artists(Some(&after)).await;
+                        sink.submit_command(
+                            cmd::UPDATE_FOLLOWED_ARTISTS,
+                            (Some(after), result),
+                            Target::Auto,
+                        )
+                        .unwrap();
+                    });
+                }
+            }
+            Handled::Yes
+        } else if let Some((after, result)) = cmd.get(cmd::UPDATE_FOLLOWED_ARTISTS).cloned() {
+            match (after, result) {
+                (None, Ok(page)) => {
+                    data.common_ctx
+                        .followed_artists
+                        .extend(page.artists.iter().map(|artist| artist.id.clone()));
+                    data.library_mut().followed_artists.resolve(page);
+                }
+                (None, Err(err)) => {
+                    data.library_mut().followed_artists.reject(err);
+                }
+                (Some(after), Ok(page)) => {
+                    data.common_ctx
+                        .followed_artists
+                        .extend(page.artists.iter().map(|artist| artist.id.clone()));
+                    if let Promise::Resolved(followed) = &mut data.library_mut().followed_artists {
+                        // Ignore pages that don't continue the list we have.
+                        if followed.next_cursor.as_ref() == Some(&after) {
+                            followed.artists.extend(page.artists);
+                            followed.next_cursor = page.next_cursor;
+                        }
+                    }
+                }
+                (Some(_), Err(err)) => {
+                    log::error!("failed to load more followed artists: {:?}", err);
+                }
+            }
+            Handled::Yes
+        } else if let Some((offset, result)) = cmd.get(cmd::UPDATE_SAVED_TRACKS).cloned() {
+            match (offset, result) {
+                (0, Ok(page)) => {
+                    data.common_ctx.add_saved_tracks(&page.tracks);
+                    data.library_mut().saved_tracks.resolve(page);
+                }
+                (0, Err(err)) => {
+                    data.library_mut().saved_tracks.reject(err);
+                }
+                (offset, Ok(page)) => {
+                    data.common_ctx.add_saved_tracks(&page.tracks);
+                    if let Promise::Resolved(saved) = &mut data.library_mut().saved_tracks {
+                        // Ignore pages that don't continue the list we have.
+                        if saved.next_offset == Some(offset) {
+                            saved.append(page);
+                        }
+                    }
+                }
+                (_, Err(err)) => {
+                    log::error!("failed to load more saved tracks: {:?}", err);
+                }
+            };
+            Handled::Yes
+        } else if let Some((offset, result)) = cmd.get(cmd::UPDATE_SAVED_ALBUMS).cloned() {
+            match (offset, result) {
+                (0, Ok(albums)) => {
+                    data.common_ctx.add_saved_albums(&albums);
+                    data.library_mut().saved_albums.resolve(albums);
+                }
+                (0, Err(err)) => {
+                    data.library_mut().saved_albums.reject(err);
+                }
+                (_, Ok(page)) => {
+                    data.common_ctx.add_saved_albums(&page);
+                    if let Promise::Resolved(albums) = &mut data.library_mut().saved_albums {
+                        // Albums saved in the meantime shift the pages.
+                        for album in page {
+                            if !albums.iter().any(|a| a.id == album.id) {
+                                albums.push_back(album);
+                            }
+                        }
+                    }
+                }
+                (_, Err(err)) => {
+                    log::error!("failed to load more saved albums: {:?}", err);
+                }
+            };
+            Handled::Yes
+        } else if let Some(track) = cmd.get(cmd::SAVE_TRACK).cloned() {
+            let track_id = track.id.to_base62();
+            data.save_track(track);
+            let webapi = self.webapi;
+            self.spawn(async move {
+                let result = webapi.save_track(&track_id).await;
+                if result.is_err() {
+                    // TODO: Refresh saved tracks.
+                }
+            });
+            Handled::Yes
+        } else if let Some(track_id) = cmd.get(cmd::UNSAVE_TRACK).cloned() {
+            data.unsave_track(&track_id);
+            let webapi = self.webapi;
+            self.spawn(async move {
+                let result = webapi.unsave_track(&track_id.to_base62()).await;
+                if result.is_err() {
+                    // TODO: Refresh saved tracks.
+                }
+            });
+            Handled::Yes
+        } else if let Some(tracks) = cmd.get(cmd::SAVE_TRACKS).cloned() {
+            let track_ids: Vec<TrackId> = tracks.iter().map(|track| track.id).collect();
+            for track in tracks {
+                data.save_track(track);
+            }
+            let webapi = self.webapi;
+            self.spawn(async move {
+                let result = webapi.save_tracks(&track_ids).await;
+                if result.is_err() {
+                    // TODO: Refresh saved tracks.
+                }
+            });
+            Handled::Yes
+        } else if let Some(track_ids) = cmd.get(cmd::UNSAVE_TRACKS).cloned() {
+            for track_id in &track_ids {
+                data.unsave_track(track_id);
+            }
+            let track_ids: Vec<TrackId> = track_ids.into_iter().collect();
+            let webapi = self.webapi;
+            self.spawn(async move {
+                let result = webapi.unsave_tracks(&track_ids).await;
+                if result.is_err() {
+                    // TODO: Refresh saved tracks.
+                }
+            });
+            Handled::Yes
+        } else if let Some(track_ids) = cmd.get(cmd::CHECK_SAVED_TRACKS) {
+            let unchecked: Vector<TrackId> = track_ids
+                .iter()
+                .filter(|track_id| !data.common_ctx.saved_tracks.contains(track_id))
+                .filter(|track_id| self.checked_tracks.insert(**track_id))
+                .copied()
+                .collect();
+            self.pending_checked_tracks.append(unchecked);
+            if !self.is_checking_tracks {
+                self.check_pending_tracks(ctx);
+            }
+            Handled::Yes
+        } else if let Some((track_ids, result)) = cmd.get(cmd::UPDATE_CHECKED_TRACKS).cloned() {
+            self.is_checking_tracks = false;
+            self.check_pending_tracks(ctx);
+            match result {
+                Ok(saved) => {
+                    for (track_id, is_saved) in track_ids.into_iter().zip(saved) {
+                        data.common_ctx.set_track_saved(track_id, is_saved);
+                    }
+                }
+                Err(err) => {
+                    log::error!("failed to check saved tracks: {:?}", err);
+                    // Let them be checked again next time they are shown.
+                    for track_id in &track_ids {
+                        self.checked_tracks.remove(track_id);
+                    }
+                }
+            }
+            Handled::Yes
+        } else if let Some(album_ids) = cmd.get(cmd::CHECK_SAVED_ALBUMS) {
+            let unchecked: Vector<Arc<str>> = album_ids
+                .iter()
+                .filter(|album_id| !data.common_ctx.saved_albums.contains(*album_id))
+                .filter(|album_id| self.checked_albums.insert(Arc::clone(album_id)))
+                .cloned()
+                .collect();
+            if !unchecked.is_empty() {
+                let sink = ctx.get_external_handle();
+                let webapi = self.webapi;
+                self.spawn(async move {
+                    let ids: Vec<Arc<str>> = unchecked.iter().cloned().collect();
+                    let result = webapi.contains_saved_albums(&ids).await;
+                    sink.submit_command(
+                        cmd::UPDATE_CHECKED_ALBUMS,
+                        (unchecked, result),
+                        Target::Auto,
+                    )
+                    .unwrap();
+                });
+            }
+            Handled::Yes
+        } else if let Some((album_ids, result)) = cmd.get(cmd::UPDATE_CHECKED_ALBUMS).cloned() {
+            match result {
+                Ok(saved) => {
+                    for (album_id, is_saved) in album_ids.into_iter().zip(saved) {
+                        data.common_ctx.set_album_saved(album_id, is_saved);
+                    }
+                }
+                Err(err) => {
+                    log::error!("failed to check saved albums: {:?}", err);
+                    for album_id in &album_ids {
+                        self.checked_albums.remove(album_id);
+                    }
+                }
+            }
+            Handled::Yes
+        } else if cmd.is(cmd::TOGGLE_SAVE_NOW_PLAYING) {
+            if let Some(now_playing) = &data.playback.now_playing {
+                let track = now_playing.item.clone();
+                if data.common_ctx.is_track_saved(&track) {
+                    ctx.submit_command(cmd::UNSAVE_TRACK.with(track.id));
+                } else {
+                    ctx.submit_command(cmd::SAVE_TRACK.with(track));
+                }
+            }
+            Handled::Yes
+        } else if let Some(album) = cmd.get(cmd::SAVE_ALBUM).cloned() {
+            let album_id = album.id.clone();
+            data.save_album(album);
+            let webapi = self.webapi;
+            self.spawn(async move {
+                let result = webapi.save_album(&album_id).await;
+                if result.is_err() {
+                    // TODO: Refresh saved albums.
+                }
+            });
+            Handled::Yes
+        } else if let Some(link) = cmd.get(cmd::UNSAVE_ALBUM).cloned() {
+            data.unsave_album(&link.id);
+            let webapi = self.webapi;
+            self.spawn(async move {
+                let result = webapi.unsave_album(&link.id).await;
+                if result.is_err() {
+                    // TODO: Refresh saved albums.
+                }
+            });
+            Handled::Yes
+        } else {
+            Handled::No
+        }
+    }
+
+    fn command_album(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        _target: Target,
+        cmd: &Command,
+        data: &mut State,
+    ) -> Handled {
+        if let Some(link) = cmd.get(cmd::LOAD_ALBUM_DETAIL).cloned() {
+            data.album.album.defer(link.clone());
+            let webapi = self.webapi;
+            self.spawn_for_route(ctx, move |sink| async move {
+                let result = webapi.get_album(&link.id).await;
+                sink.submit_command(cmd::UPDATE_ALBUM_DETAIL, (link, result), Target::Auto)
+                    .unwrap();
+            });
+            Handled::Yes
+        } else if let Some((link, result)) = cmd.get(cmd::UPDATE_ALBUM_DETAIL).cloned() {
+            if data.album.album.is_deferred(&link) {
+                data.album.album.resolve_or_reject(result);
+            }
+            Handled::Yes
+        } else {
+            Handled::No
+        }
+    }
+
+    fn command_artist(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        _target: Target,
+        cmd: &Command,
+        data: &mut State,
+    ) -> Handled {
+        if let Some(album_link) = cmd.get(cmd::LOAD_ARTIST_DETAIL) {
+            // Load artist detail
+            data.artist.artist.defer(album_link.clone());
+            let link = album_link.clone();
+            let webapi = self.webapi;
+            self.spawn_for_route(ctx, move |sink| async move {
+                let result = webapi.get_artist(&link.id).await;
+                sink.submit_command(cmd::UPDATE_ARTIST_DETAIL, (link, result), Target::Auto)
+                    .unwrap();
+            });
+            // Load artist top tracks
+            data.artist.top_tracks.defer(album_link.clone());
+            let link = album_link.clone();
+            let webapi = self.webapi;
+            self.spawn_for_route(ctx, move |sink| async move {
+                let result = webapi.get_artist_top_tracks(&link.id).await;
+                sink.submit_command(cmd::UPDATE_ARTIST_TOP_TRACKS, (link, result), Target::Auto)
+                    .unwrap();
+            });
+            // Load artist's related artists
+            data.artist.related_artists.defer(album_link.clone());
+            let link = album_link.clone();
+            let webapi = self.webapi;
+            self.spawn_for_route(ctx, move |sink| async move {
+                let result = webapi.get_related_artists(&link.id).await;
                 sink.submit_command(cmd::UPDATE_ARTIST_RELATED, (link, result), Target::Auto)
                     .unwrap();
             });
             // Load artist albums
             data.artist.albums.defer(album_link.clone());
             let link = album_link.clone();
-            let sink = ctx.get_external_handle();
-            self.spawn(move || {
-                let result = WebApi::global().get_artist_albums(&link.id);
+            let webapi = self.webapi;
+            self.spawn_for_route(ctx, move |sink| async move {
+                let result = webapi.get_artist_albums(&link.id).await;
                 sink.submit_command(cmd::UPDATE_ARTIST_ALBUMS, (link, result), Target::Auto)
                     .unwrap();
             });
+            // Load artist biography and stats
+            data.artist.info.defer(album_link.clone());
+            let link = album_link.clone();
+            let webapi = self.webapi;
+            self.spawn_for_route(ctx, move |sink| async move {
+                let result = webapi.get_artist_info(&link.id).await;
+                sink.submit_command(cmd::UPDATE_ARTIST_INFO, (link, result), Target::Auto)
+                    .unwrap();
+            });
+            // Check if we follow the artist
+            let link = album_link.clone();
+            let webapi = self.webapi;
+            self.spawn_for_route(ctx, move |sink| async move {
+                let result = webapi.is_following_artist(&link.id).await;
+                sink.submit_command(cmd::UPDATE_ARTIST_FOLLOWED, (link, result), Target::Auto)
+                    .unwrap();
+            });
+            Handled::Yes
+        } else if let Some((link, result)) = cmd.get(cmd::UPDATE_ARTIST_FOLLOWED).cloned() {
+            match result {
+                Ok(true) => {
+                    data.common_ctx.followed_artists.insert(link.id);
+                }
+                Ok(false) => {
+                    data.common_ctx.followed_artists.remove(&link.id);
+                }
+                Err(err) => {
+                    log::error!("failed to check if artist is followed: {:?}", err);
+                }
+            }
+            Handled::Yes
+        } else if let Some(link) = cmd.get(cmd::FOLLOW_ARTIST).cloned() {
+            data.common_ctx.followed_artists.insert(link.id.clone());
+            // We don't have the full artist here, reload the list next time.
+            data.library_mut().followed_artists = Promise::Empty;
+            let sink = ctx.get_external_handle();
+            let webapi = self.webapi;
+            self.spawn(async move {
+                if let Err(err) = webapi.follow_artist(&link.id).await {
+                    log::error!("failed to follow artist: {:?}", err);
+                    sink.submit_command(
+                        cmd::UPDATE_ARTIST_FOLLOWED,
+                        (link, Ok(false)),
+                        Target::Auto,
+                    )
+                    .unwrap();
+                }
+            });
+            Handled::Yes
+        } else if let Some(link) = cmd.get(cmd::UNFOLLOW_ARTIST).cloned() {
+            data.common_ctx.followed_artists.remove(&link.id);
+            if let Promise::Resolved(followed) = &mut data.library_mut().followed_artists {
+                followed.artists.retain(|artist| artist.id != link.id);
+            }
+            let sink = ctx.get_external_handle();
+            let webapi = self.webapi;
+            self.spawn(async move {
+                if let Err(err) = webapi.unfollow_artist(&link.id).await {
+                    log::error!("failed to unfollow artist: {:?}", err);
+                    sink.submit_command(
+                        cmd::UPDATE_ARTIST_FOLLOWED,
+                        (link, Ok(true)),
+                        Target::Auto,
+                    )
+                    .unwrap();
+                }
+            });
             Handled::Yes
         } else if let Some((link, result)) = cmd.get(cmd::UPDATE_ARTIST_DETAIL).cloned() {
             if data.artist.artist.is_deferred(&link) {
@@ -397,29 +1798,604 @@ impl Delegate {
                 data.artist.related_artists.resolve_or_reject(result);
             }
             Handled::Yes
+        } else if let Some((link, result)) = cmd.get(cmd::UPDATE_ARTIST_INFO).cloned() {
+            if data.artist.info.is_deferred(&link) {
+                data.artist.info.resolve_or_reject(result);
+            }
+            Handled::Yes
         } else {
             Handled::No
         }
     }
 
-    fn command_search(
+    fn command_home(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        _target: Target,
+        cmd: &Command,
+        data: &mut State,
+    ) -> Handled {
+        if cmd.is(cmd::LOAD_HOME) {
+            // Load the shelves concurrently, each one shows up as soon as it's
+            // ready.
+            let home = &mut data.home;
+            if home.featured_playlists.is_empty() || home.featured_playlists.is_rejected() {
+                home.featured_playlists.defer_default();
+                let sink = ctx.get_external_handle();
+                let webapi = self.webapi;
+                self.spawn(async move {
+                    let result = webapi.get_featured_playlists().await;
+                    sink.submit_command(cmd::UPDATE_FEATURED_PLAYLISTS, result, Target::Auto)
+                        .unwrap();
+                });
+            }
+            if home.new_releases.is_empty() || home.new_releases.is_rejected() {
+                home.new_releases.defer_default();
+                let sink = ctx.get_external_handle();
+                let webapi = self.webapi;
+                self.spawn(async move {
+                    let result = webapi.get_new_releases().await;
+                    sink.submit_command(cmd::UPDATE_NEW_RELEASES, result, Target::Auto)
+                        .unwrap();
+                });
+            }
+            // Always reload, the plays keep on coming.
+            if !home.recently_played.is_deferred(&()) {
+                home.recently_played.defer_default();
+                let sink = ctx.get_external_handle();
+                let webapi = self.webapi;
+                self.spawn(async move {
+                    let result = webapi.get_recently_played(None).await;
+                    sink.submit_command(cmd::UPDATE_HOME_RECENTLY_PLAYED, result, Target::Auto)
+                        .unwrap();
+                });
+            }
+            Handled::Yes
+        } else if let Some(result) = cmd.get(cmd::UPDATE_FEATURED_PLAYLISTS).cloned() {
+            data.home.featured_playlists.resolve_or_reject(result);
+            Handled::Yes
+        } else if let Some(result) = cmd.get(cmd::UPDATE_NEW_RELEASES).cloned() {
+            data.home.new_releases.resolve_or_reject(result);
+            Handled::Yes
+        } else if let Some(result) = cmd.get(cmd::UPDATE_HOME_RECENTLY_PLAYED).cloned() {
+            data.home
+                .recently_played
+                .resolve_or_reject(result.map(|mut recent| {
+                    recent.items.truncate(HOME_RECENTLY_PLAYED_LENGTH);
+                    recent.next_cursor = None;
+                    recent
+                }));
+            Handled::Yes
+        } else {
+            Handled::No
+        }
+    }
+
+    fn command_show(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        _target: Target,
+        cmd: &Command,
+        data: &mut State,
+    ) -> Handled {
+        if let Some(link) = cmd.get(cmd::LOAD_SHOW_DETAIL).cloned() {
+            data.show.show.defer(link.clone());
+            data.show.episodes.defer(link.clone());
+            let webapi = self.webapi;
+            self.spawn_for_route(ctx, move |sink| async move {
+                let result = webapi.get_show(&link.id).await;
+                sink.submit_command(
+                    cmd::UPDATE_SHOW_DETAIL,
+                    (link.clone(), result),
+                    Target::Auto,
+                )
+                .unwrap();
+                let result = webapi.get_show_episodes(&link.id).await;
+                sink.submit_command(cmd::UPDATE_SHOW_EPISODES, (link, result), Target::Auto)
+                    .unwrap();
+            });
+            Handled::Yes
+        } else if let Some((link, result)) = cmd.get(cmd::UPDATE_SHOW_DETAIL).cloned() {
+            if data.show.show.is_deferred(&link) {
+                data.show.show.resolve_or_reject(result);
+            }
+            Handled::Yes
+        } else if let Some((link, result)) = cmd.get(cmd::UPDATE_SHOW_EPISODES).cloned() {
+            if data.show.episodes.is_deferred(&link) {
+                data.show
+                    .episodes
+                    .resolve_or_reject(result.map(|episodes| ShowEpisodes {
+                        show: link,
+                        episodes,
+                    }));
+            }
+            Handled::Yes
+        } else {
+            Handled::No
+        }
+    }
+
+    fn command_top(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        _target: Target,
+        cmd: &Command,
+        data: &mut State,
+    ) -> Handled {
+        if let Some(range) = cmd.get(cmd::LOAD_TOP_ITEMS).copied() {
+            let is_loaded = data.top.range == range
+                && !data.top.tracks.is_empty()
+                && !data.top.tracks.is_rejected()
+                && !data.top.artists.is_empty()
+                && !data.top.artists.is_rejected();
+            if !is_loaded {
+                data.top.range = range;
+                data.top.tracks.defer(range);
+                data.top.artists.defer(range);
+                let sink = ctx.get_external_handle();
+                let webapi = self.webapi;
+                self.spawn(async move {
+                    let result = webapi.get_top_tracks(range).await;
+                    sink.submit_command(cmd::UPDATE_TOP_TRACKS, (range, result), Target::Auto)
+                        .unwrap();
+                    let result = webapi.get_top_artists(range).await;
+                    sink.submit_command(cmd::UPDATE_TOP_ARTISTS, (range, result), Target::Auto)
+                        .unwrap();
+                });
+            }
+            Handled::Yes
+        } else if let Some((range, result)) = cmd.get(cmd::UPDATE_TOP_TRACKS).cloned() {
+            if data.top.tracks.is_deferred(&range) {
+                data.top
+                    .tracks
+                    .resolve_or_reject(result.map(|tracks| TopTracks { range, tracks }));
+            }
+            Handled::Yes
+        } else if let Some((range, result)) = cmd.get(cmd::UPDATE_TOP_ARTISTS).cloned() {
+            if data.top.artists.is_deferred(&range) {
+                data.top.artists.resolve_or_reject(result);
+            }
+            Handled::Yes
+        } else {
+            Handled::No
+        }
+    }
+
+    fn command_radio(
         &mut self,
         ctx: &mut DelegateCtx,
         _target: Target,
         cmd: &Command,
         data: &mut State,
     ) -> Handled {
-        if let Some(query) = cmd.get(cmd::LOAD_SEARCH_RESULTS).cloned() {
+        if let Some(seed) = cmd.get(cmd::LOAD_RADIO).cloned() {
+            // Reload even when coming back to the same seed, so the radio
+            // serves fresh recommendations.
+            data.radio.tracks.defer(seed.clone());
+            let webapi = self.webapi;
+            self.spawn_for_route(ctx, move |sink| async move {
+                let result = match &seed {
+                    RadioSeed::Track { id, .. } => webapi.get_recommendations(&[*id], &[]).await,
+                    RadioSeed::Artist(link) => {
+                        webapi.get_recommendations(&[], &[link.id.clone()]).await
+                    }
+                };
+                sink.submit_command(cmd::UPDATE_RADIO, (seed, result), Target::Auto)
+                    .unwrap();
+            });
+            Handled::Yes
+        } else if let Some((seed, result)) = cmd.get(cmd::UPDATE_RADIO).cloned() {
+            if data.radio.tracks.is_deferred(&seed) {
+                data.radio
+                    .tracks
+                    .resolve_or_reject(result.map(|tracks| RadioTracks { seed, tracks }));
+            }
+            Handled::Yes
+        } else if cmd.is(cmd::SAVE_RADIO_AS_PLAYLIST) {
+            if let Promise::Resolved(radio) = &data.radio.tracks {
+                let name = radio.seed.to_title();
+                let description = match &radio.seed {
+                    RadioSeed::Track { .. } => "Generated from the song radio.",
+                    RadioSeed::Artist(_) => "Generated from the artist radio.",
+                };
+                let track_ids: Vec<TrackId> = radio.tracks.iter().map(|track| track.id).collect();
+                let sink = ctx.get_external_handle();
+                let webapi = self.webapi;
+                self.spawn(async move {
+                    let result = async {
+                        let mut playlist = webapi.create_playlist(&name, description).await?;
+                        webapi
+                            .add_tracks_to_playlist(&playlist.id, &track_ids)
+                            .await?;
+                        playlist.track_count = track_ids.len();
+                        Ok(playlist)
+                    }
+                    .await;
+                    sink.submit_command(cmd::PLAYLIST_CREATED, result, Target::Auto)
+                        .unwrap();
+                });
+            }
+            Handled::Yes
+        } else {
+            Handled::No
+        }
+    }
+
+    fn command_track_info(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        _target: Target,
+        cmd: &Command,
+        data: &mut State,
+    ) -> Handled {
+        if let Some(track) = cmd.get(cmd::SHOW_TRACK_INFO).cloned() {
+            data.track_info.defer(track.id);
+            match self.track_info_window {
+                Some(id) => {
+                    ctx.submit_command(commands::SHOW_WINDOW.to(id));
+                }
+                None => {
+                    let window = ui::track_info_window();
+                    self.track_info_window.replace(window.id);
+                    ctx.new_window(window);
+                }
+            }
             let sink = ctx.get_external_handle();
+            let webapi = self.webapi;
+            self.spawn(async move {
+                let result = webapi.get_audio_features(&track.id.to_base62()).await;
+                sink.submit_command(cmd::UPDATE_TRACK_INFO, (track, result), Target::Auto)
+                    .unwrap();
+            });
+            Handled::Yes
+        } else if let Some((track, result)) = cmd.get(cmd::UPDATE_TRACK_INFO).cloned() {
+            if data.track_info.is_deferred(&track.id) {
+                data.track_info
+                    .resolve_or_reject(result.map(|features| TrackInfo { track, features }));
+            }
+            Handled::Yes
+        } else if let Some(track_ids) = cmd.get(cmd::LOAD_AUDIO_FEATURES) {
+            let missing: Vec<TrackId> = track_ids
+                .iter()
+                .filter(|track_id| !data.common_ctx.audio_features.contains_key(track_id))
+                .copied()
+                .collect();
+            if !missing.is_empty() {
+                let sink = ctx.get_external_handle();
+                let webapi = self.webapi;
+                self.spawn(async move {
+                    let result = webapi.get_several_audio_features(&missing).await;
+                    sink.submit_command(cmd::UPDATE_AUDIO_FEATURES, result, Target::Auto)
+                        .unwrap();
+                });
+            }
+            Handled::Yes
+        } else if let Some((view, columns)) = cmd.get(cmd::SET_TRACK_COLUMNS).cloned() {
+            data.config
+                .track_columns
+                .insert(view, columns.iter().copied().collect());
+            data.config.save();
+            // Don't keep ordering by a column that is gone.
+            if let Some(sort) = data.common_ctx.track_sort {
+                if sort.column != TrackColumn::Title && !columns.contains(&sort.column) {
+                    data.common_ctx.track_sort = None;
+                }
+            }
+            data.common_ctx.track_columns.insert(view, columns);
+            Handled::Yes
+        } else if let Some(result) = cmd.get(cmd::UPDATE_AUDIO_FEATURES).cloned() {
+            match result {
+                Ok(features) => {
+                    for track_features in features {
+                        data.common_ctx
+                            .audio_features
+                            .insert(track_features.id, track_features);
+                    }
+                }
+                Err(err) => {
+                    log::error!("failed to load audio features: {:?}", err);
+                }
+            }
+            Handled::Yes
+        } else {
+            Handled::No
+        }
+    }
+
+    fn command_palette(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        _target: Target,
+        cmd: &Command,
+        data: &mut State,
+    ) -> Handled {
+        if cmd.is(cmd::TOGGLE_COMMAND_PALETTE) {
+            ctx.submit_command(cmd::SHOW_COMMAND_PALETTE.with(!data.palette.open));
+            Handled::Yes
+        } else if let Some(&open) = cmd.get(cmd::SHOW_COMMAND_PALETTE) {
+            data.palette.reset();
+            data.palette.open = open;
+            if open {
+                data.palette.update_entries(&data.history);
+                ctx.submit_command(cmd::SET_FOCUS.to(cmd::WIDGET_PALETTE_INPUT));
+            }
+            Handled::Yes
+        } else if cmd.is(cmd::FILTER_COMMAND_PALETTE) {
+            data.palette.selected = 0;
+            data.palette.update_entries(&data.history);
+            Handled::Yes
+        } else if let Some(&delta) = cmd.get(cmd::MOVE_PALETTE_SELECTION) {
+            data.palette.move_selection(delta, &data.history);
+            Handled::Yes
+        } else if cmd.is(cmd::SUBMIT_COMMAND_PALETTE) {
+            // The entries can lag behind the input by the filter delay.
+            data.palette.update_entries(&data.history);
+            if let Some(entry) = data.palette.selected_entry() {
+                ctx.submit_command(cmd::RUN_PALETTE_ENTRY.with(entry));
+            }
+            Handled::Yes
+        } else if let Some(entry) = cmd.get(cmd::RUN_PALETTE_ENTRY).cloned() {
+            data.palette.reset();
+            data.palette.open = false;
+            match entry {
+                PaletteEntry::Page(nav) | PaletteEntry::Recent(nav) => {
+                    ctx.submit_command(cmd::NAVIGATE.with(nav));
+                }
+                PaletteEntry::Command(command) => match command {
+                    PaletteCommand::PlayPause => ctx.submit_command(cmd::PLAY_PAUSE_OR_RESUME),
+                    PaletteCommand::Next => ctx.submit_command(cmd::PLAY_NEXT),
+                    PaletteCommand::Previous => ctx.submit_command(cmd::PLAY_PREVIOUS),
+                    PaletteCommand::ToggleShuffle => {
+                        let behavior = match data.playback.queue_behavior {
+                            QueueBehavior::Random => QueueBehavior::Sequential,
+                            QueueBehavior::Sequential => QueueBehavior::Random,
+                        };
+                        ctx.submit_command(cmd::PLAY_QUEUE_BEHAVIOR.with(behavior));
+                    }
+                    PaletteCommand::CycleRepeat => ctx.submit_command(cmd::PLAY_CYCLE_REPEAT_MODE),
+                    PaletteCommand::ToggleSave => ctx.submit_command(cmd::TOGGLE_SAVE_NOW_PLAYING),
+                    PaletteCommand::Search => {
+                        ctx.submit_command(cmd::SET_FOCUS.to(cmd::WIDGET_SEARCH_INPUT))
+                    }
+                    PaletteCommand::GoBack => ctx.submit_command(cmd::NAVIGATE_BACK.with(1)),
+                    PaletteCommand::Refresh => ctx.submit_command(cmd::REFRESH),
+                    PaletteCommand::ToggleMiniPlayer => ctx.submit_command(cmd::TOGGLE_MINI_PLAYER),
+                    PaletteCommand::ToggleQueue => ctx.submit_command(cmd::TOGGLE_QUEUE_PANEL),
+                    PaletteCommand::OpenPreferences => {
+                        ctx.submit_command(commands::SHOW_PREFERENCES)
+                    }
+                },
+            }
+            Handled::Yes
+        } else {
+            Handled::No
+        }
+    }
+
+    fn command_search(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        _target: Target,
+        cmd: &Command,
+        data: &mut State,
+    ) -> Handled {
+        if let Some(query) = cmd.get(cmd::SUBMIT_SEARCH).cloned() {
+            let selected = data
+                .search
+                .selected_suggestion()
+                .filter(|_| data.search.suggesting);
+            if let Some(suggestion) = selected {
+                ctx.submit_command(cmd::OPEN_SEARCH_SUGGESTION.with(suggestion));
+            } else if let Some(url) = SpotifyUrl::parse(&query) {
+                // Pasted a link, go straight to the item.
+                ctx.submit_command(cmd::OPEN_LINK.with(url));
+            } else if !query.trim().is_empty() {
+                data.search.remember(&query);
+                ctx.submit_command(cmd::NAVIGATE.with(Nav::SearchResults(query)));
+            }
+            data.search.suggesting = false;
+            Handled::Yes
+        } else if let Some(query) = cmd.get(cmd::LIVE_SEARCH).cloned() {
+            if query.trim().is_empty() || SpotifyUrl::parse(&query).is_some() {
+                return Handled::Yes;
+            }
+            match data.route.clone() {
+                // Typing on the results page replaces them, instead of
+                // piling up a history entry for every pause.
+                Nav::SearchResults(current) if current != query => {
+                    self.route_jobs.cancel();
+                    data.common_ctx.track_selection.clear();
+                    data.route = Nav::SearchResults(query.clone());
+                    ctx.submit_command(cmd::LOAD_SEARCH_RESULTS.with(query));
+                }
+                Nav::SearchTopicResults(current, topic) if current != query => {
+                    self.route_jobs.cancel();
+                    data.common_ctx.track_selection.clear();
+                    data.route = Nav::SearchTopicResults(query.clone(), topic);
+                    ctx.submit_command(cmd::LOAD_SEARCH_TOPIC.with((query, topic)));
+                }
+                Nav::SearchResults(_) | Nav::SearchTopicResults(..) => {}
+                _ => {
+                    ctx.submit_command(cmd::NAVIGATE.with(Nav::SearchResults(query)));
+                    // Keep the suggestions open, the user is still typing.
+                    ctx.submit_command(cmd::SHOW_SEARCH_SUGGESTIONS.with(true));
+                }
+            }
+            Handled::Yes
+        } else if let Some(query) = cmd.get(cmd::LOAD_SEARCH_SUGGESTIONS).cloned() {
+            data.search.suggesting = true;
+            data.search.selected = None;
+            if query.trim().is_empty() || SpotifyUrl::parse(&query).is_some() {
+                data.search.top_results = None;
+            } else {
+                let sink = ctx.get_external_handle();
+                let webapi = self.webapi;
+                self.spawn(async move {
+                    let result = webapi.search_suggestions(&query).await;
+                    sink.submit_command(
+                        cmd::UPDATE_SEARCH_SUGGESTIONS,
+                        (query, result),
+                        Target::Auto,
+                    )
+                    .unwrap();
+                });
+            }
+            data.search.update_suggestions();
+            Handled::Yes
+        } else if let Some((query, result)) = cmd.get(cmd::UPDATE_SEARCH_SUGGESTIONS).cloned() {
+            // Drop the results of the text that was typed over in the meantime.
+            if query == data.search.input {
+                match result {
+                    Ok(results) => data.search.top_results = Some(results),
+                    Err(err) => log::warn!("failed to load search suggestions: {:?}", err),
+                }
+                data.search.update_suggestions();
+            }
+            Handled::Yes
+        } else if let Some(&suggesting) = cmd.get(cmd::SHOW_SEARCH_SUGGESTIONS) {
+            data.search.suggesting = suggesting;
+            if suggesting {
+                data.search.update_suggestions();
+            }
+            Handled::Yes
+        } else if let Some(&delta) = cmd.get(cmd::MOVE_SEARCH_SELECTION) {
+            data.search.suggesting = true;
+            data.search.move_selection(delta);
+            Handled::Yes
+        } else if let Some(suggestion) = cmd.get(cmd::OPEN_SEARCH_SUGGESTION).cloned() {
+            let nav = match suggestion {
+                SearchSuggestion::Query(query) => {
+                    data.search.remember(&query);
+                    data.search.input = query.clone();
+                    Some(Nav::SearchResults(query))
+                }
+                SearchSuggestion::Artist(link) => Some(Nav::ArtistDetail(link)),
+                SearchSuggestion::Album(link) => Some(Nav::AlbumDetail(link)),
+                SearchSuggestion::Track(track) => track.album.clone().map(Nav::AlbumDetail),
+            };
+            if let Some(nav) = nav {
+                ctx.submit_command(cmd::NAVIGATE.with(nav));
+            }
+            data.search.suggesting = false;
+            data.search.selected = None;
+            Handled::Yes
+        } else if cmd.is(cmd::CLEAR_SEARCH_HISTORY) {
+            data.search.clear_history();
+            data.search.save_history();
+            Handled::Yes
+        } else if let Some(query) = cmd.get(cmd::LOAD_SEARCH_RESULTS).cloned() {
             data.search.results.defer(query.clone());
-            self.spawn(move || {
-                let result = WebApi::global().search(&query);
-                sink.submit_command(cmd::UPDATE_SEARCH_RESULTS, result, Target::Auto)
+            let webapi = self.webapi;
+            self.spawn_for_route(ctx, move |sink| async move {
+                let result = webapi.search(&query).await;
+                sink.submit_command(cmd::UPDATE_SEARCH_RESULTS, (query, result), Target::Auto)
+                    .unwrap();
+            });
+            Handled::Yes
+        } else if let Some((query, result)) = cmd.get(cmd::UPDATE_SEARCH_RESULTS).cloned() {
+            if data.search.results.is_deferred(&query) {
+                data.search.results.resolve_or_reject(result);
+            }
+            Handled::Yes
+        } else if let Some((query, topic)) = cmd.get(cmd::LOAD_SEARCH_TOPIC).cloned() {
+            let key = (query, topic);
+            data.search.topic_results.defer(key.clone());
+            let webapi = self.webapi;
+            self.spawn_for_route(ctx, move |sink| async move {
+                let result = webapi.search_topic(&key.0, key.1, 0).await;
+                sink.submit_command(cmd::UPDATE_SEARCH_TOPIC, (key, 0, result), Target::Auto)
+                    .unwrap();
+            });
+            Handled::Yes
+        } else if cmd.is(cmd::LOAD_MORE_SEARCH_TOPIC) {
+            if let (Promise::Resolved(results), Nav::SearchTopicResults(query, topic)) =
+                (&data.search.topic_results, &data.route)
+            {
+                if let Some(offset) = results.next_offset {
+                    let key = (query.clone(), *topic);
+                    let webapi = self.webapi;
+                    self.spawn_for_route(ctx, move |sink| async move {
+                        let result = webapi.search_topic(&key.0, key.1, offset).await;
+                        sink.submit_command(
+                            cmd::UPDATE_SEARCH_TOPIC,
+                            (key, offset, result),
+                            Target::Auto,
+                        )
+                        .unwrap();
+                    });
+                }
+            }
+            Handled::Yes
+        } else if let Some((key, offset, result)) = cmd.get(cmd::UPDATE_SEARCH_TOPIC).cloned() {
+            match (offset, result) {
+                (0, result) => {
+                    if data.search.topic_results.is_deferred(&key) {
+                        data.search.topic_results.resolve_or_reject(result);
+                    }
+                }
+                (offset, Ok(page)) => {
+                    if let Promise::Resolved(results) = &mut data.search.topic_results {
+                        // Ignore pages that don't continue the list we have.
+                        if results.query == key.0 && results.next_offset == Some(offset) {
+                            results.append(page);
+                        }
+                    }
+                }
+                (_, Err(err)) => {
+                    log::error!("failed to load more search results: {:?}", err);
+                }
+            }
+            Handled::Yes
+        } else if let Some(url) = cmd.get(cmd::OPEN_LINK).cloned() {
+            let sink = ctx.get_external_handle();
+            let webapi = self.webapi;
+            self.spawn(async move {
+                match webapi.load_nav(&url).await {
+                    Ok(nav) => {
+                        sink.submit_command(cmd::NAVIGATE, nav, Target::Auto)
+                            .unwrap();
+                    }
+                    Err(err) => {
+                        log::error!("failed to open {:?}: {:?}", url, err);
+                    }
+                }
+            });
+            Handled::Yes
+        } else if let Some(url) = cmd.get(cmd::PLAY_LINK).cloned() {
+            self.spawn_link_playback(ctx, url, |sink, payload| {
+                sink.submit_command(cmd::PLAY_TRACKS, payload, Target::Auto)
+                    .unwrap();
+            });
+            Handled::Yes
+        } else if let Some(url) = cmd.get(cmd::SHUFFLE_LINK).cloned() {
+            self.spawn_link_playback(ctx, url, |sink, mut payload| {
+                // Start from a random track, the rest follow shuffled.
+                payload.position = rand::thread_rng().gen_range(0..payload.tracks.len());
+                sink.submit_command(
+                    cmd::PLAY_QUEUE_BEHAVIOR,
+                    QueueBehavior::Random,
+                    Target::Auto,
+                )
+                .unwrap();
+                sink.submit_command(cmd::PLAY_TRACKS, payload, Target::Auto)
                     .unwrap();
             });
             Handled::Yes
-        } else if let Some(result) = cmd.get(cmd::UPDATE_SEARCH_RESULTS).cloned() {
-            data.search.results.resolve_or_reject(result);
+        } else if let Some(url) = cmd.get(cmd::QUEUE_LINK).cloned() {
+            self.spawn_link_playback(ctx, url, |sink, payload| {
+                let origin = payload.origin;
+                let queued = payload
+                    .tracks
+                    .into_iter()
+                    .map(|track| QueuedTrack {
+                        track,
+                        origin: origin.clone(),
+                        added: true,
+                    })
+                    .collect();
+                sink.submit_command(cmd::ADD_ALL_TO_QUEUE, queued, Target::Auto)
+                    .unwrap();
+            });
             Handled::Yes
         } else {
             Handled::No
@@ -441,15 +2417,172 @@ impl Delegate {
             });
             let item = item.clone();
             let sink = ctx.get_external_handle();
-            self.spawn(move || {
-                let result = WebApi::global().get_audio_analysis(&item.to_base62());
+            let webapi = self.webapi;
+            self.spawn(async move {
+                let result = webapi.get_audio_analysis(&item.to_base62()).await;
                 sink.submit_command(cmd::UPDATE_AUDIO_ANALYSIS, (item, result), Target::Auto)
                     .unwrap();
             });
 
+            // Episodes don't have any lyrics, nor can they be saved as tracks.
+            if item.id_type != ItemIdType::Podcast {
+                ctx.submit_command(cmd::CHECK_SAVED_TRACKS.with(Vector::unit(item)));
+
+                data.playback.now_playing.as_mut().map(|current| {
+                    current.lyrics.defer(item);
+                });
+                let sink = ctx.get_external_handle();
+                let webapi = self.webapi;
+                self.spawn(async move {
+                    let result = webapi.get_lyrics(item).await;
+                    sink.submit_command(cmd::UPDATE_LYRICS, (item, result), Target::Auto)
+                        .unwrap();
+                });
+            }
+
             Handled::No
+        } else if cmd.is(cmd::PLAYBACK_ENDED) {
+            if data.config.autoplay {
+                // Seed the recommendations with the last few tracks played, the
+                // endpoint accepts at most five seeds, and no episodes.
+                const MAX_SEEDS: usize = 5;
+
+                let mut seeds: Vec<TrackId> = Vec::new();
+                for played in data.playback.history.iter() {
+                    let id = played.track.id;
+                    if id.id_type == ItemIdType::Track && !seeds.contains(&id) {
+                        seeds.push(id);
+                    }
+                    if seeds.len() == MAX_SEEDS {
+                        break;
+                    }
+                }
+                if !seeds.is_empty() {
+                    let sink = ctx.get_external_handle();
+                    let webapi = self.webapi;
+                    self.spawn(async move {
+                        match webapi.get_recommendations(&seeds, &[]).await {
+                            Ok(tracks) if !tracks.is_empty() => {
+                                let payload = PlaybackPayload {
+                                    origin: PlaybackOrigin::Recommendations,
+                                    tracks,
+                                    position: 0,
+                                };
+                                sink.submit_command(cmd::PLAY_TRACKS, payload, Target::Auto)
+                                    .unwrap();
+                            }
+                            Ok(_) => {
+                                log::info!("no recommendations to autoplay");
+                            }
+                            Err(err) => {
+                                log::error!("failed to load recommendations: {:?}", err);
+                            }
+                        }
+                    });
+                }
+            }
+            Handled::Yes
         } else {
             Handled::No
         }
     }
 }
+
+/// Resolves the tracks of a context loaded from another Connect device.
+async fn load_connect_tracks(
+    webapi: &dyn SpotifyApi,
+    load: ConnectLoad,
+) -> Result<Option<(PlaybackPayload, Duration, bool)>, Error> {
+    let ids: Vec<TrackId> = load.tracks.iter().copied().map(TrackId::from).collect();
+    let tracks = webapi.get_tracks(&ids).await?;
+    if tracks.is_empty() {
+        return Ok(None);
+    }
+    // Some of the tracks might not be available, find the current one again.
+    let current = load.tracks.get(load.position);
+    let (position, progress) = match tracks.iter().position(|t| Some(&*t.id) == current) {
+        Some(position) => (position, load.progress),
+        None => (0, Duration::default()),
+    };
+    let origin = connect_origin(webapi, &load.context_uri, &tracks[position]).await;
+    let payload = PlaybackPayload {
+        origin,
+        tracks,
+        position,
+    };
+    Ok(Some((payload, progress, load.paused)))
+}
+
+async fn connect_origin(
+    webapi: &dyn SpotifyApi,
+    context_uri: &str,
+    track: &Track,
+) -> PlaybackOrigin {
+    let mut parts = context_uri.split(':').skip(1);
+    match (parts.next(), parts.next()) {
+        (Some("album"), Some(id)) => match &track.album {
+            Some(album) if &*album.id == id => PlaybackOrigin::Album(album.clone()),
+            _ => PlaybackOrigin::Library,
+        },
+        (Some("artist"), Some(id)) => match track.artists.iter().find(|a| &*a.id == id) {
+            Some(artist) => PlaybackOrigin::Artist(artist.clone()),
+            None => PlaybackOrigin::Library,
+        },
+        (Some("playlist"), Some(id)) => match webapi.get_playlist(id).await {
+            Ok(playlist) => PlaybackOrigin::Playlist(playlist.link()),
+            Err(_) => PlaybackOrigin::Library,
+        },
+        _ => PlaybackOrigin::Library,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{data::AlbumLink, webapi::FakeSpotifyApi};
+    use psst_core::item_id::ItemId;
+
+    fn track(id: u128, album_id: &str) -> Arc<Track> {
+        Arc::new(Track {
+            id: TrackId::from(ItemId::new(id, ItemIdType::Track)),
+            name: format!("Track {}", id).into(),
+            album: Some(AlbumLink {
+                id: album_id.into(),
+                name: "Album".into(),
+                images: Vector::new(),
+            }),
+            artists: Vector::new(),
+            duration: Duration::from_secs(180),
+            disc_number: 1,
+            track_number: id as usize,
+            explicit: false,
+            is_local: false,
+            is_playable: Some(true),
+            is_available: None,
+            linked_from: None,
+            popularity: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn connect_load_finds_the_current_track_among_the_available_ones() {
+        let tracks = [track(1, "album"), track(2, "album"), track(3, "album")];
+        // The first track is not available, the rest move up.
+        let webapi = FakeSpotifyApi::new().with_tracks(tracks[1..].iter().cloned());
+        let load = ConnectLoad {
+            context_uri: "spotify:album:album".to_string(),
+            tracks: tracks.iter().map(|track| *track.id).collect(),
+            position: 2,
+            progress: Duration::from_secs(30),
+            paused: true,
+        };
+
+        let (payload, progress, paused) =
+            load_connect_tracks(&webapi, load).await.unwrap().unwrap();
+        assert_eq!(payload.tracks.len(), 2);
+        assert_eq!(payload.position, 1);
+        assert_eq!(progress, Duration::from_secs(30));
+        assert!(paused);
+        assert!(matches!(payload.origin, PlaybackOrigin::Album(album) if &*album.id == "album"));
+    }
+}
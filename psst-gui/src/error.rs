@@ -1,16 +1,54 @@
 use druid::Data;
-use std::{error, fmt};
+use std::{error, fmt, time::Duration};
 
 #[derive(Clone, Debug, Data)]
 pub enum Error {
+    /// Spotify asked us to slow down, and kept asking for longer than we were
+    /// willing to wait.
+    RateLimited {
+        retry_after: Duration,
+    },
+    /// The access token was refused, a new one is requested next time.
+    Unauthorized,
+    NotFound,
+    /// The request didn't make it to the server, or the response didn't make
+    /// it back.
+    Network(String),
+    /// The server stopped responding in the middle of a request.
+    TimedOut,
+    /// The response came in a shape we don't understand.
+    Parse(String),
+    /// Reading or writing the local cache failed.
+    Io(String),
     WebApiError(String),
 }
 
+impl Error {
+    /// True if trying the same thing again later has a chance to succeed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::RateLimited { .. } | Self::Network(_) | Self::TimedOut
+        )
+    }
+}
+
 impl error::Error for Error {}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            Self::RateLimited { retry_after } => write!(
+                f,
+                "Too many requests, try again in {} seconds",
+                retry_after.as_secs().max(1)
+            ),
+            Self::Unauthorized => f.write_str("Not authorized, try again to log in anew"),
+            Self::NotFound => f.write_str("Not found, it might have been removed"),
+            Self::Network(err) => write!(f, "Network error: {}", err),
+            Self::TimedOut => f.write_str("Request timed out"),
+            Self::Parse(err) => write!(f, "Unexpected response: {}", err),
+            Self::Io(err) => write!(f, "Cache error: {}", err),
             Self::WebApiError(err) => f.write_str(err),
         }
     }
@@ -0,0 +1,310 @@
+//! Runs the player without any window, controlled only through the IPC
+//! channel.  Meant for servers and kiosks.
+
+use std::{
+    collections::HashMap,
+    io::BufReader,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use crossbeam_channel::Sender;
+use psst_core::{
+    audio_normalize::NormalizationLevel,
+    audio_output::AudioOutput,
+    audio_player::{PlaybackConfig, PlaybackItem, Player, PlayerCommand, PlayerEvent},
+    cache::Cache,
+    cdn::Cdn,
+    ipc::{
+        self, IpcInstance, IpcListener, IpcRequest, IpcResponse, IpcStatus, IpcStream, IpcToken,
+        IpcTrack,
+    },
+    item_id::ItemId,
+    session::{SessionConfig, SessionHandle},
+};
+use tokio::runtime::{self, Runtime};
+
+use crate::{
+    data::{Config, Normalization, PlaybackOrigin, Track},
+    error::Error,
+    webapi::WebApi,
+};
+
+// How long to wait before reconnecting after the session got lost.
+const RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+#[derive(Default)]
+struct Status {
+    state: &'static str,
+    item: Option<ItemId>,
+    progress: Duration,
+    // Metadata of the queued tracks, used for reporting the status.
+    tracks: HashMap<ItemId, Arc<Track>>,
+}
+
+struct Daemon {
+    sender: Sender<PlayerEvent>,
+    status: Mutex<Status>,
+    playback_config: Mutex<PlaybackConfig>,
+    normalization: Normalization,
+    /// Drives the Web API requests of the control connections.
+    runtime: Runtime,
+}
+
+pub fn run(config: Config, session: SessionHandle) {
+    if !config.has_credentials() {
+        log::error!("no credentials configured, log in from the preferences first");
+        return;
+    }
+    let listener = match IpcListener::bind() {
+        Ok(listener) => listener,
+        Err(err) => {
+            log::error!("failed to open the control channel: {}", err);
+            return;
+        }
+    };
+
+    thread::spawn({
+        let session = session.clone();
+        let session_config = config.session();
+        move || connect_and_service(session, session_config)
+    });
+
+    let mut output = AudioOutput::open().unwrap();
+    output.set_device(config.audio_device.clone());
+    let output_remote = output.remote();
+
+    let cache = Cache::new(Config::cache_dir().unwrap()).unwrap();
+    if let Err(err) = cache.set_audio_limit(config.audio_cache_limit()) {
+        log::error!("failed to limit audio cache: {:?}", err);
+    }
+    let proxy_url = Config::proxy();
+    let mut player = Player::new(
+        session.clone(),
+        Cdn::new(session, proxy_url.as_deref()).unwrap(),
+        cache,
+        config.playback(),
+        output.remote(),
+    );
+    let source = player.audio_source();
+    let output_thread = thread::spawn(move || {
+        output.start_playback(source).expect("Playback failed");
+    });
+
+    let daemon = Arc::new(Daemon {
+        sender: player.event_sender(),
+        status: Mutex::new(Status {
+            state: "stopped",
+            ..Status::default()
+        }),
+        playback_config: Mutex::new(config.playback()),
+        normalization: config.normalization,
+        runtime: runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .expect("failed to start the background runtime"),
+    });
+
+    thread::spawn({
+        let daemon = daemon.clone();
+        move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let token = listener.token();
+                        let daemon = daemon.clone();
+                        thread::spawn(move || daemon.serve_client(stream, &token));
+                    }
+                    Err(err) => {
+                        log::warn!("failed to accept control connection: {}", err);
+                    }
+                }
+            }
+        }
+    });
+
+    log::info!("running headless");
+    for event in player.event_receiver() {
+        daemon.update_status(&event);
+        player.handle(event);
+    }
+    output_remote.close();
+    output_thread.join().unwrap();
+}
+
+fn connect_and_service(handle: SessionHandle, config: SessionConfig) {
+    loop {
+        let result = handle
+            .connect(config.clone())
+            .and_then(|session| session.service());
+        match result {
+            Ok(_) => log::info!("connection shutdown"),
+            Err(err) => log::error!("connection error: {:?}", err),
+        }
+        thread::sleep(RECONNECT_DELAY);
+    }
+}
+
+impl Daemon {
+    fn serve_client(&self, stream: IpcStream, token: &IpcToken) {
+        let mut writer = match stream.try_clone() {
+            Ok(writer) => writer,
+            Err(err) => {
+                log::warn!("failed to open control connection: {}", err);
+                return;
+            }
+        };
+        let mut reader = BufReader::new(stream);
+        if let Err(err) = token.verify(&mut reader) {
+            log::warn!("refused control connection: {}", err);
+            return;
+        }
+        loop {
+            let response = match ipc::read_message(&mut reader) {
+                Ok(Some(request)) => self.handle_request(request),
+                Ok(None) => break,
+                Err(err) => {
+                    // Whoever is on the other side doesn't speak the protocol,
+                    // tell them and hang up.
+                    let response = IpcResponse::Error {
+                        message: err.to_string(),
+                    };
+                    ipc::write_message(&mut writer, &response).ok();
+                    break;
+                }
+            };
+            if let Err(err) = ipc::write_message(&mut writer, &response) {
+                log::warn!("failed to respond to control request: {}", err);
+                break;
+            }
+        }
+    }
+
+    fn handle_request(&self, request: IpcRequest) -> IpcResponse {
+        match request {
+            IpcRequest::Status => {
+                return IpcResponse::Status(self.status());
+            }
+            IpcRequest::Show => {
+                // There's no window to show.
+            }
+            IpcRequest::Open { .. } => {
+                return IpcResponse::Error {
+                    message: "there are no pages to open in the headless mode".to_string(),
+                };
+            }
+            IpcRequest::Play { uri: Some(uri) } => {
+                if let Err(err) = self.play_uri(&uri) {
+                    return IpcResponse::Error {
+                        message: err.to_string(),
+                    };
+                }
+            }
+            IpcRequest::Play { uri: None } => self.send(PlayerCommand::Resume),
+            IpcRequest::Pause => self.send(PlayerCommand::Pause),
+            IpcRequest::Toggle => self.send(PlayerCommand::PauseOrResume),
+            IpcRequest::Next => self.send(PlayerCommand::Next),
+            IpcRequest::Previous => self.send(PlayerCommand::Previous),
+            IpcRequest::Stop => self.send(PlayerCommand::Stop),
+            IpcRequest::Seek { position_ms } => self.send(PlayerCommand::Seek {
+                position: Duration::from_millis(position_ms),
+            }),
+            IpcRequest::Volume { level } => {
+                let mut config = self.playback_config.lock().unwrap();
+                config.volume = level.min(100) as f32 / 100.0;
+                self.send(PlayerCommand::Configure {
+                    config: config.clone(),
+                });
+            }
+        }
+        IpcResponse::Ok {
+            instance: IpcInstance::Headless,
+        }
+    }
+
+    fn play_uri(&self, uri: &str) -> Result<(), Error> {
+        let payload = self.runtime.block_on(WebApi::global().load_uri(uri))?;
+        let norm_level = match (self.normalization, &payload.origin) {
+            (Normalization::Off, _) => NormalizationLevel::None,
+            (Normalization::Track, _) => NormalizationLevel::Track,
+            (Normalization::Album, _) => NormalizationLevel::Album,
+            (Normalization::Automatic, PlaybackOrigin::Album(_)) => NormalizationLevel::Album,
+            (Normalization::Automatic, _) => NormalizationLevel::Track,
+        };
+        let items = payload
+            .tracks
+            .iter()
+            .map(|track| PlaybackItem {
+                item_id: *track.id,
+                norm_level,
+            })
+            .collect();
+        self.status.lock().unwrap().tracks = payload
+            .tracks
+            .into_iter()
+            .map(|track| (*track.id, track))
+            .collect();
+        self.send(PlayerCommand::LoadQueue {
+            items,
+            position: payload.position,
+        });
+        Ok(())
+    }
+
+    fn send(&self, command: PlayerCommand) {
+        self.sender.send(PlayerEvent::Command(command)).unwrap();
+    }
+
+    fn update_status(&self, event: &PlayerEvent) {
+        let mut status = self.status.lock().unwrap();
+        match event {
+            PlayerEvent::Loading { item } => {
+                status.state = "loading";
+                status.item.replace(item.item_id);
+                status.progress = Duration::default();
+            }
+            PlayerEvent::Playing { path, duration } => {
+                status.state = "playing";
+                status.item.replace(path.item_id);
+                status.progress = *duration;
+            }
+            PlayerEvent::Pausing { .. } => {
+                status.state = "paused";
+            }
+            PlayerEvent::Resuming { .. } => {
+                status.state = "playing";
+            }
+            PlayerEvent::Progress { duration, .. } => {
+                status.progress = *duration;
+            }
+            PlayerEvent::Stopped => {
+                status.state = "stopped";
+                status.item.take();
+                status.progress = Duration::default();
+            }
+            _ => {}
+        }
+    }
+
+    fn status(&self) -> IpcStatus {
+        let status = self.status.lock().unwrap();
+        let track = status.item.map(|item_id| {
+            let track = status.tracks.get(&item_id);
+            IpcTrack {
+                uri: format!("spotify:track:{}", item_id.to_base62()),
+                name: track.map(|t| t.name.to_string()),
+                artist: track.map(|t| t.artist_name()),
+                album: track.map(|t| t.album_name()),
+                duration_ms: track.map(|t| t.duration.as_millis() as u64),
+            }
+        });
+        IpcStatus {
+            state: status.state.to_string(),
+            track,
+            progress_ms: status.progress.as_millis() as u64,
+            volume: (self.playback_config.lock().unwrap().volume * 100.0).round() as u32,
+        }
+    }
+}
@@ -3,35 +3,61 @@ mod controller;
 mod data;
 mod delegate;
 mod error;
+mod headless;
 mod ui;
 mod webapi;
 mod widget;
+mod worker;
 
 use crate::{
-    data::{Config, State},
+    data::{Config, EpisodePositions, SavedPlayback, Search, SpotifyUrl, State},
     delegate::Delegate,
 };
-use druid::AppLauncher;
+use druid::{AppLauncher, Target};
 use env_logger::{Builder, Env};
+use psst_core::ipc::{self, IpcInstance, IpcRequest, IpcResponse};
+use std::{env, io::BufReader, thread, time::Duration};
 use webapi::WebApi;
 
 const ENV_LOG: &str = "PSST_LOG";
 const ENV_LOG_STYLE: &str = "PSST_LOG_STYLE";
 
+// How often the library is checked for changes made on other devices.
+const LIBRARY_REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
 fn main() {
     // Setup logging from the env variables, with defaults.
     Builder::from_env(
         Env::new()
-            // `ureq` is a bit too noisy, log only warnings by default.
-            .filter_or(ENV_LOG, "info,ureq::unit=warn")
+            .filter_or(ENV_LOG, "info")
             .write_style(ENV_LOG_STYLE),
     )
     .init();
 
-    let state = State {
+    let headless = env::args().any(|arg| arg == "--headless");
+    if !headless && activate_running_instance() {
+        return;
+    }
+
+    let mut state = State {
         config: Config::load().unwrap_or_default(),
         ..State::default()
     };
+    state.common_ctx.track_columns = state
+        .config
+        .track_columns
+        .iter()
+        .map(|(view, columns)| (*view, columns.iter().copied().collect()))
+        .collect();
+    if let Some(saved) = SavedPlayback::load() {
+        state.restore_playback(saved);
+    }
+    if let Some(positions) = EpisodePositions::load() {
+        state.common_ctx.episode_positions = positions;
+    }
+    if let Some(history) = Search::load_history() {
+        state.search.history = history;
+    }
 
     WebApi::new(
         state.session.clone(),
@@ -39,23 +65,121 @@ fn main() {
         Config::cache_dir(),
     )
     .install_as_global();
+    WebApi::global().set_image_cache_limit(state.config.image_cache_limit());
+
+    if headless {
+        // Play without any window, controlled only over the IPC channel.
+        headless::run(state.config, state.session);
+        return;
+    }
 
     let delegate;
     let launcher;
     if state.config.has_credentials() {
         // Credentials are configured, open the main window.
         let window = ui::main_window();
-        delegate = Delegate::with_main(window.id);
+        delegate = Delegate::with_main(WebApi::global(), window.id);
         launcher = AppLauncher::with_window(window).configure_env(ui::theme::setup);
     } else {
         // No configured credentials, open the preferences.
         let window = ui::preferences_window();
-        delegate = Delegate::with_preferences(window.id);
+        delegate = Delegate::with_preferences(WebApi::global(), window.id);
         launcher = AppLauncher::with_window(window).configure_env(ui::theme::setup);
     };
 
+    let sink = launcher.get_external_handle();
+    WebApi::global().set_retry_listener(move |retrying| {
+        sink.submit_command(cmd::UPDATE_RETRYING, retrying, Target::Auto)
+            .ok();
+    });
+
+    let sink = launcher.get_external_handle();
+    WebApi::global().set_error_listener(move |err| {
+        sink.submit_command(cmd::WEBAPI_ERROR, err.to_owned(), Target::Auto)
+            .ok();
+    });
+
+    let sink = launcher.get_external_handle();
+    thread::spawn(move || loop {
+        thread::sleep(LIBRARY_REFRESH_INTERVAL);
+        if sink
+            .submit_command(cmd::REFRESH_LIBRARY, (), Target::Auto)
+            .is_err()
+        {
+            break;
+        }
+    });
+
+    if let Some(url) = link_arg().as_deref().and_then(SpotifyUrl::parse) {
+        launcher
+            .get_external_handle()
+            .submit_command(cmd::OPEN_LINK, url, Target::Auto)
+            .unwrap();
+    }
+
     launcher
         .delegate(delegate)
         .launch(state)
         .expect("Application launch");
 }
+
+/// If the psst app is already running, focus its window, hand over the link
+/// from the command line (if any) and return true.  A headless instance has no
+/// window to show, so the app starts anyway.
+fn activate_running_instance() -> bool {
+    let stream = match ipc::connect() {
+        Ok(stream) => stream,
+        Err(_) => return false,
+    };
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(err) => {
+            log::error!("failed to talk to the running instance: {}", err);
+            return false;
+        }
+    };
+    let mut reader = BufReader::new(stream);
+    let mut send = |request: &IpcRequest| {
+        ipc::write_message(&mut writer, request)
+            .and_then(|_| ipc::read_message::<IpcResponse>(&mut reader))
+    };
+
+    match send(&IpcRequest::Show) {
+        Ok(Some(IpcResponse::Ok {
+            instance: IpcInstance::Gui,
+        })) => {
+            log::info!("psst is already running, activating the existing instance");
+        }
+        Ok(Some(IpcResponse::Ok {
+            instance: IpcInstance::Headless,
+        })) => {
+            log::info!("psst is running headless, starting the app anyway");
+            return false;
+        }
+        Ok(Some(IpcResponse::Error { message })) => {
+            log::error!("{}", message);
+            return false;
+        }
+        Ok(_) => return false,
+        Err(err) => {
+            log::error!("failed to talk to the running instance: {}", err);
+            return false;
+        }
+    }
+    if let Some(uri) = link_arg() {
+        match send(&IpcRequest::Open { uri }) {
+            Ok(Some(IpcResponse::Error { message })) => log::error!("{}", message),
+            Ok(_) => {}
+            Err(err) => log::error!("failed to talk to the running instance: {}", err),
+        }
+    }
+    true
+}
+
+/// `spotify:` URI or `open.spotify.com` link passed on the command line, i.e.
+/// by the OS when psst is registered as the handler of the `spotify` scheme.
+fn link_arg() -> Option<String> {
+    env::args()
+        .skip(1)
+        .find(|arg| SpotifyUrl::parse(arg).is_some())
+}
@@ -1,17 +1,28 @@
 use crate::{
     cmd,
-    data::{Album, AlbumDetail, ArtistLink, Cached, CommonCtx, Ctx, Nav, State},
+    data::{
+        Album, AlbumDetail, ArtistLink, Cached, CommonCtx, Copyright, Ctx, Nav, SpotifyUrl, State,
+        TrackView,
+    },
     ui::{
+        artist::artist_menu,
+        download::download_button_widget,
         theme,
         track::{tracklist_widget, TrackDisplay},
-        utils::{error_widget, placeholder_widget, spinner_widget},
+        utils::{
+            error_widget, open_in_spotify_menu, placeholder_widget, play_button_widget,
+            spinner_widget,
+        },
     },
-    widget::{Async, Clip, LinkExt, RemoteImage},
+    widget::{Async, Clip, Hover, ImageTint, LinkExt, RemoteImage},
 };
 use druid::{
-    widget::{CrossAxisAlignment, Flex, Label, LineBreaking, List},
-    LensExt, LocalizedString, Menu, MenuItem, MouseButton, Size, Widget, WidgetExt,
+    im::Vector,
+    widget::{Controller, ControllerHost, CrossAxisAlignment, Flex, Label, LineBreaking, List},
+    Env, Insets, LensExt, LifeCycle, LifeCycleCtx, LocalizedString, Menu, MenuItem, MouseButton,
+    Size, UnitPoint, UpdateCtx, Widget, WidgetExt,
 };
+use std::sync::Arc;
 
 pub fn detail_widget() -> impl Widget<State> {
     Async::new(
@@ -25,63 +36,136 @@ pub fn detail_widget() -> impl Widget<State> {
 }
 
 fn loaded_detail_widget() -> impl Widget<Ctx<CommonCtx, Cached<Album>>> {
-    let album_cover = rounded_cover_widget(theme::grid(10.0));
+    let cover_size = theme::grid(10.0);
+    let album_cover = rounded_cover_widget(cover_size);
 
     let album_artists = List::new(|| {
         Label::raw()
             .with_line_break_mode(LineBreaking::WordWrap)
             .with_font(theme::UI_FONT_MEDIUM)
             .link()
-            .lens(ArtistLink::name)
-            .on_click(|ctx, artist_link: &mut ArtistLink, _| {
-                let nav = Nav::ArtistDetail(artist_link.to_owned());
-                ctx.submit_command(cmd::NAVIGATE.with(nav));
+            .lens(Ctx::data().then(ArtistLink::name))
+            .on_ex_click(|ctx, event, artist: &mut Ctx<CommonCtx, ArtistLink>, _| {
+                match event.button {
+                    MouseButton::Left => {
+                        let nav = Nav::ArtistDetail(artist.data.to_owned());
+                        ctx.submit_command(cmd::NAVIGATE.with(nav));
+                    }
+                    MouseButton::Right => {
+                        let menu = artist_menu(&artist.data, &artist.ctx);
+                        ctx.show_context_menu(menu, event.window_pos);
+                    }
+                    _ => {}
+                }
             })
     })
-    .lens(Album::artists);
+    .lens(Ctx::map(Album::artists));
 
     let album_date = Label::dynamic(|album: &Album, _| album.release())
-        .with_text_size(theme::TEXT_SIZE_SMALL)
-        .with_text_color(theme::PLACEHOLDER_COLOR);
-
-    let album_label = Label::raw()
-        .with_line_break_mode(LineBreaking::WordWrap)
         .with_text_size(theme::TEXT_SIZE_SMALL)
         .with_text_color(theme::PLACEHOLDER_COLOR)
-        .lens(Album::label);
+        .lens(Ctx::data());
 
     let album_info = Flex::column()
         .cross_axis_alignment(CrossAxisAlignment::Start)
         .with_child(album_artists)
         .with_default_spacer()
         .with_child(album_date)
-        .with_default_spacer()
-        .with_child(album_label)
         .padding(theme::grid(1.0));
 
-    let album_tracks = tracklist_widget(TrackDisplay {
-        number: true,
-        title: true,
-        ..TrackDisplay::empty()
-    });
+    let album_tracks = tracklist_widget(
+        TrackView::Album,
+        TrackDisplay {
+            number: true,
+            title: true,
+            discs: true,
+            duration: true,
+            ..TrackDisplay::empty()
+        },
+    );
 
     Flex::column()
         .cross_axis_alignment(CrossAxisAlignment::Start)
         .with_child(
-            Flex::row()
-                .with_spacer(theme::grid(4.0))
-                .with_child(album_cover)
-                .with_default_spacer()
-                .with_child(album_info)
-                .lens(Ctx::data()),
+            ImageTint::new(
+                Flex::row()
+                    .with_spacer(theme::grid(4.0))
+                    .with_child(album_cover.lens(Ctx::data()))
+                    .with_default_spacer()
+                    .with_child(album_info)
+                    .padding((0.0, theme::grid(1.0)))
+                    .expand_width(),
+                move |album: &Ctx<CommonCtx, Album>, env| {
+                    let size = cover_size * env.get(theme::IMAGE_SCALE);
+                    album.data.image(size, size).map(|image| image.url.clone())
+                },
+            )
+            .on_ex_click(|ctx, event, album: &mut Ctx<CommonCtx, Album>, _| {
+                if event.button == MouseButton::Right {
+                    ctx.show_context_menu(album_menu(album), event.window_pos);
+                }
+            }),
+        )
+        .with_spacer(theme::grid(1.0))
+        .with_child(
+            download_button_widget()
+                .lens(Ctx::map(Album::tracks))
+                .padding((theme::grid(4.0), 0.0)),
         )
         .with_spacer(theme::grid(1.0))
         .with_child(album_tracks)
+        .with_child(credits_widget().lens(Ctx::data()))
         .lens(Ctx::map(Cached::data))
 }
 
+fn credits_widget() -> impl Widget<Album> {
+    let detail = |text: fn(&Album) -> String| {
+        Label::dynamic(move |album: &Album, _| text(album))
+            .with_line_break_mode(LineBreaking::WordWrap)
+            .with_text_size(theme::TEXT_SIZE_SMALL)
+            .with_text_color(theme::PLACEHOLDER_COLOR)
+    };
+
+    let album_copyrights = List::new(|| {
+        Label::dynamic(|copyright: &Copyright, _| copyright.notice())
+            .with_line_break_mode(LineBreaking::WordWrap)
+            .with_text_size(theme::TEXT_SIZE_SMALL)
+            .with_text_color(theme::PLACEHOLDER_COLOR)
+            .padding((0.0, 1.0))
+    })
+    .lens(Album::copyrights);
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Label::new("Credits / Info")
+                .with_font(theme::UI_FONT_MEDIUM)
+                .with_text_color(theme::PLACEHOLDER_COLOR)
+                .with_text_size(theme::TEXT_SIZE_SMALL)
+                .padding(Insets::new(0.0, theme::grid(2.0), 0.0, theme::grid(1.0))),
+        )
+        .with_child(detail(|album| format!("Released {}", album.release())))
+        .with_spacer(1.0)
+        .with_child(detail(|album| {
+            if album.label.is_empty() {
+                String::new()
+            } else {
+                format!("Label: {}", album.label)
+            }
+        }))
+        .with_default_spacer()
+        .with_child(album_copyrights)
+        .padding(Insets::new(
+            theme::grid(1.0),
+            0.0,
+            theme::grid(1.0),
+            theme::grid(1.0),
+        ))
+}
+
 fn cover_widget(size: f64) -> impl Widget<Album> {
-    RemoteImage::new(placeholder_widget(), move |album: &Album, _| {
+    RemoteImage::new(placeholder_widget(), move |album: &Album, env| {
+        let size = size * env.get(theme::IMAGE_SCALE);
         album.image(size, size).map(|image| image.url.clone())
     })
     .fix_size(size, size)
@@ -122,7 +206,15 @@ pub fn album_widget() -> impl Widget<Ctx<CommonCtx, Album>> {
     let album = Flex::row()
         .with_child(album_cover)
         .with_default_spacer()
-        .with_flex_child(album_label, 1.0)
+        .with_flex_child(album_label, 1.0);
+
+    // Centered over the cover.
+    let play_button = play_button_widget(|ctx, album: &mut Album| {
+        ctx.submit_command(cmd::PLAY_LINK.with(SpotifyUrl::Album(album.id.clone())));
+    });
+    let album = Hover::new(album, play_button)
+        .align(UnitPoint::LEFT)
+        .offset((theme::grid(1.75), 0.0))
         .lens(Ctx::data());
 
     album
@@ -141,8 +233,83 @@ pub fn album_widget() -> impl Widget<Ctx<CommonCtx, Album>> {
         )
 }
 
+/// List of albums that asks whether they are saved in the library, so their
+/// menus can offer the right action.
+pub fn album_list_widget() -> impl Widget<Ctx<CommonCtx, Vector<Album>>> {
+    ControllerHost::new(List::new(album_widget), SavedStatusController)
+}
+
+struct SavedStatusController;
+
+impl SavedStatusController {
+    fn album_ids(data: &Ctx<CommonCtx, Vector<Album>>) -> Vector<Arc<str>> {
+        data.data.iter().map(|album| album.id.clone()).collect()
+    }
+}
+
+impl<W> Controller<Ctx<CommonCtx, Vector<Album>>, W> for SavedStatusController
+where
+    W: Widget<Ctx<CommonCtx, Vector<Album>>>,
+{
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &Ctx<CommonCtx, Vector<Album>>,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            let album_ids = Self::album_ids(data);
+            if !album_ids.is_empty() {
+                ctx.submit_command(cmd::CHECK_SAVED_ALBUMS.with(album_ids));
+            }
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &Ctx<CommonCtx, Vector<Album>>,
+        data: &Ctx<CommonCtx, Vector<Album>>,
+        env: &Env,
+    ) {
+        if !old_data.data.ptr_eq(&data.data) {
+            let album_ids = Self::album_ids(data);
+            if !album_ids.is_empty() {
+                ctx.submit_command(cmd::CHECK_SAVED_ALBUMS.with(album_ids));
+            }
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
+
 fn album_menu(album: &Ctx<CommonCtx, Album>) -> Menu<State> {
     let mut menu = Menu::empty();
+    let url = SpotifyUrl::Album(album.data.id.clone());
+
+    menu = menu.entry(
+        MenuItem::new(LocalizedString::new("menu-item-play").with_placeholder("Play"))
+            .command(cmd::PLAY_LINK.with(url.clone())),
+    );
+
+    menu = menu.entry(
+        MenuItem::new(
+            LocalizedString::new("menu-item-shuffle-play").with_placeholder("Shuffle Play"),
+        )
+        .command(cmd::SHUFFLE_LINK.with(url.clone())),
+    );
+
+    menu = menu.entry(
+        MenuItem::new(
+            LocalizedString::new("menu-item-add-to-queue").with_placeholder("Add to Queue"),
+        )
+        .command(cmd::QUEUE_LINK.with(url)),
+    );
+
+    menu = menu.separator();
 
     for artist_link in &album.data.artists {
         let more_than_one_artist = album.data.artists.len() > 1;
@@ -163,6 +330,15 @@ fn album_menu(album: &Ctx<CommonCtx, Album>) -> Menu<State> {
             .command(cmd::COPY.with(album.data.url())),
     );
 
+    menu = menu.entry(
+        MenuItem::new(
+            LocalizedString::new("menu-item-copy-uri").with_placeholder("Copy Spotify URI"),
+        )
+        .command(cmd::COPY.with(album.data.uri())),
+    );
+
+    menu = menu.entry(open_in_spotify_menu(album.data.url(), album.data.uri()));
+
     menu = menu.separator();
 
     if album.ctx.is_album_saved(&album.data) {
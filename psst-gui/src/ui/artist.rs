@@ -1,20 +1,24 @@
 use crate::{
     cmd,
-    data::{Artist, ArtistAlbums, ArtistDetail, ArtistTracks, Cached, CommonCtx, Ctx, Nav, State},
+    data::{
+        Artist, ArtistAlbums, ArtistDetail, ArtistInfo, ArtistLink, ArtistTracks, Cached,
+        CommonCtx, Ctx, Nav, RadioSeed, SpotifyUrl, State, TrackView,
+    },
     ui::{
-        album::album_widget,
+        album::album_list_widget,
         theme,
         track::{tracklist_widget, TrackDisplay},
-        utils::{error_widget, placeholder_widget, spinner_widget},
+        utils::{error_widget, open_in_spotify_menu, placeholder_widget, spinner_widget},
     },
     widget::{Async, Clip, LinkExt, RemoteImage},
 };
 use druid::{
     im::Vector,
     kurbo::Circle,
-    widget::{CrossAxisAlignment, Flex, Label, LabelText, List},
-    Data, Insets, LensExt, Widget, WidgetExt,
+    widget::{Button, CrossAxisAlignment, Either, Flex, Label, LabelText, LineBreaking, List},
+    Data, Insets, LensExt, LocalizedString, Menu, MenuItem, MouseButton, Widget, WidgetExt,
 };
+use itertools::Itertools;
 
 pub fn detail_widget() -> impl Widget<State> {
     let top_tracks = Async::new(
@@ -41,17 +45,54 @@ pub fn detail_widget() -> impl Widget<State> {
     )
     .padding((theme::grid(1.0), 0.0));
 
-    let related_artists = Async::new(|| spinner_widget(), || related_widget(), || error_widget())
-        .lens(State::artist.then(ArtistDetail::related_artists))
+    let related_artists = Async::new(
+        || spinner_widget(),
+        || related_widget(),
+        || error_widget().lens(Ctx::data()),
+    )
+    .lens(
+        Ctx::make(
+            State::common_ctx,
+            State::artist.then(ArtistDetail::related_artists),
+        )
+        .then(Ctx::in_promise()),
+    )
+    .padding((theme::grid(1.0), 0.0));
+
+    let info = Async::new(|| spinner_widget(), || info_widget(), || error_widget())
+        .lens(State::artist.then(ArtistDetail::info))
         .padding((theme::grid(1.0), 0.0));
 
     Flex::column()
+        .with_child(follow_button_widget())
         .with_child(top_tracks)
         .with_child(albums)
+        .with_child(info)
         .with_child(related_artists)
 }
 
-pub fn artist_widget() -> impl Widget<Artist> {
+fn follow_button_widget() -> impl Widget<State> {
+    Either::new(
+        |state: &State, _| match &state.route {
+            Nav::ArtistDetail(link) => state.common_ctx.is_artist_followed(&link.id),
+            _ => false,
+        },
+        Button::new("Following").on_click(|ctx, state: &mut State, _| {
+            if let Nav::ArtistDetail(link) = &state.route {
+                ctx.submit_command(cmd::UNFOLLOW_ARTIST.with(link.to_owned()));
+            }
+        }),
+        Button::new("Follow").on_click(|ctx, state: &mut State, _| {
+            if let Nav::ArtistDetail(link) = &state.route {
+                ctx.submit_command(cmd::FOLLOW_ARTIST.with(link.to_owned()));
+            }
+        }),
+    )
+    .align_left()
+    .padding((theme::grid(1.0), 0.0, theme::grid(1.0), theme::grid(1.0)))
+}
+
+pub fn artist_widget() -> impl Widget<Ctx<CommonCtx, Artist>> {
     let artist_image = cover_widget(theme::grid(7.0));
     let artist_label = Label::raw()
         .with_font(theme::UI_FONT_MEDIUM)
@@ -59,21 +100,72 @@ pub fn artist_widget() -> impl Widget<Artist> {
     let artist = Flex::row()
         .with_child(artist_image)
         .with_default_spacer()
-        .with_flex_child(artist_label, 1.);
-    artist
-        .padding(theme::grid(0.5))
-        .link()
-        .on_click(|ctx, artist, _| {
-            let nav = Nav::ArtistDetail(artist.link());
-            ctx.submit_command(cmd::NAVIGATE.with(nav));
-        })
+        .with_flex_child(artist_label, 1.)
+        .lens(Ctx::data());
+    artist.padding(theme::grid(0.5)).link().on_ex_click(
+        |ctx, event, artist: &mut Ctx<CommonCtx, Artist>, _| match event.button {
+            MouseButton::Left => {
+                let nav = Nav::ArtistDetail(artist.data.link());
+                ctx.submit_command(cmd::NAVIGATE.with(nav));
+            }
+            MouseButton::Right => {
+                let menu = artist_menu(&artist.data.link(), &artist.ctx);
+                ctx.show_context_menu(menu, event.window_pos);
+            }
+            _ => {}
+        },
+    )
+}
+
+pub fn artist_menu(link: &ArtistLink, common: &CommonCtx) -> Menu<State> {
+    let follow_item = if common.is_artist_followed(&link.id) {
+        MenuItem::new(
+            LocalizedString::new("menu-item-unfollow-artist").with_placeholder("Unfollow"),
+        )
+        .command(cmd::UNFOLLOW_ARTIST.with(link.to_owned()))
+    } else {
+        MenuItem::new(LocalizedString::new("menu-item-follow-artist").with_placeholder("Follow"))
+            .command(cmd::FOLLOW_ARTIST.with(link.to_owned()))
+    };
+    Menu::empty()
+        .entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-play-top-tracks")
+                    .with_placeholder("Play Top Tracks"),
+            )
+            .command(cmd::PLAY_LINK.with(SpotifyUrl::Artist(link.id.clone()))),
+        )
+        .separator()
+        .entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-copy-link").with_placeholder("Copy Link"),
+            )
+            .command(cmd::COPY.with(link.url())),
+        )
+        .entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-copy-uri").with_placeholder("Copy Spotify URI"),
+            )
+            .command(cmd::COPY.with(link.uri())),
+        )
+        .entry(open_in_spotify_menu(link.url(), link.uri()))
+        .entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-artist-radio")
+                    .with_placeholder("Go To Artist Radio"),
+            )
+            .command(cmd::NAVIGATE.with(Nav::Radio(RadioSeed::Artist(link.to_owned())))),
+        )
+        .separator()
+        .entry(follow_item)
 }
 
 pub fn cover_widget(size: f64) -> impl Widget<Artist> {
     let radius = size / 2.0;
     Clip::new(
         Circle::new((radius, radius), radius),
-        RemoteImage::new(placeholder_widget(), move |artist: &Artist, _| {
+        RemoteImage::new(placeholder_widget(), move |artist: &Artist, env| {
+            let size = size * env.get(theme::IMAGE_SCALE);
             artist.image(size, size).map(|image| image.url.clone())
         })
         .fix_size(size, size),
@@ -81,31 +173,75 @@ pub fn cover_widget(size: f64) -> impl Widget<Artist> {
 }
 
 fn top_tracks_widget() -> impl Widget<Ctx<CommonCtx, ArtistTracks>> {
-    tracklist_widget(TrackDisplay {
-        title: true,
-        album: true,
-        popularity: true,
-        ..TrackDisplay::empty()
-    })
+    tracklist_widget(
+        TrackView::ArtistTopTracks,
+        TrackDisplay {
+            title: true,
+            album: true,
+            popularity: true,
+            duration: true,
+            ..TrackDisplay::empty()
+        },
+    )
 }
 
 fn albums_widget() -> impl Widget<Ctx<CommonCtx, ArtistAlbums>> {
     Flex::column()
         .cross_axis_alignment(CrossAxisAlignment::Start)
         .with_child(label_widget("Albums"))
-        .with_child(List::new(album_widget).lens(Ctx::map(ArtistAlbums::albums)))
+        .with_child(album_list_widget().lens(Ctx::map(ArtistAlbums::albums)))
         .with_child(label_widget("Singles"))
-        .with_child(List::new(album_widget).lens(Ctx::map(ArtistAlbums::singles)))
+        .with_child(album_list_widget().lens(Ctx::map(ArtistAlbums::singles)))
         .with_child(label_widget("Compilations"))
-        .with_child(List::new(album_widget).lens(Ctx::map(ArtistAlbums::compilations)))
+        .with_child(album_list_widget().lens(Ctx::map(ArtistAlbums::compilations)))
+}
+
+fn info_widget() -> impl Widget<ArtistInfo> {
+    let stats = Label::dynamic(|info: &ArtistInfo, _| {
+        let listeners = info
+            .monthly_listeners
+            .map(|count| format!("{} monthly listeners", thousands(count)));
+        let followers = info
+            .followers
+            .map(|count| format!("{} followers", thousands(count)));
+        listeners.into_iter().chain(followers).join(" · ")
+    })
+    .with_text_size(theme::TEXT_SIZE_SMALL)
+    .with_text_color(theme::PLACEHOLDER_COLOR);
+
+    let biography = Label::raw()
+        .with_line_break_mode(LineBreaking::WordWrap)
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .lens(ArtistInfo::biography);
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(label_widget("About"))
+        .with_child(stats)
+        .with_default_spacer()
+        .with_child(biography)
+        .expand_width()
+}
+
+/// Format `count` with separated thousands, i.e. "1,234,567".
+fn thousands(count: u64) -> String {
+    let digits = count.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
 }
 
-fn related_widget() -> impl Widget<Cached<Vector<Artist>>> {
+fn related_widget() -> impl Widget<Ctx<CommonCtx, Cached<Vector<Artist>>>> {
     Flex::column()
         .cross_axis_alignment(CrossAxisAlignment::Start)
         .with_child(label_widget("Related Artists"))
         .with_child(List::new(artist_widget))
-        .lens(Cached::data)
+        .lens(Ctx::map(Cached::data))
 }
 
 fn label_widget<T: Data>(text: impl Into<LabelText<T>>) -> impl Widget<T> {
@@ -0,0 +1,260 @@
+use crate::{
+    cmd,
+    data::{Connect, Promise, RemoteCommand, RemotePlayback, State, Track},
+    ui::theme,
+    widget::{icons, Link, LinkExt, Maybe},
+};
+use druid::{
+    widget::{Controller, CrossAxisAlignment, Either, Flex, Label, LineBreaking, Slider},
+    Env, Event, EventCtx, LensExt, LifeCycle, LifeCycleCtx, LocalizedString, Menu, MenuItem,
+    TimerToken, Widget, WidgetExt,
+};
+use icons::SvgIcon;
+use std::{sync::Arc, time::Duration};
+
+// How often do we ask for the state of the remote device.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Button opening a menu of the available Spotify Connect devices.
+pub fn devices_widget() -> impl Widget<State> {
+    icons::SPEAKER
+        .scale((theme::grid(2.0), theme::grid(2.0)))
+        .with_color(theme::PLACEHOLDER_COLOR)
+        .padding(theme::grid(1.0))
+        .link()
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .on_ex_click(|ctx, event, state: &mut State, _| {
+            ctx.show_context_menu(devices_menu(&state.connect), event.window_pos);
+            // Devices come and go, refresh the list for the next time.
+            ctx.submit_command(cmd::LOAD_CONNECT_DEVICES);
+            ctx.submit_command(cmd::LOAD_CAST_RENDERERS);
+        })
+}
+
+/// Playback panel shown while we are controlling another device.
+pub fn remote_panel_widget() -> impl Widget<State> {
+    Flex::row()
+        .must_fill_main_axis(true)
+        .with_flex_child(
+            Maybe::new(remote_item_widget, || {
+                Label::new("Connecting…")
+                    .with_text_size(theme::TEXT_SIZE_SMALL)
+                    .with_text_color(theme::PLACEHOLDER_COLOR)
+                    .padding(theme::grid(2.0))
+            })
+            .lens(State::connect.then(Connect::remote)),
+            1.0,
+        )
+        .with_flex_child(
+            Flex::row()
+                .with_child(remote_controls_widget().lens(State::connect))
+                .with_default_spacer()
+                .with_child(devices_widget()),
+            1.0,
+        )
+}
+
+fn remote_item_widget() -> impl Widget<RemotePlayback> {
+    let device_name =
+        Label::dynamic(|remote: &RemotePlayback, _| format!("Playing on {}", remote.device.name))
+            .with_line_break_mode(LineBreaking::Clip)
+            .with_text_size(theme::TEXT_SIZE_SMALL)
+            .with_text_color(theme::PLACEHOLDER_COLOR);
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Maybe::or_empty(remote_track_widget).lens(RemotePlayback::item))
+        .with_child(device_name)
+        .padding(theme::grid(2.0))
+        .expand_width()
+}
+
+fn remote_track_widget() -> impl Widget<Arc<Track>> {
+    let track_name = Label::raw()
+        .with_line_break_mode(LineBreaking::Clip)
+        .with_font(theme::UI_FONT_MEDIUM)
+        .lens(Track::name.in_arc());
+
+    let track_artist = Label::dynamic(|track: &Arc<Track>, _| track.artist_name())
+        .with_line_break_mode(LineBreaking::Clip)
+        .with_text_size(theme::TEXT_SIZE_SMALL);
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(track_name)
+        .with_spacer(2.0)
+        .with_child(track_artist)
+        .with_spacer(2.0)
+}
+
+fn remote_controls_widget() -> impl Widget<Connect> {
+    let play_previous = control_icon(&icons::SKIP_BACK, 2.0)
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .on_click(|ctx, _, _| {
+            ctx.submit_command(cmd::REMOTE_CONTROL.with(RemoteCommand::Previous))
+        });
+
+    let play_pause = Either::new(
+        |connect: &Connect, _| connect.remote.as_ref().map_or(false, |r| r.is_playing),
+        control_icon(&icons::PAUSE, 3.0)
+            .circle()
+            .border(theme::GREY_500, 1.0)
+            .on_click(|ctx, _, _| {
+                ctx.submit_command(cmd::REMOTE_CONTROL.with(RemoteCommand::Pause))
+            }),
+        control_icon(&icons::PLAY, 3.0)
+            .circle()
+            .border(theme::GREY_500, 1.0)
+            .on_click(|ctx, _, _| {
+                ctx.submit_command(cmd::REMOTE_CONTROL.with(RemoteCommand::Play))
+            }),
+    );
+
+    let play_next = control_icon(&icons::SKIP_FORWARD, 2.0)
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .on_click(|ctx, _, _| ctx.submit_command(cmd::REMOTE_CONTROL.with(RemoteCommand::Next)));
+
+    let volume = Slider::new()
+        .with_range(0.0, 1.0)
+        .controller(SetRemoteVolume)
+        .fix_width(theme::grid(12.0))
+        .lens(Connect::volume);
+
+    Flex::row()
+        .with_child(play_previous)
+        .with_default_spacer()
+        .with_child(play_pause)
+        .with_default_spacer()
+        .with_child(play_next)
+        .with_default_spacer()
+        .with_child(volume)
+}
+
+fn control_icon(svg: &SvgIcon, size: f64) -> Link<Connect> {
+    svg.scale((theme::grid(size), theme::grid(size)))
+        .padding(theme::grid(1.0))
+        .link()
+}
+
+fn devices_menu(connect: &Connect) -> Menu<State> {
+    let mut menu = Menu::empty().entry(
+        MenuItem::new(
+            LocalizedString::new("menu-item-this-computer").with_placeholder("This Computer"),
+        )
+        .command(cmd::DISCONNECT_REMOTE)
+        .selected(!connect.is_remote() && connect.cast_renderer.is_none()),
+    );
+    match &connect.devices {
+        Promise::Resolved(devices) => {
+            for device in devices {
+                let is_active = device.id.is_some() && device.id == connect.active_device;
+                menu = menu.entry(
+                    MenuItem::new(
+                        LocalizedString::new("menu-item-connect-device")
+                            .with_placeholder(format!("{} ({})", device.name, device.device_type)),
+                    )
+                    .command(cmd::TRANSFER_PLAYBACK.with(device.clone()))
+                    .enabled(device.id.is_some() && !device.is_restricted)
+                    .selected(is_active),
+                );
+            }
+        }
+        Promise::Deferred(_) => {
+            menu = menu.entry(
+                MenuItem::new(
+                    LocalizedString::new("menu-item-connect-loading")
+                        .with_placeholder("Looking for Devices…"),
+                )
+                .enabled(false),
+            );
+        }
+        _ => {}
+    }
+    if let Promise::Resolved(renderers) = &connect.renderers {
+        if !renderers.is_empty() {
+            menu = menu.separator();
+        }
+        for renderer in renderers {
+            menu = menu.entry(
+                MenuItem::new(
+                    LocalizedString::new("menu-item-cast-renderer")
+                        .with_placeholder(format!("{} (DLNA)", renderer.name)),
+                )
+                .command(cmd::CAST_TO.with(renderer.clone()))
+                .selected(connect.cast_renderer.as_ref() == Some(renderer)),
+            );
+        }
+    }
+    menu
+}
+
+/// Sends the new volume to the remote device once the user lets go of the
+/// slider, so we don't flood the API while dragging.
+struct SetRemoteVolume;
+
+impl<W: Widget<f64>> Controller<f64, W> for SetRemoteVolume {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut f64,
+        env: &Env,
+    ) {
+        child.event(ctx, event, data, env);
+        if let Event::MouseUp(_) = event {
+            let volume = (data.max(0.0).min(1.0) * 100.0).round() as u32;
+            ctx.submit_command(cmd::REMOTE_CONTROL.with(RemoteCommand::SetVolume(volume)));
+        }
+    }
+}
+
+/// Periodically refreshes the state of the remote device.  Needs to sit above
+/// the panel switching between the local and the remote playback, so the timer
+/// keeps running while we are not a remote.
+pub struct PollRemotePlayback {
+    timer: TimerToken,
+}
+
+impl PollRemotePlayback {
+    pub fn new() -> Self {
+        Self {
+            timer: TimerToken::INVALID,
+        }
+    }
+}
+
+impl<W: Widget<State>> Controller<State, W> for PollRemotePlayback {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut State,
+        env: &Env,
+    ) {
+        match event {
+            Event::Timer(token) if token == &self.timer => {
+                if data.connect.is_remote() {
+                    ctx.submit_command(cmd::LOAD_REMOTE_PLAYBACK);
+                }
+                self.timer = ctx.request_timer(POLL_INTERVAL);
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &State,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.timer = ctx.request_timer(POLL_INTERVAL);
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{
+    cmd,
+    data::{CommonCtx, Ctx, Downloads, State, Track},
+    ui::theme,
+    widget::Empty,
+};
+use druid::{
+    im::Vector,
+    lens,
+    widget::{Button, CrossAxisAlignment, Either, Flex, Label, LineBreaking, ProgressBar},
+    Widget, WidgetExt,
+};
+use std::sync::Arc;
+
+pub fn download_button_widget() -> impl Widget<Ctx<CommonCtx, Vector<Arc<Track>>>> {
+    Either::new(
+        |tracks: &Ctx<CommonCtx, Vector<Arc<Track>>>, _| tracks.ctx.are_tracks_pinned(&tracks.data),
+        Button::new("Remove Download").on_click(
+            |ctx, tracks: &mut Ctx<CommonCtx, Vector<Arc<Track>>>, _| {
+                ctx.submit_command(cmd::REMOVE_DOWNLOADS.with(tracks.data.clone()));
+            },
+        ),
+        Button::new("Download").on_click(
+            |ctx, tracks: &mut Ctx<CommonCtx, Vector<Arc<Track>>>, _| {
+                ctx.submit_command(cmd::DOWNLOAD_TRACKS.with(tracks.data.clone()));
+            },
+        ),
+    )
+}
+
+pub fn status_widget() -> impl Widget<State> {
+    let title = Label::dynamic(|downloads: &Downloads, _| match downloads.queue.len() {
+        1 => "Downloading 1 track".to_string(),
+        n => format!("Downloading {} tracks", n),
+    })
+    .with_text_size(theme::TEXT_SIZE_SMALL);
+
+    let current = Label::dynamic(|downloads: &Downloads, _| {
+        downloads
+            .current()
+            .map(|download| download.track.name.to_string())
+            .unwrap_or_default()
+    })
+    .with_line_break_mode(LineBreaking::Clip)
+    .with_text_size(theme::TEXT_SIZE_SMALL)
+    .with_text_color(theme::PLACEHOLDER_COLOR);
+
+    let progress = ProgressBar::new().lens(lens::Map::new(
+        |downloads: &Downloads| {
+            downloads
+                .current()
+                .map(|download| download.progress)
+                .unwrap_or(0.0)
+        },
+        |_, _| {},
+    ));
+
+    let status = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(title)
+        .with_spacer(2.0)
+        .with_child(current)
+        .with_default_spacer()
+        .with_child(progress.expand_width())
+        .padding((theme::grid(2.0), theme::grid(1.0)));
+
+    Either::new(
+        |downloads: &Downloads, _| downloads.queue.is_empty(),
+        Empty,
+        status,
+    )
+    .lens(State::downloads)
+}
@@ -0,0 +1,277 @@
+use crate::{
+    cmd,
+    data::{
+        Library, Playback, PlaybackOrigin, PlaybackPayload, PlayedTrack, QueuedTrack,
+        RecentlyPlayed, RecentlyPlayedItem, SpotifyUrl, State, Track,
+    },
+    ui::{
+        theme,
+        utils::{error_widget, open_in_spotify_menu, spinner_widget},
+    },
+    widget::{Async, Empty, LinkExt},
+};
+use chrono::Local;
+use druid::{
+    im::Vector,
+    widget::{
+        Controller, ControllerHost, CrossAxisAlignment, Either, Flex, Label, LineBreaking, List,
+        ListIter,
+    },
+    Data, Env, Event, EventCtx, Lens, LensExt, LocalizedString, Menu, MenuItem, MouseButton,
+    Widget, WidgetExt,
+};
+use std::sync::Arc;
+
+pub fn history_widget() -> impl Widget<State> {
+    Either::new(
+        |history: &Vector<PlayedTrack>, _| history.is_empty(),
+        Label::new("Nothing has been played yet.")
+            .with_text_color(theme::PLACEHOLDER_COLOR)
+            .padding(theme::grid(1.0)),
+        List::new(played_track_widget),
+    )
+    .lens(State::playback.then(Playback::history))
+}
+
+fn played_track_widget() -> impl Widget<PlayedTrack> {
+    let played_at =
+        Label::dynamic(|played: &PlayedTrack, _| played.played_at.format("%H:%M").to_string())
+            .with_text_size(theme::TEXT_SIZE_SMALL)
+            .with_text_color(theme::PLACEHOLDER_COLOR)
+            .fix_width(theme::grid(5.0));
+
+    let track_name = Label::raw()
+        .with_font(theme::UI_FONT_MEDIUM)
+        .with_line_break_mode(LineBreaking::Clip)
+        .lens(PlayedTrack::track.then(Track::name.in_arc()));
+
+    let track_details = Label::dynamic(|played: &PlayedTrack, _| {
+        format!(
+            "{} · {}",
+            played.track.artist_name(),
+            played.origin.to_string()
+        )
+    })
+    .with_line_break_mode(LineBreaking::Clip)
+    .with_text_size(theme::TEXT_SIZE_SMALL);
+
+    Flex::row()
+        .with_child(played_at)
+        .with_flex_child(
+            Flex::column()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(track_name)
+                .with_spacer(2.0)
+                .with_child(track_details),
+            1.0,
+        )
+        .padding(theme::grid(1.0))
+        .link()
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .on_click(|ctx, played: &mut PlayedTrack, _| {
+            let payload = PlaybackPayload {
+                origin: played.origin.clone(),
+                tracks: Vector::unit(Arc::clone(&played.track)),
+                position: 0,
+            };
+            ctx.submit_command(cmd::PLAY_TRACKS.with(payload));
+        })
+}
+
+pub fn recently_played_widget() -> impl Widget<State> {
+    Async::new(
+        || spinner_widget(),
+        || {
+            Flex::column()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(recent_tracks_widget())
+                .with_child(load_more_widget())
+        },
+        || error_widget(),
+    )
+    .lens(State::library.then(Library::recently_played.in_arc()))
+}
+
+pub fn recent_tracks_widget() -> impl Widget<RecentlyPlayed> {
+    ControllerHost::new(List::new(recent_track_widget), PlayController)
+}
+
+fn load_more_widget() -> impl Widget<RecentlyPlayed> {
+    Either::new(
+        |recent: &RecentlyPlayed, _| recent.next_cursor.is_some(),
+        Label::new("Load More")
+            .with_text_color(theme::PLACEHOLDER_COLOR)
+            .padding(theme::grid(1.0))
+            .link()
+            .on_click(|ctx, _, _| ctx.submit_command(cmd::LOAD_MORE_RECENTLY_PLAYED)),
+        Empty,
+    )
+}
+
+impl ListIter<RecentRow> for RecentlyPlayed {
+    fn for_each(&self, mut cb: impl FnMut(&RecentRow, usize)) {
+        ListIter::for_each(&self.items, |item, index| {
+            let d = RecentRow {
+                item: item.to_owned(),
+                position: index,
+            };
+            cb(&d, index);
+        });
+    }
+
+    fn for_each_mut(&mut self, mut cb: impl FnMut(&mut RecentRow, usize)) {
+        ListIter::for_each(&self.items, |item, index| {
+            let mut d = RecentRow {
+                item: item.to_owned(),
+                position: index,
+            };
+            cb(&mut d, index);
+
+            // Mutation intentionally ignored.
+        });
+    }
+
+    fn data_len(&self) -> usize {
+        self.items.len()
+    }
+}
+
+#[derive(Clone, Data, Lens)]
+struct RecentRow {
+    item: RecentlyPlayedItem,
+    position: usize,
+}
+
+struct PlayController;
+
+impl<W> Controller<RecentlyPlayed, W> for PlayController
+where
+    W: Widget<RecentlyPlayed>,
+{
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut RecentlyPlayed,
+        env: &Env,
+    ) {
+        match event {
+            Event::Notification(note) => {
+                if let Some(position) = note.get(cmd::PLAY_TRACK_AT) {
+                    let payload = PlaybackPayload {
+                        origin: PlaybackOrigin::RecentlyPlayed,
+                        tracks: data.tracks(),
+                        position: position.to_owned(),
+                    };
+                    ctx.submit_command(cmd::PLAY_TRACKS.with(payload));
+                    ctx.set_handled();
+                }
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
+fn recent_track_widget() -> impl Widget<RecentRow> {
+    let played_at = Label::dynamic(|row: &RecentRow, _| {
+        row.item
+            .played_at
+            .with_timezone(&Local)
+            .format("%b %d, %H:%M")
+            .to_string()
+    })
+    .with_text_size(theme::TEXT_SIZE_SMALL)
+    .with_text_color(theme::PLACEHOLDER_COLOR)
+    .fix_width(theme::grid(10.0));
+
+    let track_name = Label::raw()
+        .with_font(theme::UI_FONT_MEDIUM)
+        .with_line_break_mode(LineBreaking::Clip)
+        .lens(
+            RecentRow::item
+                .then(RecentlyPlayedItem::track)
+                .then(Track::name.in_arc()),
+        );
+
+    let track_details = Label::dynamic(|row: &RecentRow, _| match &row.item.context {
+        Some(context) => format!("{} · from {}", row.item.track.artist_name(), context.kind),
+        None => row.item.track.artist_name(),
+    })
+    .with_line_break_mode(LineBreaking::Clip)
+    .with_text_size(theme::TEXT_SIZE_SMALL);
+
+    Flex::row()
+        .with_child(played_at)
+        .with_flex_child(
+            Flex::column()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(track_name)
+                .with_spacer(2.0)
+                .with_child(track_details),
+            1.0,
+        )
+        .padding(theme::grid(1.0))
+        .link()
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .on_ex_click(|ctx, event, row: &mut RecentRow, _| match event.button {
+            MouseButton::Left => {
+                ctx.submit_notification(cmd::PLAY_TRACK_AT.with(row.position));
+            }
+            MouseButton::Right => {
+                ctx.show_context_menu(recent_track_menu(row), event.window_pos);
+            }
+            _ => {}
+        })
+}
+
+fn recent_track_menu(row: &RecentRow) -> Menu<State> {
+    let mut menu = Menu::empty();
+
+    if let Some(url) = row
+        .item
+        .context
+        .as_ref()
+        .and_then(|context| SpotifyUrl::parse(&context.uri))
+    {
+        menu = menu.entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-show-context")
+                    .with_placeholder("Go To Played From"),
+            )
+            .command(cmd::OPEN_LINK.with(url)),
+        );
+    }
+
+    menu = menu.entry(
+        MenuItem::new(LocalizedString::new("menu-item-copy-link").with_placeholder("Copy Link"))
+            .command(cmd::COPY.with(row.item.track.url())),
+    );
+
+    menu = menu.entry(
+        MenuItem::new(
+            LocalizedString::new("menu-item-copy-uri").with_placeholder("Copy Spotify URI"),
+        )
+        .command(cmd::COPY.with(row.item.track.uri())),
+    );
+
+    menu = menu.entry(open_in_spotify_menu(
+        row.item.track.url(),
+        row.item.track.uri(),
+    ));
+
+    menu = menu.separator();
+
+    menu = menu.entry(
+        MenuItem::new(
+            LocalizedString::new("menu-item-add-to-queue").with_placeholder("Add to Queue"),
+        )
+        .command(cmd::ADD_TO_QUEUE.with(QueuedTrack {
+            origin: PlaybackOrigin::RecentlyPlayed,
+            track: row.item.track.clone(),
+            added: true,
+        })),
+    );
+
+    menu
+}
@@ -0,0 +1,81 @@
+use crate::{
+    data::{Ctx, FeaturedPlaylists, Home, State},
+    ui::{
+        album::album_list_widget,
+        history::recent_tracks_widget,
+        playlist::playlist_widget,
+        theme,
+        utils::{error_widget, spinner_widget},
+    },
+    widget::Async,
+};
+use druid::{
+    widget::{CrossAxisAlignment, Flex, Label, LabelText, List},
+    Data, Env, Insets, LensExt, Widget, WidgetExt,
+};
+
+pub fn home_widget() -> impl Widget<State> {
+    let featured_playlists = Async::new(
+        || spinner_widget(),
+        || {
+            Flex::column()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(
+                    label_widget(|featured: &FeaturedPlaylists, _: &Env| {
+                        featured
+                            .message
+                            .as_deref()
+                            .unwrap_or("Featured Playlists")
+                            .to_string()
+                    })
+                    .lens(Ctx::data()),
+                )
+                .with_child(List::new(playlist_widget).lens(Ctx::map(FeaturedPlaylists::playlists)))
+        },
+        || error_widget().lens(Ctx::data()),
+    )
+    .lens(
+        Ctx::make(
+            State::common_ctx,
+            State::home.then(Home::featured_playlists),
+        )
+        .then(Ctx::in_promise()),
+    );
+
+    let new_releases = Async::new(
+        || spinner_widget(),
+        album_list_widget,
+        || error_widget().lens(Ctx::data()),
+    )
+    .lens(
+        Ctx::make(State::common_ctx, State::home.then(Home::new_releases)).then(Ctx::in_promise()),
+    );
+
+    let recently_played = Async::new(
+        || spinner_widget(),
+        || recent_tracks_widget(),
+        || error_widget(),
+    )
+    .lens(State::home.then(Home::recently_played));
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(featured_playlists)
+        .with_child(label_widget("New Releases"))
+        .with_child(new_releases)
+        .with_child(label_widget("Recently Played"))
+        .with_child(recently_played)
+}
+
+fn label_widget<T: Data>(text: impl Into<LabelText<T>>) -> impl Widget<T> {
+    Label::new(text)
+        .with_font(theme::UI_FONT_MEDIUM)
+        .with_text_color(theme::PLACEHOLDER_COLOR)
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .padding(Insets::new(
+            theme::grid(1.0),
+            theme::grid(2.0),
+            theme::grid(1.0),
+            theme::grid(1.0),
+        ))
+}
@@ -1,24 +1,42 @@
 use crate::{
-    data::{Ctx, Library, State},
+    cmd,
+    data::{Ctx, FollowedArtists, Library, State, TrackView},
     ui::{
         album::album_widget,
-        track::{tracklist_widget, TrackDisplay},
+        artist::artist_widget,
+        theme,
+        track::{filter_widget, tracklist_widget, TrackDisplay},
         utils::{error_widget, spinner_widget},
     },
-    widget::Async,
+    widget::{Async, Empty, LinkExt},
+};
+use druid::{
+    widget::{CrossAxisAlignment, Either, Flex, Label, List},
+    LensExt, Widget, WidgetExt,
 };
-use druid::{widget::List, LensExt, Widget, WidgetExt};
 
 pub fn saved_tracks_widget() -> impl Widget<State> {
     Async::new(
         || spinner_widget(),
         || {
-            tracklist_widget(TrackDisplay {
-                title: true,
-                artist: true,
-                album: true,
-                ..TrackDisplay::empty()
-            })
+            Flex::column()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(
+                    filter_widget()
+                        .lens(Ctx::ctx())
+                        .padding((theme::grid(1.0), 0.0)),
+                )
+                .with_child(tracklist_widget(
+                    TrackView::SavedTracks,
+                    TrackDisplay {
+                        title: true,
+                        artist: true,
+                        album: true,
+                        date_added: true,
+                        duration: true,
+                        ..TrackDisplay::empty()
+                    },
+                ))
         },
         || error_widget().lens(Ctx::data()),
     )
@@ -45,3 +63,35 @@ pub fn saved_albums_widget() -> impl Widget<State> {
         .then(Ctx::in_promise()),
     )
 }
+
+pub fn followed_artists_widget() -> impl Widget<State> {
+    Async::new(
+        || spinner_widget(),
+        || {
+            Flex::column()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(List::new(artist_widget).lens(Ctx::map(FollowedArtists::artists)))
+                .with_child(load_more_widget().lens(Ctx::data()))
+        },
+        || error_widget().lens(Ctx::data()),
+    )
+    .lens(
+        Ctx::make(
+            State::common_ctx,
+            State::library.then(Library::followed_artists.in_arc()),
+        )
+        .then(Ctx::in_promise()),
+    )
+}
+
+fn load_more_widget() -> impl Widget<FollowedArtists> {
+    Either::new(
+        |followed: &FollowedArtists, _| followed.next_cursor.is_some(),
+        Label::new("Load More")
+            .with_text_color(theme::PLACEHOLDER_COLOR)
+            .padding(theme::grid(1.0))
+            .link()
+            .on_click(|ctx, _, _| ctx.submit_command(cmd::LOAD_MORE_FOLLOWED_ARTISTS)),
+        Empty,
+    )
+}
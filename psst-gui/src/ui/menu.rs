@@ -56,9 +56,30 @@ fn view_menu() -> Menu<State> {
             .command(cmd::NAVIGATE.with(Nav::SavedAlbums))
             .hotkey(SysMods::Cmd, "3"),
         )
+        .entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-followed-artists")
+                    .with_placeholder("Followed Artists"),
+            )
+            .command(cmd::NAVIGATE.with(Nav::FollowedArtists))
+            .hotkey(SysMods::Cmd, "4"),
+        )
+        .entry(
+            MenuItem::new(LocalizedString::new("menu-item-podcasts").with_placeholder("Podcasts"))
+                .command(cmd::NAVIGATE.with(Nav::SavedShows))
+                .hotkey(SysMods::Cmd, "5"),
+        )
         .entry(
             MenuItem::new(LocalizedString::new("menu-item-search").with_placeholder("Search..."))
                 .command(cmd::SET_FOCUS.to(cmd::WIDGET_SEARCH_INPUT))
                 .hotkey(SysMods::Cmd, "l"),
         )
+        .separator()
+        .entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-mini-player").with_placeholder("Mini Player"),
+            )
+            .command(cmd::TOGGLE_MINI_PLAYER)
+            .hotkey(SysMods::CmdShift, "m"),
+        )
 }
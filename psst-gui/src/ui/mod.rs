@@ -1,27 +1,41 @@
 use crate::{
     cmd,
-    controller::{NavController, PlaybackController, SessionController},
-    data::{Nav, State},
+    controller::{
+        ConnectController, DiscordController, DownloadController, HotkeyController, IpcController,
+        KeymapController, MeteredController, NavController, PlaybackController,
+        RemoteApiController, SessionController,
+    },
+    data::{Nav, PlaylistLink, State},
     ui::utils::Border,
-    widget::{icons, Empty, LinkExt, ThemeScope, ViewDispatcher},
+    widget::{icons, Empty, LinkExt, OnScrollEnd, ThemeScope, ViewDispatcher},
 };
 use druid::{
     lens::Unit,
-    widget::{CrossAxisAlignment, Either, Flex, Label, Scroll, Split, ViewSwitcher},
+    widget::{CrossAxisAlignment, Either, Flex, Label, LineBreaking, Scroll, Split, ViewSwitcher},
     Insets, Menu, MenuItem, MouseButton, Widget, WidgetExt, WindowDesc, WindowLevel,
 };
 use icons::SvgIcon;
 
 pub mod album;
 pub mod artist;
+pub mod connect;
+pub mod download;
+pub mod history;
+pub mod home;
 pub mod library;
 pub mod menu;
+pub mod palette;
 pub mod playback;
 pub mod playlist;
 pub mod preferences;
+pub mod queue;
+pub mod radio;
 pub mod search;
+pub mod show;
 pub mod theme;
+pub mod top;
 pub mod track;
+pub mod track_info;
 pub mod user;
 pub mod utils;
 
@@ -54,6 +68,67 @@ pub fn preferences_window() -> WindowDesc<State> {
     }
 }
 
+pub fn mini_player_window() -> WindowDesc<State> {
+    let win = WindowDesc::new(mini_player_widget())
+        .title("Psst")
+        .window_size((theme::grid(40.0), theme::grid(17.0)))
+        .resizable(false)
+        .show_title(false)
+        .transparent_titlebar(true)
+        // Keep the mini player floating above the other windows.
+        .set_level(WindowLevel::Modal);
+    if cfg!(target_os = "macos") {
+        win.menu(menu::main_menu)
+    } else {
+        win
+    }
+}
+
+pub fn unfollow_playlist_window(link: PlaylistLink) -> WindowDesc<State> {
+    let win = WindowDesc::new(ThemeScope::new(
+        playlist::unfollow_confirmation_widget(link)
+            .background(theme::BACKGROUND_DARK)
+            .expand(),
+    ))
+    .title("Remove Playlist")
+    .window_size((theme::grid(45.0), theme::grid(16.0)))
+    .resizable(false)
+    .show_title(false)
+    .transparent_titlebar(true)
+    .set_level(WindowLevel::Modal);
+    if cfg!(target_os = "macos") {
+        win.menu(menu::main_menu)
+    } else {
+        win
+    }
+}
+
+pub fn track_info_window() -> WindowDesc<State> {
+    let win = WindowDesc::new(ThemeScope::new(
+        track_info::info_widget()
+            .background(theme::BACKGROUND_DARK)
+            .expand(),
+    ))
+    .title("Track Info")
+    .window_size((theme::grid(45.0), theme::grid(40.0)))
+    .resizable(false)
+    .show_title(false)
+    .transparent_titlebar(true);
+    if cfg!(target_os = "macos") {
+        win.menu(menu::main_menu)
+    } else {
+        win
+    }
+}
+
+fn mini_player_widget() -> impl Widget<State> {
+    ThemeScope::new(
+        playback::mini_player_widget()
+            .background(theme::BACKGROUND_DARK)
+            .expand(),
+    )
+}
+
 fn preferences_widget() -> impl Widget<State> {
     ThemeScope::new(
         preferences::preferences_widget()
@@ -69,7 +144,9 @@ fn root_widget() -> impl Widget<State> {
         .with_child(logo_widget())
         .with_child(menu_widget())
         .with_default_spacer()
+        .with_child(playlist::new_playlist_widget())
         .with_flex_child(playlists.expand_height(), 1.0)
+        .with_child(download::status_widget())
         .with_child(user::user_widget())
         .padding(if cfg!(target_os = "macos") {
             Insets::new(0.0, 24.0, 0.0, 0.0)
@@ -82,12 +159,23 @@ fn root_widget() -> impl Widget<State> {
         .must_fill_main_axis(true)
         .with_child(back_button_widget())
         .with_child(title_widget())
+        .with_flex_spacer(1.0)
+        .with_child(retrying_widget())
+        .with_child(refresh_button_widget())
         .background(Border::Bottom.with_color(theme::BACKGROUND_DARK));
 
     let main = Flex::column()
         .cross_axis_alignment(CrossAxisAlignment::Start)
         .with_child(topbar)
-        .with_flex_child(route_widget(), 1.0)
+        .with_child(palette::palette_widget())
+        .with_child(offline_banner_widget())
+        .with_flex_child(
+            Flex::row()
+                .cross_axis_alignment(CrossAxisAlignment::Fill)
+                .with_flex_child(route_widget(), 1.0)
+                .with_child(queue::panel_widget()),
+            1.0,
+        )
         .with_child(playback::panel_widget())
         .background(theme::BACKGROUND_LIGHT);
 
@@ -102,7 +190,15 @@ fn root_widget() -> impl Widget<State> {
 
     let controlled = themed
         .controller(PlaybackController::new())
+        .controller(DownloadController::new())
+        .controller(DiscordController::new())
+        .controller(ConnectController::new())
+        .controller(RemoteApiController::new())
+        .controller(IpcController::new())
+        .controller(HotkeyController::new())
+        .controller(KeymapController::new())
         .controller(SessionController::new())
+        .controller(MeteredController::new())
         .controller(NavController);
 
     controlled
@@ -124,8 +220,14 @@ fn menu_widget() -> impl Widget<State> {
     Flex::column()
         .with_default_spacer()
         .with_child(menu_link_widget("Home", Nav::Home))
+        .with_child(menu_link_widget("Now Playing", Nav::NowPlaying))
+        .with_child(menu_link_widget("History", Nav::History))
+        .with_child(menu_link_widget("Recently Played", Nav::RecentlyPlayed))
+        .with_child(menu_link_widget("Top", Nav::TopItems))
         .with_child(menu_link_widget("Tracks", Nav::SavedTracks))
         .with_child(menu_link_widget("Albums", Nav::SavedAlbums))
+        .with_child(menu_link_widget("Artists", Nav::FollowedArtists))
+        .with_child(menu_link_widget("Podcasts", Nav::SavedShows))
         .with_child(menu_search_widget())
 }
 
@@ -169,10 +271,29 @@ fn route_widget() -> impl Widget<State> {
     ViewDispatcher::new(
         |state: &State, _| state.route.clone(),
         |route: &Nav, _, _| match route {
-            Nav::Home => home_widget().padding(theme::grid(1.0)).boxed(),
+            Nav::Home => Scroll::new(home::home_widget().padding(theme::grid(1.0)))
+                .vertical()
+                .boxed(),
+            Nav::NowPlaying => playback::now_playing_widget()
+                .padding(theme::grid(1.0))
+                .boxed(),
+            Nav::History => Scroll::new(history::history_widget().padding(theme::grid(1.0)))
+                .vertical()
+                .boxed(),
+            Nav::RecentlyPlayed => {
+                Scroll::new(history::recently_played_widget().padding(theme::grid(1.0)))
+                    .vertical()
+                    .boxed()
+            }
+            Nav::TopItems => Scroll::new(top::top_items_widget().padding(theme::grid(1.0)))
+                .vertical()
+                .boxed(),
             Nav::SavedTracks => {
                 Scroll::new(library::saved_tracks_widget().padding(theme::grid(1.0)))
                     .vertical()
+                    .controller(OnScrollEnd::new(|ctx, _, _| {
+                        ctx.submit_command(cmd::LOAD_MORE_SAVED_TRACKS)
+                    }))
                     .boxed()
             }
             Nav::SavedAlbums => {
@@ -180,11 +301,27 @@ fn route_widget() -> impl Widget<State> {
                     .vertical()
                     .boxed()
             }
+            Nav::FollowedArtists => {
+                Scroll::new(library::followed_artists_widget().padding(theme::grid(1.0)))
+                    .vertical()
+                    .boxed()
+            }
+            Nav::SavedShows => Scroll::new(show::saved_shows_widget().padding(theme::grid(1.0)))
+                .vertical()
+                .boxed(),
             Nav::SearchResults(_) => {
                 Scroll::new(search::results_widget().padding(theme::grid(1.0)))
                     .vertical()
                     .boxed()
             }
+            Nav::SearchTopicResults(..) => {
+                Scroll::new(search::topic_results_widget().padding(theme::grid(1.0)))
+                    .vertical()
+                    .controller(OnScrollEnd::new(|ctx, _, _| {
+                        ctx.submit_command(cmd::LOAD_MORE_SEARCH_TOPIC)
+                    }))
+                    .boxed()
+            }
             Nav::AlbumDetail(_) => Scroll::new(album::detail_widget().padding(theme::grid(1.0)))
                 .vertical()
                 .boxed(),
@@ -194,17 +331,22 @@ fn route_widget() -> impl Widget<State> {
             Nav::PlaylistDetail(_) => {
                 Scroll::new(playlist::detail_widget().padding(theme::grid(1.0)))
                     .vertical()
+                    .controller(OnScrollEnd::new(|ctx, _, _| {
+                        ctx.submit_command(cmd::LOAD_MORE_PLAYLIST_TRACKS)
+                    }))
                     .boxed()
             }
+            Nav::ShowDetail(_) => Scroll::new(show::detail_widget().padding(theme::grid(1.0)))
+                .vertical()
+                .boxed(),
+            Nav::Radio(_) => Scroll::new(radio::detail_widget().padding(theme::grid(1.0)))
+                .vertical()
+                .boxed(),
         },
     )
     .expand()
 }
 
-fn home_widget() -> impl Widget<State> {
-    Empty
-}
-
 fn back_button_widget() -> impl Widget<State> {
     let icon = icons::BACK.scale((10.0, theme::grid(2.0)));
     let disabled = icon
@@ -232,6 +374,52 @@ fn back_button_widget() -> impl Widget<State> {
     .padding(theme::grid(1.0))
 }
 
+fn offline_banner_widget() -> impl Widget<State> {
+    Either::new(
+        |state: &State, _| state.offline,
+        Label::new("Offline. Showing the cached library and downloads, reconnecting…")
+            .with_line_break_mode(LineBreaking::WordWrap)
+            .with_text_size(theme::TEXT_SIZE_SMALL)
+            .padding(theme::grid(1.0))
+            .expand_width()
+            .background(theme::BACKGROUND_DARK),
+        Empty,
+    )
+}
+
+fn retrying_widget() -> impl Widget<State> {
+    Either::new(
+        |state: &State, _| state.retrying,
+        Label::new("Retrying…")
+            .with_text_size(theme::TEXT_SIZE_SMALL)
+            .with_text_color(theme::PLACEHOLDER_COLOR),
+        Empty,
+    )
+}
+
+fn refresh_button_widget() -> impl Widget<State> {
+    let button = icons::REFRESH
+        .scale(theme::ICON_SIZE)
+        .padding(theme::grid(1.0))
+        .link()
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .on_click(|ctx, _, _| ctx.submit_command(cmd::REFRESH))
+        .padding(theme::grid(1.0));
+    Either::new(
+        |state: &State, _| {
+            matches!(
+                state.route,
+                Nav::AlbumDetail(_)
+                    | Nav::ArtistDetail(_)
+                    | Nav::PlaylistDetail(_)
+                    | Nav::ShowDetail(_)
+            )
+        },
+        button,
+        Empty,
+    )
+}
+
 fn history_menu(state: &State) -> Menu<State> {
     let mut menu = Menu::empty();
     for (index, history) in state.history.iter().rev().take(10).enumerate() {
@@ -260,12 +448,21 @@ fn route_icon_widget() -> impl Widget<Nav> {
             let icon = |icon: &SvgIcon| icon.scale(theme::ICON_SIZE);
             match &route {
                 Nav::Home => Empty.boxed(),
+                Nav::NowPlaying => Empty.boxed(),
+                Nav::History => Empty.boxed(),
+                Nav::RecentlyPlayed => Empty.boxed(),
+                Nav::TopItems => Empty.boxed(),
                 Nav::SavedTracks => Empty.boxed(),
                 Nav::SavedAlbums => Empty.boxed(),
+                Nav::FollowedArtists => Empty.boxed(),
+                Nav::SavedShows => Empty.boxed(),
                 Nav::SearchResults(_) => icon(&icons::SEARCH).boxed(),
+                Nav::SearchTopicResults(..) => icon(&icons::SEARCH).boxed(),
                 Nav::AlbumDetail(_) => icon(&icons::ALBUM).boxed(),
                 Nav::ArtistDetail(_) => icon(&icons::ARTIST).boxed(),
                 Nav::PlaylistDetail(_) => icon(&icons::PLAYLIST).boxed(),
+                Nav::ShowDetail(_) => icon(&icons::SPEAKER).boxed(),
+                Nav::Radio(_) => icon(&icons::AUTOPLAY).boxed(),
             }
         },
     )
@@ -0,0 +1,93 @@
+use crate::{
+    cmd,
+    controller::InputController,
+    data::{Palette, PaletteRow, State},
+    ui::theme,
+    widget::{Empty, LinkExt},
+};
+use druid::{
+    widget::{CrossAxisAlignment, Either, Flex, Label, LineBreaking, List, TextBox},
+    KbKey, Widget, WidgetExt,
+};
+use std::time::Duration;
+
+// How long to wait after the last key press before filtering the entries.
+const FILTER_DELAY: Duration = Duration::from_millis(50);
+
+pub fn palette_widget() -> impl Widget<State> {
+    Either::new(
+        |palette: &Palette, _| palette.open,
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Fill)
+            .with_child(input_widget())
+            .with_spacer(theme::grid(0.5))
+            .with_child(List::new(row_widget).lens(Palette::entries))
+            .padding(theme::grid(1.0))
+            .expand_width()
+            .background(theme::BACKGROUND_DARK),
+        Empty,
+    )
+    .lens(State::palette)
+}
+
+fn input_widget() -> impl Widget<Palette> {
+    TextBox::new()
+        .with_placeholder("Type a command or page")
+        .controller(
+            InputController::new()
+                .on_submit(|ctx, _, _| ctx.submit_command(cmd::SUBMIT_COMMAND_PALETTE))
+                .on_change(FILTER_DELAY, |ctx, _, _| {
+                    ctx.submit_command(cmd::FILTER_COMMAND_PALETTE)
+                })
+                .on_focus_changed(|ctx, focused| {
+                    if !focused {
+                        ctx.submit_command(cmd::SHOW_COMMAND_PALETTE.with(false));
+                    }
+                })
+                .on_key(|ctx, key| match &key.key {
+                    KbKey::ArrowDown => {
+                        ctx.submit_command(cmd::MOVE_PALETTE_SELECTION.with(1));
+                        true
+                    }
+                    KbKey::ArrowUp => {
+                        ctx.submit_command(cmd::MOVE_PALETTE_SELECTION.with(-1));
+                        true
+                    }
+                    KbKey::Escape => {
+                        ctx.submit_command(cmd::SHOW_COMMAND_PALETTE.with(false));
+                        true
+                    }
+                    _ => false,
+                }),
+        )
+        .with_id(cmd::WIDGET_PALETTE_INPUT)
+        .expand_width()
+        .lens(Palette::input)
+}
+
+fn row_widget() -> impl Widget<PaletteRow> {
+    Flex::row()
+        .with_flex_child(
+            Label::dynamic(|row: &PaletteRow, _| row.entry.title())
+                .with_line_break_mode(LineBreaking::Clip)
+                .with_text_size(theme::TEXT_SIZE_SMALL),
+            1.0,
+        )
+        .with_default_spacer()
+        .with_child(
+            Label::dynamic(|row: &PaletteRow, _| row.entry.kind().to_string())
+                .with_text_size(theme::TEXT_SIZE_SMALL)
+                .with_text_color(theme::PLACEHOLDER_COLOR),
+        )
+        .padding((theme::grid(1.0), theme::grid(0.5)))
+        .link()
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .env_scope(|env, row: &PaletteRow| {
+            if row.selected {
+                env.set(theme::LINK_COLD_COLOR, env.get(theme::LINK_HOT_COLOR));
+            }
+        })
+        .on_click(|ctx, row: &mut PaletteRow, _| {
+            ctx.submit_command(cmd::RUN_PALETTE_ENTRY.with(row.entry.clone()));
+        })
+}
@@ -1,37 +1,297 @@
 use crate::{
     cmd,
     data::{
-        AudioAnalysis, NowPlaying, Playback, PlaybackOrigin, PlaybackState, Promise, QueueBehavior,
-        State, Track,
+        AudioAnalysis, Ctx, Lyrics, LyricsLine, NowPlaying, Playback, PlaybackOrigin,
+        PlaybackState, Promise, QueueBehavior, RepeatMode, SeekDirection, State, Track,
     },
     ui::theme,
-    widget::{icons, Empty, LinkExt, Maybe},
+    widget::{icons, Async, Empty, LinkExt, Maybe, RemoteImage},
 };
 use druid::{
     kurbo::{Affine, BezPath},
-    widget::{CrossAxisAlignment, Either, Flex, Label, LineBreaking, Spinner, ViewSwitcher},
-    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LensExt, LifeCycle, LifeCycleCtx,
-    MouseButton, PaintCtx, Point, Rect, RenderContext, Size, UpdateCtx, Widget, WidgetExt,
+    widget::{
+        Controller, CrossAxisAlignment, Either, Flex, Label, LineBreaking, List, ListIter, Scroll,
+        Spinner, ViewSwitcher,
+    },
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, Lens, LensExt, LifeCycle, LifeCycleCtx,
+    LocalizedString, Menu, MenuItem, MouseButton, PaintCtx, Point, Rect, RenderContext, Selector,
+    Size, UpdateCtx, Widget, WidgetExt,
 };
 use icons::SvgIcon;
 use itertools::Itertools;
 use std::{sync::Arc, time::Duration};
 
-use super::utils;
+use super::{connect, utils};
 
 pub fn panel_widget() -> impl Widget<State> {
-    Flex::column()
-        .with_child(Maybe::or_empty(SeekBar::new).lens(Playback::now_playing))
+    let local = Flex::column()
+        .with_child(Maybe::or_empty(SeekBar::new).lens(State::playback.then(Playback::now_playing)))
         .with_child(
             Flex::row()
                 .must_fill_main_axis(true)
                 .with_flex_child(
-                    Maybe::or_empty(playback_item_widget).lens(Playback::now_playing),
+                    Maybe::or_empty(playback_item_widget)
+                        .lens(State::playback.then(Playback::now_playing)),
                     1.0,
                 )
-                .with_flex_child(player_widget(), 1.0),
+                .with_flex_child(
+                    Flex::row()
+                        .with_child(player_widget().lens(State::playback))
+                        .with_default_spacer()
+                        .with_child(connect::devices_widget()),
+                    1.0,
+                ),
+        );
+
+    Either::new(
+        |state: &State, _| state.connect.is_remote(),
+        connect::remote_panel_widget(),
+        local,
+    )
+    .controller(connect::PollRemotePlayback::new())
+}
+
+/// Compact player shown in its own window, floating above the other ones.
+pub fn mini_player_widget() -> impl Widget<State> {
+    let now_playing = Maybe::new(mini_player_item_widget, || {
+        Label::new("Nothing is playing")
+            .with_text_size(theme::TEXT_SIZE_SMALL)
+            .with_text_color(theme::PLACEHOLDER_COLOR)
+            .center()
+    })
+    .lens(State::playback.then(Playback::now_playing));
+
+    Flex::column()
+        .with_flex_child(now_playing, 1.0)
+        .with_default_spacer()
+        .with_child(mini_player_controls_widget().lens(State::playback))
+        .padding(theme::grid(2.0))
+}
+
+fn mini_player_item_widget() -> impl Widget<NowPlaying> {
+    let track_name = Label::raw()
+        .with_line_break_mode(LineBreaking::Clip)
+        .with_font(theme::UI_FONT_MEDIUM)
+        .lens(NowPlaying::item.then(Track::name.in_arc()));
+
+    let track_artist = Label::dynamic(|track: &Arc<Track>, _| track.artist_name())
+        .with_line_break_mode(LineBreaking::Clip)
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .lens(NowPlaying::item);
+
+    let info = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(track_name)
+        .with_spacer(2.0)
+        .with_child(track_artist)
+        .with_spacer(2.0)
+        .with_child(player_times_widget());
+
+    Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(mini_player_cover_widget(theme::grid(8.0)))
+                .with_default_spacer()
+                .with_flex_child(info, 1.0),
         )
-        .lens(State::playback)
+        .with_default_spacer()
+        .with_child(SeekBar::new())
+}
+
+fn mini_player_cover_widget(size: f64) -> impl Widget<NowPlaying> {
+    RemoteImage::new(
+        utils::placeholder_widget(),
+        move |now_playing: &NowPlaying, env| {
+            let size = size * env.get(theme::IMAGE_SCALE);
+            now_playing
+                .item
+                .album
+                .as_ref()
+                .and_then(|album| album.image(size, size))
+                .map(|image| image.url.clone())
+        },
+    )
+    .fix_size(size, size)
+}
+
+fn mini_player_controls_widget() -> impl Widget<Playback> {
+    let control = |svg: &SvgIcon, command: Selector| {
+        svg.scale((theme::grid(2.0), theme::grid(2.0)))
+            .padding(theme::grid(1.0))
+            .link()
+            .rounded(theme::BUTTON_BORDER_RADIUS)
+            .on_click(move |ctx, _, _| ctx.submit_command(command))
+    };
+
+    Flex::row()
+        .with_child(control(&icons::SKIP_BACK, cmd::PLAY_PREVIOUS))
+        .with_default_spacer()
+        .with_child(play_pause_widget())
+        .with_default_spacer()
+        .with_child(control(&icons::SKIP_FORWARD, cmd::PLAY_NEXT))
+}
+
+pub fn now_playing_widget() -> impl Widget<State> {
+    let now_playing = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Maybe::or_empty(now_playing_info_widget)
+                .lens(State::playback.then(Playback::now_playing)),
+        )
+        .with_spacer(theme::grid(2.0))
+        .with_flex_child(Visualizer::new(), 1.0);
+
+    let lyrics = Maybe::or_empty(lyrics_widget).lens(State::playback.then(Playback::now_playing));
+
+    Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_flex_child(now_playing, 1.0)
+        .with_spacer(theme::grid(2.0))
+        .with_child(lyrics.fix_width(theme::grid(40.0)))
+}
+
+fn lyrics_widget() -> impl Widget<NowPlaying> {
+    Async::new(
+        || utils::spinner_widget(),
+        || loaded_lyrics_widget(),
+        || utils::error_widget().lens(Ctx::data()),
+    )
+    .lens(Ctx::make(NowPlaying::progress, NowPlaying::lyrics).then(Ctx::in_promise()))
+}
+
+fn loaded_lyrics_widget() -> impl Widget<Ctx<Duration, Lyrics>> {
+    let lines = List::new(lyrics_line_widget);
+
+    let provider = Label::dynamic(|lyrics: &Ctx<Duration, Lyrics>, _| {
+        lyrics
+            .data
+            .provider
+            .as_ref()
+            .map(|provider| format!("Lyrics provided by {}", provider))
+            .unwrap_or_default()
+    })
+    .with_text_size(theme::TEXT_SIZE_SMALL)
+    .with_text_color(theme::PLACEHOLDER_COLOR);
+
+    Either::new(
+        |lyrics: &Ctx<Duration, Lyrics>, _| lyrics.data.is_empty(),
+        Label::new("No lyrics available")
+            .with_text_color(theme::PLACEHOLDER_COLOR)
+            .padding(theme::grid(1.0)),
+        Scroll::new(
+            Flex::column()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(lines)
+                .with_spacer(theme::grid(2.0))
+                .with_child(provider)
+                .padding(theme::grid(1.0)),
+        )
+        .vertical(),
+    )
+}
+
+fn lyrics_line_widget() -> impl Widget<LyricsRow> {
+    let words = || {
+        Label::raw()
+            .with_line_break_mode(LineBreaking::WordWrap)
+            .with_font(theme::UI_FONT_MEDIUM)
+            .lens(LyricsRow::line.then(LyricsLine::words))
+            .padding((0.0, theme::grid(0.5)))
+    };
+
+    // Synced lines can be clicked to seek to them.
+    Either::new(
+        |row: &LyricsRow, _| row.synced,
+        words()
+            .link()
+            .rounded(theme::BUTTON_BORDER_RADIUS)
+            .on_click(|ctx, row: &mut LyricsRow, _| {
+                ctx.submit_command(cmd::PLAY_SEEK_TO.with(row.line.start));
+            }),
+        words(),
+    )
+    .env_scope(|env, row: &LyricsRow| {
+        if row.synced && !row.is_current {
+            env.set(theme::TEXT_COLOR, env.get(theme::PLACEHOLDER_COLOR));
+        }
+    })
+    .controller(CurrentLineController)
+}
+
+impl ListIter<LyricsRow> for Ctx<Duration, Lyrics> {
+    fn for_each(&self, mut cb: impl FnMut(&LyricsRow, usize)) {
+        let current = self.data.current_line(self.ctx);
+        ListIter::for_each(&self.data.lines, |line, index| {
+            let row = LyricsRow {
+                line: line.to_owned(),
+                synced: self.data.synced,
+                is_current: current == Some(index),
+            };
+            cb(&row, index);
+        });
+    }
+
+    fn for_each_mut(&mut self, mut cb: impl FnMut(&mut LyricsRow, usize)) {
+        let current = self.data.current_line(self.ctx);
+        ListIter::for_each(&self.data.lines, |line, index| {
+            let mut row = LyricsRow {
+                line: line.to_owned(),
+                synced: self.data.synced,
+                is_current: current == Some(index),
+            };
+            cb(&mut row, index);
+
+            // Mutation intentionally ignored.
+        });
+    }
+
+    fn data_len(&self) -> usize {
+        self.data.lines.len()
+    }
+}
+
+#[derive(Clone, Data, Lens)]
+struct LyricsRow {
+    line: LyricsLine,
+    synced: bool,
+    is_current: bool,
+}
+
+/// Keeps the line being sung visible.
+struct CurrentLineController;
+
+impl<W: Widget<LyricsRow>> Controller<LyricsRow, W> for CurrentLineController {
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &LyricsRow,
+        data: &LyricsRow,
+        env: &Env,
+    ) {
+        if data.is_current && !old_data.is_current {
+            ctx.scroll_to_view();
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
+
+fn now_playing_info_widget() -> impl Widget<NowPlaying> {
+    let track_name = Label::raw()
+        .with_line_break_mode(LineBreaking::WordWrap)
+        .with_font(theme::UI_FONT_MEDIUM)
+        .with_text_size(theme::TEXT_SIZE_LARGE)
+        .lens(NowPlaying::item.then(Track::name.in_arc()));
+
+    let track_artist = Label::dynamic(|track: &Arc<Track>, _| track.artist_name())
+        .with_line_break_mode(LineBreaking::WordWrap)
+        .lens(NowPlaying::item);
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(track_name)
+        .with_spacer(theme::grid(0.5))
+        .with_child(track_artist)
 }
 
 fn playback_item_widget() -> impl Widget<NowPlaying> {
@@ -64,6 +324,11 @@ fn playback_item_widget() -> impl Widget<NowPlaying> {
                         PlaybackOrigin::Artist { .. } => &icons::ARTIST,
                         PlaybackOrigin::Playlist { .. } => &icons::PLAYLIST,
                         PlaybackOrigin::Search { .. } => &icons::SEARCH,
+                        PlaybackOrigin::Recommendations => &icons::AUTOPLAY,
+                        PlaybackOrigin::Show { .. } => &icons::SPEAKER,
+                        PlaybackOrigin::RecentlyPlayed => &icons::PLAY,
+                        PlaybackOrigin::TopTracks => &icons::HEART,
+                        PlaybackOrigin::Radio { .. } => &icons::AUTOPLAY,
                     }
                     .scale(theme::ICON_SIZE),
                 )
@@ -82,10 +347,18 @@ fn playback_item_widget() -> impl Widget<NowPlaying> {
         .padding(theme::grid(2.0))
         .expand_width()
         .link()
-        .on_ex_click(|ctx, _event, now_playing: &mut NowPlaying, _| {
-            let nav = now_playing.origin.to_nav();
-            ctx.submit_command(cmd::NAVIGATE.with(nav));
-        })
+        .on_ex_click(
+            |ctx, event, now_playing: &mut NowPlaying, _| match event.button {
+                MouseButton::Left => {
+                    let nav = now_playing.origin.to_nav();
+                    ctx.submit_command(cmd::NAVIGATE.with(nav));
+                }
+                MouseButton::Right => {
+                    ctx.show_context_menu(now_playing_menu(), event.window_pos);
+                }
+                _ => {}
+            },
+        )
 }
 
 fn player_widget() -> impl Widget<Playback> {
@@ -101,36 +374,30 @@ fn player_widget() -> impl Widget<Playback> {
         Empty,
     );
 
-    let play_pause = ViewSwitcher::new(
-        |playback: &Playback, _| playback.state,
-        |&state, _, _| match state {
-            PlaybackState::Loading => Spinner::new()
-                .with_color(theme::GREY_400)
-                .fix_size(theme::grid(3.0), theme::grid(3.0))
-                .padding(theme::grid(1.0))
-                .link()
-                .circle()
-                .border(theme::GREY_600, 1.0)
-                .on_click(|ctx, _, _| ctx.submit_command(cmd::PLAY_STOP))
-                .boxed(),
-            PlaybackState::Playing => icons::PAUSE
-                .scale((theme::grid(3.0), theme::grid(3.0)))
-                .padding(theme::grid(1.0))
-                .link()
-                .circle()
-                .border(theme::GREY_500, 1.0)
-                .on_click(|ctx, _, _| ctx.submit_command(cmd::PLAY_PAUSE))
-                .boxed(),
-            PlaybackState::Paused => icons::PLAY
-                .scale((theme::grid(3.0), theme::grid(3.0)))
-                .padding(theme::grid(1.0))
-                .link()
-                .circle()
-                .border(theme::GREY_500, 1.0)
-                .on_click(|ctx, _, _| ctx.submit_command(cmd::PLAY_RESUME))
-                .boxed(),
-            PlaybackState::Stopped => Empty.boxed(),
-        },
+    let seek_back = icons::SEEK_BACK
+        .scale((theme::grid(2.0), theme::grid(2.0)))
+        .padding(theme::grid(1.0))
+        .link()
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .on_click(|ctx, _, _| ctx.submit_command(cmd::SEEK_RELATIVE.with(SeekDirection::Backward)));
+    let seek_back = Either::new(
+        |playback: &Playback, _| playback.now_playing.is_some(),
+        seek_back,
+        Empty,
+    );
+
+    let play_pause = play_pause_widget();
+
+    let seek_forward = icons::SEEK_FORWARD
+        .scale((theme::grid(2.0), theme::grid(2.0)))
+        .padding(theme::grid(1.0))
+        .link()
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .on_click(|ctx, _, _| ctx.submit_command(cmd::SEEK_RELATIVE.with(SeekDirection::Forward)));
+    let seek_forward = Either::new(
+        |playback: &Playback, _| playback.now_playing.is_some(),
+        seek_forward,
+        Empty,
     );
 
     let play_next = icons::SKIP_FORWARD
@@ -157,9 +424,7 @@ fn player_widget() -> impl Widget<Playback> {
                     .on_click(|ctx: &mut EventCtx, playback: &mut Playback, _| {
                         let new_behavior = match playback.queue_behavior {
                             QueueBehavior::Sequential => QueueBehavior::Random,
-                            QueueBehavior::Random => QueueBehavior::LoopTrack,
-                            QueueBehavior::LoopTrack => QueueBehavior::LoopAll,
-                            QueueBehavior::LoopAll => QueueBehavior::Sequential,
+                            QueueBehavior::Random => QueueBehavior::Sequential,
                         };
                         ctx.submit_command(cmd::PLAY_QUEUE_BEHAVIOR.with(new_behavior));
                     })
@@ -168,8 +433,6 @@ fn player_widget() -> impl Widget<Playback> {
             match behavior {
                 QueueBehavior::Sequential => icon(&icons::PLAY_SEQUENTIAL),
                 QueueBehavior::Random => icon(&icons::PLAY_SHUFFLE),
-                QueueBehavior::LoopTrack => icon(&icons::PLAY_LOOP_TRACK),
-                QueueBehavior::LoopAll => icon(&icons::PLAY_LOOP_ALL),
             }
         },
     );
@@ -179,20 +442,144 @@ fn player_widget() -> impl Widget<Playback> {
         Empty,
     );
 
+    let repeat_mode = ViewSwitcher::new(
+        |playback: &Playback, _| playback.repeat_mode,
+        |repeat, _, _| {
+            let (svg, color) = match repeat {
+                RepeatMode::Off => (&icons::PLAY_LOOP_ALL, theme::GREY_600),
+                RepeatMode::Context => (&icons::PLAY_LOOP_ALL, theme::PLACEHOLDER_COLOR),
+                RepeatMode::Track => (&icons::PLAY_LOOP_TRACK, theme::PLACEHOLDER_COLOR),
+            };
+            svg.scale((theme::grid(2.0), theme::grid(2.0)))
+                .with_color(color)
+                .padding(theme::grid(1.0))
+                .link()
+                .rounded(theme::BUTTON_BORDER_RADIUS)
+                .on_click(|ctx, _, _| ctx.submit_command(cmd::PLAY_CYCLE_REPEAT_MODE))
+                .boxed()
+        },
+    );
+    let repeat_mode = Either::new(
+        |playback: &Playback, _| playback.now_playing.is_some(),
+        repeat_mode,
+        Empty,
+    );
+
+    let speed = Label::dynamic(|playback: &Playback, _| format!("{}×", playback.speed))
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .with_text_color(theme::PLACEHOLDER_COLOR)
+        .padding(theme::grid(1.0))
+        .link()
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .on_ex_click(|ctx, event, playback: &mut Playback, _| {
+            ctx.show_context_menu(speed_menu(playback.speed), event.window_pos);
+        });
+    let speed = Either::new(
+        |playback: &Playback, _| playback.now_playing.is_some(),
+        speed,
+        Empty,
+    );
+
+    let queue = icons::QUEUE
+        .scale((theme::grid(2.0), theme::grid(2.0)))
+        .with_color(theme::PLACEHOLDER_COLOR)
+        .padding(theme::grid(1.0))
+        .link()
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .on_click(|ctx, _, _| ctx.submit_command(cmd::TOGGLE_QUEUE_PANEL));
+
     let times = Maybe::or_empty(player_times_widget).lens(Playback::now_playing);
 
     Flex::row()
         .with_child(play_previous)
         .with_default_spacer()
+        .with_child(seek_back)
+        .with_default_spacer()
         .with_child(play_pause)
         .with_default_spacer()
+        .with_child(seek_forward)
+        .with_default_spacer()
         .with_child(play_next)
         .with_default_spacer()
         .with_child(queue_behavior)
         .with_default_spacer()
+        .with_child(repeat_mode)
+        .with_default_spacer()
+        .with_child(speed)
+        .with_default_spacer()
+        .with_child(queue)
+        .with_default_spacer()
         .with_child(times)
 }
 
+fn play_pause_widget() -> impl Widget<Playback> {
+    ViewSwitcher::new(
+        |playback: &Playback, _| playback.state,
+        |&state, _, _| match state {
+            PlaybackState::Loading => Spinner::new()
+                .with_color(theme::GREY_400)
+                .fix_size(theme::grid(3.0), theme::grid(3.0))
+                .padding(theme::grid(1.0))
+                .link()
+                .circle()
+                .border(theme::GREY_600, 1.0)
+                .on_click(|ctx, _, _| ctx.submit_command(cmd::PLAY_STOP))
+                .boxed(),
+            PlaybackState::Playing => icons::PAUSE
+                .scale((theme::grid(3.0), theme::grid(3.0)))
+                .padding(theme::grid(1.0))
+                .link()
+                .circle()
+                .border(theme::GREY_500, 1.0)
+                .on_click(|ctx, _, _| ctx.submit_command(cmd::PLAY_PAUSE))
+                .boxed(),
+            PlaybackState::Paused => icons::PLAY
+                .scale((theme::grid(3.0), theme::grid(3.0)))
+                .padding(theme::grid(1.0))
+                .link()
+                .circle()
+                .border(theme::GREY_500, 1.0)
+                .on_click(|ctx, _, _| ctx.submit_command(cmd::PLAY_RESUME))
+                .boxed(),
+            PlaybackState::Stopped => Empty.boxed(),
+        },
+    )
+}
+
+fn now_playing_menu() -> Menu<State> {
+    Menu::empty()
+        .entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-save-queue-as-playlist")
+                    .with_placeholder("Save Queue as Playlist"),
+            )
+            .command(cmd::SAVE_QUEUE_AS_PLAYLIST),
+        )
+        .entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-mini-player").with_placeholder("Mini Player"),
+            )
+            .command(cmd::TOGGLE_MINI_PLAYER),
+        )
+}
+
+fn speed_menu(current: f64) -> Menu<State> {
+    const SPEEDS: [f64; 8] = [0.5, 0.75, 1.0, 1.25, 1.5, 1.75, 2.0, 3.0];
+
+    let mut menu = Menu::empty();
+    for &speed in SPEEDS.iter() {
+        menu = menu.entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-playback-speed")
+                    .with_placeholder(format!("{}×", speed)),
+            )
+            .command(cmd::PLAY_SPEED.with(speed))
+            .selected((speed - current).abs() < f64::EPSILON),
+        );
+    }
+    menu
+}
+
 fn player_times_widget() -> impl Widget<NowPlaying> {
     Label::dynamic(|now_playing: &NowPlaying, _| {
         format!(
@@ -205,6 +592,82 @@ fn player_times_widget() -> impl Widget<NowPlaying> {
     .with_text_color(theme::PLACEHOLDER_COLOR)
 }
 
+struct Visualizer {
+    bands: Vec<f64>,
+}
+
+impl Visualizer {
+    const BAND_COUNT: usize = 48;
+    // Amount the bars fall every frame, unless pushed up by the signal.
+    const BAND_DECAY: f64 = 0.03;
+
+    fn new() -> Self {
+        Self {
+            bands: vec![0.0; Self::BAND_COUNT],
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.bands.iter().all(|&band| band <= 0.0)
+    }
+}
+
+impl Widget<State> for Visualizer {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut State, _env: &Env) {
+        if let Event::AnimFrame(_) = event {
+            let is_playing = data.playback.state == PlaybackState::Playing;
+            let levels = if is_playing {
+                data.spectrum.bands(Self::BAND_COUNT)
+            } else {
+                vec![0.0; Self::BAND_COUNT]
+            };
+            for (band, &level) in self.bands.iter_mut().zip(&levels) {
+                *band = (*band - Self::BAND_DECAY).max(level.into());
+            }
+            ctx.request_paint();
+            if is_playing || !self.is_idle() {
+                ctx.request_anim_frame();
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &State, _env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            ctx.request_anim_frame();
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &State, data: &State, _env: &Env) {
+        if old_data.playback.state != data.playback.state {
+            ctx.request_anim_frame();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &State,
+        _env: &Env,
+    ) -> Size {
+        bc.max()
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &State, env: &Env) {
+        let bounds = ctx.size();
+        let gap = 2.0;
+        let bar_width = bounds.width / Self::BAND_COUNT as f64 - gap;
+        let color = env.get(theme::GREY_400);
+
+        for (i, band) in self.bands.iter().enumerate() {
+            let bar_height = (bounds.height * band).max(1.0);
+            let x = i as f64 * (bar_width + gap);
+            let bar = Rect::new(x, bounds.height - bar_height, x + bar_width, bounds.height);
+            ctx.fill(bar, &color);
+        }
+    }
+}
+
 struct SeekBar {
     loudness_path: BezPath,
 }
@@ -218,7 +681,7 @@ impl SeekBar {
 }
 
 impl Widget<NowPlaying> for SeekBar {
-    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut NowPlaying, _env: &Env) {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut NowPlaying, _env: &Env) {
         match event {
             Event::MouseDown(mouse) => {
                 if mouse.button == MouseButton::Left {
@@ -228,7 +691,7 @@ impl Widget<NowPlaying> for SeekBar {
             Event::MouseUp(mouse) => {
                 if ctx.is_active() && mouse.button == MouseButton::Left {
                     if ctx.is_hot() {
-                        let fraction = mouse.pos.x / ctx.size().width;
+                        let fraction = seek_fraction(data, mouse.pos.x / ctx.size().width);
                         ctx.submit_command(cmd::PLAY_SEEK.with(fraction));
                     }
                     ctx.set_active(false);
@@ -247,7 +710,7 @@ impl Widget<NowPlaying> for SeekBar {
     ) {
         match &event {
             LifeCycle::Size(bounds) => {
-                // self.loudness_path = compute_loudness_path(bounds, &data);
+                self.loudness_path = compute_loudness_path(bounds, &data);
             }
             LifeCycle::HotChanged(_) => {
                 ctx.request_paint();
@@ -264,7 +727,9 @@ impl Widget<NowPlaying> for SeekBar {
         _env: &Env,
     ) {
         if !old_data.analysis.same(&data.analysis) || !old_data.item.same(&data.item) {
-            // self.loudness_path = compute_loudness_path(&ctx.size(), &data);
+            self.loudness_path = compute_loudness_path(&ctx.size(), &data);
+            // The bar grows taller once the analysis is available.
+            ctx.request_layout();
         }
         if !old_data.same(data) {
             ctx.request_paint();
@@ -275,10 +740,14 @@ impl Widget<NowPlaying> for SeekBar {
         &mut self,
         _ctx: &mut LayoutCtx,
         bc: &BoxConstraints,
-        _data: &NowPlaying,
+        data: &NowPlaying,
         _env: &Env,
     ) -> Size {
-        Size::new(bc.max().width, theme::grid(0.5))
+        if data.analysis.is_resolved() {
+            Size::new(bc.max().width, theme::grid(2.0))
+        } else {
+            Size::new(bc.max().width, theme::grid(0.5))
+        }
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, data: &NowPlaying, env: &Env) {
@@ -290,6 +759,20 @@ impl Widget<NowPlaying> for SeekBar {
     }
 }
 
+fn seek_fraction(data: &NowPlaying, fraction: f64) -> f64 {
+    // Snap to the closest beat, so seeking lands on the rhythm of the track.
+    const BEAT_TOLERANCE: Duration = Duration::from_millis(500);
+
+    if let Promise::Resolved(analysis) = &data.analysis {
+        let total = data.item.duration.as_secs_f64();
+        let position = Duration::from_secs_f64(total * fraction.max(0.0));
+        if let Some(beat) = analysis.nearest_beat(position, BEAT_TOLERANCE) {
+            return beat.as_secs_f64() / total;
+        }
+    }
+    fraction
+}
+
 fn compute_loudness_path(bounds: &Size, data: &NowPlaying) -> BezPath {
     if let Promise::Resolved(analysis) = &data.analysis {
         compute_loudness_path_from_analysis(&bounds, &data.item.duration, &analysis)
@@ -310,7 +793,8 @@ fn compute_loudness_path_from_analysis(
         .minmax()
         .into_option()
         .unwrap_or((0.0, 0.0));
-    let total_loudness = loudness_max - loudness_min;
+    // Guard against a flat (or empty) analysis, we would divide by zero below.
+    let total_loudness = (loudness_max - loudness_min).max(f64::EPSILON);
 
     let mut path = BezPath::new();
 
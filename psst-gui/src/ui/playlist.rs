@@ -1,19 +1,37 @@
 use crate::{
     cmd,
-    data::{CommonCtx, Ctx, Library, Nav, Playlist, PlaylistDetail, State},
+    controller::InputController,
+    data::{
+        CommonCtx, Ctx, Library, Nav, Playlist, PlaylistDetail, PlaylistLink, PlaylistTracks,
+        SpotifyUrl, State, TrackView,
+    },
     ui::{
+        download::download_button_widget,
         theme,
-        track::{tracklist_widget, TrackDisplay},
-        utils::{error_widget, spinner_widget},
+        track::{filter_widget, tracklist_widget, TrackDisplay},
+        utils::{error_widget, open_in_spotify_menu, play_button_widget, spinner_widget},
     },
-    webapi::WebApi,
-    widget::{Async, AsyncAction, LinkExt},
+    widget::{Async, Hover, LinkExt},
 };
 use druid::{
-    widget::{CrossAxisAlignment, Flex, Label, LineBreaking, List},
-    Insets, LensExt, MouseButton, Widget, WidgetExt,
+    commands,
+    widget::{
+        Button, Controller, CrossAxisAlignment, Either, Flex, Label, LineBreaking, List, TextBox,
+    },
+    Env, Event, EventCtx, Insets, LensExt, LocalizedString, Menu, MenuItem, MouseButton, UnitPoint,
+    Widget, WidgetExt,
 };
 
+pub fn new_playlist_widget() -> impl Widget<State> {
+    Label::new("New Playlist")
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .with_text_color(theme::PLACEHOLDER_COLOR)
+        .expand_width()
+        .padding(Insets::uniform_xy(theme::grid(2.0), theme::grid(0.6)))
+        .link()
+        .on_click(|ctx, _, _| ctx.submit_command(cmd::CREATE_PLAYLIST))
+}
+
 pub fn list_widget() -> impl Widget<State> {
     Async::new(
         || spinner_widget(),
@@ -22,20 +40,144 @@ pub fn list_widget() -> impl Widget<State> {
                 Label::raw()
                     .with_line_break_mode(LineBreaking::WordWrap)
                     .with_text_size(theme::TEXT_SIZE_SMALL)
-                    .lens(Playlist::name)
+                    .lens(Ctx::data().then(Playlist::name))
                     .expand_width()
                     .padding(Insets::uniform_xy(theme::grid(2.0), theme::grid(0.6)))
                     .link()
-                    .on_click(|ctx, playlist, _| {
-                        let nav = Nav::PlaylistDetail(playlist.link());
-                        ctx.submit_command(cmd::NAVIGATE.with(nav));
+                    .env_scope(|env, pc: &Ctx<CommonCtx, Playlist>| {
+                        // Highlight the playlist the dragged tracks would be
+                        // dropped on.
+                        if !pc.ctx.dragged_tracks.is_empty()
+                            && pc.ctx.is_playlist_editable(&pc.data.id)
+                        {
+                            env.set(
+                                theme::LINK_HOT_COLOR,
+                                env.get(theme::BLUE_200).with_alpha(0.3),
+                            );
+                        }
+                    })
+                    .on_ex_click(|ctx, event, pc: &mut Ctx<CommonCtx, Playlist>, _| {
+                        match event.button {
+                            MouseButton::Left => {
+                                let nav = Nav::PlaylistDetail(pc.data.link());
+                                ctx.submit_command(cmd::NAVIGATE.with(nav));
+                            }
+                            MouseButton::Right => {
+                                ctx.show_context_menu(playlist_menu(pc), event.window_pos);
+                            }
+                            _ => {}
+                        }
                     })
+                    .controller(DropTracksController)
             })
         },
-        || error_widget(),
+        || error_widget().lens(Ctx::data()),
+    )
+    .lens(
+        Ctx::make(
+            State::common_ctx,
+            State::library.then(Library::playlists.in_arc()),
+        )
+        .then(Ctx::in_promise()),
+    )
+}
+
+/// Adds the tracks dragged out of a track list to the playlist they get
+/// dropped on.
+struct DropTracksController;
+
+impl<W> Controller<Ctx<CommonCtx, Playlist>, W> for DropTracksController
+where
+    W: Widget<Ctx<CommonCtx, Playlist>>,
+{
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut Ctx<CommonCtx, Playlist>,
+        env: &Env,
+    ) {
+        if let Event::MouseUp(_) = event {
+            if ctx.is_hot()
+                && !data.ctx.dragged_tracks.is_empty()
+                && data.ctx.is_playlist_editable(&data.data.id)
+            {
+                ctx.submit_command(
+                    cmd::ADD_TO_PLAYLIST.with((data.data.link(), data.ctx.dragged_tracks.clone())),
+                );
+            }
+        }
+        child.event(ctx, event, data, env);
+    }
+}
+
+fn playlist_menu(pc: &Ctx<CommonCtx, Playlist>) -> Menu<State> {
+    let playlist = &pc.data;
+    let url = SpotifyUrl::Playlist(playlist.id.clone());
+    let mut menu = Menu::empty()
+        .entry(
+            MenuItem::new(LocalizedString::new("menu-item-play").with_placeholder("Play"))
+                .command(cmd::PLAY_LINK.with(url.clone())),
+        )
+        .entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-shuffle-play").with_placeholder("Shuffle Play"),
+            )
+            .command(cmd::SHUFFLE_LINK.with(url)),
+        )
+        .separator()
+        .entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-copy-link").with_placeholder("Copy Link"),
+            )
+            .command(cmd::COPY.with(playlist.url())),
+        )
+        .entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-copy-uri").with_placeholder("Copy Spotify URI"),
+            )
+            .command(cmd::COPY.with(playlist.uri())),
+        )
+        .entry(open_in_spotify_menu(playlist.url(), playlist.uri()));
+
+    if pc.ctx.is_playlist_editable(&playlist.id) {
+        menu = menu.entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-edit-playlist").with_placeholder("Edit Details"),
+            )
+            .command(cmd::EDIT_PLAYLIST.with(playlist.link())),
+        );
+    }
+
+    menu.separator().entry(
+        MenuItem::new(
+            LocalizedString::new("menu-item-remove-playlist").with_placeholder("Unfollow"),
+        )
+        .command(cmd::CONFIRM_UNFOLLOW_PLAYLIST.with(playlist.link())),
     )
-    .controller(AsyncAction::new(|_| WebApi::global().get_playlists()))
-    .lens(State::library.then(Library::playlists.in_arc()))
+}
+
+pub fn unfollow_confirmation_widget(link: PlaylistLink) -> impl Widget<State> {
+    let message = Label::new(format!("Remove “{}” from your library?", link.name))
+        .with_line_break_mode(LineBreaking::WordWrap);
+
+    let buttons = Flex::row()
+        .with_child(Button::new("Cancel").on_click(|ctx, _, _| {
+            ctx.submit_command(commands::CLOSE_WINDOW);
+        }))
+        .with_default_spacer()
+        .with_child(Button::new("Remove").on_click(move |ctx, _, _| {
+            ctx.submit_command(cmd::UNFOLLOW_PLAYLIST.with(link.clone()));
+            ctx.submit_command(commands::CLOSE_WINDOW);
+        }));
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::End)
+        .with_child(message.expand_width())
+        .with_spacer(theme::grid(2.0))
+        .with_child(buttons)
+        .padding(theme::grid(3.0))
 }
 
 pub fn playlist_widget() -> impl Widget<Ctx<CommonCtx, Playlist>> {
@@ -53,35 +195,109 @@ pub fn playlist_widget() -> impl Widget<Ctx<CommonCtx, Playlist>> {
     .with_text_size(theme::TEXT_SIZE_SMALL)
     .lens(Playlist::track_count);
 
-    Flex::column()
+    let playlist = Flex::column()
         .cross_axis_alignment(CrossAxisAlignment::Start)
         .with_child(playlist_name)
         .with_spacer(2.0)
         .with_child(track_count)
-        .padding(theme::grid(1.0))
+        .padding(theme::grid(1.0));
+
+    let play_button = play_button_widget(|ctx, playlist: &mut Playlist| {
+        ctx.submit_command(cmd::PLAY_LINK.with(SpotifyUrl::Playlist(playlist.id.clone())));
+    });
+    Hover::new(playlist, play_button)
+        .align(UnitPoint::RIGHT)
+        .offset((-theme::grid(1.0), 0.0))
         .link()
+        .lens(Ctx::data())
         .on_ex_click(
-            move |ctx, event, playlist: &mut Playlist, _| match event.button {
+            move |ctx, event, pc: &mut Ctx<CommonCtx, Playlist>, _| match event.button {
                 MouseButton::Left => {
-                    let nav = Nav::PlaylistDetail(playlist.link());
+                    let nav = Nav::PlaylistDetail(pc.data.link());
                     ctx.submit_command(cmd::NAVIGATE.with(nav));
                 }
+                MouseButton::Right => {
+                    ctx.show_context_menu(playlist_menu(pc), event.window_pos);
+                }
                 _ => {}
             },
         )
-        .lens(Ctx::data())
 }
 
 pub fn detail_widget() -> impl Widget<State> {
+    let header = Flex::row()
+        .with_child(name_editor_widget())
+        .with_child(follow_button_widget());
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(header)
+        .with_child(tracks_widget())
+}
+
+fn follow_button_widget() -> impl Widget<State> {
+    Either::new(
+        |state: &State, _| match &state.route {
+            Nav::PlaylistDetail(link) => state.is_playlist_followed(&link.id),
+            _ => false,
+        },
+        Button::new("Unfollow").on_click(|ctx, state: &mut State, _| {
+            if let Nav::PlaylistDetail(link) = &state.route {
+                ctx.submit_command(cmd::UNFOLLOW_PLAYLIST.with(link.to_owned()));
+            }
+        }),
+        Button::new("Follow").on_click(|ctx, state: &mut State, _| {
+            if let Nav::PlaylistDetail(link) = &state.route {
+                ctx.submit_command(cmd::FOLLOW_PLAYLIST.with(link.to_owned()));
+            }
+        }),
+    )
+    .padding((0.0, 0.0, theme::grid(1.0), theme::grid(1.0)))
+}
+
+fn name_editor_widget() -> impl Widget<State> {
+    TextBox::new()
+        .with_placeholder("Playlist name")
+        .with_text_size(theme::TEXT_SIZE_LARGE)
+        .controller(InputController::new().on_submit(|ctx, name, _| {
+            ctx.submit_command(cmd::RENAME_PLAYLIST.with(name.clone()));
+        }))
+        .with_id(cmd::WIDGET_PLAYLIST_NAME_INPUT)
+        .fix_width(theme::grid(40.0))
+        .padding((theme::grid(1.0), 0.0, theme::grid(1.0), theme::grid(1.0)))
+        .lens(State::playlist.then(PlaylistDetail::name_input))
+}
+
+fn tracks_widget() -> impl Widget<State> {
     Async::new(
         || spinner_widget(),
         || {
-            tracklist_widget(TrackDisplay {
-                title: true,
-                artist: true,
-                album: true,
-                ..TrackDisplay::empty()
-            })
+            Flex::column()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(
+                    download_button_widget()
+                        .lens(Ctx::map(PlaylistTracks::tracks))
+                        .padding((theme::grid(1.0), 0.0)),
+                )
+                .with_spacer(theme::grid(1.0))
+                .with_child(
+                    filter_widget()
+                        .lens(Ctx::ctx())
+                        .padding((theme::grid(1.0), 0.0)),
+                )
+                .with_spacer(theme::grid(1.0))
+                .with_child(tracklist_widget(
+                    TrackView::Playlist,
+                    TrackDisplay {
+                        title: true,
+                        artist: true,
+                        album: true,
+                        bpm: true,
+                        key: true,
+                        duration: true,
+                        ..TrackDisplay::empty()
+                    },
+                ))
         },
         || error_widget().lens(Ctx::data()),
     )
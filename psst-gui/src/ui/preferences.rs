@@ -1,21 +1,29 @@
-use std::thread::{self, JoinHandle};
+use std::{
+    sync::Arc,
+    thread::{self, JoinHandle},
+};
 
 use crate::{
     cmd,
     controller::InputController,
     data::{
-        AudioQuality, Authentication, Config, Preferences, PreferencesTab, Promise, State, Theme,
+        AudioQuality, Authentication, CacheBucket, CacheUsage, Config, Equalizer, EqualizerPreset,
+        Hotkeys, Keymap, KeymapPreset, Normalization, Preferences, PreferencesTab, Promise, State,
+        Theme,
     },
     ui::{icons::SvgIcon, theme, utils::Border},
-    widget::{icons, Empty, LinkExt},
+    widget::{icons, Async, Empty, LinkExt},
 };
 use druid::{
     commands,
+    im::Vector,
+    lens,
     widget::{
-        Button, Controller, CrossAxisAlignment, Flex, Label, LineBreaking, MainAxisAlignment,
-        RadioGroup, TextBox, ViewSwitcher,
+        Button, Checkbox, Controller, CrossAxisAlignment, Flex, Label, LineBreaking, List,
+        MainAxisAlignment, RadioGroup, Scroll, Slider, TextBox, ViewSwitcher,
     },
-    Env, Event, EventCtx, LifeCycle, LifeCycleCtx, Selector, Widget, WidgetExt,
+    Env, Event, EventCtx, ExtEventSink, Lens, LifeCycle, LifeCycleCtx, Selector, Widget, WidgetExt,
+    WidgetId,
 };
 use psst_core::connection::Credentials;
 
@@ -28,7 +36,8 @@ pub fn preferences_widget() -> impl Widget<State> {
         |state: &State, _env| state.preferences.active,
         |active: &PreferencesTab, _state, _env| match active {
             PreferencesTab::General => general_tab_widget().boxed(),
-            PreferencesTab::Cache => cache_tab_widget().boxed(),
+            PreferencesTab::Playback => Scroll::new(playback_tab_widget()).vertical().boxed(),
+            PreferencesTab::Cache => Scroll::new(cache_tab_widget()).vertical().boxed(),
         },
     )
     .padding(theme::grid(4.0))
@@ -74,6 +83,8 @@ fn tabs_widget() -> impl Widget<State> {
             PreferencesTab::General,
         ))
         .with_default_spacer()
+        .with_child(label("Playback", &icons::SPEAKER, PreferencesTab::Playback))
+        .with_default_spacer()
         .with_child(label("Cache", &icons::STORAGE, PreferencesTab::Cache))
 }
 
@@ -92,6 +103,107 @@ fn general_tab_widget() -> impl Widget<State> {
 
     col = col.with_spacer(theme::grid(3.0));
 
+    // Artwork resolution
+    col = col
+        .with_child(Label::new("Artwork resolution").with_font(theme::UI_FONT_MEDIUM))
+        .with_spacer(theme::grid(2.0))
+        .with_child(
+            RadioGroup::new(vec![("Standard", 1.0), ("HiDPI (2x)", 2.0)])
+                .lens(Config::image_scale)
+                .lens(State::config),
+        );
+
+    col = col.with_spacer(theme::grid(3.0));
+
+    // Window title
+    col = col
+        .with_child(Label::new("Window title").with_font(theme::UI_FONT_MEDIUM))
+        .with_spacer(theme::grid(2.0))
+        .with_child(
+            TextBox::new()
+                .with_placeholder("Psst")
+                .controller(InputController::new())
+                .lens(Config::window_title)
+                .lens(State::config)
+                .fix_width(theme::grid(30.0)),
+        )
+        .with_spacer(theme::grid(1.0))
+        .with_child(
+            Label::new(
+                "Use {title}, {artist}, {album}, {elapsed} and {duration} for the playing track.",
+            )
+            .with_text_color(theme::PLACEHOLDER_COLOR)
+            .with_text_size(theme::TEXT_SIZE_SMALL),
+        );
+
+    col = col.with_spacer(theme::grid(3.0));
+
+    // Integrations
+    col = col
+        .with_child(Label::new("Integrations").with_font(theme::UI_FONT_MEDIUM))
+        .with_spacer(theme::grid(2.0))
+        .with_child(
+            Checkbox::new("Show the current track in Discord")
+                .lens(Config::discord_presence)
+                .lens(State::config),
+        )
+        .with_spacer(theme::grid(1.0))
+        .with_child(
+            Checkbox::new("Play music from other devices with Spotify Connect")
+                .lens(Config::connect_device)
+                .lens(State::config),
+        )
+        .with_spacer(theme::grid(1.0))
+        .with_child(
+            Checkbox::new("Allow controlling the playback over HTTP on localhost")
+                .lens(Config::remote_api)
+                .lens(State::config),
+        )
+        .with_spacer(theme::grid(1.0))
+        .with_child(remote_api_token_widget());
+
+    col = col.with_spacer(theme::grid(3.0));
+
+    // Global hotkeys
+    col = col
+        .with_child(Label::new("Global Hotkeys").with_font(theme::UI_FONT_MEDIUM))
+        .with_spacer(theme::grid(2.0))
+        .with_child(
+            Checkbox::new("Control the playback from other applications")
+                .lens(Hotkeys::enabled)
+                .lens(Config::hotkeys)
+                .lens(State::config),
+        )
+        .with_spacer(theme::grid(1.0))
+        .with_child(hotkey_widget("Play / Pause", Hotkeys::play_pause))
+        .with_spacer(theme::grid(1.0))
+        .with_child(hotkey_widget("Next Track", Hotkeys::next))
+        .with_spacer(theme::grid(1.0))
+        .with_child(hotkey_widget("Previous Track", Hotkeys::previous))
+        .with_spacer(theme::grid(1.0))
+        .with_child(hotkey_widget("Save to Library", Hotkeys::like));
+
+    col = col.with_spacer(theme::grid(3.0));
+
+    // Keyboard shortcuts
+    col = col
+        .with_child(Label::new("Keyboard Shortcuts").with_font(theme::UI_FONT_MEDIUM))
+        .with_spacer(theme::grid(2.0))
+        .with_child(
+            RadioGroup::new(vec![
+                ("Default", KeymapPreset::Default),
+                (
+                    "Vim (j/k to move, / to search, gg/G to jump)",
+                    KeymapPreset::Vim,
+                ),
+            ])
+            .lens(Keymap::preset)
+            .lens(Config::keymap)
+            .lens(State::config),
+        );
+
+    col = col.with_spacer(theme::grid(3.0));
+
     // Authentication
     col = col
         .with_child(Label::new("Credentials").with_font(theme::UI_FONT_MEDIUM))
@@ -146,6 +258,69 @@ fn general_tab_widget() -> impl Widget<State> {
 
     col = col.with_spacer(theme::grid(3.0));
 
+    // Save
+    col = col.with_child(
+        Button::new("Save")
+            .on_click(move |ctx, config: &mut Config, _env| {
+                config.save();
+                ctx.submit_command(cmd::SESSION_CONNECT);
+                ctx.submit_command(cmd::SHOW_MAIN);
+                ctx.submit_command(commands::CLOSE_WINDOW);
+            })
+            .fix_width(theme::grid(10.0))
+            .align_right()
+            .lens(State::config),
+    );
+
+    col.controller(Authenticate::new())
+}
+
+fn hotkey_widget(label: &str, binding: impl Lens<Hotkeys, String> + 'static) -> impl Widget<State> {
+    Flex::row()
+        .with_child(
+            Label::new(label)
+                .with_text_size(theme::TEXT_SIZE_SMALL)
+                .fix_width(theme::grid(16.0)),
+        )
+        .with_child(
+            TextBox::new()
+                .with_placeholder("None")
+                .controller(InputController::new())
+                .lens(binding)
+                .lens(Config::hotkeys)
+                .lens(State::config),
+        )
+}
+
+fn remote_api_token_widget() -> impl Widget<State> {
+    Flex::row()
+        .with_child(
+            Label::new("Token")
+                .with_text_size(theme::TEXT_SIZE_SMALL)
+                .fix_width(theme::grid(16.0)),
+        )
+        .with_child(
+            Label::dynamic(|config: &Config, _| config.remote_api_token.clone())
+                .with_text_size(theme::TEXT_SIZE_SMALL),
+        )
+        .with_default_spacer()
+        .with_child(Button::new("Copy").on_click(|ctx, config: &mut Config, _| {
+            ctx.submit_command(cmd::COPY.with(config.remote_api_token.clone()));
+        }))
+        .lens(State::config)
+}
+
+fn playback_tab_widget() -> impl Widget<State> {
+    let mut col = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+
+    // Output device
+    col = col
+        .with_child(Label::new("Output device").with_font(theme::UI_FONT_MEDIUM))
+        .with_spacer(theme::grid(2.0))
+        .with_child(output_device_widget());
+
+    col = col.with_spacer(theme::grid(3.0));
+
     // Audio quality
     col = col
         .with_child(Label::new("Audio quality").with_font(theme::UI_FONT_MEDIUM))
@@ -158,25 +333,314 @@ fn general_tab_widget() -> impl Widget<State> {
             ])
             .lens(Config::audio_quality)
             .lens(State::config),
+        )
+        .with_spacer(theme::grid(1.0))
+        .with_child(
+            Checkbox::new("Data saver, always stream in low quality")
+                .lens(Config::data_saver)
+                .lens(State::config),
+        )
+        .with_spacer(theme::grid(1.0))
+        .with_child(
+            Checkbox::new("Stream in low quality on metered connections")
+                .lens(Config::data_saver_on_metered)
+                .lens(State::config),
+        )
+        .with_child(
+            Label::dynamic(|config: &Config, _| {
+                if config.metered {
+                    "The current connection is metered.".to_string()
+                } else {
+                    String::new()
+                }
+            })
+            .with_text_color(theme::PLACEHOLDER_COLOR)
+            .with_text_size(theme::TEXT_SIZE_SMALL)
+            .lens(State::config),
         );
 
     col = col.with_spacer(theme::grid(3.0));
 
-    // Save
-    col = col.with_child(
-        Button::new("Save")
-            .on_click(move |ctx, config: &mut Config, _env| {
-                config.save();
-                ctx.submit_command(cmd::SESSION_CONNECT);
-                ctx.submit_command(cmd::SHOW_MAIN);
-                ctx.submit_command(commands::CLOSE_WINDOW);
-            })
-            .fix_width(theme::grid(10.0))
-            .align_right()
+    // Loudness normalization
+    col = col
+        .with_child(Label::new("Loudness normalization").with_font(theme::UI_FONT_MEDIUM))
+        .with_spacer(theme::grid(2.0))
+        .with_child(
+            RadioGroup::new(vec![
+                ("Off", Normalization::Off),
+                ("Automatic", Normalization::Automatic),
+                ("Track", Normalization::Track),
+                ("Album", Normalization::Album),
+            ])
+            .lens(Config::normalization)
             .lens(State::config),
-    );
+        )
+        .with_spacer(theme::grid(1.0))
+        .with_child(
+            Flex::row()
+                .with_child(Label::new("Pre-amp").with_text_size(theme::TEXT_SIZE_SMALL))
+                .with_default_spacer()
+                .with_child(Slider::new().with_range(-10.0, 10.0))
+                .with_default_spacer()
+                .with_child(Label::dynamic(|db: &f64, _| format!("{:+} dB", db.round())))
+                .lens(Config::pregain_db)
+                .lens(State::config),
+        );
 
-    col.controller(Authenticate::new())
+    col = col.with_spacer(theme::grid(3.0));
+
+    // Crossfade
+    col = col
+        .with_child(Label::new("Crossfade").with_font(theme::UI_FONT_MEDIUM))
+        .with_spacer(theme::grid(2.0))
+        .with_child(
+            Flex::row()
+                .with_child(Slider::new().with_range(0.0, Config::MAX_CROSSFADE_SECS))
+                .with_default_spacer()
+                .with_child(Label::dynamic(|secs: &f64, _| match secs.round() as u64 {
+                    0 => "Off".to_string(),
+                    secs => format!("{} s", secs),
+                }))
+                .lens(Config::crossfade_secs)
+                .lens(State::config),
+        );
+
+    col = col.with_spacer(theme::grid(3.0));
+
+    // Fades
+    col = col
+        .with_child(Label::new("Fade on pause and seek").with_font(theme::UI_FONT_MEDIUM))
+        .with_spacer(theme::grid(2.0))
+        .with_child(
+            RadioGroup::new(vec![
+                ("Off", 0),
+                ("Short (50 ms)", 50),
+                ("Medium (200 ms)", 200),
+                ("Long (500 ms)", 500),
+            ])
+            .lens(Config::fade_millis)
+            .lens(State::config),
+        );
+
+    col = col.with_spacer(theme::grid(3.0));
+
+    // Seek step
+    col = col
+        .with_child(Label::new("Seek step").with_font(theme::UI_FONT_MEDIUM))
+        .with_spacer(theme::grid(2.0))
+        .with_child(
+            RadioGroup::new(vec![
+                ("5 seconds", 5),
+                ("10 seconds", 10),
+                ("30 seconds", 30),
+            ])
+            .lens(Config::seek_step_secs)
+            .lens(State::config),
+        );
+
+    col = col.with_spacer(theme::grid(3.0));
+
+    // Autoplay
+    col = col
+        .with_child(Label::new("Autoplay").with_font(theme::UI_FONT_MEDIUM))
+        .with_spacer(theme::grid(2.0))
+        .with_child(
+            Checkbox::new("Continue with similar tracks when the queue ends")
+                .lens(Config::autoplay)
+                .lens(State::config),
+        );
+
+    col = col.with_spacer(theme::grid(3.0));
+
+    // Explicit content
+    col = col
+        .with_child(Label::new("Explicit content").with_font(theme::UI_FONT_MEDIUM))
+        .with_spacer(theme::grid(2.0))
+        .with_child(
+            Checkbox::new("Skip tracks marked as explicit")
+                .lens(Config::skip_explicit)
+                .lens(State::config),
+        );
+
+    col = col.with_spacer(theme::grid(3.0));
+
+    // Equalizer
+    col = col
+        .with_child(Label::new("Equalizer").with_font(theme::UI_FONT_MEDIUM))
+        .with_spacer(theme::grid(2.0))
+        .with_child(
+            equalizer_widget()
+                .lens(Config::equalizer)
+                .lens(State::config),
+        );
+
+    col = col.with_spacer(theme::grid(3.0));
+
+    // Accessibility
+    col = col
+        .with_child(Label::new("Accessibility").with_font(theme::UI_FONT_MEDIUM))
+        .with_spacer(theme::grid(2.0))
+        .with_child(
+            Checkbox::new("Mono audio, play both channels on each speaker")
+                .lens(Config::mono_audio)
+                .lens(State::config),
+        );
+
+    col
+}
+
+fn output_device_widget() -> impl Widget<State> {
+    ViewSwitcher::new(
+        |state: &State, _| state.preferences.output_devices.to_owned(),
+        |devices, _, _| match devices {
+            Promise::Empty | Promise::Deferred(_) => Label::new("Looking for devices...")
+                .with_text_size(theme::TEXT_SIZE_SMALL)
+                .boxed(),
+            Promise::Rejected(_) => Label::new("Failed to list devices.")
+                .with_text_size(theme::TEXT_SIZE_SMALL)
+                .with_text_color(theme::RED)
+                .boxed(),
+            Promise::Resolved(devices) => {
+                let mut variants = vec![("System default".to_string(), None)];
+                variants.extend(
+                    devices
+                        .iter()
+                        .map(|name| (name.to_owned(), Some(name.to_owned()))),
+                );
+                RadioGroup::new(variants)
+                    .lens(Config::audio_device)
+                    .lens(State::config)
+                    .boxed()
+            }
+        },
+    )
+    .controller(ListOutputDevices::new())
+}
+
+struct ListOutputDevices {
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ListOutputDevices {
+    fn new() -> Self {
+        Self { thread: None }
+    }
+}
+
+impl ListOutputDevices {
+    const RESULT: Selector<Option<Vector<String>>> =
+        Selector::new("app.preferences.list-output-devices");
+}
+
+impl<W: Widget<State>> Controller<State, W> for ListOutputDevices {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut State,
+        env: &Env,
+    ) {
+        match &event {
+            Event::Command(cmd) if cmd.is(Self::RESULT) => {
+                let result = cmd.get_unchecked(Self::RESULT).to_owned();
+                data.preferences
+                    .output_devices
+                    .resolve_or_reject(result.ok_or(()));
+                self.thread.take();
+                ctx.set_handled();
+            }
+            _ => {
+                child.event(ctx, event, data, env);
+            }
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &State,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = &event {
+            let handle = thread::spawn({
+                let widget_id = ctx.widget_id();
+                let event_sink = ctx.get_external_handle();
+                move || {
+                    let devices = Preferences::list_output_devices();
+                    event_sink
+                        .submit_command(Self::RESULT, devices, widget_id)
+                        .unwrap();
+                }
+            });
+            self.thread.replace(handle);
+        }
+        child.lifecycle(ctx, event, data, env);
+    }
+}
+
+fn equalizer_widget() -> impl Widget<Equalizer> {
+    let presets = RadioGroup::new(vec![
+        ("Flat", EqualizerPreset::Flat),
+        ("Bass boost", EqualizerPreset::BassBoost),
+        ("Treble boost", EqualizerPreset::TrebleBoost),
+        ("Vocal", EqualizerPreset::Vocal),
+        ("Rock", EqualizerPreset::Rock),
+        ("Pop", EqualizerPreset::Pop),
+        ("Electronic", EqualizerPreset::Electronic),
+        ("Custom", EqualizerPreset::Custom),
+    ])
+    .lens(lens::Map::new(
+        |eq: &Equalizer| eq.preset,
+        |eq: &mut Equalizer, preset| {
+            if eq.preset != preset {
+                eq.set_preset(preset);
+            }
+        },
+    ));
+
+    let mut bands = Flex::column().cross_axis_alignment(CrossAxisAlignment::End);
+    for (band, freq) in Equalizer::frequencies().enumerate() {
+        let freq = if freq >= 1000.0 {
+            format!("{}k", freq / 1000.0)
+        } else {
+            format!("{}", freq)
+        };
+        bands = bands.with_child(
+            Flex::row()
+                .with_child(
+                    Label::new(freq)
+                        .with_text_size(theme::TEXT_SIZE_SMALL)
+                        .fix_width(theme::grid(4.0)),
+                )
+                .with_child(
+                    Slider::new()
+                        .with_range(-12.0, 12.0)
+                        .fix_width(theme::grid(16.0)),
+                )
+                .with_child(
+                    Label::dynamic(|db: &f64, _| format!("{:+} dB", db.round()))
+                        .with_text_size(theme::TEXT_SIZE_SMALL)
+                        .fix_width(theme::grid(5.0)),
+                )
+                .lens(lens::Map::new(
+                    move |eq: &Equalizer| eq.gains[band],
+                    move |eq: &mut Equalizer, gain| {
+                        if (eq.gains[band] - gain).abs() > f64::EPSILON {
+                            eq.set_gain(band, gain);
+                        }
+                    },
+                )),
+        );
+    }
+
+    Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(presets)
+        .with_default_spacer()
+        .with_child(bands)
 }
 
 struct Authenticate {
@@ -254,25 +718,104 @@ fn cache_tab_widget() -> impl Widget<State> {
     col = col
         .with_child(Label::new("Size").with_font(theme::UI_FONT_MEDIUM))
         .with_spacer(theme::grid(2.0))
-        .with_child(Label::dynamic(
-            |preferences: &Preferences, _| match preferences.cache_size {
-                Promise::Empty | Promise::Rejected(_) => {
-                    format!("Unknown")
-                }
-                Promise::Deferred(_) => {
-                    format!("Computing")
-                }
-                Promise::Resolved(0) => {
-                    format!("Empty")
-                }
-                Promise::Resolved(b) => {
-                    format!("{:.2} MB", b as f64 / 1e6 as f64)
-                }
-            },
-        ));
+        .with_child(
+            Flex::column()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(Label::dynamic(
+                    |preferences: &Preferences, _| match &preferences.cache_usage {
+                        Promise::Empty | Promise::Rejected(_) => {
+                            format!("Unknown")
+                        }
+                        Promise::Deferred(_) => {
+                            format!("Computing")
+                        }
+                        Promise::Resolved(usage) if usage.total == 0 => {
+                            format!("Empty")
+                        }
+                        Promise::Resolved(usage) => {
+                            format!("{} used", as_megabytes(usage.total))
+                        }
+                    },
+                ))
+                .with_default_spacer()
+                .with_child(
+                    Async::new(
+                        || Empty,
+                        || List::new(cache_bucket_widget).lens(CacheUsage::buckets),
+                        || Empty,
+                    )
+                    .lens(Preferences::cache_usage),
+                )
+                .controller(MeasureCacheSize::new())
+                .lens(State::preferences),
+        );
+
+    col = col.with_spacer(theme::grid(3.0));
 
-    col.controller(MeasureCacheSize::new())
-        .lens(State::preferences)
+    col = col
+        .with_child(Label::new("Audio cache limit").with_font(theme::UI_FONT_MEDIUM))
+        .with_spacer(theme::grid(2.0))
+        .with_child(
+            RadioGroup::new(vec![
+                ("1 GB", Some(1)),
+                ("2 GB", Some(2)),
+                ("5 GB", Some(5)),
+                ("10 GB", Some(10)),
+                ("Unlimited", None),
+            ])
+            .lens(Config::audio_cache_limit_gb)
+            .lens(State::config),
+        )
+        .with_spacer(theme::grid(1.0))
+        .with_child(
+            Label::new("Least recently played tracks are removed first, downloads are kept.")
+                .with_line_break_mode(LineBreaking::WordWrap)
+                .with_text_size(theme::TEXT_SIZE_SMALL)
+                .with_text_color(theme::PLACEHOLDER_COLOR),
+        );
+
+    col = col.with_spacer(theme::grid(3.0));
+
+    col = col
+        .with_child(Label::new("Artwork cache limit").with_font(theme::UI_FONT_MEDIUM))
+        .with_spacer(theme::grid(2.0))
+        .with_child(
+            RadioGroup::new(vec![
+                ("100 MB", Some(100)),
+                ("250 MB", Some(250)),
+                ("500 MB", Some(500)),
+                ("1 GB", Some(1000)),
+                ("Unlimited", None),
+            ])
+            .lens(Config::image_cache_limit_mb)
+            .lens(State::config),
+        );
+
+    col
+}
+
+fn cache_bucket_widget() -> impl Widget<CacheBucket> {
+    Flex::row()
+        .with_child(
+            Label::raw()
+                .lens(CacheBucket::name)
+                .fix_width(theme::grid(20.0)),
+        )
+        .with_child(
+            Label::dynamic(|bucket: &CacheBucket, _| as_megabytes(bucket.size))
+                .with_text_color(theme::PLACEHOLDER_COLOR)
+                .fix_width(theme::grid(12.0)),
+        )
+        .with_child(
+            Button::new("Clear").on_click(|ctx, bucket: &mut CacheBucket, _| {
+                ctx.submit_command(MeasureCacheSize::CLEAR.with(bucket.id.clone()));
+            }),
+        )
+        .padding((0.0, theme::grid(0.5)))
+}
+
+fn as_megabytes(bytes: u64) -> String {
+    format!("{:.2} MB", bytes as f64 / 1e6)
 }
 
 struct MeasureCacheSize {
@@ -286,7 +829,27 @@ impl MeasureCacheSize {
 }
 
 impl MeasureCacheSize {
-    const RESULT: Selector<Option<u64>> = Selector::new("app.preferences.measure-cache-size");
+    const RESULT: Selector<Option<CacheUsage>> =
+        Selector::new("app.preferences.measure-cache-size");
+    const CLEAR: Selector<Arc<str>> = Selector::new("app.preferences.clear-cache");
+
+    fn measure(
+        &mut self,
+        widget_id: WidgetId,
+        event_sink: ExtEventSink,
+        clear_first: Option<Arc<str>>,
+    ) {
+        let handle = thread::spawn(move || {
+            if let Some(bucket) = clear_first {
+                Preferences::clear_cache(&bucket);
+            }
+            let size = Preferences::measure_cache_usage();
+            event_sink
+                .submit_command(Self::RESULT, size, widget_id)
+                .unwrap();
+        });
+        self.thread.replace(handle);
+    }
 }
 
 impl<W: Widget<Preferences>> Controller<Preferences, W> for MeasureCacheSize {
@@ -301,10 +864,16 @@ impl<W: Widget<Preferences>> Controller<Preferences, W> for MeasureCacheSize {
         match &event {
             Event::Command(cmd) if cmd.is(Self::RESULT) => {
                 let result = cmd.get_unchecked(Self::RESULT).to_owned();
-                data.cache_size.resolve_or_reject(result.ok_or(()));
+                data.cache_usage.resolve_or_reject(result.ok_or(()));
                 self.thread.take();
                 ctx.set_handled();
             }
+            Event::Command(cmd) if cmd.is(Self::CLEAR) => {
+                let bucket = cmd.get_unchecked(Self::CLEAR).clone();
+                data.cache_usage.defer_default();
+                self.measure(ctx.widget_id(), ctx.get_external_handle(), Some(bucket));
+                ctx.set_handled();
+            }
             _ => {
                 child.event(ctx, event, data, env);
             }
@@ -320,17 +889,7 @@ impl<W: Widget<Preferences>> Controller<Preferences, W> for MeasureCacheSize {
         env: &Env,
     ) {
         if let LifeCycle::WidgetAdded = &event {
-            let handle = thread::spawn({
-                let widget_id = ctx.widget_id();
-                let event_sink = ctx.get_external_handle();
-                move || {
-                    let size = Preferences::measure_cache_usage();
-                    event_sink
-                        .submit_command(Self::RESULT, size, widget_id)
-                        .unwrap();
-                }
-            });
-            self.thread.replace(handle);
+            self.measure(ctx.widget_id(), ctx.get_external_handle(), None);
         }
         child.lifecycle(ctx, event, data, env);
     }
@@ -0,0 +1,219 @@
+use crate::{
+    cmd,
+    data::{NowPlaying, Playback, QueueEntry, QueuedTrack, State, Track},
+    ui::{theme, utils::Border},
+    widget::{icons, Empty, LinkExt, Maybe},
+};
+use druid::{
+    im::Vector,
+    lens::Map,
+    widget::{Controller, CrossAxisAlignment, Either, Flex, Label, LineBreaking, List, Scroll},
+    Cursor, Data, Env, Event, EventCtx, LensExt, LocalizedString, Menu, MenuItem, MouseButton,
+    Point, Widget, WidgetExt,
+};
+use std::sync::Arc;
+
+const PANEL_WIDTH: f64 = theme::GRID * 36.0;
+
+/// Panel next to the page, with the playing track and the ones coming up.
+pub fn panel_widget() -> impl Widget<State> {
+    let now_playing = Maybe::or_empty(now_playing_widget).lens(Playback::now_playing);
+
+    let next_in_queue = section_widget("Next in Queue", true);
+    let next_from_context = section_widget("Next from Context", false);
+
+    let content = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Fill)
+        .with_child(header_widget("Now Playing"))
+        .with_child(now_playing)
+        .with_child(next_in_queue)
+        .with_child(next_from_context)
+        .padding(theme::grid(1.0))
+        .lens(State::playback);
+
+    let panel = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Fill)
+        .with_child(title_widget())
+        .with_flex_child(Scroll::new(content).vertical(), 1.0)
+        .fix_width(PANEL_WIDTH)
+        .background(Border::Left.with_color(theme::BACKGROUND_DARK));
+
+    Either::new(|state: &State, _| state.show_queue, panel, Empty)
+}
+
+fn title_widget() -> impl Widget<State> {
+    let close = icons::CLOSE
+        .scale(theme::ICON_SIZE)
+        .padding(theme::grid(1.0))
+        .link()
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .on_click(|ctx, _, _| ctx.submit_command(cmd::TOGGLE_QUEUE_PANEL));
+
+    Flex::row()
+        .must_fill_main_axis(true)
+        .with_child(
+            Label::new("Queue")
+                .with_font(theme::UI_FONT_MEDIUM)
+                .padding(theme::grid(1.0)),
+        )
+        .with_flex_spacer(1.0)
+        .with_child(close)
+        .padding(theme::grid(0.5))
+        .background(Border::Bottom.with_color(theme::BACKGROUND_DARK))
+}
+
+fn header_widget<T: Data>(title: &str) -> impl Widget<T> {
+    Label::new(title)
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .with_text_color(theme::PLACEHOLDER_COLOR)
+        .padding((theme::grid(1.0), theme::grid(1.0), 0.0, theme::grid(0.5)))
+}
+
+/// Upcoming tracks of one kind, hidden if there are none.
+fn section_widget(title: &str, added: bool) -> impl Widget<Playback> {
+    let entries = Map::new(
+        move |playback: &Playback| playback.upcoming(added),
+        |_playback: &mut Playback, _entries: Vector<QueueEntry>| {
+            // Changes go through the queue commands.
+        },
+    );
+
+    Either::new(
+        move |playback: &Playback, _| !playback.upcoming(added).is_empty(),
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Fill)
+            .with_child(header_widget(title))
+            .with_child(List::new(entry_widget).lens(entries)),
+        Empty,
+    )
+}
+
+fn now_playing_widget() -> impl Widget<NowPlaying> {
+    track_info_widget()
+        .padding((theme::grid(1.0), theme::grid(0.5)))
+        .lens(NowPlaying::item)
+}
+
+fn entry_widget() -> impl Widget<QueueEntry> {
+    let remove = icons::CLOSE
+        .scale(theme::ICON_SIZE)
+        .with_color(theme::PLACEHOLDER_COLOR)
+        .padding(theme::grid(0.5))
+        .link()
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .on_click(|ctx, entry: &mut QueueEntry, _| {
+            ctx.submit_command(cmd::REMOVE_FROM_QUEUE.with(entry.index));
+        });
+
+    Flex::row()
+        .with_flex_child(
+            track_info_widget().lens(QueueEntry::queued.then(QueuedTrack::track)),
+            1.0,
+        )
+        .with_default_spacer()
+        .with_child(remove)
+        .padding((theme::grid(1.0), theme::grid(0.5)))
+        .link()
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .on_ex_click(|ctx, event, entry: &mut QueueEntry, _| {
+            if event.button == MouseButton::Right {
+                ctx.show_context_menu(entry_menu(entry), event.window_pos);
+            }
+        })
+        .controller(DragController::new())
+}
+
+fn entry_menu(entry: &QueueEntry) -> Menu<State> {
+    Menu::empty().entry(
+        MenuItem::new(
+            LocalizedString::new("menu-item-remove-from-queue")
+                .with_placeholder("Remove from Queue"),
+        )
+        .command(cmd::REMOVE_FROM_QUEUE.with(entry.index)),
+    )
+}
+
+fn track_info_widget() -> impl Widget<Arc<Track>> {
+    let name = Label::raw()
+        .with_line_break_mode(LineBreaking::Clip)
+        .with_font(theme::UI_FONT_MEDIUM)
+        .lens(Track::name.in_arc());
+
+    let artist = Label::dynamic(|track: &Arc<Track>, _| track.artist_name())
+        .with_line_break_mode(LineBreaking::Clip)
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .with_text_color(theme::PLACEHOLDER_COLOR);
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(name)
+        .with_spacer(2.0)
+        .with_child(artist)
+}
+
+/// Moves the upcoming tracks to where they get dragged to, within their
+/// section.
+struct DragController {
+    drag_start: Option<Point>,
+    is_dragging: bool,
+}
+
+impl DragController {
+    // Distance the mouse has to travel before a press becomes a drag.
+    const DRAG_THRESHOLD: f64 = 4.0;
+
+    fn new() -> Self {
+        Self {
+            drag_start: None,
+            is_dragging: false,
+        }
+    }
+}
+
+impl<W> Controller<QueueEntry, W> for DragController
+where
+    W: Widget<QueueEntry>,
+{
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut QueueEntry,
+        env: &Env,
+    ) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                self.drag_start = Some(mouse.pos);
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => {
+                if let Some(start) = self.drag_start {
+                    if !self.is_dragging && mouse.pos.distance(start) > Self::DRAG_THRESHOLD {
+                        self.is_dragging = true;
+                        ctx.set_cursor(&Cursor::ResizeUpDown);
+                    }
+                }
+            }
+            Event::MouseUp(mouse) if self.is_dragging => {
+                self.drag_start = None;
+                self.is_dragging = false;
+                ctx.set_active(false);
+                ctx.clear_cursor();
+                ctx.request_paint();
+                // Rows of the panel are of the same height, so we can tell the
+                // target row from how far the mouse went.
+                let offset = (mouse.pos.y / ctx.size().height).floor() as isize;
+                if offset != 0 {
+                    ctx.submit_command(cmd::MOVE_IN_QUEUE.with((data.index, offset)));
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseUp(_) => {
+                self.drag_start = None;
+            }
+            _ => {}
+        }
+        child.event(ctx, event, data, env);
+    }
+}
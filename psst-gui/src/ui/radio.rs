@@ -0,0 +1,65 @@
+use crate::{
+    cmd,
+    data::{
+        CommonCtx, Ctx, PlaybackOrigin, PlaybackPayload, RadioDetail, RadioTracks, State, TrackView,
+    },
+    ui::{
+        theme,
+        track::{tracklist_widget, TrackDisplay},
+        utils::{error_widget, spinner_widget},
+    },
+    widget::Async,
+};
+use druid::{
+    widget::{Button, CrossAxisAlignment, Flex},
+    LensExt, Widget, WidgetExt,
+};
+
+pub fn detail_widget() -> impl Widget<State> {
+    Async::new(
+        || spinner_widget(),
+        || loaded_detail_widget(),
+        || error_widget().lens(Ctx::data()),
+    )
+    .lens(
+        Ctx::make(State::common_ctx, State::radio.then(RadioDetail::tracks))
+            .then(Ctx::in_promise()),
+    )
+}
+
+fn loaded_detail_widget() -> impl Widget<Ctx<CommonCtx, RadioTracks>> {
+    let play_button = Button::new("Play").on_click(|ctx, radio: &mut RadioTracks, _| {
+        if !radio.tracks.is_empty() {
+            let payload = PlaybackPayload {
+                origin: PlaybackOrigin::Radio(radio.seed.clone()),
+                tracks: radio.tracks.clone(),
+                position: 0,
+            };
+            ctx.submit_command(cmd::PLAY_TRACKS.with(payload));
+        }
+    });
+
+    let save_button = Button::new("Save as Playlist")
+        .on_click(|ctx, _, _| ctx.submit_command(cmd::SAVE_RADIO_AS_PLAYLIST));
+
+    let buttons = Flex::row()
+        .with_child(play_button)
+        .with_default_spacer()
+        .with_child(save_button)
+        .padding((theme::grid(1.0), 0.0, theme::grid(1.0), theme::grid(1.0)))
+        .lens(Ctx::data());
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(buttons)
+        .with_child(tracklist_widget(
+            TrackView::Radio,
+            TrackDisplay {
+                title: true,
+                artist: true,
+                album: true,
+                duration: true,
+                ..TrackDisplay::empty()
+            },
+        ))
+}
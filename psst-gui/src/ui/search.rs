@@ -1,77 +1,330 @@
 use crate::{
     cmd,
     controller::InputController,
-    data::{CommonCtx, Ctx, Nav, Search, SearchResults, State},
+    data::{
+        CommonCtx, Ctx, Nav, Search, SearchResults, SearchTopic, State, SuggestionRow, TrackView,
+    },
     ui::{
-        album::album_widget,
+        album::album_list_widget,
         artist::artist_widget,
         theme,
         track::{tracklist_widget, TrackDisplay},
         utils::{error_widget, spinner_widget},
     },
-    widget::Async,
+    widget::{Async, Empty, LinkExt},
 };
 use druid::{
-    widget::{CrossAxisAlignment, Flex, Label, List, TextBox},
-    LensExt, Widget, WidgetExt,
+    widget::{CrossAxisAlignment, Either, Flex, Label, LineBreaking, List, TextBox},
+    KbKey, LensExt, Widget, WidgetExt,
 };
+use std::time::Duration;
+
+use super::{playlist::playlist_widget, show::show_widget};
 
-use super::playlist::playlist_widget;
+// How long to wait after the last key press before searching.
+const SEARCH_DELAY: Duration = Duration::from_millis(300);
 
 pub fn input_widget() -> impl Widget<State> {
-    TextBox::new()
+    let input = TextBox::new()
         .with_placeholder("Search")
-        .controller(InputController::new().on_submit(|ctx, query, _env| {
-            let nav = Nav::SearchResults(query.clone());
-            ctx.submit_command(cmd::NAVIGATE.with(nav));
-        }))
+        .controller(
+            InputController::new()
+                .on_submit(|ctx, query, _env| {
+                    ctx.submit_command(cmd::SUBMIT_SEARCH.with(query.clone()));
+                })
+                .on_change(SEARCH_DELAY, |ctx, query, _env| {
+                    ctx.submit_command(cmd::LOAD_SEARCH_SUGGESTIONS.with(query.clone()));
+                    ctx.submit_command(cmd::LIVE_SEARCH.with(query.clone()));
+                })
+                .on_focus_changed(|ctx, focused| {
+                    ctx.submit_command(cmd::SHOW_SEARCH_SUGGESTIONS.with(focused));
+                })
+                .on_key(|ctx, key| match &key.key {
+                    KbKey::ArrowDown => {
+                        ctx.submit_command(cmd::MOVE_SEARCH_SELECTION.with(1));
+                        true
+                    }
+                    KbKey::ArrowUp => {
+                        ctx.submit_command(cmd::MOVE_SEARCH_SELECTION.with(-1));
+                        true
+                    }
+                    KbKey::Escape => {
+                        ctx.submit_command(cmd::SHOW_SEARCH_SUGGESTIONS.with(false));
+                        true
+                    }
+                    _ => false,
+                }),
+        )
         .with_id(cmd::WIDGET_SEARCH_INPUT)
         .expand_width()
-        .lens(State::search.then(Search::input))
+        .lens(State::search.then(Search::input));
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Fill)
+        .with_child(input)
+        .with_child(suggestions_widget().lens(State::search))
+}
+
+fn suggestions_widget() -> impl Widget<Search> {
+    let row = || {
+        Flex::row()
+            .with_flex_child(
+                Label::dynamic(|row: &SuggestionRow, _| row.suggestion.title())
+                    .with_line_break_mode(LineBreaking::Clip)
+                    .with_text_size(theme::TEXT_SIZE_SMALL),
+                1.0,
+            )
+            .with_default_spacer()
+            .with_child(
+                Label::dynamic(|row: &SuggestionRow, _| row.suggestion.kind().to_string())
+                    .with_text_size(theme::TEXT_SIZE_SMALL)
+                    .with_text_color(theme::PLACEHOLDER_COLOR),
+            )
+            .padding((theme::grid(1.0), theme::grid(0.5)))
+            .link()
+            .rounded(theme::BUTTON_BORDER_RADIUS)
+            .env_scope(|env, row: &SuggestionRow| {
+                if row.selected {
+                    env.set(theme::LINK_COLD_COLOR, env.get(theme::LINK_HOT_COLOR));
+                }
+            })
+            .on_click(|ctx, row: &mut SuggestionRow, _| {
+                ctx.submit_command(cmd::OPEN_SEARCH_SUGGESTION.with(row.suggestion.clone()));
+            })
+    };
+    let clear_history = Either::new(
+        |search: &Search, _| search.input.trim().is_empty() && !search.history.is_empty(),
+        Label::new("Clear history")
+            .with_text_size(theme::TEXT_SIZE_SMALL)
+            .with_text_color(theme::PLACEHOLDER_COLOR)
+            .padding((theme::grid(1.0), theme::grid(0.5)))
+            .link()
+            .on_click(|ctx, _, _| ctx.submit_command(cmd::CLEAR_SEARCH_HISTORY)),
+        Empty,
+    );
+    Either::new(
+        |search: &Search, _| search.suggesting && !search.suggestions.is_empty(),
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Fill)
+            .with_spacer(theme::grid(0.5))
+            .with_child(List::new(row).lens(Search::suggestions))
+            .with_child(clear_history),
+        Empty,
+    )
 }
 
 pub fn results_widget() -> impl Widget<State> {
-    Async::new(
+    let results = Async::new(
         || spinner_widget(),
         || {
-            let label = |text| {
-                Label::new(text)
-                    .with_font(theme::UI_FONT_MEDIUM)
-                    .with_text_color(theme::PLACEHOLDER_COLOR)
-                    .with_text_size(theme::TEXT_SIZE_SMALL)
-                    .padding((0.0, theme::grid(2.0), 0.0, theme::grid(1.0)))
-            };
             Flex::column()
                 .cross_axis_alignment(CrossAxisAlignment::Fill)
-                .with_child(label("Artists"))
+                .with_child(heading_widget(SearchTopic::Artist))
                 .with_child(artist_results_widget())
-                .with_child(label("Albums"))
+                .with_child(heading_widget(SearchTopic::Album))
                 .with_child(album_results_widget())
-                .with_child(label("Tracks"))
+                .with_child(heading_widget(SearchTopic::Track))
                 .with_child(track_results_widget())
-                .with_child(label("Playlists"))
+                .with_child(heading_widget(SearchTopic::Playlist))
                 .with_child(playlist_results_widget())
+                .with_child(heading_widget(SearchTopic::Show))
+                .with_child(show_results_widget())
         },
         || error_widget().lens(Ctx::data()),
     )
-    .lens(Ctx::make(State::common_ctx, State::search.then(Search::results)).then(Ctx::in_promise()))
+    .lens(
+        Ctx::make(State::common_ctx, State::search.then(Search::results)).then(Ctx::in_promise()),
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Fill)
+        .with_child(filters_widget())
+        .with_child(results)
+}
+
+/// All the results of the type picked in `results_widget`.
+pub fn topic_results_widget() -> impl Widget<State> {
+    let results = Async::new(
+        || spinner_widget(),
+        || {
+            // Only one of the lists has any items.
+            Flex::column()
+                .cross_axis_alignment(CrossAxisAlignment::Fill)
+                .with_child(artist_results_widget())
+                .with_child(album_results_widget())
+                .with_child(track_results_widget())
+                .with_child(playlist_results_widget())
+                .with_child(show_results_widget())
+        },
+        || error_widget().lens(Ctx::data()),
+    )
+    .lens(
+        Ctx::make(State::common_ctx, State::search.then(Search::topic_results))
+            .then(Ctx::in_promise()),
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Fill)
+        .with_child(filters_widget())
+        .with_child(results)
+}
+
+/// Query of the search page that is open.
+fn route_query(route: &Nav) -> Option<&str> {
+    match route {
+        Nav::SearchResults(query) | Nav::SearchTopicResults(query, _) => Some(query),
+        _ => None,
+    }
+}
+
+fn filters_widget() -> impl Widget<State> {
+    // Picks the type of the results, `None` showing a few of each.
+    let chip = |topic: Option<SearchTopic>| {
+        Label::new(topic.map_or("All", |topic| topic.title()))
+            .with_text_size(theme::TEXT_SIZE_SMALL)
+            .padding((theme::grid(1.5), theme::grid(0.5)))
+            .link()
+            .rounded(theme::BUTTON_BORDER_RADIUS)
+            .env_scope(move |env, state: &State| {
+                let active = match &state.route {
+                    Nav::SearchResults(_) => topic.is_none(),
+                    Nav::SearchTopicResults(_, t) => topic == Some(*t),
+                    _ => false,
+                };
+                if active {
+                    env.set(theme::LINK_COLD_COLOR, env.get(theme::BACKGROUND_DARK));
+                    env.set(theme::TEXT_COLOR, env.get(theme::FOREGROUND_LIGHT));
+                }
+            })
+            .on_click(move |ctx, state: &mut State, _| {
+                if let Some(query) = route_query(&state.route) {
+                    let nav = match topic {
+                        Some(topic) => Nav::SearchTopicResults(query.to_owned(), topic),
+                        None => Nav::SearchResults(query.to_owned()),
+                    };
+                    ctx.submit_command(cmd::NAVIGATE.with(nav));
+                }
+            })
+    };
+    let mut chips = Flex::row().with_child(chip(None));
+    for &topic in &[
+        SearchTopic::Track,
+        SearchTopic::Album,
+        SearchTopic::Artist,
+        SearchTopic::Playlist,
+        SearchTopic::Show,
+    ] {
+        chips.add_spacer(theme::grid(0.5));
+        chips.add_child(chip(Some(topic)));
+    }
+
+    // Spotify understands a couple of field filters in the query, these help
+    // with writing them.
+    let field = |text, filter: &'static str| {
+        Label::new(text)
+            .with_text_size(theme::TEXT_SIZE_SMALL)
+            .with_text_color(theme::PLACEHOLDER_COLOR)
+            .padding((theme::grid(1.0), theme::grid(0.5)))
+            .link()
+            .rounded(theme::BUTTON_BORDER_RADIUS)
+            .on_click(move |ctx, state: &mut State, _| {
+                let input = state.search.input.trim_end();
+                state.search.input = if input.is_empty() {
+                    filter.to_string()
+                } else {
+                    format!("{} {}", input, filter)
+                };
+                ctx.submit_command(cmd::SET_FOCUS.to(cmd::WIDGET_SEARCH_INPUT));
+            })
+    };
+    let new_albums = Label::new("New albums")
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .with_text_color(theme::PLACEHOLDER_COLOR)
+        .padding((theme::grid(1.0), theme::grid(0.5)))
+        .link()
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .on_click(|ctx, state: &mut State, _| {
+            if let Some(query) = route_query(&state.route) {
+                // Only albums can be tagged as new.
+                let query = if query.contains("tag:new") {
+                    query.to_owned()
+                } else {
+                    format!("{} tag:new", query.trim_end())
+                };
+                state.search.input = query.clone();
+                let nav = Nav::SearchTopicResults(query, SearchTopic::Album);
+                ctx.submit_command(cmd::NAVIGATE.with(nav));
+            }
+        });
+    let fields = Flex::row()
+        .with_child(
+            Label::new("Refine:")
+                .with_text_size(theme::TEXT_SIZE_SMALL)
+                .with_text_color(theme::PLACEHOLDER_COLOR),
+        )
+        .with_child(field("Artist", "artist:"))
+        .with_child(field("Album", "album:"))
+        .with_child(field("Year", "year:"))
+        .with_child(field("Genre", "genre:"))
+        .with_child(new_albums);
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(chips)
+        .with_spacer(theme::grid(1.0))
+        .with_child(fields)
+        .padding((0.0, 0.0, 0.0, theme::grid(1.0)))
+}
+
+fn heading_widget(topic: SearchTopic) -> impl Widget<Ctx<CommonCtx, SearchResults>> {
+    let show_all = Label::new(format!("Show all {}", topic.title().to_lowercase()))
+        .with_text_color(theme::PLACEHOLDER_COLOR)
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .link()
+        .on_click(move |ctx, results: &mut SearchResults, _| {
+            let nav = Nav::SearchTopicResults(results.query.clone(), topic);
+            ctx.submit_command(cmd::NAVIGATE.with(nav));
+        });
+    Flex::row()
+        .with_child(
+            Label::new(topic.title())
+                .with_font(theme::UI_FONT_MEDIUM)
+                .with_text_color(theme::PLACEHOLDER_COLOR)
+                .with_text_size(theme::TEXT_SIZE_SMALL),
+        )
+        .with_flex_spacer(1.0)
+        .with_child(Either::new(
+            move |results: &SearchResults, _| results.more.contains(&topic),
+            show_all,
+            Empty,
+        ))
+        .padding((0.0, theme::grid(2.0), 0.0, theme::grid(1.0)))
+        .lens(Ctx::data())
+}
+
+fn show_results_widget() -> impl Widget<Ctx<CommonCtx, SearchResults>> {
+    List::new(show_widget).lens(Ctx::map(SearchResults::shows))
 }
 
 fn artist_results_widget() -> impl Widget<Ctx<CommonCtx, SearchResults>> {
-    List::new(artist_widget).lens(Ctx::data().then(SearchResults::artists))
+    List::new(artist_widget).lens(Ctx::map(SearchResults::artists))
 }
 
 fn album_results_widget() -> impl Widget<Ctx<CommonCtx, SearchResults>> {
-    List::new(album_widget).lens(Ctx::map(SearchResults::albums))
+    album_list_widget().lens(Ctx::map(SearchResults::albums))
 }
 
 fn track_results_widget() -> impl Widget<Ctx<CommonCtx, SearchResults>> {
-    tracklist_widget(TrackDisplay {
-        title: true,
-        artist: true,
-        album: true,
-        ..TrackDisplay::empty()
-    })
+    tracklist_widget(
+        TrackView::SearchResults,
+        TrackDisplay {
+            title: true,
+            artist: true,
+            album: true,
+            duration: true,
+            ..TrackDisplay::empty()
+        },
+    )
 }
 
 fn playlist_results_widget() -> impl Widget<Ctx<CommonCtx, SearchResults>> {
@@ -0,0 +1,350 @@
+use crate::{
+    cmd,
+    data::{
+        CommonCtx, Ctx, Episode, Library, Nav, PlaybackOrigin, PlaybackPayload, Promise, Show,
+        ShowDetail, ShowEpisodes, State,
+    },
+    ui::{
+        theme,
+        utils::{self, error_widget, placeholder_widget, spinner_widget},
+    },
+    widget::{Async, Clip, LinkExt, RemoteImage},
+};
+use druid::{
+    widget::{
+        Button, Controller, ControllerHost, CrossAxisAlignment, Either, Flex, Label, LineBreaking,
+        List, ListIter, Painter,
+    },
+    Data, Env, Event, EventCtx, Lens, LensExt, LocalizedString, Menu, MenuItem, MouseButton,
+    RenderContext, Size, Widget, WidgetExt,
+};
+use std::{sync::Arc, time::Duration};
+
+pub fn detail_widget() -> impl Widget<State> {
+    let show_info = Async::new(
+        || spinner_widget(),
+        || loaded_info_widget(),
+        || error_widget(),
+    )
+    .lens(State::show.then(ShowDetail::show));
+
+    let show_episodes = Async::new(
+        || spinner_widget(),
+        || episode_list_widget(),
+        || error_widget().lens(Ctx::data()),
+    )
+    .lens(
+        Ctx::make(State::common_ctx, State::show.then(ShowDetail::episodes))
+            .then(Ctx::in_promise()),
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(show_info)
+        .with_spacer(theme::grid(1.0))
+        .with_child(save_button_widget())
+        .with_spacer(theme::grid(1.0))
+        .with_child(show_episodes)
+}
+
+pub fn saved_shows_widget() -> impl Widget<State> {
+    Async::new(
+        || spinner_widget(),
+        || List::new(show_widget),
+        || error_widget().lens(Ctx::data()),
+    )
+    .lens(
+        Ctx::make(
+            State::common_ctx,
+            State::library.then(Library::saved_shows.in_arc()),
+        )
+        .then(Ctx::in_promise()),
+    )
+}
+
+fn save_button_widget() -> impl Widget<State> {
+    Either::new(
+        |state: &State, _| match &state.route {
+            Nav::ShowDetail(link) => state.common_ctx.is_show_saved(&link.id),
+            _ => false,
+        },
+        Button::new("Saved").on_click(|ctx, state: &mut State, _| {
+            if let Nav::ShowDetail(link) = &state.route {
+                ctx.submit_command(cmd::UNSAVE_SHOW.with(link.to_owned()));
+            }
+        }),
+        Button::new("Save").on_click(|ctx, state: &mut State, _| {
+            if let Promise::Resolved(show) = &state.show.show {
+                ctx.submit_command(cmd::SAVE_SHOW.with(show.to_owned()));
+            }
+        }),
+    )
+    .padding((theme::grid(4.0), 0.0))
+}
+
+fn loaded_info_widget() -> impl Widget<Show> {
+    let show_cover = rounded_cover_widget(theme::grid(10.0));
+
+    let show_publisher = Label::raw()
+        .with_line_break_mode(LineBreaking::WordWrap)
+        .with_font(theme::UI_FONT_MEDIUM)
+        .lens(Show::publisher);
+
+    let show_description = Label::raw()
+        .with_line_break_mode(LineBreaking::WordWrap)
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .with_text_color(theme::PLACEHOLDER_COLOR)
+        .lens(Show::description);
+
+    let show_info = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(show_publisher)
+        .with_default_spacer()
+        .with_child(show_description)
+        .padding(theme::grid(1.0));
+
+    Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_spacer(theme::grid(4.0))
+        .with_child(show_cover)
+        .with_default_spacer()
+        .with_flex_child(show_info, 1.0)
+}
+
+fn cover_widget(size: f64) -> impl Widget<Show> {
+    RemoteImage::new(placeholder_widget(), move |show: &Show, env| {
+        let size = size * env.get(theme::IMAGE_SCALE);
+        show.image(size, size).map(|image| image.url.clone())
+    })
+    .fix_size(size, size)
+}
+
+fn rounded_cover_widget(size: f64) -> impl Widget<Show> {
+    // TODO: Take the radius from theme.
+    Clip::new(
+        Size::new(size, size).to_rounded_rect(4.0),
+        cover_widget(size),
+    )
+}
+
+pub fn show_widget() -> impl Widget<Ctx<CommonCtx, Show>> {
+    let show_cover = cover_widget(theme::grid(7.0));
+
+    let show_name = Label::raw()
+        .with_font(theme::UI_FONT_MEDIUM)
+        .with_line_break_mode(LineBreaking::Clip)
+        .lens(Show::name);
+
+    let show_publisher = Label::raw()
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .with_text_color(theme::PLACEHOLDER_COLOR)
+        .with_line_break_mode(LineBreaking::Clip)
+        .lens(Show::publisher);
+
+    let show_label = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(show_name)
+        .with_spacer(1.0)
+        .with_child(show_publisher);
+
+    Flex::row()
+        .with_child(show_cover)
+        .with_default_spacer()
+        .with_flex_child(show_label, 1.0)
+        .lens(Ctx::data())
+        .link()
+        .on_ex_click(
+            move |ctx, event, show: &mut Ctx<CommonCtx, Show>, _| match event.button {
+                MouseButton::Left => {
+                    let nav = Nav::ShowDetail(show.data.link());
+                    ctx.submit_command(cmd::NAVIGATE.with(nav));
+                }
+                MouseButton::Right => {
+                    ctx.show_context_menu(show_menu(show), event.window_pos);
+                }
+                _ => {}
+            },
+        )
+}
+
+fn show_menu(show: &Ctx<CommonCtx, Show>) -> Menu<State> {
+    let mut menu = Menu::empty();
+
+    menu = menu.entry(
+        MenuItem::new(LocalizedString::new("menu-item-copy-link").with_placeholder("Copy Link"))
+            .command(cmd::COPY.with(show.data.url())),
+    );
+
+    menu = menu.separator();
+
+    if show.ctx.is_show_saved(&show.data.id) {
+        menu = menu.entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-remove-from-library")
+                    .with_placeholder("Remove from Library"),
+            )
+            .command(cmd::UNSAVE_SHOW.with(show.data.link())),
+        );
+    } else {
+        menu = menu.entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-save-to-library")
+                    .with_placeholder("Save to Library"),
+            )
+            .command(cmd::SAVE_SHOW.with(show.data.clone())),
+        );
+    }
+
+    menu
+}
+
+fn episode_list_widget() -> impl Widget<Ctx<CommonCtx, ShowEpisodes>> {
+    ControllerHost::new(List::new(episode_widget), PlayController)
+}
+
+impl ListIter<EpisodeRow> for Ctx<CommonCtx, ShowEpisodes> {
+    fn for_each(&self, mut cb: impl FnMut(&EpisodeRow, usize)) {
+        ListIter::for_each(&self.data.episodes, |episode, index| {
+            let d = EpisodeRow {
+                ctx: self.ctx.to_owned(),
+                episode: episode.to_owned(),
+                position: index,
+            };
+            cb(&d, index);
+        });
+    }
+
+    fn for_each_mut(&mut self, mut cb: impl FnMut(&mut EpisodeRow, usize)) {
+        ListIter::for_each(&self.data.episodes, |episode, index| {
+            let mut d = EpisodeRow {
+                ctx: self.ctx.to_owned(),
+                episode: episode.to_owned(),
+                position: index,
+            };
+            cb(&mut d, index);
+
+            // Mutation intentionally ignored.
+        });
+    }
+
+    fn data_len(&self) -> usize {
+        self.data.episodes.len()
+    }
+}
+
+#[derive(Clone, Data, Lens)]
+struct EpisodeRow {
+    ctx: CommonCtx,
+    episode: Arc<Episode>,
+    position: usize,
+}
+
+struct PlayController;
+
+impl<W> Controller<Ctx<CommonCtx, ShowEpisodes>, W> for PlayController
+where
+    W: Widget<Ctx<CommonCtx, ShowEpisodes>>,
+{
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut Ctx<CommonCtx, ShowEpisodes>,
+        env: &Env,
+    ) {
+        match event {
+            Event::Notification(note) => {
+                if let Some(position) = note.get(cmd::PLAY_TRACK_AT) {
+                    let resume_position =
+                        data.data.episodes.get(*position).and_then(|episode| {
+                            episode.resume_position(&data.ctx.episode_positions)
+                        });
+                    let payload = PlaybackPayload {
+                        origin: PlaybackOrigin::Show(data.data.show.to_owned()),
+                        tracks: data
+                            .data
+                            .episodes
+                            .iter()
+                            .map(|episode| episode.to_track())
+                            .collect(),
+                        position: position.to_owned(),
+                    };
+                    match resume_position {
+                        Some(progress) => {
+                            ctx.submit_command(
+                                cmd::PLAY_TRACKS_FROM.with((payload, progress, false)),
+                            );
+                        }
+                        None => {
+                            ctx.submit_command(cmd::PLAY_TRACKS.with(payload));
+                        }
+                    }
+                    ctx.set_handled();
+                }
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
+fn episode_widget() -> impl Widget<EpisodeRow> {
+    let episode_name = Label::raw()
+        .with_font(theme::UI_FONT_MEDIUM)
+        .with_line_break_mode(LineBreaking::WordWrap)
+        .lens(EpisodeRow::episode.then(Episode::name.in_arc()));
+
+    let episode_release = Label::dynamic(|er: &EpisodeRow, _| er.episode.release())
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .with_text_color(theme::PLACEHOLDER_COLOR);
+
+    let episode_duration =
+        Label::dynamic(|er: &EpisodeRow, _| utils::as_minutes_and_seconds(&er.episode.duration))
+            .with_text_size(theme::TEXT_SIZE_SMALL)
+            .with_text_color(theme::PLACEHOLDER_COLOR);
+
+    let episode_progress = Painter::new(|ctx, er: &EpisodeRow, env| {
+        let progress = er.episode.progress(&er.ctx.episode_positions);
+        if progress == Duration::default() {
+            return;
+        }
+        let bounds = ctx.size().to_rect();
+        let elapsed = if er.episode.is_played(&er.ctx.episode_positions) {
+            1.0
+        } else {
+            progress.as_secs_f64() / er.episode.duration.as_secs_f64().max(1.0)
+        };
+        ctx.fill(bounds, &env.get(theme::GREY_500));
+        ctx.fill(
+            bounds.with_size((bounds.width() * elapsed, bounds.height())),
+            &env.get(theme::GREY_200),
+        );
+    })
+    .fix_size(theme::grid(6.0), 3.0);
+
+    let episode_description = Label::raw()
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .with_line_break_mode(LineBreaking::Clip)
+        .lens(EpisodeRow::episode.then(Episode::description.in_arc()));
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(episode_name)
+        .with_spacer(2.0)
+        .with_child(
+            Flex::row()
+                .with_child(episode_release)
+                .with_default_spacer()
+                .with_child(episode_duration)
+                .with_default_spacer()
+                .with_child(episode_progress),
+        )
+        .with_spacer(2.0)
+        .with_child(episode_description)
+        .padding(theme::grid(1.0))
+        .link()
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .on_click(|ctx, er: &mut EpisodeRow, _| {
+            ctx.submit_notification(cmd::PLAY_TRACK_AT.with(er.position));
+        })
+}
@@ -29,6 +29,7 @@ pub const MENU_BUTTON_FG_INACTIVE: Key<Color> = Key::new("app.menu-fg-inactive")
 pub const UI_FONT_MEDIUM: Key<FontDescriptor> = Key::new("app.ui-font-medium");
 pub const UI_FONT_MONO: Key<FontDescriptor> = Key::new("app.ui-font-mono");
 pub const TEXT_SIZE_SMALL: Key<f64> = Key::new("app.text-size-small");
+pub const IMAGE_SCALE: Key<f64> = Key::new("app.image-scale");
 
 pub const ICON_COLOR: Key<Color> = Key::new("app.icon-color");
 pub const ICON_SIZE: Size = Size::new(12.0, 12.0);
@@ -74,6 +75,8 @@ pub fn setup(env: &mut Env, state: &State) {
 
     env.set(CURSOR_COLOR, env.get(GREY_000));
 
+    env.set(IMAGE_SCALE, state.config.image_scale);
+
     env.set(PROGRESS_BAR_RADIUS, 4.0);
     env.set(BUTTON_BORDER_RADIUS, 4.0);
     env.set(BUTTON_BORDER_WIDTH, 1.0);
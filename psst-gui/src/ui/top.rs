@@ -0,0 +1,93 @@
+use crate::{
+    cmd,
+    data::{Ctx, State, TopItems, TopRange, TrackView},
+    ui::{
+        artist::artist_widget,
+        theme,
+        track::{tracklist_widget, TrackDisplay},
+        utils::{error_widget, spinner_widget},
+    },
+    widget::{Async, LinkExt},
+};
+use druid::{
+    widget::{CrossAxisAlignment, Flex, Label, LabelText, List},
+    Data, Insets, LensExt, Widget, WidgetExt,
+};
+
+pub fn top_items_widget() -> impl Widget<State> {
+    let top_tracks = Async::new(
+        || spinner_widget(),
+        || {
+            tracklist_widget(
+                TrackView::TopTracks,
+                TrackDisplay {
+                    number: true,
+                    title: true,
+                    artist: true,
+                    album: true,
+                    duration: true,
+                    ..TrackDisplay::empty()
+                },
+            )
+        },
+        || error_widget().lens(Ctx::data()),
+    )
+    .lens(Ctx::make(State::common_ctx, State::top.then(TopItems::tracks)).then(Ctx::in_promise()));
+
+    let top_artists = Async::new(
+        || spinner_widget(),
+        || List::new(artist_widget),
+        || error_widget().lens(Ctx::data()),
+    )
+    .lens(Ctx::make(State::common_ctx, State::top.then(TopItems::artists)).then(Ctx::in_promise()));
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(range_widget().lens(State::top))
+        .with_child(label_widget("Top Tracks"))
+        .with_child(top_tracks)
+        .with_child(label_widget("Top Artists"))
+        .with_child(top_artists)
+}
+
+fn range_widget() -> impl Widget<TopItems> {
+    let range_link = |range: TopRange| {
+        Label::dynamic(move |top: &TopItems, _| {
+            if top.range == range {
+                format!("{} ▾", range.to_title())
+            } else {
+                range.to_title().to_string()
+            }
+        })
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .padding(theme::grid(0.5))
+        .link()
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .on_click(move |ctx, _, _| ctx.submit_command(cmd::LOAD_TOP_ITEMS.with(range)))
+    };
+
+    Flex::row()
+        .with_child(
+            Label::new("Listened in")
+                .with_text_size(theme::TEXT_SIZE_SMALL)
+                .with_text_color(theme::PLACEHOLDER_COLOR),
+        )
+        .with_default_spacer()
+        .with_child(range_link(TopRange::ShortTerm))
+        .with_child(range_link(TopRange::MediumTerm))
+        .with_child(range_link(TopRange::LongTerm))
+        .padding((theme::grid(1.0), 0.0))
+}
+
+fn label_widget<T: Data>(text: impl Into<LabelText<T>>) -> impl Widget<T> {
+    Label::new(text)
+        .with_font(theme::UI_FONT_MEDIUM)
+        .with_text_color(theme::PLACEHOLDER_COLOR)
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .padding(Insets::new(
+            theme::grid(1.0),
+            theme::grid(2.0),
+            theme::grid(1.0),
+            theme::grid(1.0),
+        ))
+}
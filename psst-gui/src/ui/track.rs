@@ -1,34 +1,48 @@
 use crate::{
     cmd,
     data::{
-        Album, ArtistTracks, CommonCtx, Ctx, Nav, PlaybackOrigin, PlaybackPayload, PlaylistTracks,
-        SavedTracks, SearchResults, State, Track,
+        Album, ArtistTracks, CommonCtx, Ctx, CursorMove, Nav, PlaybackOrigin, PlaybackPayload,
+        PlaylistLink, PlaylistTracks, QueuedTrack, RadioSeed, RadioTracks, SavedTracks,
+        SearchResults, ShownKey, State, TopTracks, Track, TrackColumn, TrackId, TrackSort,
+        TrackView,
     },
     ui::theme,
-    widget::LinkExt,
+    widget::{icons, Empty, Hover, LinkExt},
 };
+use chrono::{DateTime, Local, Utc};
 use druid::{
     im::Vector,
     kurbo::Line,
     lens::Map,
     piet::StrokeStyle,
     widget::{
-        Controller, ControllerHost, CrossAxisAlignment, Flex, Label, List, ListIter, Painter,
+        Controller, ControllerHost, CrossAxisAlignment, Either, Flex, Label, List, ListIter,
+        Painter, TextBox, ViewSwitcher,
     },
-    Data, Env, Event, EventCtx, Lens, LensExt, LocalizedString, Menu, MenuItem, MouseButton,
-    RenderContext, TextAlignment, Widget, WidgetExt,
+    Cursor, Data, Env, Event, EventCtx, Insets, KbKey, Lens, LensExt, LifeCycle, LifeCycleCtx,
+    LocalizedString, Menu, MenuItem, MouseButton, Point, RenderContext, TextAlignment, UnitPoint,
+    UpdateCtx, Vec2, Widget, WidgetExt,
 };
 use std::sync::Arc;
 
 use super::utils;
 
-#[derive(Copy, Clone)]
+// Width of the track numbers, covered by the play button on hover.
+const PLAY_SLOT_WIDTH: f64 = theme::GRID * 2.0;
+
+#[derive(Copy, Clone, PartialEq)]
 pub struct TrackDisplay {
     pub number: bool,
     pub title: bool,
     pub artist: bool,
     pub album: bool,
     pub popularity: bool,
+    pub duration: bool,
+    pub date_added: bool,
+    pub bpm: bool,
+    pub key: bool,
+    /// Group the tracks of multi-disc albums under a header per disc.
+    pub discs: bool,
 }
 
 impl TrackDisplay {
@@ -39,20 +53,183 @@ impl TrackDisplay {
             artist: false,
             album: false,
             popularity: false,
+            duration: false,
+            date_added: false,
+            bpm: false,
+            key: false,
+            discs: false,
+        }
+    }
+
+    fn has_column(&self, column: TrackColumn) -> bool {
+        match column {
+            TrackColumn::Number => self.number,
+            TrackColumn::Title => self.title,
+            TrackColumn::Artist => self.artist,
+            TrackColumn::Album => self.album,
+            TrackColumn::Duration => self.duration,
+            TrackColumn::Popularity => self.popularity,
+            TrackColumn::DateAdded => self.date_added,
+            TrackColumn::Bpm => self.bpm,
+            TrackColumn::Key => self.key,
+        }
+    }
+
+    fn columns(&self) -> Vector<TrackColumn> {
+        TrackColumn::CONFIGURABLE
+            .iter()
+            .copied()
+            .filter(|&column| self.has_column(column))
+            .collect()
+    }
+
+    /// Shows exactly the configurable `columns`, keeping the rest as it is.
+    fn with_columns(self, columns: &Vector<TrackColumn>) -> Self {
+        TrackDisplay {
+            number: columns.contains(&TrackColumn::Number),
+            artist: columns.contains(&TrackColumn::Artist),
+            album: columns.contains(&TrackColumn::Album),
+            popularity: columns.contains(&TrackColumn::Popularity),
+            duration: columns.contains(&TrackColumn::Duration),
+            date_added: columns.contains(&TrackColumn::DateAdded),
+            bpm: columns.contains(&TrackColumn::Bpm),
+            key: columns.contains(&TrackColumn::Key),
+            ..self
         }
     }
 }
 
-pub fn tracklist_widget<T>(mode: TrackDisplay) -> impl Widget<Ctx<CommonCtx, T>>
+/// Track list of `view`, showing the columns of `mode` unless the user picked
+/// different ones.
+pub fn tracklist_widget<T>(view: TrackView, mode: TrackDisplay) -> impl Widget<Ctx<CommonCtx, T>>
 where
     T: TrackIter + Data,
 {
-    ControllerHost::new(List::new(move || track_widget(mode)), PlayController)
+    ViewSwitcher::new(
+        move |d: &Ctx<CommonCtx, T>, _| {
+            d.ctx
+                .track_columns
+                .get(&view)
+                .map_or(mode, |columns| mode.with_columns(columns))
+        },
+        move |&mode, _, _| {
+            let list = ControllerHost::new(List::new(move || track_widget(mode)), PlayController)
+                .controller(SelectController)
+                .controller(KeyboardController);
+            let list = if mode.bpm || mode.key {
+                list.controller(AudioFeaturesController).boxed()
+            } else {
+                list.boxed()
+            };
+            Flex::column()
+                .cross_axis_alignment(CrossAxisAlignment::Fill)
+                .with_child(header_widget(view, mode).lens(Ctx::ctx().then(CommonCtx::track_sort)))
+                .with_child(list)
+                .boxed()
+        },
+    )
+}
+
+/// Row of the column names of the track list, ordering the tracks by a
+/// column when its name gets clicked.  Right-clicking it offers to show or
+/// hide the columns.
+fn header_widget(view: TrackView, mode: TrackDisplay) -> impl Widget<Option<TrackSort>> {
+    let mut major = Flex::row();
+    if mode.number {
+        major.add_child(column_widget(TrackColumn::Number));
+    } else {
+        major.add_spacer(PLAY_SLOT_WIDTH);
+    }
+    major.add_default_spacer();
+    if mode.title {
+        major.add_child(column_widget(TrackColumn::Title));
+        major.add_default_spacer();
+    }
+    if mode.artist {
+        major.add_child(column_widget(TrackColumn::Artist));
+        major.add_default_spacer();
+    }
+    if mode.album {
+        major.add_child(column_widget(TrackColumn::Album));
+    }
+    major.add_flex_spacer(1.0);
+    for &column in &[
+        TrackColumn::Popularity,
+        TrackColumn::DateAdded,
+        TrackColumn::Bpm,
+        TrackColumn::Key,
+        TrackColumn::Duration,
+    ] {
+        if mode.has_column(column) {
+            major.add_default_spacer();
+            major.add_child(column_widget(column));
+        }
+    }
+    major
+        .padding((theme::grid(1.0), 0.0))
+        .on_ex_click(move |ctx, event, _, _| {
+            if event.button == MouseButton::Right {
+                ctx.show_context_menu(columns_menu(view, mode), event.window_pos);
+            }
+        })
+}
+
+fn column_widget(column: TrackColumn) -> impl Widget<Option<TrackSort>> {
+    Label::dynamic(move |sort: &Option<TrackSort>, _| match sort {
+        Some(sort) if sort.column == column && sort.descending => {
+            format!("{} \u{25BE}", column.title())
+        }
+        Some(sort) if sort.column == column => format!("{} \u{25B4}", column.title()),
+        _ => column.title().to_string(),
+    })
+    .with_text_size(theme::TEXT_SIZE_SMALL)
+    .with_text_color(theme::PLACEHOLDER_COLOR)
+    .padding((theme::grid(0.5), theme::grid(0.25)))
+    .link()
+    .rounded(theme::BUTTON_BORDER_RADIUS)
+    .on_click(move |_, sort: &mut Option<TrackSort>, _| {
+        *sort = TrackSort::toggle(*sort, column);
+    })
+}
+
+fn columns_menu(view: TrackView, mode: TrackDisplay) -> Menu<State> {
+    let shown = mode.columns();
+    let mut menu = Menu::empty();
+    for &column in &TrackColumn::CONFIGURABLE {
+        let is_shown = shown.contains(&column);
+        let columns = if is_shown {
+            shown.iter().copied().filter(|&c| c != column).collect()
+        } else {
+            let mut columns = shown.clone();
+            columns.push_back(column);
+            columns
+        };
+        menu = menu.entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-track-column").with_placeholder(column.title()),
+            )
+            .command(cmd::SET_TRACK_COLUMNS.with((view, columns)))
+            .selected(is_shown),
+        );
+    }
+    menu
+}
+
+/// Text box narrowing down the track lists of the page.
+pub fn filter_widget() -> impl Widget<CommonCtx> {
+    TextBox::new()
+        .with_placeholder("Filter")
+        .fix_width(theme::grid(24.0))
+        .lens(CommonCtx::track_filter)
 }
 
 pub trait TrackIter {
     fn origin(&self) -> PlaybackOrigin;
     fn tracks(&self) -> &Vector<Arc<Track>>;
+
+    fn added_at(&self, _track: &Track) -> Option<DateTime<Utc>> {
+        None
+    }
 }
 
 impl TrackIter for Album {
@@ -103,6 +280,30 @@ impl TrackIter for SavedTracks {
     fn tracks(&self) -> &Vector<Arc<Track>> {
         &self.tracks
     }
+
+    fn added_at(&self, track: &Track) -> Option<DateTime<Utc>> {
+        self.added_at.get(&track.id).copied()
+    }
+}
+
+impl TrackIter for RadioTracks {
+    fn origin(&self) -> PlaybackOrigin {
+        PlaybackOrigin::Radio(self.seed.clone())
+    }
+
+    fn tracks(&self) -> &Vector<Arc<Track>> {
+        &self.tracks
+    }
+}
+
+impl TrackIter for TopTracks {
+    fn origin(&self) -> PlaybackOrigin {
+        PlaybackOrigin::TopTracks
+    }
+
+    fn tracks(&self) -> &Vector<Arc<Track>> {
+        &self.tracks
+    }
 }
 
 impl<T> ListIter<TrackRow> for Ctx<CommonCtx, T>
@@ -112,35 +313,129 @@ where
     fn for_each(&self, mut cb: impl FnMut(&TrackRow, usize)) {
         let origin = self.data.origin();
         let tracks = self.data.tracks();
-        ListIter::for_each(tracks, |track, index| {
+        // Filtered out or reordered rows break up the discs, and can't be
+        // dragged over.
+        let rearranged = is_rearranged(&self.ctx);
+        let is_multi_disc = !rearranged && is_multi_disc(tracks);
+        for (row, &index) in shown_positions(&self.ctx, &self.data).iter().enumerate() {
+            let track = &tracks[index];
             let d = TrackRow {
                 ctx: self.ctx.to_owned(),
                 origin: origin.to_owned(),
                 track: track.to_owned(),
                 position: index,
+                added_at: self.data.added_at(track),
+                is_sorted: rearranged,
+                disc_header: disc_header(tracks, index, is_multi_disc),
             };
-            cb(&d, index);
-        });
+            cb(&d, row);
+        }
     }
 
     fn for_each_mut(&mut self, mut cb: impl FnMut(&mut TrackRow, usize)) {
         let origin = self.data.origin();
         let tracks = self.data.tracks();
-        ListIter::for_each(tracks, |track, index| {
+        let rearranged = is_rearranged(&self.ctx);
+        let is_multi_disc = !rearranged && is_multi_disc(tracks);
+        for (row, &index) in shown_positions(&self.ctx, &self.data).iter().enumerate() {
+            let track = &tracks[index];
             let mut d = TrackRow {
                 ctx: self.ctx.to_owned(),
                 origin: origin.to_owned(),
                 track: track.to_owned(),
                 position: index,
+                added_at: self.data.added_at(track),
+                is_sorted: rearranged,
+                disc_header: disc_header(tracks, index, is_multi_disc),
             };
-            cb(&mut d, index);
+            cb(&mut d, row);
 
             // Mutation intentionally ignored.
-        });
+        }
     }
 
     fn data_len(&self) -> usize {
-        self.data.tracks().len()
+        shown_positions(&self.ctx, &self.data).len()
+    }
+}
+
+fn is_rearranged(ctx: &CommonCtx) -> bool {
+    ctx.is_filtering_tracks() || ctx.track_sort.is_some()
+}
+
+/// Positions of the tracks in `data` that are shown, in the order they are
+/// shown in.  The order is cached in `ctx` until the tracks, the filter or the
+/// sort change.
+fn shown_positions<T: TrackIter>(ctx: &CommonCtx, data: &T) -> Arc<[usize]> {
+    let key = ShownKey {
+        tracks: data.tracks().to_owned(),
+        filter: ctx.track_filter.to_owned(),
+        sort: ctx.track_sort,
+        audio_features: ctx.audio_features.to_owned(),
+    };
+    ctx.shown_tracks
+        .positions(key, || filter_and_sort_tracks(ctx, data))
+}
+
+fn filter_and_sort_tracks<T: TrackIter>(ctx: &CommonCtx, data: &T) -> Vec<usize> {
+    let tracks = data.tracks();
+    let mut positions: Vec<usize> = (0..tracks.len())
+        .filter(|&index| ctx.track_matches_filter(&tracks[index]))
+        .collect();
+    if let Some(sort) = ctx.track_sort {
+        let key = |index: &usize| -> (i64, String) {
+            let track = &tracks[*index];
+            match sort.column {
+                TrackColumn::Number => (track.track_number as i64, String::new()),
+                TrackColumn::Title => (0, track.name.to_lowercase()),
+                TrackColumn::Artist => (0, track.artist_name().to_lowercase()),
+                TrackColumn::Album => (0, track.album_name().to_lowercase()),
+                TrackColumn::Duration => (track.duration.as_millis() as i64, String::new()),
+                TrackColumn::Popularity => (
+                    track.popularity.map_or(-1, |popularity| popularity as i64),
+                    String::new(),
+                ),
+                TrackColumn::DateAdded => (
+                    data.added_at(track)
+                        .map_or(i64::MIN, |added_at| added_at.timestamp()),
+                    String::new(),
+                ),
+                TrackColumn::Bpm => (
+                    ctx.audio_features
+                        .get(&track.id)
+                        .map_or(-1, |features| (features.tempo * 100.0) as i64),
+                    String::new(),
+                ),
+                TrackColumn::Key => (
+                    ctx.audio_features
+                        .get(&track.id)
+                        .map_or(-1, |features| features.key as i64),
+                    String::new(),
+                ),
+            }
+        };
+        positions.sort_by_cached_key(key);
+        if sort.descending {
+            positions.reverse();
+        }
+    }
+    positions
+}
+
+fn is_multi_disc(tracks: &Vector<Arc<Track>>) -> bool {
+    tracks.iter().any(|track| track.disc_number > 1)
+}
+
+/// Number of the disc the track at `index` starts, if there are more discs.
+fn disc_header(tracks: &Vector<Arc<Track>>, index: usize, is_multi_disc: bool) -> Option<usize> {
+    let disc_number = tracks.get(index)?.disc_number;
+    let previous = index
+        .checked_sub(1)
+        .and_then(|previous| tracks.get(previous));
+    match previous {
+        Some(previous) if previous.disc_number == disc_number => None,
+        _ if is_multi_disc => Some(disc_number),
+        _ => None,
     }
 }
 
@@ -150,6 +445,10 @@ struct TrackRow {
     track: Arc<Track>,
     origin: PlaybackOrigin,
     position: usize,
+    #[data(same_fn = "PartialEq::eq")]
+    added_at: Option<DateTime<Utc>>,
+    is_sorted: bool,
+    disc_header: Option<usize>,
 }
 
 impl TrackRow {
@@ -161,6 +460,382 @@ impl TrackRow {
             },
         )
     }
+
+    /// Returns the playlist this row can be dragged around in, if any.
+    fn reorderable_playlist(&self) -> Option<&PlaylistLink> {
+        match &self.origin {
+            PlaybackOrigin::Playlist(link)
+                if !self.is_sorted && self.ctx.is_playlist_editable(&link.id) =>
+            {
+                Some(link)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Moves tracks of editable playlists to where they get dragged to, and lets
+/// any track be dragged out of the list, onto a playlist in the sidebar.
+struct ReorderController {
+    drag_start: Option<Point>,
+    is_dragging: bool,
+}
+
+impl ReorderController {
+    // Distance the mouse has to travel before a press becomes a drag.
+    const DRAG_THRESHOLD: f64 = 4.0;
+
+    fn new() -> Self {
+        Self {
+            drag_start: None,
+            is_dragging: false,
+        }
+    }
+}
+
+impl<W> Controller<TrackRow, W> for ReorderController
+where
+    W: Widget<TrackRow>,
+{
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut TrackRow,
+        env: &Env,
+    ) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                self.drag_start = Some(mouse.pos);
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => {
+                if let Some(start) = self.drag_start {
+                    if !self.is_dragging && mouse.pos.distance(start) > Self::DRAG_THRESHOLD {
+                        self.is_dragging = true;
+                        // Drag all of the selected tracks along.
+                        let tracks = if data.ctx.track_selection.contains(data.position) {
+                            data.ctx.track_selection.tracks.clone()
+                        } else {
+                            Vector::unit(data.track.clone())
+                        };
+                        ctx.submit_command(cmd::DRAG_TRACKS.with(tracks));
+                    }
+                }
+                if self.is_dragging {
+                    // Leaving the list to the left heads for the sidebar.
+                    if mouse.pos.x >= 0.0 && data.reorderable_playlist().is_some() {
+                        ctx.set_cursor(&Cursor::ResizeUpDown);
+                    } else {
+                        ctx.set_cursor(&Cursor::Pointer);
+                    }
+                }
+            }
+            Event::MouseUp(mouse) if self.is_dragging => {
+                self.drag_start = None;
+                self.is_dragging = false;
+                ctx.set_active(false);
+                ctx.clear_cursor();
+                ctx.request_paint();
+                // Playlists in the sidebar pick the tracks up before this.
+                ctx.submit_command(cmd::DRAG_TRACKS.with(Vector::new()));
+                // Rows in a tracklist are of the same height, so we can tell the
+                // target position from how far the mouse went.
+                let offset = (mouse.pos.y / ctx.size().height).floor() as isize;
+                let target = (data.position as isize + offset).max(0) as usize;
+                if let Some(link) = data.reorderable_playlist() {
+                    if target != data.position && mouse.pos.x >= 0.0 {
+                        ctx.submit_command(cmd::MOVE_PLAYLIST_TRACK.with((
+                            link.to_owned(),
+                            data.position,
+                            target,
+                        )));
+                    }
+                }
+                // Don't let the release play the track.
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseUp(_) => {
+                self.drag_start = None;
+            }
+            _ => {}
+        }
+        child.event(ctx, event, data, env);
+    }
+}
+
+/// Keeps the row the arrow keys moved to visible, and opens its menu from the
+/// keyboard.
+struct CursorController;
+
+impl CursorController {
+    fn is_cursor(tr: &TrackRow) -> bool {
+        tr.ctx.track_selection.cursor == Some(tr.position)
+    }
+}
+
+impl<W> Controller<TrackRow, W> for CursorController
+where
+    W: Widget<TrackRow>,
+{
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut TrackRow,
+        env: &Env,
+    ) {
+        match event {
+            Event::Command(cmd) if cmd.is(cmd::SHOW_TRACK_MENU) && Self::is_cursor(data) => {
+                let pos = ctx.window_origin() + Vec2::new(theme::grid(2.0), ctx.size().height);
+                ctx.show_context_menu(row_menu(data), pos);
+                ctx.set_handled();
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &TrackRow,
+        data: &TrackRow,
+        env: &Env,
+    ) {
+        if Self::is_cursor(data) && !Self::is_cursor(old_data) {
+            ctx.scroll_to_view();
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
+
+/// Asks whether the track of the row is saved in the library once the row
+/// scrolls into view, so the heart and the menus can offer the right action
+/// without checking the whole list up front.
+struct SavedStatusController {
+    is_visible: bool,
+    is_checked: bool,
+}
+
+impl SavedStatusController {
+    fn new() -> Self {
+        Self {
+            is_visible: false,
+            is_checked: false,
+        }
+    }
+
+    fn check(&mut self, submit: impl FnOnce(Vector<TrackId>), data: &TrackRow) {
+        if self.is_visible && !self.is_checked && !data.track.is_local {
+            self.is_checked = true;
+            submit(Vector::unit(data.track.id));
+        }
+    }
+}
+
+impl<W> Controller<TrackRow, W> for SavedStatusController
+where
+    W: Widget<TrackRow>,
+{
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &TrackRow,
+        env: &Env,
+    ) {
+        if let LifeCycle::ViewContextChanged(view_ctx) = event {
+            self.is_visible = view_ctx.clip.area() > 0.0;
+            self.check(
+                |track_ids| ctx.submit_command(cmd::CHECK_SAVED_TRACKS.with(track_ids)),
+                data,
+            );
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &TrackRow,
+        data: &TrackRow,
+        env: &Env,
+    ) {
+        // Rows get reused for other tracks when the list changes.
+        if old_data.track.id != data.track.id {
+            self.is_checked = false;
+            self.check(
+                |track_ids| ctx.submit_command(cmd::CHECK_SAVED_TRACKS.with(track_ids)),
+                data,
+            );
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
+
+/// Asks for the audio features of the listed tracks, so their tempo and key
+/// can be shown.
+struct AudioFeaturesController;
+
+impl AudioFeaturesController {
+    fn missing<T: TrackIter>(data: &Ctx<CommonCtx, T>) -> Vector<TrackId> {
+        data.data
+            .tracks()
+            .iter()
+            .filter(|track| !track.is_local && !data.ctx.audio_features.contains_key(&track.id))
+            .map(|track| track.id)
+            .collect()
+    }
+}
+
+impl<T, W> Controller<Ctx<CommonCtx, T>, W> for AudioFeaturesController
+where
+    T: TrackIter + Data,
+    W: Widget<Ctx<CommonCtx, T>>,
+{
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &Ctx<CommonCtx, T>,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            let missing = Self::missing(data);
+            if !missing.is_empty() {
+                ctx.submit_command(cmd::LOAD_AUDIO_FEATURES.with(missing));
+            }
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &Ctx<CommonCtx, T>,
+        data: &Ctx<CommonCtx, T>,
+        env: &Env,
+    ) {
+        if !old_data.data.tracks().ptr_eq(data.data.tracks()) {
+            let missing = Self::missing(data);
+            if !missing.is_empty() {
+                ctx.submit_command(cmd::LOAD_AUDIO_FEATURES.with(missing));
+            }
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
+
+/// Selects the tracks at `selected` positions, keeping them in the order they
+/// are shown in.
+fn select_tracks<T: TrackIter + Data>(
+    data: &mut Ctx<CommonCtx, T>,
+    shown: &[usize],
+    selected: &[usize],
+) {
+    let tracks = data.data.tracks();
+    let selection = &mut data.ctx.track_selection;
+    selection.positions = shown
+        .iter()
+        .copied()
+        .filter(|index| selected.contains(index))
+        .collect();
+    selection.tracks = selection
+        .positions
+        .iter()
+        .map(|&index| tracks[index].to_owned())
+        .collect();
+}
+
+/// Positions of the shown tracks between `from` and `to`, inclusive.
+fn shown_range(shown: &[usize], from: usize, to: usize) -> Option<Vec<usize>> {
+    let from = shown.iter().position(|&index| index == from)?;
+    let to = shown.iter().position(|&index| index == to)?;
+    Some(shown[from.min(to)..=from.max(to)].to_vec())
+}
+
+/// Selects the tracks shift- or ctrl-clicked in the list.
+struct SelectController;
+
+impl<T, W> Controller<Ctx<CommonCtx, T>, W> for SelectController
+where
+    T: TrackIter + Data,
+    W: Widget<Ctx<CommonCtx, T>>,
+{
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut Ctx<CommonCtx, T>,
+        env: &Env,
+    ) {
+        match event {
+            Event::Notification(note) => {
+                if let Some(&(position, mods)) = note.get(cmd::SELECT_TRACK_AT) {
+                    let shown = shown_positions(&data.ctx, &data.data);
+                    let selection = &mut data.ctx.track_selection;
+                    let anchor = *selection.anchor.get_or_insert(position);
+                    selection.cursor = Some(position);
+                    let selected = if mods.shift() {
+                        shown_range(&shown, anchor, position).unwrap_or_else(|| vec![position])
+                    } else {
+                        selection.anchor = Some(position);
+                        let mut selected: Vec<usize> =
+                            selection.positions.iter().copied().collect();
+                        if let Some(index) = selected.iter().position(|&index| index == position) {
+                            selected.remove(index);
+                        } else {
+                            selected.push(position);
+                        }
+                        selected
+                    };
+                    select_tracks(data, &shown, &selected);
+                    ctx.set_handled();
+                }
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
+/// Plays the tracks that are shown, in the order they are shown in, starting
+/// with the one at `position`.
+fn play_shown_tracks<T: TrackIter + Data>(
+    ctx: &mut EventCtx,
+    data: &mut Ctx<CommonCtx, T>,
+    position: usize,
+) {
+    data.ctx.track_selection.clear();
+    data.ctx.track_selection.cursor = Some(position);
+    let tracks = data.data.tracks();
+    let (tracks, position) = if is_rearranged(&data.ctx) {
+        let shown = shown_positions(&data.ctx, &data.data);
+        let position = shown
+            .iter()
+            .position(|&index| index == position)
+            .unwrap_or_default();
+        let shown: Vector<Arc<Track>> = shown
+            .iter()
+            .map(|&index| tracks[index].to_owned())
+            .collect();
+        (shown, position)
+    } else {
+        (tracks.to_owned(), position)
+    };
+    let payload = PlaybackPayload {
+        origin: data.data.origin().to_owned(),
+        tracks,
+        position,
+    };
+    ctx.submit_command(cmd::PLAY_TRACKS.with(payload));
 }
 
 struct PlayController;
@@ -180,41 +855,180 @@ where
     ) {
         match event {
             Event::Notification(note) => {
-                if let Some(position) = note.get(cmd::PLAY_TRACK_AT) {
-                    let payload = PlaybackPayload {
-                        origin: data.data.origin().to_owned(),
-                        tracks: data.data.tracks().to_owned(),
-                        position: position.to_owned(),
-                    };
-                    ctx.submit_command(cmd::PLAY_TRACKS.with(payload));
+                if let Some(&position) = note.get(cmd::PLAY_TRACK_AT) {
+                    play_shown_tracks(ctx, data, position);
+                    ctx.set_handled();
+                }
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
+/// Moves the cursor through the shown rows, selecting them on the way, or
+/// extending the selection with `extend`.  Returns false if there are no rows.
+fn move_cursor<T: TrackIter + Data>(
+    data: &mut Ctx<CommonCtx, T>,
+    movement: CursorMove,
+    extend: bool,
+) -> bool {
+    let shown = shown_positions(&data.ctx, &data.data);
+    let cursor = data
+        .ctx
+        .track_selection
+        .cursor
+        .and_then(|cursor| shown.iter().position(|&index| index == cursor));
+    let row = match movement {
+        CursorMove::Next => cursor.map_or(0, |row| row + 1),
+        CursorMove::Previous => cursor.map_or(0, |row| row.saturating_sub(1)),
+        CursorMove::First => 0,
+        CursorMove::Last => shown.len().saturating_sub(1),
+    };
+    let position = match shown.get(row).or_else(|| shown.last()) {
+        Some(&position) => position,
+        None => return false,
+    };
+    let selection = &mut data.ctx.track_selection;
+    selection.cursor = Some(position);
+    let selected = if extend {
+        let anchor = *selection.anchor.get_or_insert(position);
+        shown_range(&shown, anchor, position).unwrap_or_else(|| vec![position])
+    } else {
+        selection.anchor = Some(position);
+        vec![position]
+    };
+    select_tracks(data, &shown, &selected);
+    true
+}
+
+/// Plays the row under the cursor.  Returns false if there is none.
+fn play_cursor<T: TrackIter + Data>(ctx: &mut EventCtx, data: &mut Ctx<CommonCtx, T>) -> bool {
+    match data.ctx.track_selection.cursor {
+        Some(position) if position < data.data.tracks().len() => {
+            if data.data.tracks()[position].is_playable() {
+                play_shown_tracks(ctx, data, position);
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Moves through the rows of a focused list with the arrow keys, selecting
+/// them on the way, plays the row with Enter and opens its menu with the
+/// context menu key.  The keymap moves through the list with commands.
+struct KeyboardController;
+
+impl<T, W> Controller<Ctx<CommonCtx, T>, W> for KeyboardController
+where
+    T: TrackIter + Data,
+    W: Widget<Ctx<CommonCtx, T>>,
+{
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut Ctx<CommonCtx, T>,
+        env: &Env,
+    ) {
+        match event {
+            Event::MouseDown(_) => {
+                ctx.request_focus();
+                child.event(ctx, event, data, env);
+            }
+            Event::Command(cmd) if cmd.is(cmd::MOVE_TRACK_CURSOR) => {
+                let movement = *cmd.get_unchecked(cmd::MOVE_TRACK_CURSOR);
+                if move_cursor(data, movement, false) {
                     ctx.set_handled();
                 }
             }
+            Event::Command(cmd) if cmd.is(cmd::PLAY_TRACK_CURSOR) => {
+                if play_cursor(ctx, data) {
+                    ctx.set_handled();
+                }
+            }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                let movement = match &key.key {
+                    KbKey::ArrowDown => Some(CursorMove::Next),
+                    KbKey::ArrowUp => Some(CursorMove::Previous),
+                    KbKey::Home => Some(CursorMove::First),
+                    KbKey::End => Some(CursorMove::Last),
+                    _ => None,
+                };
+                let handled = if let Some(movement) = movement {
+                    move_cursor(data, movement, key.mods.shift())
+                } else {
+                    match &key.key {
+                        KbKey::Enter => play_cursor(ctx, data),
+                        KbKey::ContextMenu => {
+                            ctx.submit_command(cmd::SHOW_TRACK_MENU);
+                            true
+                        }
+                        KbKey::F10 if key.mods.shift() => {
+                            ctx.submit_command(cmd::SHOW_TRACK_MENU);
+                            true
+                        }
+                        KbKey::Escape if !data.ctx.track_selection.is_empty() => {
+                            data.ctx.track_selection.clear();
+                            true
+                        }
+                        _ => false,
+                    }
+                };
+                if handled {
+                    ctx.set_handled();
+                } else {
+                    child.event(ctx, event, data, env);
+                }
+            }
             _ => child.event(ctx, event, data, env),
         }
     }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &Ctx<CommonCtx, T>,
+        env: &Env,
+    ) {
+        if let LifeCycle::BuildFocusChain = event {
+            ctx.register_for_focus();
+        }
+        child.lifecycle(ctx, event, data, env);
+    }
 }
 
 fn track_widget(display: TrackDisplay) -> impl Widget<TrackRow> {
     let mut major = Flex::row();
     let mut minor = Flex::row();
 
+    // Leaves room for the play button, even without the numbers.
     if display.number {
         let track_number = Label::dynamic(|tr: &TrackRow, _| tr.track.track_number.to_string())
             .with_text_size(theme::TEXT_SIZE_SMALL)
             .with_text_color(theme::PLACEHOLDER_COLOR)
             .with_text_alignment(TextAlignment::Center)
             .center()
-            .fix_width(theme::grid(2.0));
+            .fix_width(PLAY_SLOT_WIDTH);
         major.add_child(track_number);
-        major.add_default_spacer();
+    } else {
+        major.add_spacer(PLAY_SLOT_WIDTH);
     }
+    major.add_default_spacer();
 
     if display.title {
         let track_name = Label::raw()
             .with_font(theme::UI_FONT_MEDIUM)
             .lens(TrackRow::track.then(Track::name.in_arc()));
         major.add_child(track_name);
+        major.add_child(Either::new(
+            |tr: &TrackRow, _| tr.track.explicit,
+            explicit_badge_widget().padding((theme::grid(0.5), 0.0, 0.0, 0.0)),
+            Empty,
+        ));
     }
 
     if display.artist {
@@ -262,31 +1076,192 @@ fn track_widget(display: TrackDisplay) -> impl Widget<TrackRow> {
         major.add_child(track_popularity);
     }
 
-    let track_duration =
-        Label::dynamic(|tr: &TrackRow, _| utils::as_minutes_and_seconds(&tr.track.duration))
-            .with_text_size(theme::TEXT_SIZE_SMALL)
-            .with_text_color(theme::PLACEHOLDER_COLOR);
+    if display.date_added {
+        let track_added_at = Label::dynamic(|tr: &TrackRow, _| {
+            tr.added_at
+                .map(|added_at| {
+                    added_at
+                        .with_timezone(&Local)
+                        .format("%b %d, %Y")
+                        .to_string()
+                })
+                .unwrap_or_default()
+        })
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .with_text_color(theme::PLACEHOLDER_COLOR);
+        major.add_default_spacer();
+        major.add_child(track_added_at);
+    }
+
+    if display.bpm {
+        let track_bpm = Label::dynamic(|tr: &TrackRow, _| {
+            tr.ctx
+                .audio_features
+                .get(&tr.track.id)
+                .map(|features| format!("{:.0} BPM", features.tempo))
+                .unwrap_or_default()
+        })
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .with_text_color(theme::PLACEHOLDER_COLOR);
+        major.add_default_spacer();
+        major.add_child(track_bpm);
+    }
+
+    if display.key {
+        let track_key = Label::dynamic(|tr: &TrackRow, _| {
+            tr.ctx
+                .audio_features
+                .get(&tr.track.id)
+                .and_then(|features| features.key_abbreviation())
+                .unwrap_or_default()
+        })
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .with_text_color(theme::PLACEHOLDER_COLOR)
+        .with_text_alignment(TextAlignment::End)
+        .fix_width(theme::grid(3.5));
+        major.add_default_spacer();
+        major.add_child(track_key);
+    }
+
     major.add_default_spacer();
-    major.add_child(track_duration);
+    major.add_child(saved_widget());
+
+    if display.duration {
+        let track_duration =
+            Label::dynamic(|tr: &TrackRow, _| utils::as_minutes_and_seconds(&tr.track.duration))
+                .with_text_size(theme::TEXT_SIZE_SMALL)
+                .with_text_color(theme::PLACEHOLDER_COLOR);
+        major.add_default_spacer();
+        major.add_child(track_duration);
+    }
 
-    Flex::column()
+    let track = Flex::column()
         .cross_axis_alignment(CrossAxisAlignment::Start)
         .with_child(major)
         .with_spacer(2.0)
         .with_child(minor)
-        .padding(theme::grid(1.0))
+        .env_scope(|env, tr: &TrackRow| {
+            // Grey out the tracks we can't play.
+            if !tr.track.is_playable() {
+                env.set(theme::TEXT_COLOR, env.get(theme::PLACEHOLDER_COLOR));
+                env.set(theme::PLACEHOLDER_COLOR, env.get(theme::GREY_500));
+            }
+        })
+        .padding(theme::grid(1.0));
+
+    let track = Hover::new(track, play_button_widget())
+        .align(UnitPoint::TOP_LEFT)
+        .offset((theme::grid(1.0), theme::grid(1.0)))
         .link()
         .rounded(theme::BUTTON_BORDER_RADIUS)
+        .env_scope(|env, tr: &TrackRow| {
+            if tr.ctx.track_selection.contains(tr.position) {
+                let color = env.get(theme::BLUE_200).with_alpha(0.2);
+                env.set(theme::LINK_COLD_COLOR, color.clone());
+                env.set(theme::LINK_HOT_COLOR, color);
+            }
+        })
         .on_ex_click(move |ctx, event, tr: &mut TrackRow, _| match event.button {
             MouseButton::Left => {
-                ctx.submit_notification(cmd::PLAY_TRACK_AT.with(tr.position));
+                if event.mods.shift() || event.mods.ctrl() || event.mods.meta() {
+                    ctx.submit_notification(cmd::SELECT_TRACK_AT.with((tr.position, event.mods)));
+                } else if tr.track.is_playable() {
+                    ctx.submit_notification(cmd::PLAY_TRACK_AT.with(tr.position));
+                }
             }
             MouseButton::Right => {
-                ctx.show_context_menu(track_menu(tr), event.window_pos);
+                ctx.show_context_menu(row_menu(tr), event.window_pos);
                 ctx.set_active(true);
             }
             _ => {}
         })
+        .controller(ReorderController::new())
+        .controller(CursorController)
+        .controller(SavedStatusController::new());
+
+    if display.discs {
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(disc_header_widget())
+            .with_child(track)
+            .boxed()
+    } else {
+        track.boxed()
+    }
+}
+
+/// Play button covering the track number of the hovered row.
+fn play_button_widget() -> impl Widget<TrackRow> {
+    Either::new(
+        |tr: &TrackRow, _| tr.track.is_playable(),
+        icons::PLAY
+            .scale(theme::ICON_SIZE)
+            .center()
+            .fix_size(PLAY_SLOT_WIDTH, theme::grid(2.0))
+            .background(theme::LINK_HOT_COLOR)
+            .on_ex_click(|ctx, event, tr: &mut TrackRow, _| {
+                if event.button == MouseButton::Left {
+                    ctx.submit_notification(cmd::PLAY_TRACK_AT.with(tr.position));
+                }
+            }),
+        Empty,
+    )
+}
+
+/// Heart showing if the track is saved in the library, clicking it saves or
+/// removes the track.
+fn saved_widget() -> impl Widget<TrackRow> {
+    Either::new(
+        |tr: &TrackRow, _| tr.ctx.is_track_saved(&tr.track),
+        icons::HEART_FILLED
+            .scale(theme::ICON_SIZE)
+            .with_color(theme::ICON_COLOR),
+        icons::HEART
+            .scale(theme::ICON_SIZE)
+            .with_color(theme::PLACEHOLDER_COLOR),
+    )
+    .padding(theme::grid(0.25))
+    .link()
+    .rounded(theme::BUTTON_BORDER_RADIUS)
+    .on_ex_click(|ctx, event, tr: &mut TrackRow, _| match event.button {
+        MouseButton::Left if tr.ctx.is_track_saved(&tr.track) => {
+            ctx.submit_command(cmd::UNSAVE_TRACK.with(tr.track.id.clone()));
+        }
+        MouseButton::Left => {
+            ctx.submit_command(cmd::SAVE_TRACK.with(tr.track.clone()));
+        }
+        MouseButton::Right => {
+            ctx.show_context_menu(row_menu(tr), event.window_pos);
+        }
+        _ => {}
+    })
+}
+
+fn explicit_badge_widget<T: Data>() -> impl Widget<T> {
+    Label::new("E")
+        .with_font(theme::UI_FONT_MEDIUM)
+        .with_text_size(theme::TEXT_SIZE_SMALL)
+        .with_text_color(theme::GREY_700)
+        .padding((3.0, 0.0))
+        .background(theme::GREY_300)
+        .rounded(2.0)
+}
+
+fn disc_header_widget() -> impl Widget<TrackRow> {
+    Either::new(
+        |tr: &TrackRow, _| tr.disc_header.is_some(),
+        Label::dynamic(|tr: &TrackRow, _| format!("Disc {}", tr.disc_header.unwrap_or_default()))
+            .with_font(theme::UI_FONT_MEDIUM)
+            .with_text_size(theme::TEXT_SIZE_SMALL)
+            .with_text_color(theme::PLACEHOLDER_COLOR)
+            .padding(Insets::new(
+                theme::grid(1.0),
+                theme::grid(2.0),
+                theme::grid(1.0),
+                theme::grid(0.5),
+            )),
+        Empty,
+    )
 }
 
 fn popularity_stars(popularity: u32) -> String {
@@ -306,6 +1281,16 @@ fn popularity_stars(popularity: u32) -> String {
     stars
 }
 
+/// Menu of the row, or of all the selected tracks if the row is one of them.
+fn row_menu(tr: &TrackRow) -> Menu<State> {
+    let selection = &tr.ctx.track_selection;
+    if selection.len() > 1 && selection.contains(tr.position) {
+        selection_menu(tr)
+    } else {
+        track_menu(tr)
+    }
+}
+
 fn track_menu(tr: &TrackRow) -> Menu<State> {
     let mut menu = Menu::empty();
 
@@ -332,13 +1317,76 @@ fn track_menu(tr: &TrackRow) -> Menu<State> {
         )
     }
 
+    menu = menu.entry(
+        MenuItem::new(
+            LocalizedString::new("menu-item-song-radio").with_placeholder("Go To Song Radio"),
+        )
+        .command(cmd::NAVIGATE.with(Nav::Radio(RadioSeed::track(&tr.track)))),
+    );
+
+    if let Some(artist_link) = tr.track.artists.front() {
+        menu = menu.entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-artist-radio")
+                    .with_placeholder("Go To Artist Radio"),
+            )
+            .command(cmd::NAVIGATE.with(Nav::Radio(RadioSeed::Artist(artist_link.to_owned())))),
+        );
+    }
+
     menu = menu.entry(
         MenuItem::new(LocalizedString::new("menu-item-copy-link").with_placeholder("Copy Link"))
             .command(cmd::COPY.with(tr.track.url())),
     );
 
+    menu = menu.entry(
+        MenuItem::new(
+            LocalizedString::new("menu-item-copy-uri").with_placeholder("Copy Spotify URI"),
+        )
+        .command(cmd::COPY.with(tr.track.uri())),
+    );
+
+    menu = menu.entry(utils::open_in_spotify_menu(tr.track.url(), tr.track.uri()));
+
+    menu = menu.entry(
+        MenuItem::new(
+            LocalizedString::new("menu-item-track-info").with_placeholder("Show Track Info"),
+        )
+        .command(cmd::SHOW_TRACK_INFO.with(tr.track.clone())),
+    );
+
     menu = menu.separator();
 
+    menu = menu.entry(
+        MenuItem::new(
+            LocalizedString::new("menu-item-add-to-queue").with_placeholder("Add to Queue"),
+        )
+        .command(cmd::ADD_TO_QUEUE.with(QueuedTrack {
+            origin: tr.origin.to_owned(),
+            track: tr.track.to_owned(),
+            added: true,
+        })),
+    );
+
+    if !tr.ctx.editable_playlists.is_empty() {
+        let mut playlist_menu = Menu::new(
+            LocalizedString::new("menu-item-add-to-playlist").with_placeholder("Add to Playlist"),
+        );
+        for playlist in &tr.ctx.editable_playlists {
+            playlist_menu = playlist_menu.entry(
+                MenuItem::new(
+                    LocalizedString::new("menu-item-playlist-name")
+                        .with_placeholder(playlist.name.clone()),
+                )
+                .command(
+                    cmd::ADD_TO_PLAYLIST
+                        .with((playlist.to_owned(), Vector::unit(tr.track.clone()))),
+                ),
+            );
+        }
+        menu = menu.entry(playlist_menu);
+    }
+
     if tr.ctx.is_track_saved(&tr.track) {
         menu = menu.entry(
             MenuItem::new(
@@ -359,3 +1407,107 @@ fn track_menu(tr: &TrackRow) -> Menu<State> {
 
     menu
 }
+
+/// Menu of the actions that apply to all of the selected tracks.
+fn selection_menu(tr: &TrackRow) -> Menu<State> {
+    let selection = &tr.ctx.track_selection;
+    let mut menu = Menu::empty();
+
+    menu = menu.entry(
+        MenuItem::new(
+            LocalizedString::new("menu-item-add-all-to-queue")
+                .with_placeholder(format!("Add {} Tracks to Queue", selection.len())),
+        )
+        .command(
+            cmd::ADD_ALL_TO_QUEUE.with(
+                selection
+                    .tracks
+                    .iter()
+                    .map(|track| QueuedTrack {
+                        origin: tr.origin.to_owned(),
+                        track: track.to_owned(),
+                        added: true,
+                    })
+                    .collect(),
+            ),
+        ),
+    );
+
+    if !tr.ctx.editable_playlists.is_empty() {
+        let mut playlist_menu = Menu::new(
+            LocalizedString::new("menu-item-add-to-playlist").with_placeholder("Add to Playlist"),
+        );
+        for playlist in &tr.ctx.editable_playlists {
+            playlist_menu = playlist_menu.entry(
+                MenuItem::new(
+                    LocalizedString::new("menu-item-playlist-name")
+                        .with_placeholder(playlist.name.clone()),
+                )
+                .command(
+                    cmd::ADD_TO_PLAYLIST.with((playlist.to_owned(), selection.tracks.clone())),
+                ),
+            );
+        }
+        menu = menu.entry(playlist_menu);
+    }
+
+    let unsaved: Vector<Arc<Track>> = selection
+        .tracks
+        .iter()
+        .filter(|track| !tr.ctx.is_track_saved(track))
+        .cloned()
+        .collect();
+    if unsaved.is_empty() {
+        menu = menu.entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-remove-all-from-library")
+                    .with_placeholder("Remove from Library"),
+            )
+            .command(
+                cmd::UNSAVE_TRACKS.with(selection.tracks.iter().map(|track| track.id).collect()),
+            ),
+        );
+    } else {
+        menu = menu.entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-save-all-to-library")
+                    .with_placeholder("Save to Library"),
+            )
+            .command(cmd::SAVE_TRACKS.with(unsaved)),
+        );
+    }
+
+    menu = menu.separator();
+
+    menu = menu.entry(
+        MenuItem::new(LocalizedString::new("menu-item-copy-links").with_placeholder("Copy Links"))
+            .command(
+                cmd::COPY.with(
+                    selection
+                        .tracks
+                        .iter()
+                        .map(|track| track.url())
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                ),
+            ),
+    );
+
+    menu = menu.entry(
+        MenuItem::new(
+            LocalizedString::new("menu-item-copy-uris").with_placeholder("Copy Spotify URIs"),
+        )
+        .command(
+            cmd::COPY.with(
+                selection
+                    .tracks
+                    .iter()
+                    .map(|track| track.uri())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+        ),
+    );
+
+    menu
+}
@@ -0,0 +1,79 @@
+use crate::{
+    data::{AudioFeatures, State, Track, TrackInfo},
+    ui::{
+        theme,
+        utils::{error_widget, spinner_widget},
+    },
+    widget::Async,
+};
+use druid::{
+    widget::{CrossAxisAlignment, Flex, Label, LineBreaking},
+    LensExt, Widget, WidgetExt,
+};
+
+pub fn info_widget() -> impl Widget<State> {
+    Async::new(
+        || spinner_widget(),
+        || loaded_info_widget(),
+        || error_widget(),
+    )
+    .lens(State::track_info)
+    .padding(theme::grid(3.0))
+}
+
+fn loaded_info_widget() -> impl Widget<TrackInfo> {
+    let track_name = Label::raw()
+        .with_font(theme::UI_FONT_MEDIUM)
+        .with_line_break_mode(LineBreaking::WordWrap)
+        .lens(TrackInfo::track.then(Track::name.in_arc()));
+
+    let track_artist = Label::dynamic(|info: &TrackInfo, _| info.track.artist_name())
+        .with_text_color(theme::PLACEHOLDER_COLOR)
+        .with_line_break_mode(LineBreaking::WordWrap);
+
+    let features = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(feature_widget("Tempo", |f| format!("{:.0} BPM", f.tempo)))
+        .with_child(feature_widget("Key", |f| {
+            f.key_name().unwrap_or_else(|| "Unknown".to_string())
+        }))
+        .with_child(feature_widget("Time Signature", |f| {
+            format!("{}/4", f.time_signature)
+        }))
+        .with_child(feature_widget("Energy", |f| percent(f.energy)))
+        .with_child(feature_widget("Danceability", |f| percent(f.danceability)))
+        .with_child(feature_widget("Positivity", |f| percent(f.valence)))
+        .with_child(feature_widget("Acousticness", |f| percent(f.acousticness)))
+        .with_child(feature_widget("Loudness", |f| {
+            format!("{:.1} dB", f.loudness)
+        }))
+        .lens(TrackInfo::features);
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(track_name)
+        .with_spacer(2.0)
+        .with_child(track_artist)
+        .with_spacer(theme::grid(2.0))
+        .with_child(features)
+}
+
+fn feature_widget(
+    title: &str,
+    value: impl Fn(&AudioFeatures) -> String + 'static,
+) -> impl Widget<AudioFeatures> {
+    Flex::row()
+        .with_child(
+            Label::new(title)
+                .with_text_color(theme::PLACEHOLDER_COLOR)
+                .fix_width(theme::grid(16.0)),
+        )
+        .with_child(Label::dynamic(move |features: &AudioFeatures, _| {
+            value(features)
+        }))
+        .padding((0.0, theme::grid(0.5)))
+}
+
+fn percent(value: f64) -> String {
+    format!("{:.0} %", value * 100.0)
+}
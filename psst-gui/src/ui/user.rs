@@ -7,8 +7,7 @@ use druid::{
 use crate::{
     data::{State, UserProfile},
     ui::theme,
-    webapi::WebApi,
-    widget::{Async, AsyncAction, Empty, LinkExt},
+    widget::{Async, Empty, LinkExt},
 };
 
 pub fn user_widget() -> impl Widget<State> {
@@ -32,7 +31,6 @@ pub fn user_widget() -> impl Widget<State> {
         },
         || Empty,
     )
-    .controller(AsyncAction::new(|_| WebApi::global().get_user_profile()))
     .lens(State::user_profile);
 
     Flex::column()
@@ -1,18 +1,26 @@
-use crate::{error::Error, ui::theme, widget::icons};
+use crate::{
+    cmd,
+    data::State,
+    error::Error,
+    ui::theme,
+    widget::{icons, LinkExt},
+};
 use druid::{
     image,
     kurbo::Line,
     widget::{
-        prelude::*, BackgroundBrush, CrossAxisAlignment, FillStrat, Flex, Image, Label, Painter,
-        SizedBox,
+        prelude::*, BackgroundBrush, Button, CrossAxisAlignment, Either, FillStrat, Flex, Image,
+        Label, Painter, SizedBox,
     },
-    Affine, Color, Data, ImageBuf, KeyOrValue, RenderContext, Widget, WidgetExt,
+    Affine, Color, Data, ImageBuf, KeyOrValue, LocalizedString, Menu, MenuItem, MouseButton,
+    RenderContext, Widget, WidgetExt,
 };
 use std::{f64::consts::TAU, time::Duration};
 
 pub enum Border {
     Top,
     Bottom,
+    Left,
 }
 
 impl Border {
@@ -24,12 +32,16 @@ impl Border {
 
         Painter::new(move |ctx, _, env| {
             let h = 1.0;
-            let y = match self {
-                Self::Top => h / 2.0,
-                Self::Bottom => ctx.size().height - h / 2.0,
+            let size = ctx.size();
+            let line = match self {
+                Self::Top => Line::new((0.0, h / 2.0), (size.width, h / 2.0)),
+                Self::Bottom => Line::new(
+                    (0.0, size.height - h / 2.0),
+                    (size.width, size.height - h / 2.0),
+                ),
+                Self::Left => Line::new((h / 2.0, 0.0), (h / 2.0, size.height)),
             };
             let color = color.resolve(&env);
-            let line = Line::new((0.0, y), (ctx.size().width, y));
             ctx.stroke(line, &color, h);
         })
     }
@@ -118,7 +130,18 @@ pub fn error_widget() -> impl Widget<Error> {
             Label::dynamic(|err: &Error, _| err.to_string())
                 .with_text_size(theme::TEXT_SIZE_SMALL)
                 .with_text_color(theme::PLACEHOLDER_COLOR),
-        );
+        )
+        .with_spacer(theme::grid(1.0))
+        .with_child(Either::new(
+            |err: &Error, _| err.is_retryable(),
+            Button::new("Retry").on_click(|ctx, _, _| ctx.submit_command(cmd::REFRESH)),
+            Either::new(
+                |err: &Error, _| matches!(err, Error::NotFound),
+                Button::new("Back")
+                    .on_click(|ctx, _, _| ctx.submit_command(cmd::NAVIGATE_BACK.with(1))),
+                SizedBox::empty(),
+            ),
+        ));
     Flex::row()
         .with_child(icon)
         .with_default_spacer()
@@ -127,8 +150,48 @@ pub fn error_widget() -> impl Widget<Error> {
         .center()
 }
 
+/// Round play button shown over the cards on hover.
+pub fn play_button_widget<T: Data>(
+    on_play: impl Fn(&mut EventCtx, &mut T) + 'static,
+) -> impl Widget<T> {
+    icons::PLAY
+        .scale(theme::ICON_SIZE)
+        .padding(theme::grid(1.0))
+        .background(theme::BACKGROUND_DARK)
+        .rounded(theme::grid(2.0))
+        .on_ex_click(move |ctx, event, data, _| {
+            if event.button == MouseButton::Left {
+                on_play(ctx, data);
+            }
+        })
+}
+
+/// Submenu opening the item in the official web player, or in the desktop app
+/// through the `spotify:` URI.
+pub fn open_in_spotify_menu(url: String, uri: String) -> Menu<State> {
+    Menu::new(LocalizedString::new("menu-item-open-in-spotify").with_placeholder("Open in Spotify"))
+        .entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-open-in-web-player").with_placeholder("Web Player"),
+            )
+            .command(cmd::OPEN_EXTERNAL.with(url)),
+        )
+        .entry(
+            MenuItem::new(
+                LocalizedString::new("menu-item-open-in-app").with_placeholder("Desktop App"),
+            )
+            .command(cmd::OPEN_EXTERNAL.with(uri)),
+        )
+}
+
 pub fn as_minutes_and_seconds(dur: &Duration) -> String {
-    let minutes = dur.as_secs() / 60;
+    let hours = dur.as_secs() / 3600;
+    let minutes = dur.as_secs() / 60 % 60;
     let seconds = dur.as_secs() % 60;
-    format!("{}:{:02}", minutes, seconds)
+    if hours > 0 {
+        // Podcast episodes can run for hours.
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
 }
@@ -0,0 +1,452 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use druid::{im::Vector, image};
+
+use crate::{
+    data::{
+        Album, Artist, ArtistAlbums, ArtistInfo, AudioAnalysis, AudioFeatures, Cached, Device,
+        Episode, FeaturedPlaylists, FollowedArtists, Lyrics, Nav, PlaybackPayload, Playlist,
+        PlaylistLink, PlaylistTracks, RecentlyPlayed, RemotePlayback, SavedTracks, SearchResults,
+        SearchTopic, Show, SpotifyUrl, TopRange, Track, TrackId, UserProfile,
+    },
+    error::Error,
+};
+
+use super::WebApi;
+
+/// The part of the Web API the delegate works with.  `WebApi` talks to
+/// Spotify, `FakeSpotifyApi` keeps a library in memory, so the delegate logic
+/// can run without the network.
+#[async_trait]
+pub trait SpotifyApi: Send + Sync {
+    // Connection state and caching.
+    fn set_offline(&self, offline: bool);
+    fn set_image_cache_limit(&self, limit: Option<u64>);
+    fn expire_cached(&self, nav: &Nav);
+
+    // Links.
+    async fn load_nav(&self, url: &SpotifyUrl) -> Result<Nav, Error>;
+    async fn load_playback(&self, url: &SpotifyUrl) -> Result<PlaybackPayload, Error>;
+
+    // User.
+    async fn get_user_profile(&self) -> Result<UserProfile, Error>;
+    async fn get_top_tracks(&self, range: TopRange) -> Result<Vector<Arc<Track>>, Error>;
+    async fn get_top_artists(&self, range: TopRange) -> Result<Vector<Artist>, Error>;
+    async fn get_recently_played(&self, before: Option<&str>) -> Result<RecentlyPlayed, Error>;
+
+    // Artists.
+    async fn get_artist(&self, id: &str) -> Result<Artist, Error>;
+    async fn get_artist_albums(&self, id: &str) -> Result<ArtistAlbums, Error>;
+    async fn get_artist_top_tracks(&self, id: &str) -> Result<Vector<Arc<Track>>, Error>;
+    async fn get_artist_info(&self, id: &str) -> Result<ArtistInfo, Error>;
+    async fn get_related_artists(&self, id: &str) -> Result<Cached<Vector<Artist>>, Error>;
+    async fn get_followed_artists(&self, after: Option<&str>) -> Result<FollowedArtists, Error>;
+    async fn is_following_artist(&self, id: &str) -> Result<bool, Error>;
+    async fn follow_artist(&self, id: &str) -> Result<(), Error>;
+    async fn unfollow_artist(&self, id: &str) -> Result<(), Error>;
+
+    // Albums.
+    async fn get_album(&self, id: &str) -> Result<Cached<Album>, Error>;
+    async fn get_saved_albums(
+        &self,
+        on_page: &mut (dyn FnMut(usize, Vector<Album>) + Send),
+    ) -> Result<(), Error>;
+    async fn contains_saved_albums(&self, ids: &[Arc<str>]) -> Result<Vector<bool>, Error>;
+    async fn save_album(&self, id: &str) -> Result<(), Error>;
+    async fn unsave_album(&self, id: &str) -> Result<(), Error>;
+
+    // Shows.
+    async fn get_show(&self, id: &str) -> Result<Show, Error>;
+    async fn get_show_episodes(&self, id: &str) -> Result<Vector<Arc<Episode>>, Error>;
+    async fn get_saved_shows(
+        &self,
+        on_page: &mut (dyn FnMut(usize, Vector<Show>) + Send),
+    ) -> Result<(), Error>;
+    async fn save_show(&self, id: &str) -> Result<(), Error>;
+    async fn unsave_show(&self, id: &str) -> Result<(), Error>;
+
+    // Tracks.
+    async fn get_tracks(&self, track_ids: &[TrackId]) -> Result<Vector<Arc<Track>>, Error>;
+    async fn get_saved_tracks(&self, offset: usize) -> Result<SavedTracks, Error>;
+    async fn contains_saved_tracks(&self, track_ids: &[TrackId]) -> Result<Vector<bool>, Error>;
+    async fn save_track(&self, id: &str) -> Result<(), Error>;
+    async fn unsave_track(&self, id: &str) -> Result<(), Error>;
+    async fn save_tracks(&self, track_ids: &[TrackId]) -> Result<(), Error>;
+    async fn unsave_tracks(&self, track_ids: &[TrackId]) -> Result<(), Error>;
+    async fn get_audio_features(&self, track_id: &str) -> Result<AudioFeatures, Error>;
+    async fn get_several_audio_features(
+        &self,
+        track_ids: &[TrackId],
+    ) -> Result<Vector<AudioFeatures>, Error>;
+    async fn get_audio_analysis(&self, track_id: &str) -> Result<AudioAnalysis, Error>;
+    async fn get_lyrics(&self, track_id: TrackId) -> Result<Lyrics, Error>;
+    async fn get_recommendations(
+        &self,
+        seed_tracks: &[TrackId],
+        seed_artists: &[Arc<str>],
+    ) -> Result<Vector<Arc<Track>>, Error>;
+
+    // Playlists.
+    async fn get_playlists(
+        &self,
+        on_page: &mut (dyn FnMut(usize, Vector<Playlist>) + Send),
+    ) -> Result<(), Error>;
+    async fn get_playlist(&self, id: &str) -> Result<Playlist, Error>;
+    async fn get_playlist_tracks_page(
+        &self,
+        link: &PlaylistLink,
+        snapshot_id: Option<&str>,
+        offset: usize,
+    ) -> Result<PlaylistTracks, Error>;
+    async fn create_playlist(&self, name: &str, description: &str) -> Result<Playlist, Error>;
+    async fn rename_playlist(&self, id: &str, name: &str) -> Result<(), Error>;
+    async fn add_tracks_to_playlist(
+        &self,
+        id: &str,
+        track_ids: &[TrackId],
+    ) -> Result<Arc<str>, Error>;
+    async fn move_playlist_track(
+        &self,
+        id: &str,
+        range_start: usize,
+        insert_before: usize,
+        snapshot_id: &str,
+    ) -> Result<Arc<str>, Error>;
+    async fn follow_playlist(&self, id: &str) -> Result<(), Error>;
+    async fn unfollow_playlist(&self, id: &str) -> Result<(), Error>;
+
+    // Browse and search.
+    async fn get_featured_playlists(&self) -> Result<FeaturedPlaylists, Error>;
+    async fn get_new_releases(&self) -> Result<Vector<Album>, Error>;
+    async fn search(&self, query: &str) -> Result<SearchResults, Error>;
+    async fn search_topic(
+        &self,
+        query: &str,
+        topic: SearchTopic,
+        offset: usize,
+    ) -> Result<SearchResults, Error>;
+    async fn search_suggestions(&self, query: &str) -> Result<SearchResults, Error>;
+
+    // Remote playback on other devices.
+    async fn get_devices(&self) -> Result<Vector<Device>, Error>;
+    async fn get_remote_playback(&self) -> Result<Option<RemotePlayback>, Error>;
+    async fn transfer_playback(&self, device_id: &str) -> Result<(), Error>;
+    async fn remote_play(&self, device_id: &str) -> Result<(), Error>;
+    async fn remote_pause(&self, device_id: &str) -> Result<(), Error>;
+    async fn remote_next(&self, device_id: &str) -> Result<(), Error>;
+    async fn remote_previous(&self, device_id: &str) -> Result<(), Error>;
+    async fn set_remote_volume(&self, device_id: &str, volume_percent: u32) -> Result<(), Error>;
+
+    // Images.
+    async fn get_image(
+        &self,
+        uri: &str,
+        format: image::ImageFormat,
+    ) -> Result<image::DynamicImage, Error>;
+    fn image_color(&self, uri: &str, image: &image::DynamicImage) -> [u8; 3];
+    fn get_image_color(&self, uri: &str) -> Option<[u8; 3]>;
+}
+
+#[async_trait]
+impl SpotifyApi for WebApi {
+    fn set_offline(&self, offline: bool) {
+        WebApi::set_offline(self, offline)
+    }
+
+    fn set_image_cache_limit(&self, limit: Option<u64>) {
+        WebApi::set_image_cache_limit(self, limit)
+    }
+
+    fn expire_cached(&self, nav: &Nav) {
+        WebApi::expire_cached(self, nav)
+    }
+
+    async fn load_nav(&self, url: &SpotifyUrl) -> Result<Nav, Error> {
+        WebApi::load_nav(self, url).await
+    }
+
+    async fn load_playback(&self, url: &SpotifyUrl) -> Result<PlaybackPayload, Error> {
+        WebApi::load_playback(self, url).await
+    }
+
+    async fn get_user_profile(&self) -> Result<UserProfile, Error> {
+        WebApi::get_user_profile(self).await
+    }
+
+    async fn get_top_tracks(&self, range: TopRange) -> Result<Vector<Arc<Track>>, Error> {
+        WebApi::get_top_tracks(self, range).await
+    }
+
+    async fn get_top_artists(&self, range: TopRange) -> Result<Vector<Artist>, Error> {
+        WebApi::get_top_artists(self, range).await
+    }
+
+    async fn get_recently_played(&self, before: Option<&str>) -> Result<RecentlyPlayed, Error> {
+        WebApi::get_recently_played(self, before).await
+    }
+
+    async fn get_artist(&self, id: &str) -> Result<Artist, Error> {
+        WebApi::get_artist(self, id).await
+    }
+
+    async fn get_artist_albums(&self, id: &str) -> Result<ArtistAlbums, Error> {
+        WebApi::get_artist_albums(self, id).await
+    }
+
+    async fn get_artist_top_tracks(&self, id: &str) -> Result<Vector<Arc<Track>>, Error> {
+        WebApi::get_artist_top_tracks(self, id).await
+    }
+
+    async fn get_artist_info(&self, id: &str) -> Result<ArtistInfo, Error> {
+        WebApi::get_artist_info(self, id).await
+    }
+
+    async fn get_related_artists(&self, id: &str) -> Result<Cached<Vector<Artist>>, Error> {
+        WebApi::get_related_artists(self, id).await
+    }
+
+    async fn get_followed_artists(&self, after: Option<&str>) -> Result<FollowedArtists, Error> {
+        WebApi::get_followed_artists(self, after).await
+    }
+
+    async fn is_following_artist(&self, id: &str) -> Result<bool, Error> {
+        WebApi::is_following_artist(self, id).await
+    }
+
+    async fn follow_artist(&self, id: &str) -> Result<(), Error> {
+        WebApi::follow_artist(self, id).await
+    }
+
+    async fn unfollow_artist(&self, id: &str) -> Result<(), Error> {
+        WebApi::unfollow_artist(self, id).await
+    }
+
+    async fn get_album(&self, id: &str) -> Result<Cached<Album>, Error> {
+        WebApi::get_album(self, id).await
+    }
+
+    async fn get_saved_albums(
+        &self,
+        on_page: &mut (dyn FnMut(usize, Vector<Album>) + Send),
+    ) -> Result<(), Error> {
+        WebApi::get_saved_albums(self, on_page).await
+    }
+
+    async fn contains_saved_albums(&self, ids: &[Arc<str>]) -> Result<Vector<bool>, Error> {
+        WebApi::contains_saved_albums(self, ids).await
+    }
+
+    async fn save_album(&self, id: &str) -> Result<(), Error> {
+        WebApi::save_album(self, id).await
+    }
+
+    async fn unsave_album(&self, id: &str) -> Result<(), Error> {
+        WebApi::unsave_album(self, id).await
+    }
+
+    async fn get_show(&self, id: &str) -> Result<Show, Error> {
+        WebApi::get_show(self, id).await
+    }
+
+    async fn get_show_episodes(&self, id: &str) -> Result<Vector<Arc<Episode>>, Error> {
+        WebApi::get_show_episodes(self, id).await
+    }
+
+    async fn get_saved_shows(
+        &self,
+        on_page: &mut (dyn FnMut(usize, Vector<Show>) + Send),
+    ) -> Result<(), Error> {
+        WebApi::get_saved_shows(self, on_page).await
+    }
+
+    async fn save_show(&self, id: &str) -> Result<(), Error> {
+        WebApi::save_show(self, id).await
+    }
+
+    async fn unsave_show(&self, id: &str) -> Result<(), Error> {
+        WebApi::unsave_show(self, id).await
+    }
+
+    async fn get_tracks(&self, track_ids: &[TrackId]) -> Result<Vector<Arc<Track>>, Error> {
+        WebApi::get_tracks(self, track_ids).await
+    }
+
+    async fn get_saved_tracks(&self, offset: usize) -> Result<SavedTracks, Error> {
+        WebApi::get_saved_tracks(self, offset).await
+    }
+
+    async fn contains_saved_tracks(&self, track_ids: &[TrackId]) -> Result<Vector<bool>, Error> {
+        WebApi::contains_saved_tracks(self, track_ids).await
+    }
+
+    async fn save_track(&self, id: &str) -> Result<(), Error> {
+        WebApi::save_track(self, id).await
+    }
+
+    async fn unsave_track(&self, id: &str) -> Result<(), Error> {
+        WebApi::unsave_track(self, id).await
+    }
+
+    async fn save_tracks(&self, track_ids: &[TrackId]) -> Result<(), Error> {
+        WebApi::save_tracks(self, track_ids).await
+    }
+
+    async fn unsave_tracks(&self, track_ids: &[TrackId]) -> Result<(), Error> {
+        WebApi::unsave_tracks(self, track_ids).await
+    }
+
+    async fn get_audio_features(&self, track_id: &str) -> Result<AudioFeatures, Error> {
+        WebApi::get_audio_features(self, track_id).await
+    }
+
+    async fn get_several_audio_features(
+        &self,
+        track_ids: &[TrackId],
+    ) -> Result<Vector<AudioFeatures>, Error> {
+        WebApi::get_several_audio_features(self, track_ids).await
+    }
+
+    async fn get_audio_analysis(&self, track_id: &str) -> Result<AudioAnalysis, Error> {
+        WebApi::get_audio_analysis(self, track_id).await
+    }
+
+    async fn get_lyrics(&self, track_id: TrackId) -> Result<Lyrics, Error> {
+        WebApi::get_lyrics(self, track_id).await
+    }
+
+    async fn get_recommendations(
+        &self,
+        seed_tracks: &[TrackId],
+        seed_artists: &[Arc<str>],
+    ) -> Result<Vector<Arc<Track>>, Error> {
+        WebApi::get_recommendations(self, seed_tracks, seed_artists).await
+    }
+
+    async fn get_playlists(
+        &self,
+        on_page: &mut (dyn FnMut(usize, Vector<Playlist>) + Send),
+    ) -> Result<(), Error> {
+        WebApi::get_playlists(self, on_page).await
+    }
+
+    async fn get_playlist(&self, id: &str) -> Result<Playlist, Error> {
+        WebApi::get_playlist(self, id).await
+    }
+
+    async fn get_playlist_tracks_page(
+        &self,
+        link: &PlaylistLink,
+        snapshot_id: Option<&str>,
+        offset: usize,
+    ) -> Result<PlaylistTracks, Error> {
+        WebApi::get_playlist_tracks_page(self, link, snapshot_id, offset).await
+    }
+
+    async fn create_playlist(&self, name: &str, description: &str) -> Result<Playlist, Error> {
+        WebApi::create_playlist(self, name, description).await
+    }
+
+    async fn rename_playlist(&self, id: &str, name: &str) -> Result<(), Error> {
+        WebApi::rename_playlist(self, id, name).await
+    }
+
+    async fn add_tracks_to_playlist(
+        &self,
+        id: &str,
+        track_ids: &[TrackId],
+    ) -> Result<Arc<str>, Error> {
+        WebApi::add_tracks_to_playlist(self, id, track_ids).await
+    }
+
+    async fn move_playlist_track(
+        &self,
+        id: &str,
+        range_start: usize,
+        insert_before: usize,
+        snapshot_id: &str,
+    ) -> Result<Arc<str>, Error> {
+        WebApi::move_playlist_track(self, id, range_start, insert_before, snapshot_id).await
+    }
+
+    async fn follow_playlist(&self, id: &str) -> Result<(), Error> {
+        WebApi::follow_playlist(self, id).await
+    }
+
+    async fn unfollow_playlist(&self, id: &str) -> Result<(), Error> {
+        WebApi::unfollow_playlist(self, id).await
+    }
+
+    async fn get_featured_playlists(&self) -> Result<FeaturedPlaylists, Error> {
+        WebApi::get_featured_playlists(self).await
+    }
+
+    async fn get_new_releases(&self) -> Result<Vector<Album>, Error> {
+        WebApi::get_new_releases(self).await
+    }
+
+    async fn search(&self, query: &str) -> Result<SearchResults, Error> {
+        WebApi::search(self, query).await
+    }
+
+    async fn search_topic(
+        &self,
+        query: &str,
+        topic: SearchTopic,
+        offset: usize,
+    ) -> Result<SearchResults, Error> {
+        WebApi::search_topic(self, query, topic, offset).await
+    }
+
+    async fn search_suggestions(&self, query: &str) -> Result<SearchResults, Error> {
+        WebApi::search_suggestions(self, query).await
+    }
+
+    async fn get_devices(&self) -> Result<Vector<Device>, Error> {
+        WebApi::get_devices(self).await
+    }
+
+    async fn get_remote_playback(&self) -> Result<Option<RemotePlayback>, Error> {
+        WebApi::get_remote_playback(self).await
+    }
+
+    async fn transfer_playback(&self, device_id: &str) -> Result<(), Error> {
+        WebApi::transfer_playback(self, device_id).await
+    }
+
+    async fn remote_play(&self, device_id: &str) -> Result<(), Error> {
+        WebApi::remote_play(self, device_id).await
+    }
+
+    async fn remote_pause(&self, device_id: &str) -> Result<(), Error> {
+        WebApi::remote_pause(self, device_id).await
+    }
+
+    async fn remote_next(&self, device_id: &str) -> Result<(), Error> {
+        WebApi::remote_next(self, device_id).await
+    }
+
+    async fn remote_previous(&self, device_id: &str) -> Result<(), Error> {
+        WebApi::remote_previous(self, device_id).await
+    }
+
+    async fn set_remote_volume(&self, device_id: &str, volume_percent: u32) -> Result<(), Error> {
+        WebApi::set_remote_volume(self, device_id, volume_percent).await
+    }
+
+    async fn get_image(
+        &self,
+        uri: &str,
+        format: image::ImageFormat,
+    ) -> Result<image::DynamicImage, Error> {
+        WebApi::get_image(self, uri, format).await
+    }
+
+    fn image_color(&self, uri: &str, image: &image::DynamicImage) -> [u8; 3] {
+        WebApi::image_color(self, uri, image)
+    }
+
+    fn get_image_color(&self, uri: &str) -> Option<[u8; 3]> {
+        WebApi::get_image_color(self, uri)
+    }
+}
@@ -1,17 +1,52 @@
 use std::{
+    collections::HashSet,
+    convert::TryInto,
     fs::{self, File},
     path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::SystemTime,
 };
 
 use psst_core::cache::mkdir_if_not_exists;
 
+const IMAGE_BUCKET: &str = "images";
+const IMAGE_COLOR_BUCKET: &str = "image-colors";
+
+/// Buckets shown to the user, with their display names.
+pub const BUCKETS: &[(&str, &str)] = &[
+    ("album", "Albums"),
+    ("artist", "Artists"),
+    ("related-artists", "Related artists"),
+    ("audio-analysis", "Audio analyses"),
+    ("audio-features", "Audio features"),
+    ("lyrics", "Lyrics"),
+    ("library", "Library"),
+    (IMAGE_BUCKET, "Artwork"),
+];
+
 pub struct WebApiCache {
     base: Option<PathBuf>,
+    // Maximum size of the image cache in bytes, zero if unlimited.
+    image_limit: AtomicU64,
+    // Size of the image cache in bytes, as of the last eviction plus the
+    // images stored since.
+    image_size: AtomicU64,
+    // Entries that should be revalidated on their next use, regardless of
+    // their age.
+    expired: Mutex<HashSet<PathBuf>>,
 }
 
 impl WebApiCache {
     pub fn new(base: Option<PathBuf>) -> Self {
-        Self { base }
+        Self {
+            base,
+            image_limit: AtomicU64::new(0),
+            image_size: AtomicU64::new(0),
+            expired: Mutex::new(HashSet::new()),
+        }
     }
 
     pub fn get(&self, bucket: &str, key: &str) -> Option<File> {
@@ -25,12 +60,93 @@ impl WebApiCache {
             }
         }
         if let Some(path) = self.key(bucket, key) {
+            self.expired.lock().unwrap().remove(&path);
             if let Err(err) = fs::write(path, value) {
                 log::error!("failed to save to WebAPI cache: {:?}", err);
             }
         }
     }
 
+    pub fn expire(&self, bucket: &str, key: &str) {
+        if let Some(path) = self.key(bucket, key) {
+            self.expired.lock().unwrap().insert(path);
+        }
+    }
+
+    pub fn is_expired(&self, bucket: &str, key: &str) -> bool {
+        match self.key(bucket, key) {
+            Some(path) => self.expired.lock().unwrap().contains(&path),
+            None => false,
+        }
+    }
+
+    /// Mark the cached value as fresh again, by re-writing it.
+    pub fn refresh(&self, bucket: &str, key: &str) {
+        if let Some(path) = self.key(bucket, key) {
+            self.expired.lock().unwrap().remove(&path);
+            if let Err(err) = fs::read(&path).and_then(|value| fs::write(&path, value)) {
+                log::error!("failed to refresh WebAPI cache: {:?}", err);
+            }
+        }
+    }
+
+    /// ETags are kept next to the cached values, for the conditional requests.
+    pub fn get_etag(&self, bucket: &str, key: &str) -> Option<String> {
+        let path = self.key(bucket, &Self::etag_key(key))?;
+        fs::read_to_string(path).ok()
+    }
+
+    pub fn set_etag(&self, bucket: &str, key: &str, etag: Option<&str>) {
+        match etag {
+            Some(etag) => self.set(bucket, &Self::etag_key(key), etag.as_bytes()),
+            None => {
+                if let Some(path) = self.key(bucket, &Self::etag_key(key)) {
+                    fs::remove_file(path).ok();
+                }
+            }
+        }
+    }
+
+    fn etag_key(key: &str) -> String {
+        format!("{}.etag", key)
+    }
+
+    /// Total size of the files in `bucket`, in bytes.
+    pub fn bucket_size(&self, bucket: &str) -> u64 {
+        let mut size = self
+            .bucket_entries(bucket)
+            .map_or(0, |entries| entries.iter().map(|(_, size, _)| size).sum());
+        if bucket == IMAGE_BUCKET {
+            size += self.bucket_size(IMAGE_COLOR_BUCKET);
+        }
+        size
+    }
+
+    /// Remove all the files in `bucket`.
+    pub fn clear(&self, bucket: &str) {
+        let entries = self.bucket_entries(bucket).unwrap_or_default();
+        for (path, _, _) in entries {
+            if let Err(err) = fs::remove_file(&path) {
+                log::error!("failed to clear WebAPI cache: {:?}", err);
+            }
+        }
+        if bucket == IMAGE_BUCKET {
+            // The colors are only useful together with the images.
+            self.clear(IMAGE_COLOR_BUCKET);
+            self.image_size.store(0, Ordering::Relaxed);
+        }
+    }
+
+    fn bucket_entries(&self, bucket: &str) -> Option<Vec<(PathBuf, u64, SystemTime)>> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(self.bucket(bucket)?).ok()? {
+            let entry = entry.ok()?;
+            let metadata = entry.metadata().ok()?;
+            entries.push((entry.path(), metadata.len(), metadata.modified().ok()?));
+        }
+        Some(entries)
+    }
+
     fn bucket(&self, bucket: &str) -> Option<PathBuf> {
         self.base.as_ref().map(|path| path.join(bucket))
     }
@@ -39,3 +155,72 @@ impl WebApiCache {
         self.bucket(bucket).map(|path| path.join(key))
     }
 }
+
+// Images, keyed by their URL.
+impl WebApiCache {
+    pub fn get_image(&self, url: &str) -> Option<Vec<u8>> {
+        let path = self.key(IMAGE_BUCKET, &Self::image_key(url))?;
+        fs::read(path).ok()
+    }
+
+    pub fn set_image(&self, url: &str, image: &[u8]) {
+        self.set(IMAGE_BUCKET, &Self::image_key(url), image);
+        let size = self
+            .image_size
+            .fetch_add(image.len() as u64, Ordering::Relaxed)
+            + image.len() as u64;
+        let limit = self.image_limit.load(Ordering::Relaxed);
+        if limit != 0 && size > limit {
+            self.evict_images();
+        }
+    }
+
+    pub fn get_image_color(&self, url: &str) -> Option<[u8; 3]> {
+        let path = self.key(IMAGE_COLOR_BUCKET, &Self::image_key(url))?;
+        fs::read(path).ok()?.try_into().ok()
+    }
+
+    pub fn set_image_color(&self, url: &str, color: [u8; 3]) {
+        self.set(IMAGE_COLOR_BUCKET, &Self::image_key(url), &color);
+    }
+
+    pub fn set_image_limit(&self, limit: Option<u64>) {
+        self.image_limit
+            .store(limit.unwrap_or(0), Ordering::Relaxed);
+        self.evict_images();
+    }
+
+    /// Remove the oldest images until the image cache fits into the
+    /// configured limit.
+    fn evict_images(&self) {
+        let mut entries = match self.bucket_entries(IMAGE_BUCKET) {
+            Some(entries) => entries,
+            None => return,
+        };
+        let limit = self.image_limit.load(Ordering::Relaxed);
+        let mut total_size: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        if limit != 0 {
+            entries.sort_by_key(|(_, _, modified)| *modified);
+            for (path, size, _) in entries {
+                if total_size <= limit {
+                    break;
+                }
+                if let Err(err) = fs::remove_file(&path) {
+                    log::error!("failed to evict image from cache: {:?}", err);
+                    break;
+                }
+                total_size -= size;
+            }
+        }
+        self.image_size.store(total_size, Ordering::Relaxed);
+    }
+
+    /// Image URLs only differ in their last segments, and are mostly made of
+    /// characters safe for file names.
+    fn image_key(url: &str) -> String {
+        url.trim_start_matches("https://")
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect()
+    }
+}
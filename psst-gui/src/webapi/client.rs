@@ -1,33 +1,136 @@
 use crate::{
     data::{
-        Album, AlbumType, Artist, ArtistAlbums, AudioAnalysis, Cached, Page, Playlist,
-        SearchResults, Track, UserProfile,
+        Album, AlbumType, Artist, ArtistAlbums, ArtistInfo, AudioAnalysis, AudioFeatures,
+        CacheBucket, Cached, Device, Episode, FeaturedPlaylists, FollowedArtists, Lyrics,
+        LyricsLine, Nav, Page, PlaybackOrigin, PlaybackPayload, Playlist, PlaylistLink,
+        PlaylistTracks, RecentlyPlayed, RecentlyPlayedItem, RemotePlayback, SavedTracks,
+        SearchResults, SearchTopic, Show, SpotifyUrl, TopRange, Track, TrackId, UserProfile,
     },
     error::Error,
 };
-use druid::{im::Vector, image, Data};
-use once_cell::sync::OnceCell;
-use psst_core::{
-    access_token::TokenProvider, session::SessionHandle, util::default_ureq_agent_builder,
+use chrono::{DateTime, Utc};
+use druid::{
+    im::{HashSet, Vector},
+    image, Data,
 };
+use once_cell::sync::OnceCell;
+use psst_core::{access_token::TokenProvider, session::SessionHandle};
+use rand::Rng;
+use reqwest::{Client, Method, Proxy, RequestBuilder, Response, StatusCode};
 use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::json;
 use std::{
+    borrow::Cow,
     fmt::Display,
-    io::{self, Read},
+    io,
     path::PathBuf,
-    sync::Arc,
-    thread,
-    time::Duration,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime},
 };
-use ureq::{Agent, Request, Response};
+use tokio::{task, time};
+
+use super::{
+    cache::{self, WebApiCache},
+    coalesce::Coalescer,
+    rate_limit::RateLimiter,
+};
+
+#[derive(Deserialize)]
+struct PlaylistSnapshot {
+    snapshot_id: Arc<str>,
+}
 
-use super::cache::WebApiCache;
+// Failed requests are retried after an exponentially growing delay, up to a
+// limit, and only as long as the total budget allows.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(8);
+const RETRY_BUDGET: Duration = Duration::from_secs(30);
+
+// Sustained rate and size of the bursts of the API requests, comfortably under
+// the Spotify limits.  Artwork comes from a CDN and is not limited.
+const REQUESTS_PER_SECOND: f64 = 8.0;
+const REQUEST_BURST: usize = 16;
+
+// Same as the timeouts of the other HTTP clients in `psst_core`.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(4);
+const IO_TIMEOUT: Duration = Duration::from_secs(4);
+
+// Upper bound of a whole request, so a connection trickling in bytes just
+// under the I/O timeout doesn't hold a page in the loading state forever.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 
 pub struct WebApi {
     session: SessionHandle,
-    agent: Agent,
+    /// Keeps a pool of connections, so the concurrent requests share a few of
+    /// them instead of opening one each.
+    client: Client,
     cache: WebApiCache,
-    token_provider: TokenProvider,
+    coalescer: Coalescer,
+    rate_limiter: RateLimiter,
+    token_provider: Arc<TokenProvider>,
+    /// Number of requests currently waiting for a retry.
+    retrying: AtomicUsize,
+    /// True while the session is disconnected.  The requests then fail right
+    /// away, and the library is served from the cache.
+    offline: AtomicBool,
+    retry_listener: Mutex<Option<Box<dyn Fn(bool) + Send>>>,
+    error_listener: Mutex<Option<Box<dyn Fn(&Error) + Send>>>,
+}
+
+/// Everything needed to send a request, so it can be sent again when retried.
+#[derive(Clone)]
+struct Request {
+    method: Method,
+    url: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(&'static str, String)>,
+}
+
+impl Request {
+    fn new(method: Method, url: impl Into<String>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            query: Vec::new(),
+            headers: Vec::new(),
+        }
+    }
+
+    fn query(mut self, key: &str, value: &str) -> Self {
+        self.query.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    fn set(mut self, header: &'static str, value: &str) -> Self {
+        self.headers.push((header, value.to_string()));
+        self
+    }
+
+    /// Replace the access token the request is sent with.
+    fn authorize(mut self, token: &str) -> Self {
+        self.headers
+            .retain(|(header, _)| *header != "Authorization");
+        self.set("Authorization", &format!("Bearer {}", token))
+    }
+
+    fn is_authorized(&self) -> bool {
+        self.headers
+            .iter()
+            .any(|(header, _)| *header == "Authorization")
+    }
+
+    fn build(&self, client: &Client) -> RequestBuilder {
+        let mut builder = client
+            .request(self.method.clone(), &self.url)
+            .query(&self.query);
+        for (header, value) in &self.headers {
+            builder = builder.header(*header, value);
+        }
+        builder
+    }
 }
 
 impl WebApi {
@@ -36,79 +139,238 @@ impl WebApi {
         proxy_url: Option<&str>,
         cache_base: Option<PathBuf>,
     ) -> Self {
-        let agent = default_ureq_agent_builder(proxy_url).unwrap().build();
+        let mut builder = Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT);
+        if let Some(url) = proxy_url {
+            builder = builder.proxy(Proxy::all(url).unwrap());
+        }
         Self {
             session,
-            agent,
+            client: builder.build().unwrap(),
             cache: WebApiCache::new(cache_base),
-            token_provider: TokenProvider::new(),
+            coalescer: Coalescer::new(),
+            rate_limiter: RateLimiter::new(REQUESTS_PER_SECOND, REQUEST_BURST),
+            token_provider: Arc::new(TokenProvider::new()),
+            retrying: AtomicUsize::new(0),
+            retry_listener: Mutex::new(None),
+            error_listener: Mutex::new(None),
+            offline: AtomicBool::new(false),
         }
     }
 
-    fn access_token(&self) -> Result<String, Error> {
-        let token = self
-            .token_provider
-            .get(&self.session)
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::SeqCst);
+    }
+
+    fn is_offline(&self) -> bool {
+        self.offline.load(Ordering::SeqCst)
+    }
+
+    fn offline_error() -> Error {
+        Error::WebApiError("Not available offline".into())
+    }
+
+    /// Call `listener` whenever some requests start or stop retrying.
+    pub fn set_retry_listener(&self, listener: impl Fn(bool) + Send + 'static) {
+        self.retry_listener
+            .lock()
+            .unwrap()
+            .replace(Box::new(listener));
+    }
+
+    /// Call `listener` with the errors of the requests that failed for good,
+    /// after all of the retries.
+    pub fn set_error_listener(&self, listener: impl Fn(&Error) + Send + 'static) {
+        self.error_listener
+            .lock()
+            .unwrap()
+            .replace(Box::new(listener));
+    }
+
+    /// Limit the size of the cached images, in bytes.
+    pub fn set_image_cache_limit(&self, limit: Option<u64>) {
+        self.cache.set_image_limit(limit);
+    }
+
+    async fn access_token(&self) -> Result<String, Error> {
+        // A new token is requested over the session, which blocks.
+        let token_provider = self.token_provider.clone();
+        let session = self.session.clone();
+        let token = task::spawn_blocking(move || token_provider.get(&session))
+            .await
+            .map_err(|err| Error::WebApiError(err.to_string()))?
             .map_err(|err| Error::WebApiError(err.to_string()))?;
         Ok(token.token)
     }
 
-    fn request(&self, method: &str, path: impl Display) -> Result<Request, Error> {
-        let token = self.access_token()?;
-        let request = self
-            .agent
-            .request(method, &format!("https://api.spotify.com/{}", path))
-            .set("Authorization", &format!("Bearer {}", &token));
-        Ok(request)
+    async fn request(&self, method: Method, path: impl Display) -> Result<Request, Error> {
+        let request = Request::new(method, format!("https://api.spotify.com/{}", path));
+        if self.is_offline() {
+            // There's no token without a session, but the request is not going
+            // to be sent anyway.
+            return Ok(request);
+        }
+        let token = self.access_token().await?;
+        Ok(request.authorize(&token))
     }
 
-    fn get(&self, path: impl Display) -> Result<Request, Error> {
-        self.request("GET", path)
+    async fn get(&self, path: impl Display) -> Result<Request, Error> {
+        self.request(Method::GET, path).await
     }
 
-    fn put(&self, path: impl Display) -> Result<Request, Error> {
-        self.request("PUT", path)
+    async fn post(&self, path: impl Display) -> Result<Request, Error> {
+        self.request(Method::POST, path).await
     }
 
-    fn delete(&self, path: impl Display) -> Result<Request, Error> {
-        self.request("DELETE", path)
+    async fn put(&self, path: impl Display) -> Result<Request, Error> {
+        self.request(Method::PUT, path).await
     }
 
-    fn with_retry(f: impl Fn() -> Result<Response, Error>) -> Result<Response, Error> {
-        loop {
-            let response = f()?;
-            match response.status() {
-                429 => {
-                    let retry_after_secs = response
-                        .header("Retry-After")
-                        .and_then(|secs| secs.parse().ok())
-                        .unwrap_or(2);
-                    thread::sleep(Duration::from_secs(retry_after_secs));
-                }
-                _ => {
-                    break Ok(response);
-                }
+    async fn delete(&self, path: impl Display) -> Result<Request, Error> {
+        self.request(Method::DELETE, path).await
+    }
+
+    /// Send `request`, with `body` as JSON if given, and retry it if it fails
+    /// with a rate limit, a server or a connection error.  A refused token is
+    /// replaced with a fresh one once.
+    async fn with_retry(
+        &self,
+        request: &Request,
+        body: Option<&serde_json::Value>,
+    ) -> Result<Response, Error> {
+        if self.is_offline() {
+            return Err(Self::offline_error());
+        }
+        let mut request = Cow::Borrowed(request);
+        let mut refreshed_token = false;
+        let mut attempt = 0;
+        let mut waited = Duration::default();
+        let result = loop {
+            self.rate_limiter.acquire().await;
+            let mut builder = request.build(&self.client);
+            if let Some(body) = body {
+                builder = builder.json(body);
+            }
+            let (err, delay) = match builder.send().await {
+                Ok(response) => match response.status() {
+                    StatusCode::TOO_MANY_REQUESTS => {
+                        (status_error(&response), retry_after(&response))
+                    }
+                    StatusCode::UNAUTHORIZED if request.is_authorized() && !refreshed_token => {
+                        // Most likely an expired token, try again with a new
+                        // one.
+                        self.token_provider.invalidate();
+                        match self.access_token().await {
+                            Ok(token) => {
+                                request = Cow::Owned(request.into_owned().authorize(&token));
+                                refreshed_token = true;
+                                continue;
+                            }
+                            Err(err) => break Err(err),
+                        }
+                    }
+                    StatusCode::UNAUTHORIZED => {
+                        // Refused even the fresh token, the session needs to
+                        // log in anew.
+                        self.token_provider.invalidate();
+                        break Err(Error::Unauthorized);
+                    }
+                    status if status.is_server_error() => {
+                        (status_error(&response), Self::backoff(attempt))
+                    }
+                    status if status.is_client_error() => break Err(status_error(&response)),
+                    _ => break Ok(response),
+                },
+                Err(err) if Self::is_transient(&err) => (err.into(), Self::backoff(attempt)),
+                Err(err) => break Err(err.into()),
+            };
+            waited += delay;
+            if waited > RETRY_BUDGET {
+                break Err(err);
+            }
+            if attempt == 0 {
+                self.update_retrying(true);
+            }
+            log::warn!("request failed, retrying in {:?}: {}", delay, err);
+            time::sleep(delay).await;
+            attempt += 1;
+        };
+        if attempt > 0 {
+            self.update_retrying(false);
+        }
+        if let Err(err) = &result {
+            if let Some(listener) = self.error_listener.lock().unwrap().as_ref() {
+                listener(err);
+            }
+        }
+        result
+    }
+
+    fn is_transient(err: &reqwest::Error) -> bool {
+        err.is_connect() || err.is_timeout() || err.is_request()
+    }
+
+    /// Capped exponential delay with a random jitter, so the clients failed at
+    /// once don't retry at once as well.
+    fn backoff(attempt: u32) -> Duration {
+        let delay = RETRY_BASE_DELAY
+            .saturating_mul(1 << attempt.min(16))
+            .min(RETRY_MAX_DELAY);
+        delay.mul_f64(rand::thread_rng().gen_range(0.5..1.0))
+    }
+
+    fn update_retrying(&self, started: bool) {
+        let count = if started {
+            self.retrying.fetch_add(1, Ordering::SeqCst) + 1
+        } else {
+            self.retrying.fetch_sub(1, Ordering::SeqCst) - 1
+        };
+        // Only tell about the first request starting and the last one
+        // stopping.
+        if (started && count == 1) || (!started && count == 0) {
+            if let Some(listener) = self.retry_listener.lock().unwrap().as_ref() {
+                listener(started);
             }
         }
     }
 
     /// Send a request with a empty JSON object, throw away the response body.
     /// Use for POST/PUT/DELETE requests.
-    fn send_empty_json(&self, request: Request) -> Result<(), Error> {
-        Self::with_retry(|| Ok(request.clone().send_string("{}")?))?;
+    async fn send_empty_json(&self, request: Request) -> Result<(), Error> {
+        self.with_retry(&request, Some(&json!({}))).await?;
         Ok(())
     }
 
+    /// Send a request with a JSON body, throw away the response body.
+    async fn send_json_body(&self, request: Request, body: serde_json::Value) -> Result<(), Error> {
+        self.with_retry(&request, Some(&body)).await?;
+        Ok(())
+    }
+
+    /// Send a request with a JSON body and return the deserialized JSON
+    /// response.  Use for POST/PUT requests.
+    async fn send_json<T: DeserializeOwned>(
+        &self,
+        request: Request,
+        body: serde_json::Value,
+    ) -> Result<T, Error> {
+        let response = self.with_retry(&request, Some(&body)).await?;
+        Ok(serde_json::from_slice(&read_body(response).await?)?)
+    }
+
     /// Send a request and return the deserialized JSON body.  Use for GET
     /// requests.
-    fn load<T: DeserializeOwned>(&self, request: Request) -> Result<T, Error> {
-        let result = Self::with_retry(|| Ok(request.clone().call()?))?.into_json()?;
-        Ok(result)
+    async fn load<T: DeserializeOwned>(&self, request: Request) -> Result<T, Error> {
+        let response = self.with_retry(&request, None).await?;
+        Ok(serde_json::from_slice(&read_body(response).await?)?)
     }
 
     /// Send a request using `self.load()`, but only if it isn't already present
-    /// in cache.
-    fn load_cached<T: Data + DeserializeOwned>(
+    /// in cache, or the cached value is older than the TTL of the bucket.
+    /// Cached values with an ETag are revalidated with a conditional request,
+    /// so only the changed ones get downloaded again.
+    async fn load_cached<T: Data + DeserializeOwned>(
         &self,
         request: Request,
         bucket: &str,
@@ -117,75 +379,214 @@ impl WebApi {
         if let Some(file) = self.cache.get(bucket, key) {
             let cached_at = file.metadata()?.modified()?;
             let value = serde_json::from_reader(file)?;
-            Ok(Cached::cached(value, cached_at))
-        } else {
-            let response = Self::with_retry(|| Ok(request.clone().call()?))?;
-            let body = {
-                let mut reader = response.into_reader();
-                let mut body = Vec::new();
-                reader.read_to_end(&mut body)?;
-                body
+            let is_fresh = match Self::cache_ttl(bucket) {
+                Some(ttl) => cached_at.elapsed().map_or(false, |age| age < ttl),
+                None => true,
             };
+            if self.is_offline() || (is_fresh && !self.cache.is_expired(bucket, key)) {
+                return Ok(Cached::cached(value, cached_at));
+            }
+            let etag = self.cache.get_etag(bucket, key);
+            match self
+                .download_to_cache(request, bucket, key, etag.as_deref())
+                .await
+            {
+                Ok(Some(body)) => Ok(Cached::fresh(serde_json::from_slice(&body)?)),
+                Ok(None) => Ok(Cached::cached(value, SystemTime::now())),
+                Err(err) => {
+                    // Stale data is better than none.
+                    log::warn!("failed to revalidate {}/{}: {}", bucket, key, err);
+                    Ok(Cached::cached(value, cached_at))
+                }
+            }
+        } else {
+            // Several widgets often ask for the same album or artist at once.
+            let body = self
+                .coalescer
+                .fetch(&format!("{}/{}", bucket, key), async {
+                    self.download_to_cache(request, bucket, key, None)
+                        .await?
+                        .ok_or_else(|| Error::WebApiError("Unexpected 304 response".into()))
+                })
+                .await?;
             let value = serde_json::from_slice(&body)?;
-            self.cache.set(bucket, key, &body);
             Ok(Cached::fresh(value))
         }
     }
 
+    /// How long the cached values in `bucket` are used without asking the
+    /// server, `None` if forever.
+    fn cache_ttl(bucket: &str) -> Option<Duration> {
+        const DAY: u64 = 24 * 60 * 60;
+        match bucket {
+            // Track analyses never change.
+            "audio-analysis" | "audio-features" => None,
+            "album" => Some(Duration::from_secs(7 * DAY)),
+            "related-artists" => Some(Duration::from_secs(7 * DAY)),
+            _ => Some(Duration::from_secs(DAY)),
+        }
+    }
+
+    /// Sizes of the cache buckets, in bytes.
+    pub fn cache_usage(&self) -> Vector<CacheBucket> {
+        cache::BUCKETS
+            .iter()
+            .map(|&(id, name)| CacheBucket {
+                id: id.into(),
+                name: name.into(),
+                size: self.cache.bucket_size(id),
+            })
+            .collect()
+    }
+
+    pub fn clear_cache(&self, bucket: &str) {
+        self.cache.clear(bucket);
+    }
+
+    /// Make the next loads of the items shown on `nav` skip the cache.
+    pub fn expire_cached(&self, nav: &Nav) {
+        match nav {
+            Nav::AlbumDetail(link) => {
+                self.cache.expire("album", &link.id);
+            }
+            Nav::ArtistDetail(link) => {
+                self.cache.expire("artist", &link.id);
+                self.cache.expire("related-artists", &link.id);
+            }
+            _ => {}
+        }
+    }
+
+    /// Send `request` and store the response body in cache, together with its
+    /// ETag.  With `etag` given, the request is conditional, and `None` is
+    /// returned if the cached body is still valid.
+    async fn download_to_cache(
+        &self,
+        request: Request,
+        bucket: &str,
+        key: &str,
+        etag: Option<&str>,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let request = match etag {
+            Some(etag) => request.set("If-None-Match", etag),
+            None => request,
+        };
+        let response = self.with_retry(&request, None).await?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            self.cache.refresh(bucket, key);
+            return Ok(None);
+        }
+        let etag = response
+            .headers()
+            .get("ETag")
+            .and_then(|etag| etag.to_str().ok())
+            .map(String::from);
+        let body = read_body(response).await?;
+        self.cache.set(bucket, key, &body);
+        self.cache.set_etag(bucket, key, etag.as_deref());
+        Ok(Some(body))
+    }
+
+    /// Send a request using `self.load()`, keeping the response in cache to be
+    /// used instead while offline.
+    async fn load_stored<T: DeserializeOwned>(
+        &self,
+        request: Request,
+        bucket: &str,
+        key: &str,
+    ) -> Result<T, Error> {
+        if self.is_offline() {
+            let file = self
+                .cache
+                .get(bucket, key)
+                .ok_or_else(Self::offline_error)?;
+            return Ok(serde_json::from_reader(file)?);
+        }
+        let response = self.with_retry(&request, None).await?;
+        let body = read_body(response).await?;
+        self.cache.set(bucket, key, &body);
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Load a single page of a paginated result set, starting at `offset`.
+    /// Pages of the user's library, given a `library_key`, stay available
+    /// offline.  Use with GET requests.
+    async fn load_page<T: DeserializeOwned + Clone>(
+        &self,
+        request: Request,
+        offset: usize,
+        library_key: Option<&str>,
+    ) -> Result<Page<T>, Error> {
+        const PAGE_LIMIT: usize = 50;
+
+        let request = request
+            .query("limit", &PAGE_LIMIT.to_string())
+            .query("offset", &offset.to_string());
+        match library_key {
+            Some(key) => {
+                self.load_stored(request, "library", &format!("{}-{}", key, offset))
+                    .await
+            }
+            None => self.load(request).await,
+        }
+    }
+
+    /// Load a paginated result set by sending `request` with added pagination
+    /// parameters, and pass every page to `on_page` together with its offset
+    /// as soon as it arrives.  Use with GET requests.
+    async fn load_pages_with<T: DeserializeOwned + Clone>(
+        &self,
+        request: Request,
+        library_key: Option<&str>,
+        mut on_page: impl FnMut(usize, Vector<T>) + Send,
+    ) -> Result<(), Error> {
+        let mut offset = Some(0);
+        while let Some(current) = offset {
+            let page: Page<T> = self
+                .load_page(request.clone(), current, library_key)
+                .await?;
+            offset = page.next_offset();
+            on_page(current, page.items);
+        }
+        Ok(())
+    }
+
     /// Load a paginated result set by sending `request` with added pagination
     /// parameters and return the aggregated results.  Use with GET requests.
-    fn load_all_pages<T: DeserializeOwned + Clone>(
+    /// Very big result sets, like long playlists, should rather be loaded
+    /// page by page with `self.load_page()`.
+    async fn load_all_pages<T: DeserializeOwned + Clone + Send + Sync>(
         &self,
         request: Request,
     ) -> Result<Vector<T>, Error> {
-        // TODO: Some result sets, like very long playlists and saved tracks/albums can
-        // be very big.  Implement virtualized scrolling and lazy-loading of results.
-        const PAGED_ITEMS_LIMIT: usize = 200;
-
         let mut results = Vector::new();
-        let mut limit = 50;
-        let mut offset = 0;
-        loop {
-            let req = request
-                .clone()
-                .query("limit", &limit.to_string())
-                .query("offset", &offset.to_string());
-            let page: Page<T> = self.load(req)?;
-
-            results.extend(page.items);
-
-            if page.total > results.len() && results.len() < PAGED_ITEMS_LIMIT {
-                limit = page.limit;
-                offset = page.offset + page.limit;
-            } else {
-                break;
-            }
-        }
+        self.load_pages_with(request, None, |_, items| results.append(items))
+            .await?;
         Ok(results)
     }
 }
 
-static GLOBAL_WEBAPI: OnceCell<Arc<WebApi>> = OnceCell::new();
+static GLOBAL_WEBAPI: OnceCell<WebApi> = OnceCell::new();
 
 /// Global instance.
 impl WebApi {
     pub fn install_as_global(self) {
         GLOBAL_WEBAPI
-            .set(Arc::new(self))
+            .set(self)
             .map_err(|_| "Cannot install more than once")
             .unwrap()
     }
 
-    pub fn global() -> Arc<Self> {
-        GLOBAL_WEBAPI.get().unwrap().clone()
+    pub fn global() -> &'static Self {
+        GLOBAL_WEBAPI.get().unwrap()
     }
 }
 
 /// Other endpoints.
 impl WebApi {
-    pub fn get_user_profile(&self) -> Result<UserProfile, Error> {
-        let request = self.get("v1/me")?;
-        let result = self.load(request)?;
+    pub async fn get_user_profile(&self) -> Result<UserProfile, Error> {
+        let request = self.get("v1/me").await?;
+        let result = self.load(request).await?;
         Ok(result)
     }
 }
@@ -193,18 +594,19 @@ impl WebApi {
 /// Artist endpoints.
 impl WebApi {
     // https://developer.spotify.com/documentation/web-api/reference/artists/get-artist/
-    pub fn get_artist(&self, id: &str) -> Result<Artist, Error> {
-        let request = self.get(format!("v1/artists/{}", id))?;
-        let result = self.load_cached(request, "artist", id)?;
+    pub async fn get_artist(&self, id: &str) -> Result<Artist, Error> {
+        let request = self.get(format!("v1/artists/{}", id)).await?;
+        let result = self.load_cached(request, "artist", id).await?;
         Ok(result.data)
     }
 
     // https://developer.spotify.com/documentation/web-api/reference/artists/get-artists-albums/
-    pub fn get_artist_albums(&self, id: &str) -> Result<ArtistAlbums, Error> {
+    pub async fn get_artist_albums(&self, id: &str) -> Result<ArtistAlbums, Error> {
         let request = self
-            .get(format!("v1/artists/{}/albums", id))?
+            .get(format!("v1/artists/{}/albums", id))
+            .await?
             .query("market", "from_token");
-        let result: Vector<Album> = self.load_all_pages(request)?;
+        let result: Vector<Album> = self.load_all_pages(request).await?;
 
         let mut artist_albums = ArtistAlbums {
             albums: Vector::new(),
@@ -224,214 +626,1265 @@ impl WebApi {
     }
 
     // https://developer.spotify.com/documentation/web-api/reference/artists/get-artists-top-tracks/
-    pub fn get_artist_top_tracks(&self, id: &str) -> Result<Vector<Arc<Track>>, Error> {
+    pub async fn get_artist_top_tracks(&self, id: &str) -> Result<Vector<Arc<Track>>, Error> {
         #[derive(Deserialize)]
         struct Tracks {
             tracks: Vector<Arc<Track>>,
         }
 
         let request = self
-            .get(format!("v1/artists/{}/top-tracks", id))?
+            .get(format!("v1/artists/{}/top-tracks", id))
+            .await?
             .query("market", "from_token");
-        let result: Tracks = self.load(request)?;
+        let result: Tracks = self.load(request).await?;
         Ok(result.tracks)
     }
 
     // https://developer.spotify.com/documentation/web-api/reference/artists/get-related-artists/
-    pub fn get_related_artists(&self, id: &str) -> Result<Cached<Vector<Artist>>, Error> {
+    pub async fn get_related_artists(&self, id: &str) -> Result<Cached<Vector<Artist>>, Error> {
         #[derive(Clone, Data, Deserialize)]
         struct Artists {
             artists: Vector<Artist>,
         }
 
-        let request = self.get(format!("v1/artists/{}/related-artists", id))?;
-        let result: Cached<Artists> = self.load_cached(request, "related-artists", id)?;
+        let request = self
+            .get(format!("v1/artists/{}/related-artists", id))
+            .await?;
+        let result: Cached<Artists> = self.load_cached(request, "related-artists", id).await?;
         Ok(result.map(|result| result.artists))
     }
+
+    /// Biography and listener statistics of the artist.  These are not a part
+    /// of the public Web API, so we ask the GraphQL endpoint of the web player.
+    pub async fn get_artist_info(&self, id: &str) -> Result<ArtistInfo, Error> {
+        // Hash of the persisted `queryArtistOverview` query.
+        const QUERY_HASH: &str = "35648a112beb1794e39ab931365f6ae4a8d45e65396d641eeda94e4003d41497";
+
+        #[derive(Deserialize)]
+        struct Response {
+            data: ResponseData,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ResponseData {
+            artist_union: ArtistUnion,
+        }
+        #[derive(Deserialize)]
+        struct ArtistUnion {
+            profile: Profile,
+            stats: Option<Stats>,
+        }
+        #[derive(Deserialize)]
+        struct Profile {
+            biography: Option<Biography>,
+        }
+        #[derive(Deserialize)]
+        struct Biography {
+            text: Option<String>,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Stats {
+            monthly_listeners: Option<u64>,
+            followers: Option<u64>,
+        }
+
+        let token = self.access_token().await?;
+        let variables = json!({ "uri": format!("spotify:artist:{}", id), "locale": "" });
+        let extensions = json!({
+            "persistedQuery": {
+                "version": 1,
+                "sha256Hash": QUERY_HASH,
+            }
+        });
+        let request = Request::new(
+            Method::GET,
+            "https://api-partner.spotify.com/pathfinder/v1/query",
+        )
+        .query("operationName", "queryArtistOverview")
+        .query("variables", &variables.to_string())
+        .query("extensions", &extensions.to_string())
+        .set("Authorization", &format!("Bearer {}", &token))
+        .set("App-Platform", "WebPlayer");
+        let result: Response = self.load(request).await?;
+        let artist = result.data.artist_union;
+        let biography = artist
+            .profile
+            .biography
+            .and_then(|biography| biography.text)
+            .map(|text| strip_html(&text))
+            .unwrap_or_default();
+        Ok(ArtistInfo {
+            biography: biography.into(),
+            monthly_listeners: artist
+                .stats
+                .as_ref()
+                .and_then(|stats| stats.monthly_listeners),
+            followers: artist.stats.as_ref().and_then(|stats| stats.followers),
+        })
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/follow/check-current-user-follows/
+    pub async fn is_following_artist(&self, id: &str) -> Result<bool, Error> {
+        let request = self
+            .get("v1/me/following/contains")
+            .await?
+            .query("type", "artist")
+            .query("ids", id);
+        let result: Vec<bool> = self.load(request).await?;
+        Ok(result.first().copied().unwrap_or(false))
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/follow/follow-artists-users/
+    pub async fn follow_artist(&self, id: &str) -> Result<(), Error> {
+        let request = self
+            .put("v1/me/following")
+            .await?
+            .query("type", "artist")
+            .query("ids", id);
+        self.send_empty_json(request).await
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/follow/unfollow-artists-users/
+    pub async fn unfollow_artist(&self, id: &str) -> Result<(), Error> {
+        let request = self
+            .delete("v1/me/following")
+            .await?
+            .query("type", "artist")
+            .query("ids", id);
+        self.send_empty_json(request).await
+    }
+}
+
+/// Track endpoints.
+impl WebApi {
+    // https://developer.spotify.com/documentation/web-api/reference/tracks/get-several-tracks/
+    pub async fn get_tracks(&self, track_ids: &[TrackId]) -> Result<Vector<Arc<Track>>, Error> {
+        // The endpoint accepts at most 50 tracks per request.
+        const MAX_TRACKS: usize = 50;
+
+        #[derive(Deserialize)]
+        struct Tracks {
+            tracks: Vector<Option<Arc<Track>>>,
+        }
+
+        let mut tracks = Vector::new();
+        for chunk in track_ids.chunks(MAX_TRACKS) {
+            let ids = chunk
+                .iter()
+                .map(|track_id| track_id.to_base62())
+                .collect::<Vec<_>>()
+                .join(",");
+            let request = self
+                .get("v1/tracks")
+                .await?
+                .query("ids", &ids)
+                .query("market", "from_token");
+            let result: Tracks = self.load(request).await?;
+            tracks.extend(result.tracks.into_iter().flatten());
+        }
+        Ok(tracks)
+    }
 }
 
 /// Album endpoints.
 impl WebApi {
     // https://developer.spotify.com/documentation/web-api/reference/albums/get-album/
-    pub fn get_album(&self, id: &str) -> Result<Cached<Album>, Error> {
+    pub async fn get_album(&self, id: &str) -> Result<Cached<Album>, Error> {
         let request = self
-            .get(format!("v1/albums/{}", id))?
+            .get(format!("v1/albums/{}", id))
+            .await?
             .query("market", "from_token");
-        let result = self.load_cached(request, "album", id)?;
+        let result = self.load_cached(request, "album", id).await?;
+        Ok(result)
+    }
+}
+
+/// Show endpoints.
+impl WebApi {
+    // https://developer.spotify.com/documentation/web-api/reference/shows/get-a-show/
+    pub async fn get_show(&self, id: &str) -> Result<Show, Error> {
+        let request = self
+            .get(format!("v1/shows/{}", id))
+            .await?
+            .query("market", "from_token");
+        let result = self.load(request).await?;
+        Ok(result)
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/shows/get-shows-episodes/
+    pub async fn get_show_episodes(&self, id: &str) -> Result<Vector<Arc<Episode>>, Error> {
+        let request = self
+            .get(format!("v1/shows/{}/episodes", id))
+            .await?
+            .query("market", "from_token");
+        let result = self.load_all_pages(request).await?;
         Ok(result)
     }
 }
 
 /// Library endpoints.
 impl WebApi {
+    // https://developer.spotify.com/documentation/web-api/reference/follow/get-followed/
+    pub async fn get_followed_artists(
+        &self,
+        after: Option<&str>,
+    ) -> Result<FollowedArtists, Error> {
+        #[derive(Deserialize)]
+        struct Response {
+            artists: CursorPage,
+        }
+        #[derive(Deserialize)]
+        struct CursorPage {
+            items: Vector<Artist>,
+            next: Option<String>,
+            cursors: Cursors,
+        }
+        #[derive(Deserialize)]
+        struct Cursors {
+            after: Option<Arc<str>>,
+        }
+
+        let mut request = self
+            .get("v1/me/following")
+            .await?
+            .query("type", "artist")
+            .query("limit", "50");
+        if let Some(after) = after {
+            request = request.query("after", after);
+        }
+        let result: Response = self.load(request).await?;
+        let page = result.artists;
+        Ok(FollowedArtists {
+            artists: page.items,
+            next_cursor: page.next.and(page.cursors.after),
+        })
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/library/get-users-saved-shows/
+    pub async fn get_saved_shows(
+        &self,
+        mut on_page: impl FnMut(usize, Vector<Show>) + Send,
+    ) -> Result<(), Error> {
+        #[derive(Clone, Deserialize)]
+        struct SavedShow {
+            show: Show,
+        }
+
+        let request = self.get("v1/me/shows").await?;
+
+        self.load_pages_with(
+            request,
+            Some("saved-shows"),
+            |offset, items: Vector<SavedShow>| {
+                on_page(offset, items.into_iter().map(|item| item.show).collect())
+            },
+        )
+        .await
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/library/save-shows-user/
+    pub async fn save_show(&self, id: &str) -> Result<(), Error> {
+        let request = self.put("v1/me/shows").await?.query("ids", id);
+        self.send_empty_json(request).await?;
+        Ok(())
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/library/remove-shows-user/
+    pub async fn unsave_show(&self, id: &str) -> Result<(), Error> {
+        let request = self.delete("v1/me/shows").await?.query("ids", id);
+        self.send_empty_json(request).await?;
+        Ok(())
+    }
+
     // https://developer.spotify.com/documentation/web-api/reference/library/get-users-saved-albums/
-    pub fn get_saved_albums(&self) -> Result<Vector<Album>, Error> {
+    pub async fn get_saved_albums(
+        &self,
+        mut on_page: impl FnMut(usize, Vector<Album>) + Send,
+    ) -> Result<(), Error> {
         #[derive(Clone, Deserialize)]
         struct SavedAlbum {
             album: Album,
         }
 
-        let request = self.get("v1/me/albums")?.query("market", "from_token");
+        let request = self
+            .get("v1/me/albums")
+            .await?
+            .query("market", "from_token");
 
-        Ok(self
-            .load_all_pages(request)?
-            .into_iter()
-            .map(|item: SavedAlbum| item.album)
-            .collect())
+        self.load_pages_with(
+            request,
+            Some("saved-albums"),
+            |offset, items: Vector<SavedAlbum>| {
+                on_page(offset, items.into_iter().map(|item| item.album).collect())
+            },
+        )
+        .await
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/library/check-users-saved-albums/
+    pub async fn contains_saved_albums(&self, ids: &[Arc<str>]) -> Result<Vector<bool>, Error> {
+        // The endpoint accepts at most 20 albums per request.
+        const MAX_ALBUMS: usize = 20;
+
+        let mut saved = Vector::new();
+        for chunk in ids.chunks(MAX_ALBUMS) {
+            let request = self
+                .get("v1/me/albums/contains")
+                .await?
+                .query("ids", &chunk.join(","));
+            let result: Vector<bool> = self.load(request).await?;
+            saved.append(result);
+        }
+        Ok(saved)
     }
 
     // https://developer.spotify.com/documentation/web-api/reference/library/save-albums-user/
-    pub fn save_album(&self, id: &str) -> Result<(), Error> {
-        let request = self.put("v1/me/albums")?.query("ids", id);
-        self.send_empty_json(request)?;
+    pub async fn save_album(&self, id: &str) -> Result<(), Error> {
+        let request = self.put("v1/me/albums").await?.query("ids", id);
+        self.send_empty_json(request).await?;
         Ok(())
     }
 
     // https://developer.spotify.com/documentation/web-api/reference/library/remove-albums-user/
-    pub fn unsave_album(&self, id: &str) -> Result<(), Error> {
-        let request = self.delete("v1/me/albums")?.query("ids", id);
-        self.send_empty_json(request)?;
+    pub async fn unsave_album(&self, id: &str) -> Result<(), Error> {
+        let request = self.delete("v1/me/albums").await?.query("ids", id);
+        self.send_empty_json(request).await?;
         Ok(())
     }
 
     // https://developer.spotify.com/documentation/web-api/reference/library/get-users-saved-tracks/
-    pub fn get_saved_tracks(&self) -> Result<Vector<Arc<Track>>, Error> {
+    pub async fn get_saved_tracks(&self, offset: usize) -> Result<SavedTracks, Error> {
         #[derive(Clone, Deserialize)]
         struct SavedTrack {
+            added_at: DateTime<Utc>,
             track: Arc<Track>,
         }
 
-        let request = self.get("v1/me/tracks")?.query("market", "from_token");
+        let request = self
+            .get("v1/me/tracks")
+            .await?
+            .query("market", "from_token");
+        let page: Page<SavedTrack> = self
+            .load_page(request, offset, Some("saved-tracks"))
+            .await?;
+        let next_offset = page.next_offset();
+
+        // Spotify returns the most recently saved tracks first.
+        Ok(SavedTracks {
+            added_at: page
+                .items
+                .iter()
+                .map(|item| (item.track.id, item.added_at))
+                .collect(),
+            tracks: page.items.into_iter().map(|item| item.track).collect(),
+            next_offset,
+        })
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/library/check-users-saved-tracks/
+    pub async fn contains_saved_tracks(
+        &self,
+        track_ids: &[TrackId],
+    ) -> Result<Vector<bool>, Error> {
+        // The endpoint accepts at most 50 tracks per request.
+        const MAX_TRACKS: usize = 50;
+
+        let mut saved = Vector::new();
+        for chunk in track_ids.chunks(MAX_TRACKS) {
+            let ids = chunk
+                .iter()
+                .map(|track_id| track_id.to_base62())
+                .collect::<Vec<_>>()
+                .join(",");
+            let request = self.get("v1/me/tracks/contains").await?.query("ids", &ids);
+            let result: Vector<bool> = self.load(request).await?;
+            saved.append(result);
+        }
+        Ok(saved)
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/library/save-tracks-user/
+    pub async fn save_track(&self, id: &str) -> Result<(), Error> {
+        let request = self.put("v1/me/tracks").await?.query("ids", id);
+        self.send_empty_json(request).await?;
+        Ok(())
+    }
 
-        Ok(self
-            .load_all_pages(request)?
-            .into_iter()
-            .map(|item: SavedTrack| item.track)
-            .collect())
+    // https://developer.spotify.com/documentation/web-api/reference/library/remove-tracks-user/
+    pub async fn unsave_track(&self, id: &str) -> Result<(), Error> {
+        let request = self.delete("v1/me/tracks").await?.query("ids", id);
+        self.send_empty_json(request).await?;
+        Ok(())
     }
 
     // https://developer.spotify.com/documentation/web-api/reference/library/save-tracks-user/
-    pub fn save_track(&self, id: &str) -> Result<(), Error> {
-        let request = self.put("v1/me/tracks")?.query("ids", id);
-        self.send_empty_json(request)?;
+    pub async fn save_tracks(&self, track_ids: &[TrackId]) -> Result<(), Error> {
+        // The endpoint accepts at most 50 tracks per request.
+        const MAX_TRACKS: usize = 50;
+
+        for chunk in track_ids.chunks(MAX_TRACKS) {
+            let ids: Vec<String> = chunk.iter().map(|track_id| track_id.to_base62()).collect();
+            let request = self.put("v1/me/tracks").await?;
+            self.send_json_body(request, json!({ "ids": ids })).await?;
+        }
         Ok(())
     }
 
     // https://developer.spotify.com/documentation/web-api/reference/library/remove-tracks-user/
-    pub fn unsave_track(&self, id: &str) -> Result<(), Error> {
-        let request = self.delete("v1/me/tracks")?.query("ids", id);
-        self.send_empty_json(request)?;
+    pub async fn unsave_tracks(&self, track_ids: &[TrackId]) -> Result<(), Error> {
+        // The endpoint accepts at most 50 tracks per request.
+        const MAX_TRACKS: usize = 50;
+
+        for chunk in track_ids.chunks(MAX_TRACKS) {
+            let ids: Vec<String> = chunk.iter().map(|track_id| track_id.to_base62()).collect();
+            let request = self.delete("v1/me/tracks").await?;
+            self.send_json_body(request, json!({ "ids": ids })).await?;
+        }
         Ok(())
     }
 }
 
+/// Personalization endpoints.
+impl WebApi {
+    // https://developer.spotify.com/documentation/web-api/reference/personalization/get-users-top-artists-and-tracks/
+    pub async fn get_top_tracks(&self, range: TopRange) -> Result<Vector<Arc<Track>>, Error> {
+        let request = self
+            .get("v1/me/top/tracks")
+            .await?
+            .query("time_range", range.as_str())
+            .query("limit", "50");
+        let result: Page<Arc<Track>> = self.load(request).await?;
+        Ok(result.items)
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/personalization/get-users-top-artists-and-tracks/
+    pub async fn get_top_artists(&self, range: TopRange) -> Result<Vector<Artist>, Error> {
+        let request = self
+            .get("v1/me/top/artists")
+            .await?
+            .query("time_range", range.as_str())
+            .query("limit", "50");
+        let result: Page<Artist> = self.load(request).await?;
+        Ok(result.items)
+    }
+}
+
 /// Playlist endpoints.
 impl WebApi {
     // https://developer.spotify.com/documentation/web-api/reference/playlists/get-a-list-of-current-users-playlists/
-    pub fn get_playlists(&self) -> Result<Vector<Playlist>, Error> {
-        let request = self.get("v1/me/playlists")?;
-        let result = self.load_all_pages(request)?;
-        Ok(result)
+    pub async fn get_playlists(
+        &self,
+        on_page: impl FnMut(usize, Vector<Playlist>) + Send,
+    ) -> Result<(), Error> {
+        let request = self.get("v1/me/playlists").await?;
+        self.load_pages_with(request, Some("playlists"), on_page)
+            .await
     }
 
     // https://developer.spotify.com/documentation/web-api/reference/playlists/get-playlist-tracks/
-    pub fn get_playlist_tracks(&self, id: &str) -> Result<Vector<Arc<Track>>, Error> {
+    pub async fn get_playlist_tracks(&self, id: &str) -> Result<Vector<Arc<Track>>, Error> {
         #[derive(Clone, Deserialize)]
         struct PlaylistItem {
             track: Option<Arc<Track>>,
         }
 
         let request = self
-            .get(format!("v1/playlists/{}/tracks", id))?
-            .query("marker", "from_token")
+            .get(format!("v1/playlists/{}/tracks", id))
+            .await?
+            .query("market", "from_token")
             .query("additional_types", "track");
-        let result: Vector<PlaylistItem> = self.load_all_pages(request)?;
+        let result: Vector<PlaylistItem> = self.load_all_pages(request).await?;
 
         Ok(result.into_iter().filter_map(|item| item.track).collect())
     }
+
+    // https://developer.spotify.com/documentation/web-api/reference/playlists/get-playlist-tracks/
+    pub async fn get_playlist_tracks_page(
+        &self,
+        link: &PlaylistLink,
+        snapshot_id: Option<&str>,
+        offset: usize,
+    ) -> Result<PlaylistTracks, Error> {
+        #[derive(Clone, Deserialize)]
+        struct PlaylistItem {
+            track: Option<Arc<Track>>,
+        }
+
+        let request = self
+            .get(format!("v1/playlists/{}/tracks", link.id))
+            .await?
+            .query("market", "from_token")
+            .query("additional_types", "track");
+        let page: Page<PlaylistItem> = match snapshot_id {
+            Some(snapshot_id) => {
+                // A snapshot never changes, so pages stored under it can be
+                // used without asking the server.
+                let key = format!("playlist-{}-{}", link.id, snapshot_key(snapshot_id));
+                match self.cache.get("library", &format!("{}-{}", key, offset)) {
+                    Some(file) => serde_json::from_reader(file)?,
+                    None => self.load_page(request, offset, Some(&key)).await?,
+                }
+            }
+            None => {
+                let key = format!("playlist-{}", link.id);
+                self.load_page(request, offset, Some(&key)).await?
+            }
+        };
+        let next_offset = page.next_offset();
+
+        Ok(PlaylistTracks {
+            id: link.id.clone(),
+            name: link.name.clone(),
+            tracks: page
+                .items
+                .into_iter()
+                .filter_map(|item| item.track)
+                .collect(),
+            next_offset,
+        })
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/playlists/create-playlist/
+    pub async fn create_playlist(&self, name: &str, description: &str) -> Result<Playlist, Error> {
+        let user = self.get_user_profile().await?;
+        let request = self.post(format!("v1/users/{}/playlists", user.id)).await?;
+        let result = self
+            .send_json(
+                request,
+                json!({
+                    "name": name,
+                    "description": description,
+                    "public": false,
+                }),
+            )
+            .await?;
+        Ok(result)
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/follow/follow-playlist/
+    pub async fn follow_playlist(&self, id: &str) -> Result<(), Error> {
+        let request = self.put(format!("v1/playlists/{}/followers", id)).await?;
+        self.send_empty_json(request).await
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/follow/unfollow-playlist/
+    pub async fn unfollow_playlist(&self, id: &str) -> Result<(), Error> {
+        let request = self
+            .delete(format!("v1/playlists/{}/followers", id))
+            .await?;
+        self.send_empty_json(request).await
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/playlists/change-playlist-details/
+    pub async fn rename_playlist(&self, id: &str, name: &str) -> Result<(), Error> {
+        let request = self.put(format!("v1/playlists/{}", id)).await?;
+        self.send_json_body(request, json!({ "name": name })).await
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/playlists/add-tracks-to-playlist/
+    /// Add the tracks at the end, returning the new snapshot ID of the playlist.
+    pub async fn add_tracks_to_playlist(
+        &self,
+        id: &str,
+        track_ids: &[TrackId],
+    ) -> Result<Arc<str>, Error> {
+        // The endpoint accepts at most 100 tracks per request.
+        const MAX_TRACKS: usize = 100;
+
+        let mut snapshot_id = None;
+        for chunk in track_ids.chunks(MAX_TRACKS) {
+            let uris: Vec<String> = chunk
+                .iter()
+                .map(|track_id| format!("spotify:track:{}", track_id.to_base62()))
+                .collect();
+            let request = self.post(format!("v1/playlists/{}/tracks", id)).await?;
+            let result: PlaylistSnapshot = self.send_json(request, json!({ "uris": uris })).await?;
+            snapshot_id.replace(result.snapshot_id);
+        }
+        snapshot_id.ok_or_else(|| Error::WebApiError("No tracks to add".to_string()))
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/playlists/reorder-playlists-tracks/
+    pub async fn move_playlist_track(
+        &self,
+        id: &str,
+        range_start: usize,
+        insert_before: usize,
+        snapshot_id: &str,
+    ) -> Result<Arc<str>, Error> {
+        let request = self.put(format!("v1/playlists/{}/tracks", id)).await?;
+        let result: PlaylistSnapshot = self
+            .send_json(
+                request,
+                json!({
+                    "range_start": range_start,
+                    "insert_before": insert_before,
+                    "snapshot_id": snapshot_id,
+                }),
+            )
+            .await?;
+        Ok(result.snapshot_id)
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/playlists/get-playlist/
+    pub async fn get_playlist(&self, id: &str) -> Result<Playlist, Error> {
+        let request = self.get(format!("v1/playlists/{}", id)).await?;
+        let result = self.load(request).await?;
+        Ok(result)
+    }
+}
+
+/// Playback and navigation of `spotify:` URIs and `open.spotify.com` links.
+impl WebApi {
+    pub async fn load_uri(&self, uri: &str) -> Result<PlaybackPayload, Error> {
+        let url = SpotifyUrl::parse(uri)
+            .ok_or_else(|| Error::WebApiError(format!("Unsupported URI: {}", uri)))?;
+        self.load_playback(&url).await
+    }
+
+    /// Resolve the tracks of the linked item, ready to be played.
+    pub async fn load_playback(&self, url: &SpotifyUrl) -> Result<PlaybackPayload, Error> {
+        let (origin, tracks) = match url {
+            SpotifyUrl::Track(id) => {
+                let id: TrackId = id
+                    .parse()
+                    .map_err(|_| Error::WebApiError(format!("Invalid track ID: {}", id)))?;
+                (PlaybackOrigin::Library, self.get_tracks(&[id]).await?)
+            }
+            SpotifyUrl::Album(id) => {
+                let album = self.get_album(&id).await?.data;
+                (PlaybackOrigin::Album(album.link()), album.tracks)
+            }
+            SpotifyUrl::Playlist(id) => {
+                let playlist = self.get_playlist(&id).await?;
+                (
+                    PlaybackOrigin::Playlist(playlist.link()),
+                    self.get_playlist_tracks(&id).await?,
+                )
+            }
+            SpotifyUrl::Artist(id) => {
+                let artist = self.get_artist(&id).await?;
+                (
+                    PlaybackOrigin::Artist(artist.link()),
+                    self.get_artist_top_tracks(&id).await?,
+                )
+            }
+        };
+        Ok(PlaybackPayload {
+            origin,
+            tracks,
+            position: 0,
+        })
+    }
+
+    /// Resolve the page showing the linked item.  Tracks are shown on their
+    /// album page.
+    pub async fn load_nav(&self, url: &SpotifyUrl) -> Result<Nav, Error> {
+        let nav = match url {
+            SpotifyUrl::Track(id) => {
+                let id: TrackId = id
+                    .parse()
+                    .map_err(|_| Error::WebApiError(format!("Invalid track ID: {}", id)))?;
+                let album = self
+                    .get_tracks(&[id])
+                    .await?
+                    .into_iter()
+                    .next()
+                    .and_then(|track| track.album.clone())
+                    .ok_or_else(|| Error::WebApiError("Track has no album".to_string()))?;
+                Nav::AlbumDetail(album)
+            }
+            SpotifyUrl::Album(id) => Nav::AlbumDetail(self.get_album(id).await?.data.link()),
+            SpotifyUrl::Artist(id) => Nav::ArtistDetail(self.get_artist(id).await?.link()),
+            SpotifyUrl::Playlist(id) => Nav::PlaylistDetail(self.get_playlist(id).await?.link()),
+        };
+        Ok(nav)
+    }
 }
 
 /// Search endpoints.
 impl WebApi {
     // https://developer.spotify.com/documentation/web-api/reference/search/
-    pub fn search(&self, query: &str) -> Result<SearchResults, Error> {
+    pub async fn search(&self, query: &str) -> Result<SearchResults, Error> {
+        let request = self
+            .get("v1/search")
+            .await?
+            .query("type", "artist,album,track,playlist,show");
+        self.load_search_results(request, query).await
+    }
+
+    /// A few of the best matches for `query`, shown while typing.
+    pub async fn search_suggestions(&self, query: &str) -> Result<SearchResults, Error> {
+        let request = self
+            .get("v1/search")
+            .await?
+            .query("type", "artist,album,track")
+            .query("limit", "3");
+        self.load_search_results(request, query).await
+    }
+
+    /// Load a page of the results of a single type, starting at `offset`.
+    pub async fn search_topic(
+        &self,
+        query: &str,
+        topic: SearchTopic,
+        offset: usize,
+    ) -> Result<SearchResults, Error> {
+        const PAGE_LIMIT: usize = 50;
+
+        let request = self
+            .get("v1/search")
+            .await?
+            .query("type", topic.as_str())
+            .query("limit", &PAGE_LIMIT.to_string())
+            .query("offset", &offset.to_string());
+        self.load_search_results(request, query).await
+    }
+
+    async fn load_search_results(
+        &self,
+        request: Request,
+        query: &str,
+    ) -> Result<SearchResults, Error> {
         #[derive(Deserialize)]
         struct ApiSearchResults {
             artists: Option<Page<Artist>>,
             albums: Option<Page<Album>>,
             tracks: Option<Page<Arc<Track>>>,
             playlists: Option<Page<Playlist>>,
+            shows: Option<Page<Show>>,
         }
 
-        let request = self
-            .get("v1/search")?
-            .query("q", query)
-            .query("type", "artist,album,track,playlist")
-            .query("marker", "from_token");
-        let result: ApiSearchResults = self.load(request)?;
-
-        let artists = result.artists.map_or_else(Vector::new, |page| page.items);
-        let albums = result.albums.map_or_else(Vector::new, |page| page.items);
-        let tracks = result.tracks.map_or_else(Vector::new, |page| page.items);
-        let playlists = result.playlists.map_or_else(Vector::new, |page| page.items);
+        let request = request.query("q", query).query("market", "from_token");
+        let result: ApiSearchResults = self.load(request).await?;
+
+        fn items<T: Clone>(
+            topic: SearchTopic,
+            page: Option<Page<T>>,
+            more: &mut HashSet<SearchTopic>,
+            next_offset: &mut Option<usize>,
+        ) -> Vector<T> {
+            match page {
+                Some(page) => {
+                    if let Some(offset) = page.next_offset() {
+                        more.insert(topic);
+                        *next_offset = Some(offset);
+                    }
+                    page.items
+                }
+                None => Vector::new(),
+            }
+        }
+
+        let mut more = HashSet::new();
+        let mut next_offset = None;
+        let artists = items(
+            SearchTopic::Artist,
+            result.artists,
+            &mut more,
+            &mut next_offset,
+        );
+        let albums = items(
+            SearchTopic::Album,
+            result.albums,
+            &mut more,
+            &mut next_offset,
+        );
+        let tracks = items(
+            SearchTopic::Track,
+            result.tracks,
+            &mut more,
+            &mut next_offset,
+        );
+        let playlists = items(
+            SearchTopic::Playlist,
+            result.playlists,
+            &mut more,
+            &mut next_offset,
+        );
+        let shows = items(SearchTopic::Show, result.shows, &mut more, &mut next_offset);
         Ok(SearchResults {
             query: query.to_string(),
             artists,
             albums,
             tracks,
             playlists,
+            shows,
+            more,
+            next_offset,
+        })
+    }
+}
+
+/// Browse endpoints.
+impl WebApi {
+    // https://developer.spotify.com/documentation/web-api/reference/browse/get-recommendations/
+    pub async fn get_recommendations(
+        &self,
+        seed_tracks: &[TrackId],
+        seed_artists: &[Arc<str>],
+    ) -> Result<Vector<Arc<Track>>, Error> {
+        #[derive(Deserialize)]
+        struct Recommendations {
+            tracks: Vector<Arc<Track>>,
+        }
+
+        let mut request = self
+            .get("v1/recommendations")
+            .await?
+            .query("limit", "50")
+            .query("market", "from_token");
+        if !seed_tracks.is_empty() {
+            let seed_tracks = seed_tracks
+                .iter()
+                .map(|id| id.to_base62())
+                .collect::<Vec<_>>()
+                .join(",");
+            request = request.query("seed_tracks", &seed_tracks);
+        }
+        if !seed_artists.is_empty() {
+            request = request.query("seed_artists", &seed_artists.join(","));
+        }
+        let result: Recommendations = self.load(request).await?;
+        Ok(result.tracks)
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/browse/get-list-featured-playlists/
+    pub async fn get_featured_playlists(&self) -> Result<FeaturedPlaylists, Error> {
+        #[derive(Deserialize)]
+        struct Featured {
+            message: Option<Arc<str>>,
+            playlists: Page<Playlist>,
+        }
+
+        let request = self
+            .get("v1/browse/featured-playlists")
+            .await?
+            .query("limit", "10");
+        let result: Featured = self.load(request).await?;
+        Ok(FeaturedPlaylists {
+            message: result.message,
+            playlists: result.playlists.items,
         })
     }
+
+    // https://developer.spotify.com/documentation/web-api/reference/browse/get-list-new-releases/
+    pub async fn get_new_releases(&self) -> Result<Vector<Album>, Error> {
+        #[derive(Deserialize)]
+        struct NewReleases {
+            albums: Page<Album>,
+        }
+
+        let request = self
+            .get("v1/browse/new-releases")
+            .await?
+            .query("limit", "10");
+        let result: NewReleases = self.load(request).await?;
+        Ok(result.albums.items)
+    }
 }
 
 /// Track endpoints.
 impl WebApi {
     // https://developer.spotify.com/documentation/web-api/reference/tracks/get-audio-analysis/
-    pub fn get_audio_analysis(&self, track_id: &str) -> Result<AudioAnalysis, Error> {
-        let request = self.get(format!("v1/audio-analysis/{}", track_id))?;
-        let result = self.load_cached(request, "audio-analysis", track_id)?;
+    pub async fn get_audio_analysis(&self, track_id: &str) -> Result<AudioAnalysis, Error> {
+        let request = self.get(format!("v1/audio-analysis/{}", track_id)).await?;
+        let result = self
+            .load_cached(request, "audio-analysis", track_id)
+            .await?;
         Ok(result.data)
     }
+
+    // https://developer.spotify.com/documentation/web-api/reference/tracks/get-audio-features/
+    pub async fn get_audio_features(&self, track_id: &str) -> Result<AudioFeatures, Error> {
+        let request = self.get(format!("v1/audio-features/{}", track_id)).await?;
+        let result = self
+            .load_cached(request, "audio-features", track_id)
+            .await?;
+        Ok(result.data)
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/tracks/get-several-audio-features/
+    pub async fn get_several_audio_features(
+        &self,
+        track_ids: &[TrackId],
+    ) -> Result<Vector<AudioFeatures>, Error> {
+        // The endpoint accepts at most 100 tracks per request.
+        const MAX_TRACKS: usize = 100;
+
+        #[derive(Deserialize)]
+        struct Features {
+            audio_features: Vector<Option<AudioFeatures>>,
+        }
+
+        // Share the cache with `get_audio_features`, so look the tracks up one
+        // by one first, and only ask for the missing ones.
+        let mut features = Vector::new();
+        let mut missing = Vec::new();
+        for track_id in track_ids {
+            let cached = self
+                .cache
+                .get("audio-features", &track_id.to_base62())
+                .and_then(|file| serde_json::from_reader(file).ok());
+            match cached {
+                Some(cached) => features.push_back(cached),
+                None => missing.push(*track_id),
+            }
+        }
+        for chunk in missing.chunks(MAX_TRACKS) {
+            let ids = chunk
+                .iter()
+                .map(|track_id| track_id.to_base62())
+                .collect::<Vec<_>>()
+                .join(",");
+            let request = self.get("v1/audio-features").await?.query("ids", &ids);
+            let result: Features = self.load(request).await?;
+            for track_features in result.audio_features.into_iter().flatten() {
+                if let Ok(body) = serde_json::to_vec(&track_features) {
+                    self.cache
+                        .set("audio-features", &track_features.id.to_base62(), &body);
+                }
+                features.push_back(track_features);
+            }
+        }
+        Ok(features)
+    }
+
+    /// Lyrics as shown in the official clients.  This is not a part of the
+    /// public Web API, so the response format can change without notice.
+    pub async fn get_lyrics(&self, track_id: TrackId) -> Result<Lyrics, Error> {
+        #[derive(Deserialize)]
+        struct ColorLyrics {
+            lyrics: RawLyrics,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RawLyrics {
+            sync_type: String,
+            lines: Vec<RawLine>,
+            provider_display_name: Option<Arc<str>>,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RawLine {
+            start_time_ms: String,
+            words: Arc<str>,
+        }
+
+        let key = track_id.to_base62();
+        if let Some(file) = self.cache.get("lyrics", &key) {
+            return Ok(serde_json::from_reader(file)?);
+        }
+        let token = self.access_token().await?;
+        let request = Request::new(
+            Method::GET,
+            format!(
+                "https://spclient.wg.spotify.com/color-lyrics/v2/track/{}",
+                key
+            ),
+        )
+        .query("format", "json")
+        .query("market", "from_token")
+        .set("Authorization", &format!("Bearer {}", &token))
+        .set("App-Platform", "WebPlayer");
+        let response = request.build(&self.client).send().await?;
+        let lyrics = match response.status() {
+            // The track doesn't have any lyrics.
+            StatusCode::NOT_FOUND => Lyrics::default(),
+            status if !status.is_success() => return Err(status_error(&response)),
+            _ => {
+                let result: ColorLyrics = serde_json::from_slice(&read_body(response).await?)?;
+                Lyrics {
+                    lines: result
+                        .lyrics
+                        .lines
+                        .into_iter()
+                        .map(|line| LyricsLine {
+                            start: Duration::from_millis(line.start_time_ms.parse().unwrap_or(0)),
+                            words: line.words,
+                        })
+                        .collect(),
+                    synced: result.lyrics.sync_type == "LINE_SYNCED",
+                    provider: result.lyrics.provider_display_name,
+                }
+            }
+        };
+        if let Ok(body) = serde_json::to_vec(&lyrics) {
+            self.cache.set("lyrics", &key, &body);
+        }
+        Ok(lyrics)
+    }
+}
+
+/// Player endpoints.
+impl WebApi {
+    // https://developer.spotify.com/documentation/web-api/reference/player/get-a-users-available-devices/
+    pub async fn get_devices(&self) -> Result<Vector<Device>, Error> {
+        #[derive(Deserialize)]
+        struct Devices {
+            devices: Vector<Device>,
+        }
+
+        let request = self.get("v1/me/player/devices").await?;
+        let result: Devices = self.load(request).await?;
+        Ok(result.devices)
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/player/get-information-about-the-users-current-playback/
+    pub async fn get_remote_playback(&self) -> Result<Option<RemotePlayback>, Error> {
+        let request = self
+            .get("v1/me/player")
+            .await?
+            .query("market", "from_token")
+            .query("additional_types", "track");
+        let response = self.with_retry(&request, None).await?;
+        // Nothing is playing on any of the devices.
+        if response.status() == StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_slice(&read_body(response).await?)?))
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/player/get-recently-played/
+    pub async fn get_recently_played(&self, before: Option<&str>) -> Result<RecentlyPlayed, Error> {
+        #[derive(Deserialize)]
+        struct CursorPage {
+            items: Vector<RecentlyPlayedItem>,
+            next: Option<String>,
+            cursors: Option<Cursors>,
+        }
+        #[derive(Deserialize)]
+        struct Cursors {
+            before: Option<Arc<str>>,
+        }
+
+        let mut request = self
+            .get("v1/me/player/recently-played")
+            .await?
+            .query("limit", "50");
+        if let Some(before) = before {
+            request = request.query("before", before);
+        }
+        let page: CursorPage = self.load(request).await?;
+        Ok(RecentlyPlayed {
+            items: page.items,
+            next_cursor: page
+                .next
+                .and(page.cursors.and_then(|cursors| cursors.before)),
+        })
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/player/transfer-a-users-playback/
+    pub async fn transfer_playback(&self, device_id: &str) -> Result<(), Error> {
+        let request = self.put("v1/me/player").await?;
+        self.send_json_body(request, json!({ "device_ids": [device_id], "play": true }))
+            .await?;
+        Ok(())
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/player/start-a-users-playback/
+    pub async fn remote_play(&self, device_id: &str) -> Result<(), Error> {
+        let request = self
+            .put("v1/me/player/play")
+            .await?
+            .query("device_id", device_id);
+        self.send_empty_json(request).await?;
+        Ok(())
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/player/pause-a-users-playback/
+    pub async fn remote_pause(&self, device_id: &str) -> Result<(), Error> {
+        let request = self
+            .put("v1/me/player/pause")
+            .await?
+            .query("device_id", device_id);
+        self.send_empty_json(request).await?;
+        Ok(())
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/player/skip-users-playback-to-next-track/
+    pub async fn remote_next(&self, device_id: &str) -> Result<(), Error> {
+        let request = self
+            .post("v1/me/player/next")
+            .await?
+            .query("device_id", device_id);
+        self.send_empty_json(request).await?;
+        Ok(())
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/player/skip-users-playback-to-previous-track/
+    pub async fn remote_previous(&self, device_id: &str) -> Result<(), Error> {
+        let request = self
+            .post("v1/me/player/previous")
+            .await?
+            .query("device_id", device_id);
+        self.send_empty_json(request).await?;
+        Ok(())
+    }
+
+    // https://developer.spotify.com/documentation/web-api/reference/player/set-volume-for-users-playback/
+    pub async fn set_remote_volume(
+        &self,
+        device_id: &str,
+        volume_percent: u32,
+    ) -> Result<(), Error> {
+        let request = self
+            .put("v1/me/player/volume")
+            .await?
+            .query("device_id", device_id)
+            .query("volume_percent", &volume_percent.min(100).to_string());
+        self.send_empty_json(request).await?;
+        Ok(())
+    }
 }
 
 /// Image endpoints.
 impl WebApi {
-    pub fn get_image(
+    pub async fn get_image(
         &self,
         uri: &str,
         format: image::ImageFormat,
     ) -> Result<image::DynamicImage, Error> {
-        let mut image_bytes = Vec::new();
-        self.agent
-            .get(uri)
-            .call()?
-            .into_reader()
-            .read_to_end(&mut image_bytes)?;
-        let image = image::load_from_memory_with_format(&image_bytes, format)?;
+        let image_bytes: Arc<[u8]> = match self.cache.get_image(uri) {
+            Some(cached) => cached.into(),
+            // Only the cached images are shown while offline.
+            None if self.is_offline() => return Err(Self::offline_error()),
+            None => {
+                self.coalescer
+                    .fetch(uri, async {
+                        let response = self.client.get(uri).send().await?;
+                        if !response.status().is_success() {
+                            return Err(status_error(&response));
+                        }
+                        let image_bytes = read_body(response).await?;
+                        self.cache.set_image(uri, &image_bytes);
+                        Ok(image_bytes)
+                    })
+                    .await?
+            }
+        };
+        // Decoding takes a while, keep it off the threads serving the requests.
+        let image =
+            task::spawn_blocking(move || image::load_from_memory_with_format(&image_bytes, format))
+                .await
+                .map_err(|err| Error::Parse(err.to_string()))??;
         Ok(image)
     }
+
+    /// Dominant color of the image at `uri`, if it was loaded before.
+    pub fn get_image_color(&self, uri: &str) -> Option<[u8; 3]> {
+        self.cache.get_image_color(uri)
+    }
+
+    /// Compute the dominant color of `image` loaded from `uri`, and remember it
+    /// for the next time the image is shown.
+    pub fn image_color(&self, uri: &str, image: &image::DynamicImage) -> [u8; 3] {
+        // Averaging all the pixels is good enough for the placeholders.
+        let image::Rgb(color) = *image.thumbnail_exact(1, 1).to_rgb8().get_pixel(0, 0);
+        self.cache.set_image_color(uri, color);
+        color
+    }
+}
+
+/// Whole body of `response`, already decompressed.  A body that stops coming
+/// for longer than the I/O timeout counts as timed out.
+async fn read_body(mut response: Response) -> Result<Vec<u8>, Error> {
+    let mut body = Vec::new();
+    loop {
+        match time::timeout(IO_TIMEOUT, response.chunk()).await {
+            Ok(Ok(Some(chunk))) => body.extend_from_slice(&chunk),
+            Ok(Ok(None)) => break Ok(body),
+            Ok(Err(err)) => break Err(err.into()),
+            Err(_) => break Err(Error::TimedOut),
+        }
+    }
+}
+
+/// Error of a response with a failure status.
+fn status_error(response: &Response) -> Error {
+    match response.status() {
+        StatusCode::TOO_MANY_REQUESTS => Error::RateLimited {
+            retry_after: retry_after(response),
+        },
+        StatusCode::UNAUTHORIZED => Error::Unauthorized,
+        StatusCode::NOT_FOUND => Error::NotFound,
+        status => Error::WebApiError(format!("{}: status code {}", response.url(), status)),
+    }
+}
+
+/// Turn the HTML snippets some of the endpoints return into plain text.  The
+/// markup is limited to links and line breaks, so no real parsing is needed.
+fn strip_html(html: &str) -> String {
+    let html = html.replace("<br>", "\n").replace("<br/>", "\n");
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&amp;", "&")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+}
+
+/// How long the server asks us to wait before the next request.
+fn retry_after(response: &Response) -> Duration {
+    let secs = response
+        .headers()
+        .get("Retry-After")
+        .and_then(|secs| secs.to_str().ok())
+        .and_then(|secs| secs.parse().ok())
+        .unwrap_or(2);
+    Duration::from_secs(secs)
 }
 
 impl From<io::Error> for Error {
     fn from(err: io::Error) -> Self {
-        Error::WebApiError(err.to_string())
+        // Responses are read through reqwest, the rest is local files.
+        Error::Io(err.to_string())
     }
 }
 
-impl From<ureq::Error> for Error {
-    fn from(err: ureq::Error) -> Self {
-        Error::WebApiError(err.to_string())
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            Error::TimedOut
+        } else if err.is_decode() {
+            Error::Parse(err.to_string())
+        } else {
+            Error::Network(err.to_string())
+        }
     }
 }
 
 impl From<serde_json::Error> for Error {
     fn from(err: serde_json::Error) -> Self {
-        Error::WebApiError(err.to_string())
+        if err.is_io() {
+            // Bodies are parsed once read in full, so this is a cache file.
+            io::Error::from(err).into()
+        } else {
+            Error::Parse(err.to_string())
+        }
     }
 }
 
 impl From<image::ImageError> for Error {
     fn from(err: image::ImageError) -> Self {
-        Error::WebApiError(err.to_string())
+        Error::Parse(err.to_string())
     }
 }
+
+/// Snapshot IDs are base64, keep only the characters safe in file names.
+fn snapshot_key(snapshot_id: &str) -> String {
+    snapshot_id
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect()
+}
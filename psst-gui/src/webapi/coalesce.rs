@@ -0,0 +1,133 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::watch;
+
+use crate::error::Error;
+
+type FlightResult = Option<Result<Arc<[u8]>, Error>>;
+
+/// Lets concurrent requests for the same resource share a single download.
+/// The first caller does the work, the others wait for its result.
+pub struct Coalescer {
+    in_flight: Mutex<HashMap<String, watch::Sender<FlightResult>>>,
+}
+
+impl Coalescer {
+    pub fn new() -> Self {
+        Self {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn fetch(
+        &self,
+        key: &str,
+        download: impl Future<Output = Result<Vec<u8>, Error>>,
+    ) -> Result<Arc<[u8]>, Error> {
+        let waiting = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get(key) {
+                Some(flight) => Some(flight.subscribe()),
+                None => {
+                    in_flight.insert(key.to_string(), watch::channel(None).0);
+                    None
+                }
+            }
+        };
+        match waiting {
+            None => {
+                // Lets the others go even if the download panics or gets
+                // dropped half way.
+                let mut landing = Landing {
+                    coalescer: self,
+                    key,
+                    result: None,
+                };
+                let result = download.await.map(Arc::from);
+                landing.result = Some(result.clone());
+                result
+            }
+            Some(mut flight) => loop {
+                if let Some(result) = flight.borrow_and_update().clone() {
+                    return result;
+                }
+                if flight.changed().await.is_err() {
+                    return Err(abandoned());
+                }
+            },
+        }
+    }
+}
+
+fn abandoned() -> Error {
+    Error::WebApiError("Download was abandoned".into())
+}
+
+/// Ends the flight when the first caller leaves `fetch`, however it leaves.
+struct Landing<'a> {
+    coalescer: &'a Coalescer,
+    key: &'a str,
+    result: Option<Result<Arc<[u8]>, Error>>,
+}
+
+impl Drop for Landing<'_> {
+    fn drop(&mut self) {
+        let flight = match self.coalescer.in_flight.lock() {
+            Ok(mut in_flight) => in_flight.remove(self.key),
+            Err(_) => None,
+        };
+        if let Some(flight) = flight {
+            let result = self.result.take().unwrap_or_else(|| Err(abandoned()));
+            flight.send_replace(Some(result));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{sync::oneshot, task};
+
+    #[tokio::test]
+    async fn waiters_return_when_the_first_caller_panics() {
+        let coalescer = Arc::new(Coalescer::new());
+        let (release, released) = oneshot::channel::<()>();
+
+        let first = task::spawn({
+            let coalescer = coalescer.clone();
+            async move {
+                coalescer
+                    .fetch("key", async {
+                        released.await.ok();
+                        panic!("download failed");
+                    })
+                    .await
+            }
+        });
+        while !coalescer.in_flight.lock().unwrap().contains_key("key") {
+            task::yield_now().await;
+        }
+
+        let waiter = task::spawn({
+            let coalescer = coalescer.clone();
+            async move {
+                coalescer
+                    .fetch("key", async { panic!("waiter should not download") })
+                    .await
+            }
+        });
+        // The waiter is subscribed to the flight.
+        while coalescer.in_flight.lock().unwrap()["key"].receiver_count() < 1 {
+            task::yield_now().await;
+        }
+
+        release.send(()).unwrap();
+        assert!(first.await.is_err());
+        assert!(waiter.await.unwrap().is_err());
+        assert!(coalescer.in_flight.lock().unwrap().is_empty());
+    }
+}
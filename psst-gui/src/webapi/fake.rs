@@ -0,0 +1,593 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use druid::{im::Vector, image};
+
+use crate::{
+    data::{
+        Album, Artist, ArtistAlbums, ArtistInfo, AudioAnalysis, AudioFeatures, Cached, Device,
+        Episode, FeaturedPlaylists, FollowedArtists, Lyrics, Nav, PlaybackPayload, Playlist,
+        PlaylistLink, PlaylistOwner, PlaylistTracks, RecentlyPlayed, RemotePlayback, SavedTracks,
+        SearchResults, SearchTopic, Show, SpotifyUrl, TopRange, Track, TrackId, UserProfile,
+    },
+    error::Error,
+};
+
+use super::SpotifyApi;
+
+/// In-memory stand-in for the Web API.  Knows the tracks it was given, and
+/// keeps the library and playlist changes made through it.  Everything else
+/// is not found.
+#[derive(Default)]
+pub struct FakeSpotifyApi {
+    library: Mutex<Library>,
+}
+
+#[derive(Default)]
+struct Library {
+    tracks: HashMap<TrackId, Arc<Track>>,
+    playlists: Vec<Playlist>,
+    playlist_tracks: HashMap<Arc<str>, Vector<Arc<Track>>>,
+    /// Most recently saved first, like Spotify returns them.
+    saved_tracks: Vec<TrackId>,
+    saved_albums: Vec<Arc<str>>,
+    saved_shows: HashSet<Arc<str>>,
+    followed_artists: HashSet<Arc<str>>,
+    next_snapshot: usize,
+}
+
+impl Library {
+    fn playlist_mut(&mut self, id: &str) -> Result<&mut Playlist, Error> {
+        self.playlists
+            .iter_mut()
+            .find(|playlist| &*playlist.id == id)
+            .ok_or(Error::NotFound)
+    }
+
+    fn new_snapshot(&mut self) -> Arc<str> {
+        self.next_snapshot += 1;
+        format!("snapshot-{}", self.next_snapshot).into()
+    }
+}
+
+impl FakeSpotifyApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tracks(self, tracks: impl IntoIterator<Item = Arc<Track>>) -> Self {
+        {
+            let mut library = self.library.lock().unwrap();
+            for track in tracks {
+                library.tracks.insert(track.id, track);
+            }
+        }
+        self
+    }
+}
+
+#[async_trait]
+impl SpotifyApi for FakeSpotifyApi {
+    fn set_offline(&self, _offline: bool) {}
+
+    fn set_image_cache_limit(&self, _limit: Option<u64>) {}
+
+    fn expire_cached(&self, _nav: &Nav) {}
+
+    async fn load_nav(&self, _url: &SpotifyUrl) -> Result<Nav, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn load_playback(&self, _url: &SpotifyUrl) -> Result<PlaybackPayload, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn get_user_profile(&self) -> Result<UserProfile, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn get_top_tracks(&self, _range: TopRange) -> Result<Vector<Arc<Track>>, Error> {
+        Ok(Vector::new())
+    }
+
+    async fn get_top_artists(&self, _range: TopRange) -> Result<Vector<Artist>, Error> {
+        Ok(Vector::new())
+    }
+
+    async fn get_recently_played(&self, _before: Option<&str>) -> Result<RecentlyPlayed, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn get_artist(&self, _id: &str) -> Result<Artist, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn get_artist_albums(&self, _id: &str) -> Result<ArtistAlbums, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn get_artist_top_tracks(&self, id: &str) -> Result<Vector<Arc<Track>>, Error> {
+        let library = self.library.lock().unwrap();
+        Ok(library
+            .tracks
+            .values()
+            .filter(|track| track.artists.iter().any(|artist| &*artist.id == id))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_artist_info(&self, _id: &str) -> Result<ArtistInfo, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn get_related_artists(&self, _id: &str) -> Result<Cached<Vector<Artist>>, Error> {
+        Ok(Cached::fresh(Vector::new()))
+    }
+
+    async fn get_followed_artists(&self, _after: Option<&str>) -> Result<FollowedArtists, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn is_following_artist(&self, id: &str) -> Result<bool, Error> {
+        Ok(self.library.lock().unwrap().followed_artists.contains(id))
+    }
+
+    async fn follow_artist(&self, id: &str) -> Result<(), Error> {
+        self.library
+            .lock()
+            .unwrap()
+            .followed_artists
+            .insert(id.into());
+        Ok(())
+    }
+
+    async fn unfollow_artist(&self, id: &str) -> Result<(), Error> {
+        self.library.lock().unwrap().followed_artists.remove(id);
+        Ok(())
+    }
+
+    async fn get_album(&self, _id: &str) -> Result<Cached<Album>, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn get_saved_albums(
+        &self,
+        on_page: &mut (dyn FnMut(usize, Vector<Album>) + Send),
+    ) -> Result<(), Error> {
+        // Albums are not kept, only whether they are saved.
+        on_page(0, Vector::new());
+        Ok(())
+    }
+
+    async fn contains_saved_albums(&self, ids: &[Arc<str>]) -> Result<Vector<bool>, Error> {
+        let library = self.library.lock().unwrap();
+        Ok(ids
+            .iter()
+            .map(|id| library.saved_albums.contains(id))
+            .collect())
+    }
+
+    async fn save_album(&self, id: &str) -> Result<(), Error> {
+        let mut library = self.library.lock().unwrap();
+        library.saved_albums.retain(|saved| &**saved != id);
+        library.saved_albums.insert(0, id.into());
+        Ok(())
+    }
+
+    async fn unsave_album(&self, id: &str) -> Result<(), Error> {
+        let mut library = self.library.lock().unwrap();
+        library.saved_albums.retain(|saved| &**saved != id);
+        Ok(())
+    }
+
+    async fn get_show(&self, _id: &str) -> Result<Show, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn get_show_episodes(&self, _id: &str) -> Result<Vector<Arc<Episode>>, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn get_saved_shows(
+        &self,
+        on_page: &mut (dyn FnMut(usize, Vector<Show>) + Send),
+    ) -> Result<(), Error> {
+        // Shows are not kept, only whether they are saved.
+        on_page(0, Vector::new());
+        Ok(())
+    }
+
+    async fn save_show(&self, id: &str) -> Result<(), Error> {
+        self.library.lock().unwrap().saved_shows.insert(id.into());
+        Ok(())
+    }
+
+    async fn unsave_show(&self, id: &str) -> Result<(), Error> {
+        self.library.lock().unwrap().saved_shows.remove(id);
+        Ok(())
+    }
+
+    async fn get_tracks(&self, track_ids: &[TrackId]) -> Result<Vector<Arc<Track>>, Error> {
+        let library = self.library.lock().unwrap();
+        Ok(track_ids
+            .iter()
+            .filter_map(|id| library.tracks.get(id).cloned())
+            .collect())
+    }
+
+    async fn get_saved_tracks(&self, _offset: usize) -> Result<SavedTracks, Error> {
+        let library = self.library.lock().unwrap();
+        Ok(SavedTracks {
+            tracks: library
+                .saved_tracks
+                .iter()
+                .filter_map(|id| library.tracks.get(id).cloned())
+                .collect(),
+            added_at: Default::default(),
+            next_offset: None,
+        })
+    }
+
+    async fn contains_saved_tracks(&self, track_ids: &[TrackId]) -> Result<Vector<bool>, Error> {
+        let library = self.library.lock().unwrap();
+        Ok(track_ids
+            .iter()
+            .map(|id| library.saved_tracks.contains(id))
+            .collect())
+    }
+
+    async fn save_track(&self, id: &str) -> Result<(), Error> {
+        let id: TrackId = id.parse().map_err(|_| Error::NotFound)?;
+        let mut library = self.library.lock().unwrap();
+        if !library.tracks.contains_key(&id) {
+            return Err(Error::NotFound);
+        }
+        library.saved_tracks.retain(|saved| *saved != id);
+        library.saved_tracks.insert(0, id);
+        Ok(())
+    }
+
+    async fn unsave_track(&self, id: &str) -> Result<(), Error> {
+        let id: TrackId = id.parse().map_err(|_| Error::NotFound)?;
+        let mut library = self.library.lock().unwrap();
+        library.saved_tracks.retain(|saved| *saved != id);
+        Ok(())
+    }
+
+    async fn save_tracks(&self, track_ids: &[TrackId]) -> Result<(), Error> {
+        let mut library = self.library.lock().unwrap();
+        if track_ids.iter().any(|id| !library.tracks.contains_key(id)) {
+            return Err(Error::NotFound);
+        }
+        for id in track_ids {
+            library.saved_tracks.retain(|saved| saved != id);
+            library.saved_tracks.insert(0, *id);
+        }
+        Ok(())
+    }
+
+    async fn unsave_tracks(&self, track_ids: &[TrackId]) -> Result<(), Error> {
+        let mut library = self.library.lock().unwrap();
+        library
+            .saved_tracks
+            .retain(|saved| !track_ids.contains(saved));
+        Ok(())
+    }
+
+    async fn get_audio_features(&self, _track_id: &str) -> Result<AudioFeatures, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn get_several_audio_features(
+        &self,
+        _track_ids: &[TrackId],
+    ) -> Result<Vector<AudioFeatures>, Error> {
+        Ok(Vector::new())
+    }
+
+    async fn get_audio_analysis(&self, _track_id: &str) -> Result<AudioAnalysis, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn get_lyrics(&self, _track_id: TrackId) -> Result<Lyrics, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn get_recommendations(
+        &self,
+        seed_tracks: &[TrackId],
+        _seed_artists: &[Arc<str>],
+    ) -> Result<Vector<Arc<Track>>, Error> {
+        // Anything but the seeds will do.
+        let library = self.library.lock().unwrap();
+        Ok(library
+            .tracks
+            .values()
+            .filter(|track| !seed_tracks.contains(&track.id))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_playlists(
+        &self,
+        on_page: &mut (dyn FnMut(usize, Vector<Playlist>) + Send),
+    ) -> Result<(), Error> {
+        let playlists = self
+            .library
+            .lock()
+            .unwrap()
+            .playlists
+            .iter()
+            .cloned()
+            .collect();
+        on_page(0, playlists);
+        Ok(())
+    }
+
+    async fn get_playlist(&self, id: &str) -> Result<Playlist, Error> {
+        let mut library = self.library.lock().unwrap();
+        library.playlist_mut(id).map(|playlist| playlist.clone())
+    }
+
+    async fn get_playlist_tracks_page(
+        &self,
+        link: &PlaylistLink,
+        _snapshot_id: Option<&str>,
+        offset: usize,
+    ) -> Result<PlaylistTracks, Error> {
+        let library = self.library.lock().unwrap();
+        let tracks = library
+            .playlist_tracks
+            .get(&link.id)
+            .ok_or(Error::NotFound)?;
+        Ok(PlaylistTracks {
+            id: link.id.clone(),
+            name: link.name.clone(),
+            tracks: tracks.clone().slice(offset.min(tracks.len())..),
+            next_offset: None,
+        })
+    }
+
+    async fn create_playlist(&self, name: &str, description: &str) -> Result<Playlist, Error> {
+        let mut library = self.library.lock().unwrap();
+        let snapshot_id = library.new_snapshot();
+        let playlist = Playlist {
+            id: format!("playlist-{}", library.playlists.len() + 1).into(),
+            name: name.into(),
+            images: Vector::new(),
+            description: description.into(),
+            track_count: 0,
+            owner: PlaylistOwner { id: "me".into() },
+            collaborative: false,
+            snapshot_id,
+        };
+        library
+            .playlist_tracks
+            .insert(playlist.id.clone(), Vector::new());
+        library.playlists.insert(0, playlist.clone());
+        Ok(playlist)
+    }
+
+    async fn rename_playlist(&self, id: &str, name: &str) -> Result<(), Error> {
+        let mut library = self.library.lock().unwrap();
+        library.playlist_mut(id)?.name = name.into();
+        Ok(())
+    }
+
+    async fn add_tracks_to_playlist(
+        &self,
+        id: &str,
+        track_ids: &[TrackId],
+    ) -> Result<Arc<str>, Error> {
+        let mut library = self.library.lock().unwrap();
+        let added: Vector<Arc<Track>> = track_ids
+            .iter()
+            .map(|id| library.tracks.get(id).cloned().ok_or(Error::NotFound))
+            .collect::<Result<_, _>>()?;
+        let snapshot_id = library.new_snapshot();
+        let playlist = library.playlist_mut(id)?;
+        playlist.track_count += added.len();
+        playlist.snapshot_id = snapshot_id.clone();
+        let playlist_id = playlist.id.clone();
+        library
+            .playlist_tracks
+            .entry(playlist_id)
+            .or_default()
+            .append(added);
+        Ok(snapshot_id)
+    }
+
+    async fn move_playlist_track(
+        &self,
+        id: &str,
+        range_start: usize,
+        insert_before: usize,
+        _snapshot_id: &str,
+    ) -> Result<Arc<str>, Error> {
+        let mut library = self.library.lock().unwrap();
+        let snapshot_id = library.new_snapshot();
+        let playlist = library.playlist_mut(id)?;
+        playlist.snapshot_id = snapshot_id.clone();
+        let playlist_id = playlist.id.clone();
+        let tracks = library
+            .playlist_tracks
+            .get_mut(&playlist_id)
+            .ok_or(Error::NotFound)?;
+        if range_start >= tracks.len() || insert_before > tracks.len() {
+            return Err(Error::NotFound);
+        }
+        let track = tracks.remove(range_start);
+        let index = if insert_before > range_start {
+            insert_before - 1
+        } else {
+            insert_before
+        };
+        tracks.insert(index, track);
+        Ok(snapshot_id)
+    }
+
+    async fn follow_playlist(&self, _id: &str) -> Result<(), Error> {
+        // Playlists of others are not known.
+        Err(Error::NotFound)
+    }
+
+    async fn unfollow_playlist(&self, id: &str) -> Result<(), Error> {
+        let mut library = self.library.lock().unwrap();
+        library.playlists.retain(|playlist| &*playlist.id != id);
+        Ok(())
+    }
+
+    async fn get_featured_playlists(&self) -> Result<FeaturedPlaylists, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn get_new_releases(&self) -> Result<Vector<Album>, Error> {
+        Ok(Vector::new())
+    }
+
+    async fn search(&self, _query: &str) -> Result<SearchResults, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn search_topic(
+        &self,
+        _query: &str,
+        _topic: SearchTopic,
+        _offset: usize,
+    ) -> Result<SearchResults, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn search_suggestions(&self, _query: &str) -> Result<SearchResults, Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn get_devices(&self) -> Result<Vector<Device>, Error> {
+        Ok(Vector::new())
+    }
+
+    async fn get_remote_playback(&self) -> Result<Option<RemotePlayback>, Error> {
+        Ok(None)
+    }
+
+    async fn transfer_playback(&self, _device_id: &str) -> Result<(), Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn remote_play(&self, _device_id: &str) -> Result<(), Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn remote_pause(&self, _device_id: &str) -> Result<(), Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn remote_next(&self, _device_id: &str) -> Result<(), Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn remote_previous(&self, _device_id: &str) -> Result<(), Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn set_remote_volume(&self, _device_id: &str, _volume_percent: u32) -> Result<(), Error> {
+        Err(Error::NotFound)
+    }
+
+    async fn get_image(
+        &self,
+        _uri: &str,
+        _format: image::ImageFormat,
+    ) -> Result<image::DynamicImage, Error> {
+        Err(Error::NotFound)
+    }
+
+    fn image_color(&self, _uri: &str, _image: &image::DynamicImage) -> [u8; 3] {
+        [0, 0, 0]
+    }
+
+    fn get_image_color(&self, _uri: &str) -> Option<[u8; 3]> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::AlbumLink;
+    use psst_core::item_id::{ItemId, ItemIdType};
+    use std::time::Duration;
+
+    fn track(id: u128) -> Arc<Track> {
+        Arc::new(Track {
+            id: TrackId::from(ItemId::new(id, ItemIdType::Track)),
+            name: format!("Track {}", id).into(),
+            album: Some(AlbumLink {
+                id: "album".into(),
+                name: "Album".into(),
+                images: Vector::new(),
+            }),
+            artists: Vector::new(),
+            duration: Duration::from_secs(180),
+            disc_number: 1,
+            track_number: id as usize,
+            explicit: false,
+            is_local: false,
+            is_playable: Some(true),
+            is_available: None,
+            linked_from: None,
+            popularity: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn saved_tracks_come_most_recent_first() {
+        let (first, second) = (track(1), track(2));
+        let webapi = FakeSpotifyApi::new().with_tracks(vec![first.clone(), second.clone()]);
+
+        webapi.save_track(&first.id.to_base62()).await.unwrap();
+        webapi.save_track(&second.id.to_base62()).await.unwrap();
+        let saved = webapi.get_saved_tracks(0).await.unwrap();
+        let ids: Vec<TrackId> = saved.tracks.iter().map(|track| track.id).collect();
+        assert_eq!(ids, vec![second.id, first.id]);
+
+        webapi.unsave_track(&second.id.to_base62()).await.unwrap();
+        let contains = webapi
+            .contains_saved_tracks(&[first.id, second.id])
+            .await
+            .unwrap();
+        assert_eq!(contains, Vector::from(vec![true, false]));
+    }
+
+    #[tokio::test]
+    async fn playlist_edits_change_the_snapshot() {
+        let tracks = vec![track(1), track(2), track(3)];
+        let ids: Vec<TrackId> = tracks.iter().map(|track| track.id).collect();
+        let webapi = FakeSpotifyApi::new().with_tracks(tracks);
+
+        let playlist = webapi.create_playlist("Mix", "").await.unwrap();
+        webapi
+            .add_tracks_to_playlist(&playlist.id, &ids)
+            .await
+            .unwrap();
+        let snapshot_id = webapi
+            .move_playlist_track(&playlist.id, 0, 3, &playlist.snapshot_id)
+            .await
+            .unwrap();
+        assert_ne!(snapshot_id, playlist.snapshot_id);
+
+        let page = webapi
+            .get_playlist_tracks_page(&playlist.link(), Some(&snapshot_id), 0)
+            .await
+            .unwrap();
+        let order: Vec<TrackId> = page.tracks.iter().map(|track| track.id).collect();
+        assert_eq!(order, vec![ids[1], ids[2], ids[0]]);
+        assert_eq!(
+            webapi.get_playlist(&playlist.id).await.unwrap().track_count,
+            3
+        );
+    }
+}
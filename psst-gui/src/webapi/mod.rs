@@ -1,4 +1,12 @@
+mod api;
 mod cache;
 mod client;
+mod coalesce;
+#[cfg(test)]
+mod fake;
+mod rate_limit;
 
+pub use api::SpotifyApi;
 pub use client::WebApi;
+#[cfg(test)]
+pub use fake::FakeSpotifyApi;
@@ -0,0 +1,55 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use tokio::time;
+
+/// Token bucket limiting the rate of the requests.  Holds up to `burst` tokens,
+/// refilled at `rate` tokens per second, and every request takes one.
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(rate: f64, burst: usize) -> Self {
+        Self {
+            rate,
+            burst: burst as f64,
+            bucket: Mutex::new(Bucket {
+                tokens: burst as f64,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take a token, waiting until one is available.
+    pub async fn acquire(&self) {
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap();
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+            bucket.refilled_at = now;
+            // Take the token right away, even if it's not there yet, so the
+            // waiting callers queue up in order.
+            bucket.tokens -= 1.0;
+            if bucket.tokens < 0.0 {
+                Duration::from_secs_f64(-bucket.tokens / self.rate)
+            } else {
+                Duration::default()
+            }
+        };
+        if wait > Duration::default() {
+            log::debug!("rate limited, waiting {:?}", wait);
+            time::sleep(wait).await;
+        }
+    }
+}
@@ -20,8 +20,15 @@ impl<T: Data, W: Widget<T>> Controller<T, W> for ExClick<T> {
     fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
         match event {
             Event::MouseDown(_) => {
-                ctx.set_active(true);
-                ctx.request_paint();
+                // A clickable child, i.e. a button inside of a row, takes the
+                // click for itself.
+                child.event(ctx, event, data, env);
+                if !ctx.is_handled() {
+                    ctx.set_active(true);
+                    ctx.set_handled();
+                    ctx.request_paint();
+                }
+                return;
             }
             Event::MouseUp(mouse_event) => {
                 if ctx.is_active() {
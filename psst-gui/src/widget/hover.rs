@@ -0,0 +1,76 @@
+use druid::{widget::prelude::*, Data, Point, Rect, UnitPoint, Vec2, WidgetPod};
+
+/// Shows `overlay` on top of the child while the pointer is over it, i.e. a
+/// play button over a card.
+pub struct Hover<T> {
+    inner: WidgetPod<T, Box<dyn Widget<T>>>,
+    overlay: WidgetPod<T, Box<dyn Widget<T>>>,
+    align: UnitPoint,
+    offset: Vec2,
+}
+
+impl<T: Data> Hover<T> {
+    pub fn new(inner: impl Widget<T> + 'static, overlay: impl Widget<T> + 'static) -> Self {
+        Self {
+            inner: WidgetPod::new(inner).boxed(),
+            overlay: WidgetPod::new(overlay).boxed(),
+            align: UnitPoint::CENTER,
+            offset: Vec2::ZERO,
+        }
+    }
+
+    /// Where to place the overlay within the child.
+    pub fn align(mut self, align: UnitPoint) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Move the overlay from its aligned position.
+    pub fn offset(mut self, offset: impl Into<Vec2>) -> Self {
+        self.offset = offset.into();
+        self
+    }
+}
+
+impl<T: Data> Widget<T> for Hover<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        // The overlay is only shown while we are hot, so it can take the
+        // pointer events first.
+        self.overlay.event(ctx, event, data, env);
+        if !ctx.is_handled() {
+            self.inner.event(ctx, event, data, env);
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::HotChanged(_) = event {
+            ctx.request_paint();
+        }
+        self.inner.lifecycle(ctx, event, data, env);
+        self.overlay.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.inner.update(ctx, data, env);
+        self.overlay.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.inner.layout(ctx, bc, data, env);
+        self.inner.set_origin(ctx, data, env, Point::ORIGIN);
+        let overlay_size =
+            self.overlay
+                .layout(ctx, &BoxConstraints::new(Size::ZERO, size), data, env);
+        let free_space = Rect::from_origin_size(Point::ORIGIN, size - overlay_size);
+        let origin = self.align.resolve(free_space) + self.offset;
+        self.overlay.set_origin(ctx, data, env, origin);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.inner.paint(ctx, data, env);
+        if ctx.is_hot() || self.overlay.is_active() {
+            self.overlay.paint(ctx, data, env);
+        }
+    }
+}
@@ -18,6 +18,11 @@ pub static PREFERENCES: SvgIcon = SvgIcon {
     op: PaintOp::Stroke { width: 1.0 },
 };
 
+pub static SPEAKER: SvgIcon = SvgIcon {
+    svg_path: "M7.5 3.5L4.5 6H2V10H4.5L7.5 12.5V3.5Z M10.5 5.5C11.1 6.1 11.5 7 11.5 8C11.5 9 11.1 9.9 10.5 10.5 M12.5 3.5C13.7 4.7 14.5 6.3 14.5 8C14.5 9.7 13.7 11.3 12.5 12.5",
+    svg_size: Size::new(16.0, 16.0),
+    op: PaintOp::Stroke { width: 1.0 },
+};
 pub static BACK: SvgIcon = SvgIcon {
     svg_path: "M9.70711 0.292893C10.0976 0.683417 10.0976 1.31658 9.70711 1.70711L2.41421 9L9.70711 16.2929C10.0976 16.6834 10.0976 17.3166 9.70711 17.7071C9.31658 18.0976 8.68342 18.0976 8.29289 17.7071L0.292893 9.70711C-0.0976311 9.31658 -0.0976311 8.68342 0.292893 8.29289L8.29289 0.292893C8.68342 -0.0976311 9.31658 -0.0976311 9.70711 0.292893Z",
     svg_size: Size::new(10.0, 18.0),
@@ -43,6 +48,16 @@ pub static SKIP_FORWARD: SvgIcon = SvgIcon {
     svg_size: Size::new(24.0, 24.0),
     op: PaintOp::Fill,
 };
+pub static SEEK_BACK: SvgIcon = SvgIcon {
+    svg_path: "M7.5 4L3.5 8L7.5 12 M12.5 4L8.5 8L12.5 12",
+    svg_size: Size::new(16.0, 16.0),
+    op: PaintOp::Stroke { width: 1.0 },
+};
+pub static SEEK_FORWARD: SvgIcon = SvgIcon {
+    svg_path: "M3.5 4L7.5 8L3.5 12 M8.5 4L12.5 8L8.5 12",
+    svg_size: Size::new(16.0, 16.0),
+    op: PaintOp::Stroke { width: 1.0 },
+};
 pub static PLAY_SEQUENTIAL: SvgIcon = SvgIcon {
     svg_path: "M3 8C3 8.55228 2.55228 9 2 9C1.44772 9 1 8.55228 1 8C1 7.44772 1.44772 7 2 7C2.55228 7 3 7.44772 3 8Z M7 8C7 8.55228 6.55228 9 6 9C5.44772 9 5 8.55228 5 8C5 7.44772 5.44772 7 6 7C6.55228 7 7 7.44772 7 8Z M11 8C11 8.55228 10.5523 9 10 9C9.44772 9 9 8.55228 9 8C9 7.44772 9.44772 7 10 7C10.5523 7 11 7.44772 11 8Z M15 8C15 8.55228 14.5523 9 14 9C13.4477 9 13 8.55228 13 8C13 7.44772 13.4477 7 14 7C14.5523 7 15 7.44772 15 8Z",
     svg_size: Size::new(16.0, 16.0),
@@ -64,6 +79,30 @@ pub static PLAY_LOOP_ALL: SvgIcon = SvgIcon {
     op: PaintOp::Stroke { width: 1.0 },
 };
 
+pub static AUTOPLAY: SvgIcon = SvgIcon {
+    svg_path: "M8 9.5C8.82843 9.5 9.5 8.82843 9.5 8C9.5 7.17157 8.82843 6.5 8 6.5C7.17157 6.5 6.5 7.17157 6.5 8C6.5 8.82843 7.17157 9.5 8 9.5Z M5.17 10.83C4.42 10.08 4 9.06 4 8C4 6.94 4.42 5.92 5.17 5.17 M10.83 5.17C11.58 5.92 12 6.94 12 8C12 9.06 11.58 10.08 10.83 10.83 M3.05 12.95C1.74 11.64 1 9.86 1 8C1 6.14 1.74 4.36 3.05 3.05 M12.95 3.05C14.26 4.36 15 6.14 15 8C15 9.86 14.26 11.64 12.95 12.95",
+    svg_size: Size::new(16.0, 16.0),
+    op: PaintOp::Stroke { width: 1.0 },
+};
+
+pub static QUEUE: SvgIcon = SvgIcon {
+    svg_path: "M2 4.5H14 M2 8H14 M2 11.5H9 M11.5 10V13 M10 11.5H13",
+    svg_size: Size::new(16.0, 16.0),
+    op: PaintOp::Stroke { width: 1.0 },
+};
+
+pub static CLOSE: SvgIcon = SvgIcon {
+    svg_path: "M4 4L12 12 M12 4L4 12",
+    svg_size: Size::new(16.0, 16.0),
+    op: PaintOp::Stroke { width: 1.0 },
+};
+
+pub static REFRESH: SvgIcon = SvgIcon {
+    svg_path: "M13.5 8C13.5 11.04 11.04 13.5 8 13.5C4.96 13.5 2.5 11.04 2.5 8C2.5 4.96 4.96 2.5 8 2.5C9.74 2.5 11.29 3.31 12.3 4.57 M12.5 1.5V4.75H9.25",
+    svg_size: Size::new(16.0, 16.0),
+    op: PaintOp::Stroke { width: 1.0 },
+};
+
 pub static SAD_FACE: SvgIcon = SvgIcon {
     svg_path: "M5.42858 8.00001C5.90197 8.00001 6.28573 7.61625 6.28573 7.14286C6.28573 6.66948 5.90197 6.28572 5.42858 6.28572C4.9552 6.28572 4.57144 6.66948 4.57144 7.14286C4.57144 7.61625 4.9552 8.00001 5.42858 8.00001Z M8.00002 9.14285C9.62216 9.14285 10.9864 10.1975 11.4182 11.6368C11.4304 11.6797 11.4322 11.725 11.4237 11.7688C11.4152 11.8126 11.3965 11.8539 11.3692 11.8892C11.3419 11.9245 11.3066 11.9529 11.2664 11.9722C11.2261 11.9914 11.1818 12.0009 11.1372 12H4.86252C4.81802 12.0006 4.77398 11.9909 4.73391 11.9716C4.69385 11.9522 4.65885 11.9237 4.63173 11.8885C4.6046 11.8532 4.58609 11.8121 4.57767 11.7684C4.56925 11.7247 4.57115 11.6796 4.58323 11.6368C5.01144 10.1975 6.37609 9.14285 8.00002 9.14285Z M10.5714 8.00001C11.0448 8.00001 11.4286 7.61625 11.4286 7.14286C11.4286 6.66948 11.0448 6.28572 10.5714 6.28572C10.0981 6.28572 9.71429 6.66948 9.71429 7.14286C9.71429 7.61625 10.0981 8.00001 10.5714 8.00001Z M8.00001 1.07144C4.17347 1.07144 1.07144 4.17347 1.07144 8.00001C1.07144 11.8266 4.17347 14.9286 8.00001 14.9286C11.8266 14.9286 14.9286 11.8266 14.9286 8.00001C14.9286 4.17347 11.8266 1.07144 8.00001 1.07144ZM0.0714417 8.00001C0.0714417 3.62118 3.62118 0.0714417 8.00001 0.0714417C12.3788 0.0714417 15.9286 3.62118 15.9286 8.00001C15.9286 12.3788 12.3788 15.9286 8.00001 15.9286C3.62118 15.9286 0.0714417 12.3788 0.0714417 8.00001Z",
     svg_size: Size::new(16.0, 16.0),
@@ -80,6 +119,11 @@ pub static HEART: SvgIcon = SvgIcon {
     svg_size: Size::new(16.0, 15.0),
     op: PaintOp::Stroke { width: 1.0 },
 };
+pub static HEART_FILLED: SvgIcon = SvgIcon {
+    svg_path: "M11.7099 0.642857C9.22488 0.642857 8 3.13636 8 3.13636C8 3.13636 6.77512 0.642857 4.29014 0.642857C2.27062 0.642857 0.671387 2.3626 0.650717 4.41467C0.608612 8.67428 3.97053 11.7035 7.6555 14.2492C7.75709 14.3196 7.87713 14.3572 8 14.3572C8.12287 14.3572 8.24291 14.3196 8.3445 14.2492C12.0291 11.7035 15.391 8.67428 15.3493 4.41467C15.3286 2.3626 13.7294 0.642857 11.7099 0.642857V0.642857Z",
+    svg_size: Size::new(16.0, 15.0),
+    op: PaintOp::Fill,
+};
 pub static ARTIST: SvgIcon = SvgIcon {
     svg_path: "M8 0.0191841C3.58885 0.0191841 0 3.59942 0 8C0 12.4006 3.58885 15.9808 8 15.9808C12.4112 15.9808 16 12.4006 16 8C16 3.59942 12.4112 0.0191841 8 0.0191841ZM6.06846 4.50149C6.55577 3.98619 7.24154 3.70264 8 3.70264C8.75846 3.70264 9.43808 3.9881 9.92731 4.50609C10.4231 5.03098 10.6642 5.73621 10.6073 6.49439C10.4935 8 9.32423 9.22782 8 9.22782C6.67577 9.22782 5.50423 8 5.39269 6.494C5.33615 5.72969 5.57692 5.02216 6.06846 4.50149V4.50149ZM8 14.753C7.09633 14.7536 6.20175 14.5732 5.36929 14.2224C4.53684 13.8716 3.78346 13.3577 3.15385 12.711C3.51445 12.198 3.97391 11.7618 4.50538 11.4279C5.48577 10.801 6.72654 10.4556 8 10.4556C9.27346 10.4556 10.5142 10.801 11.4935 11.4279C12.0254 11.7616 12.4852 12.1978 12.8462 12.711C12.2166 13.3577 11.4632 13.8717 10.6308 14.2225C9.79829 14.5733 8.90368 14.7537 8 14.753V14.753Z",
     svg_size: Size::new(16.0, 16.0),
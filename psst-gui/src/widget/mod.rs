@@ -1,21 +1,25 @@
 mod dispatcher;
 mod empty;
 mod ex_click;
+mod hover;
 pub mod icons;
 mod link;
 mod maybe;
 mod promise;
 pub mod remote_image;
+mod scroll_end;
 mod theme;
 mod utils;
 
 pub use dispatcher::ViewDispatcher;
 pub use empty::Empty;
 pub use ex_click::ExClick;
+pub use hover::Hover;
 pub use icons::Icon;
 pub use link::{Link, LinkExt};
 pub use maybe::Maybe;
 pub use promise::{Async, AsyncAction};
-pub use remote_image::RemoteImage;
+pub use remote_image::{ImageTint, RemoteImage};
+pub use scroll_end::OnScrollEnd;
 pub use theme::ThemeScope;
 pub use utils::{Clip, Logger};
@@ -1,16 +1,28 @@
 use druid::{
     widget::{prelude::*, Image},
-    Data, ImageBuf, Point, Selector, WidgetPod,
+    Color, Command, Data, ImageBuf, Point, Selector, WidgetPod,
 };
 use std::sync::Arc;
 
 pub const REQUEST_DATA: Selector<Arc<str>> = Selector::new("remote-image.request-data");
 pub const PROVIDE_DATA: Selector<ImagePayload> = Selector::new("remote-image.provide-data");
+pub const REQUEST_COLOR: Selector<Arc<str>> = Selector::new("remote-image.request-color");
+pub const PROVIDE_COLOR: Selector<ColorPayload> = Selector::new("remote-image.provide-color");
+
+// How long it takes the loaded image to replace its placeholder.
+const FADE_IN_NANOS: f64 = 200_000_000.0;
 
 #[derive(Clone)]
 pub struct ImagePayload {
     pub location: Arc<str>,
     pub image_buf: ImageBuf,
+    pub color: Color,
+}
+
+#[derive(Clone)]
+pub struct ColorPayload {
+    pub location: Arc<str>,
+    pub color: Color,
 }
 
 pub struct RemoteImage<T> {
@@ -18,6 +30,12 @@ pub struct RemoteImage<T> {
     image: Option<WidgetPod<T, Image>>,
     locator: Box<dyn Fn(&T, &Env) -> Option<Arc<str>>>,
     location: Option<Arc<str>>,
+    /// Dominant color of the image, shown in place of the placeholder if
+    /// known before the image loads.
+    color: Option<Color>,
+    /// Progress of the fade from the color to the image, if running.
+    fade: Option<f64>,
+    placeholder_painted: bool,
 }
 
 impl<T: Data> RemoteImage<T> {
@@ -30,21 +48,60 @@ impl<T: Data> RemoteImage<T> {
             locator: Box::new(locator),
             location: None,
             image: None,
+            color: None,
+            fade: None,
+            placeholder_painted: false,
         }
     }
+
+    fn request(&mut self, location: Option<Arc<str>>, widget_id: WidgetId) -> Option<Command> {
+        self.image = None;
+        self.color = None;
+        self.fade = None;
+        self.placeholder_painted = false;
+        self.location = location.clone();
+        location.map(|location| REQUEST_DATA.with(location).to(widget_id))
+    }
 }
 
 impl<T: Data> Widget<T> for RemoteImage<T> {
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
-        if let Event::Command(cmd) = event {
-            if let Some(payload) = cmd.get(PROVIDE_DATA) {
+        match event {
+            Event::Command(cmd) if cmd.is(PROVIDE_DATA) => {
+                let payload = cmd.get_unchecked(PROVIDE_DATA);
                 if Some(&payload.location) == self.location.as_ref() {
                     self.image
                         .replace(WidgetPod::new(Image::new(payload.image_buf.clone())));
+                    self.color.replace(payload.color.clone());
+                    // Images shown right away don't need to fade in.
+                    if self.placeholder_painted {
+                        self.fade.replace(0.0);
+                        ctx.request_anim_frame();
+                    }
                     ctx.children_changed();
                 }
                 return;
             }
+            Event::Command(cmd) if cmd.is(PROVIDE_COLOR) => {
+                let payload = cmd.get_unchecked(PROVIDE_COLOR);
+                if Some(&payload.location) == self.location.as_ref() {
+                    self.color.replace(payload.color.clone());
+                    ctx.request_paint();
+                }
+                return;
+            }
+            Event::AnimFrame(interval) => {
+                if let Some(fade) = self.fade.as_mut() {
+                    *fade += *interval as f64 / FADE_IN_NANOS;
+                    if *fade < 1.0 {
+                        ctx.request_anim_frame();
+                    } else {
+                        self.fade = None;
+                    }
+                    ctx.request_paint();
+                }
+            }
+            _ => {}
         }
         if let Some(image) = self.image.as_mut() {
             image.event(ctx, event, data, env);
@@ -56,10 +113,8 @@ impl<T: Data> Widget<T> for RemoteImage<T> {
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
         if let LifeCycle::WidgetAdded = event {
             let location = (self.locator)(data, env);
-            self.image = None;
-            self.location = location.clone();
-            if let Some(location) = location {
-                ctx.submit_command(REQUEST_DATA.with(location).to(ctx.widget_id()));
+            if let Some(request) = self.request(location, ctx.widget_id()) {
+                ctx.submit_command(request);
             }
         }
         if let Some(image) = self.image.as_mut() {
@@ -72,10 +127,8 @@ impl<T: Data> Widget<T> for RemoteImage<T> {
     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
         let location = (self.locator)(data, env);
         if location != self.location {
-            self.image = None;
-            self.location = location.clone();
-            if let Some(location) = location {
-                ctx.submit_command(REQUEST_DATA.with(location).to(ctx.widget_id()));
+            if let Some(request) = self.request(location, ctx.widget_id()) {
+                ctx.submit_command(request);
             }
             ctx.children_changed();
         }
@@ -99,10 +152,103 @@ impl<T: Data> Widget<T> for RemoteImage<T> {
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let bounds = ctx.size().to_rect();
         if let Some(image) = self.image.as_mut() {
-            image.paint(ctx, data, env)
+            image.paint(ctx, data, env);
+            if let (Some(fade), Some(color)) = (self.fade, self.color.as_ref()) {
+                ctx.fill(bounds, &color.clone().with_alpha(1.0 - fade));
+            }
+        } else if let Some(color) = self.color.as_ref() {
+            ctx.fill(bounds, color);
+            self.placeholder_painted = true;
         } else {
-            self.placeholder.paint(ctx, data, env)
+            self.placeholder.paint(ctx, data, env);
+            self.placeholder_painted = true;
+        }
+    }
+}
+
+/// Paints the background of its child with a faint shade of the dominant
+/// color of an image.
+pub struct ImageTint<T> {
+    inner: WidgetPod<T, Box<dyn Widget<T>>>,
+    locator: Box<dyn Fn(&T, &Env) -> Option<Arc<str>>>,
+    location: Option<Arc<str>>,
+    color: Option<Color>,
+}
+
+impl<T: Data> ImageTint<T> {
+    pub fn new(
+        inner: impl Widget<T> + 'static,
+        locator: impl Fn(&T, &Env) -> Option<Arc<str>> + 'static,
+    ) -> Self {
+        Self {
+            inner: WidgetPod::new(inner).boxed(),
+            locator: Box::new(locator),
+            location: None,
+            color: None,
+        }
+    }
+
+    fn request(&mut self, location: Option<Arc<str>>, widget_id: WidgetId) -> Option<Command> {
+        self.color = None;
+        self.location = location.clone();
+        location.map(|location| REQUEST_COLOR.with(location).to(widget_id))
+    }
+}
+
+impl<T: Data> Widget<T> for ImageTint<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            let provided = if let Some(payload) = cmd.get(PROVIDE_COLOR) {
+                Some((&payload.location, &payload.color))
+            } else {
+                cmd.get(PROVIDE_DATA)
+                    .map(|payload| (&payload.location, &payload.color))
+            };
+            if let Some((location, color)) = provided {
+                if Some(location) == self.location.as_ref() {
+                    self.color.replace(color.clone());
+                    ctx.request_paint();
+                }
+                return;
+            }
+        }
+        self.inner.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            let location = (self.locator)(data, env);
+            if let Some(request) = self.request(location, ctx.widget_id()) {
+                ctx.submit_command(request);
+            }
+        }
+        self.inner.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let location = (self.locator)(data, env);
+        if location != self.location {
+            if let Some(request) = self.request(location, ctx.widget_id()) {
+                ctx.submit_command(request);
+            }
+            ctx.request_paint();
+        }
+        self.inner.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.inner.layout(ctx, bc, data, env);
+        self.inner.set_origin(ctx, data, env, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        if let Some(color) = self.color.as_ref() {
+            let bounds = ctx.size().to_rect();
+            ctx.fill(bounds, &color.clone().with_alpha(0.15));
         }
+        self.inner.paint(ctx, data, env);
     }
 }
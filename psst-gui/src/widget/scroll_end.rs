@@ -0,0 +1,46 @@
+use druid::{
+    widget::{Controller, Scroll},
+    Data, Env, Event, EventCtx, Widget,
+};
+
+// How close to the end of the content the action is triggered, so the next
+// page usually arrives before the user gets there.
+const END_DISTANCE: f64 = 600.0;
+
+/// Controller of a vertical [`Scroll`] that invokes an action when the user
+/// scrolls close to the end of its content.
+pub struct OnScrollEnd<T> {
+    action: Box<dyn Fn(&mut EventCtx, &mut T, &Env)>,
+    is_near_end: bool,
+}
+
+impl<T: Data> OnScrollEnd<T> {
+    pub fn new(action: impl Fn(&mut EventCtx, &mut T, &Env) + 'static) -> Self {
+        Self {
+            action: Box::new(action),
+            is_near_end: false,
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, Scroll<T, W>> for OnScrollEnd<T> {
+    fn event(
+        &mut self,
+        child: &mut Scroll<T, W>,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut T,
+        env: &Env,
+    ) {
+        child.event(ctx, event, data, env);
+
+        let remaining = child.child_size().height - child.offset().y - ctx.size().height;
+        let is_near_end = remaining < END_DISTANCE;
+        // Only trigger once per approach, the content grows after the action
+        // takes effect and moves the end away again.
+        if is_near_end && !self.is_near_end {
+            (self.action)(ctx, data, env);
+        }
+        self.is_near_end = is_near_end;
+    }
+}
@@ -36,7 +36,9 @@ impl<W: Widget<State>> Widget<State> for ThemeScope<W> {
     }
 
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &State, data: &State, env: &Env) {
-        if !data.config.theme.same(&old_data.config.theme) {
+        if !data.config.theme.same(&old_data.config.theme)
+            || !data.config.image_scale.same(&old_data.config.image_scale)
+        {
             self.set_env(data, env);
             ctx.request_layout();
             ctx.request_paint();
@@ -0,0 +1,44 @@
+//! Background work of the delegate.  The jobs run as tasks on the delegate's
+//! runtime, and jobs that are no longer needed can be cancelled.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+/// Hands out tokens that can all be cancelled at once.
+pub struct CancelGroup {
+    generation: Arc<AtomicUsize>,
+}
+
+impl CancelGroup {
+    pub fn new() -> Self {
+        Self {
+            generation: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn token(&self) -> CancelToken {
+        CancelToken {
+            generation: self.generation.clone(),
+            issued_at: self.generation.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Cancel all of the tokens handed out so far.
+    pub fn cancel(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[derive(Clone)]
+pub struct CancelToken {
+    generation: Arc<AtomicUsize>,
+    issued_at: usize,
+}
+
+impl CancelToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.generation.load(Ordering::SeqCst) != self.issued_at
+    }
+}
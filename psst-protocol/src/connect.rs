@@ -0,0 +1,1082 @@
+// Automatically generated rust module for 'connect.proto' file
+
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(unknown_lints)]
+#![allow(clippy::all)]
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+
+use quick_protobuf::{MessageRead, MessageWrite, BytesReader, Writer, WriterBackend, Result};
+use quick_protobuf::sizeofs::*;
+use super::*;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MemberType {
+    SPIRC_V2 = 0,
+    SPIRC_V3 = 1,
+    CONNECT_STATE = 2,
+    CONNECT_STATE_EXTENDED = 5,
+    ACTIVE_DEVICE_TRACKER = 6,
+    PLAY_TOKEN = 7,
+}
+
+impl Default for MemberType {
+    fn default() -> Self {
+        MemberType::SPIRC_V2
+    }
+}
+
+impl From<i32> for MemberType {
+    fn from(i: i32) -> Self {
+        match i {
+            0 => MemberType::SPIRC_V2,
+            1 => MemberType::SPIRC_V3,
+            2 => MemberType::CONNECT_STATE,
+            5 => MemberType::CONNECT_STATE_EXTENDED,
+            6 => MemberType::ACTIVE_DEVICE_TRACKER,
+            7 => MemberType::PLAY_TOKEN,
+            _ => Self::default(),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for MemberType {
+    fn from(s: &'a str) -> Self {
+        match s {
+            "SPIRC_V2" => MemberType::SPIRC_V2,
+            "SPIRC_V3" => MemberType::SPIRC_V3,
+            "CONNECT_STATE" => MemberType::CONNECT_STATE,
+            "CONNECT_STATE_EXTENDED" => MemberType::CONNECT_STATE_EXTENDED,
+            "ACTIVE_DEVICE_TRACKER" => MemberType::ACTIVE_DEVICE_TRACKER,
+            "PLAY_TOKEN" => MemberType::PLAY_TOKEN,
+            _ => Self::default(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PutStateReason {
+    UNKNOWN_PUT_STATE_REASON = 0,
+    SPIRC_HELLO = 1,
+    SPIRC_NOTIFY = 2,
+    NEW_DEVICE = 3,
+    PLAYER_STATE_CHANGED = 4,
+    VOLUME_CHANGED = 5,
+    PICKER_OPENED = 6,
+    BECAME_INACTIVE = 7,
+}
+
+impl Default for PutStateReason {
+    fn default() -> Self {
+        PutStateReason::UNKNOWN_PUT_STATE_REASON
+    }
+}
+
+impl From<i32> for PutStateReason {
+    fn from(i: i32) -> Self {
+        match i {
+            0 => PutStateReason::UNKNOWN_PUT_STATE_REASON,
+            1 => PutStateReason::SPIRC_HELLO,
+            2 => PutStateReason::SPIRC_NOTIFY,
+            3 => PutStateReason::NEW_DEVICE,
+            4 => PutStateReason::PLAYER_STATE_CHANGED,
+            5 => PutStateReason::VOLUME_CHANGED,
+            6 => PutStateReason::PICKER_OPENED,
+            7 => PutStateReason::BECAME_INACTIVE,
+            _ => Self::default(),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for PutStateReason {
+    fn from(s: &'a str) -> Self {
+        match s {
+            "UNKNOWN_PUT_STATE_REASON" => PutStateReason::UNKNOWN_PUT_STATE_REASON,
+            "SPIRC_HELLO" => PutStateReason::SPIRC_HELLO,
+            "SPIRC_NOTIFY" => PutStateReason::SPIRC_NOTIFY,
+            "NEW_DEVICE" => PutStateReason::NEW_DEVICE,
+            "PLAYER_STATE_CHANGED" => PutStateReason::PLAYER_STATE_CHANGED,
+            "VOLUME_CHANGED" => PutStateReason::VOLUME_CHANGED,
+            "PICKER_OPENED" => PutStateReason::PICKER_OPENED,
+            "BECAME_INACTIVE" => PutStateReason::BECAME_INACTIVE,
+            _ => Self::default(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DeviceType {
+    UNKNOWN = 0,
+    COMPUTER = 1,
+    TABLET = 2,
+    SMARTPHONE = 3,
+    SPEAKER = 4,
+    TV = 5,
+    AVR = 6,
+    STB = 7,
+    AUDIO_DONGLE = 8,
+    GAME_CONSOLE = 9,
+    CAST_VIDEO = 10,
+    CAST_AUDIO = 11,
+    AUTOMOBILE = 12,
+    SMARTWATCH = 13,
+    CHROMEBOOK = 14,
+}
+
+impl Default for DeviceType {
+    fn default() -> Self {
+        DeviceType::UNKNOWN
+    }
+}
+
+impl From<i32> for DeviceType {
+    fn from(i: i32) -> Self {
+        match i {
+            0 => DeviceType::UNKNOWN,
+            1 => DeviceType::COMPUTER,
+            2 => DeviceType::TABLET,
+            3 => DeviceType::SMARTPHONE,
+            4 => DeviceType::SPEAKER,
+            5 => DeviceType::TV,
+            6 => DeviceType::AVR,
+            7 => DeviceType::STB,
+            8 => DeviceType::AUDIO_DONGLE,
+            9 => DeviceType::GAME_CONSOLE,
+            10 => DeviceType::CAST_VIDEO,
+            11 => DeviceType::CAST_AUDIO,
+            12 => DeviceType::AUTOMOBILE,
+            13 => DeviceType::SMARTWATCH,
+            14 => DeviceType::CHROMEBOOK,
+            _ => Self::default(),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for DeviceType {
+    fn from(s: &'a str) -> Self {
+        match s {
+            "UNKNOWN" => DeviceType::UNKNOWN,
+            "COMPUTER" => DeviceType::COMPUTER,
+            "TABLET" => DeviceType::TABLET,
+            "SMARTPHONE" => DeviceType::SMARTPHONE,
+            "SPEAKER" => DeviceType::SPEAKER,
+            "TV" => DeviceType::TV,
+            "AVR" => DeviceType::AVR,
+            "STB" => DeviceType::STB,
+            "AUDIO_DONGLE" => DeviceType::AUDIO_DONGLE,
+            "GAME_CONSOLE" => DeviceType::GAME_CONSOLE,
+            "CAST_VIDEO" => DeviceType::CAST_VIDEO,
+            "CAST_AUDIO" => DeviceType::CAST_AUDIO,
+            "AUTOMOBILE" => DeviceType::AUTOMOBILE,
+            "SMARTWATCH" => DeviceType::SMARTWATCH,
+            "CHROMEBOOK" => DeviceType::CHROMEBOOK,
+            _ => Self::default(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ClusterUpdateReason {
+    INVALID_CLUSTER_UPDATE_REASON = 0,
+    DEVICES_DISAPPEARED = 1,
+    DEVICE_STATE_CHANGED = 2,
+    NEW_DEVICE_APPEARED = 3,
+    DEVICE_VOLUME_CHANGED = 4,
+    DEVICE_ALIAS_CHANGED = 5,
+}
+
+impl Default for ClusterUpdateReason {
+    fn default() -> Self {
+        ClusterUpdateReason::INVALID_CLUSTER_UPDATE_REASON
+    }
+}
+
+impl From<i32> for ClusterUpdateReason {
+    fn from(i: i32) -> Self {
+        match i {
+            0 => ClusterUpdateReason::INVALID_CLUSTER_UPDATE_REASON,
+            1 => ClusterUpdateReason::DEVICES_DISAPPEARED,
+            2 => ClusterUpdateReason::DEVICE_STATE_CHANGED,
+            3 => ClusterUpdateReason::NEW_DEVICE_APPEARED,
+            4 => ClusterUpdateReason::DEVICE_VOLUME_CHANGED,
+            5 => ClusterUpdateReason::DEVICE_ALIAS_CHANGED,
+            _ => Self::default(),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for ClusterUpdateReason {
+    fn from(s: &'a str) -> Self {
+        match s {
+            "INVALID_CLUSTER_UPDATE_REASON" => ClusterUpdateReason::INVALID_CLUSTER_UPDATE_REASON,
+            "DEVICES_DISAPPEARED" => ClusterUpdateReason::DEVICES_DISAPPEARED,
+            "DEVICE_STATE_CHANGED" => ClusterUpdateReason::DEVICE_STATE_CHANGED,
+            "NEW_DEVICE_APPEARED" => ClusterUpdateReason::NEW_DEVICE_APPEARED,
+            "DEVICE_VOLUME_CHANGED" => ClusterUpdateReason::DEVICE_VOLUME_CHANGED,
+            "DEVICE_ALIAS_CHANGED" => ClusterUpdateReason::DEVICE_ALIAS_CHANGED,
+            _ => Self::default(),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct PutStateRequest {
+    pub callback_url: Option<String>,
+    pub device: Option<connect::Device>,
+    pub member_type: Option<connect::MemberType>,
+    pub is_active: Option<bool>,
+    pub put_state_reason: Option<connect::PutStateReason>,
+    pub message_id: Option<u32>,
+    pub last_command_sent_by_device_id: Option<String>,
+    pub last_command_message_id: Option<u32>,
+    pub started_playing_at: Option<u64>,
+    pub has_been_playing_for_ms: Option<u64>,
+    pub client_side_timestamp: Option<u64>,
+    pub only_write_player_state: Option<bool>,
+}
+
+impl<'a> MessageRead<'a> for PutStateRequest {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.callback_url = Some(r.read_string(bytes)?.to_owned()),
+                Ok(18) => msg.device = Some(r.read_message::<connect::Device>(bytes)?),
+                Ok(24) => msg.member_type = Some(r.read_enum(bytes)?),
+                Ok(32) => msg.is_active = Some(r.read_bool(bytes)?),
+                Ok(40) => msg.put_state_reason = Some(r.read_enum(bytes)?),
+                Ok(48) => msg.message_id = Some(r.read_uint32(bytes)?),
+                Ok(58) => msg.last_command_sent_by_device_id = Some(r.read_string(bytes)?.to_owned()),
+                Ok(64) => msg.last_command_message_id = Some(r.read_uint32(bytes)?),
+                Ok(72) => msg.started_playing_at = Some(r.read_uint64(bytes)?),
+                Ok(88) => msg.has_been_playing_for_ms = Some(r.read_uint64(bytes)?),
+                Ok(96) => msg.client_side_timestamp = Some(r.read_uint64(bytes)?),
+                Ok(104) => msg.only_write_player_state = Some(r.read_bool(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for PutStateRequest {
+    fn get_size(&self) -> usize {
+        0
+        + self.callback_url.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.device.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.member_type.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.is_active.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.put_state_reason.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.message_id.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.last_command_sent_by_device_id.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.last_command_message_id.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.started_playing_at.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.has_been_playing_for_ms.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.client_side_timestamp.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.only_write_player_state.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.callback_url { w.write_with_tag(10, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.device { w.write_with_tag(18, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.member_type { w.write_with_tag(24, |w| w.write_enum(*s as i32))?; }
+        if let Some(ref s) = self.is_active { w.write_with_tag(32, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.put_state_reason { w.write_with_tag(40, |w| w.write_enum(*s as i32))?; }
+        if let Some(ref s) = self.message_id { w.write_with_tag(48, |w| w.write_uint32(*s))?; }
+        if let Some(ref s) = self.last_command_sent_by_device_id { w.write_with_tag(58, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.last_command_message_id { w.write_with_tag(64, |w| w.write_uint32(*s))?; }
+        if let Some(ref s) = self.started_playing_at { w.write_with_tag(72, |w| w.write_uint64(*s))?; }
+        if let Some(ref s) = self.has_been_playing_for_ms { w.write_with_tag(88, |w| w.write_uint64(*s))?; }
+        if let Some(ref s) = self.client_side_timestamp { w.write_with_tag(96, |w| w.write_uint64(*s))?; }
+        if let Some(ref s) = self.only_write_player_state { w.write_with_tag(104, |w| w.write_bool(*s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Device {
+    pub device_info: Option<connect::DeviceInfo>,
+    pub player_state: Option<connect::PlayerState>,
+}
+
+impl<'a> MessageRead<'a> for Device {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.device_info = Some(r.read_message::<connect::DeviceInfo>(bytes)?),
+                Ok(18) => msg.player_state = Some(r.read_message::<connect::PlayerState>(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for Device {
+    fn get_size(&self) -> usize {
+        0
+        + self.device_info.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.player_state.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.device_info { w.write_with_tag(10, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.player_state { w.write_with_tag(18, |w| w.write_message(s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct DeviceInfo {
+    pub can_play: Option<bool>,
+    pub volume: Option<u32>,
+    pub name: Option<String>,
+    pub capabilities: Option<connect::Capabilities>,
+    pub device_software_version: Option<String>,
+    pub device_type: Option<connect::DeviceType>,
+    pub spirc_version: Option<String>,
+    pub device_id: Option<String>,
+    pub is_private_session: Option<bool>,
+    pub is_social_connect: Option<bool>,
+    pub client_id: Option<String>,
+    pub brand: Option<String>,
+    pub model: Option<String>,
+}
+
+impl<'a> MessageRead<'a> for DeviceInfo {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.can_play = Some(r.read_bool(bytes)?),
+                Ok(16) => msg.volume = Some(r.read_uint32(bytes)?),
+                Ok(26) => msg.name = Some(r.read_string(bytes)?.to_owned()),
+                Ok(34) => msg.capabilities = Some(r.read_message::<connect::Capabilities>(bytes)?),
+                Ok(50) => msg.device_software_version = Some(r.read_string(bytes)?.to_owned()),
+                Ok(56) => msg.device_type = Some(r.read_enum(bytes)?),
+                Ok(74) => msg.spirc_version = Some(r.read_string(bytes)?.to_owned()),
+                Ok(82) => msg.device_id = Some(r.read_string(bytes)?.to_owned()),
+                Ok(88) => msg.is_private_session = Some(r.read_bool(bytes)?),
+                Ok(96) => msg.is_social_connect = Some(r.read_bool(bytes)?),
+                Ok(106) => msg.client_id = Some(r.read_string(bytes)?.to_owned()),
+                Ok(114) => msg.brand = Some(r.read_string(bytes)?.to_owned()),
+                Ok(122) => msg.model = Some(r.read_string(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for DeviceInfo {
+    fn get_size(&self) -> usize {
+        0
+        + self.can_play.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.volume.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.name.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.capabilities.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.device_software_version.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.device_type.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.spirc_version.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.device_id.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.is_private_session.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.is_social_connect.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.client_id.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.brand.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.model.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.can_play { w.write_with_tag(8, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.volume { w.write_with_tag(16, |w| w.write_uint32(*s))?; }
+        if let Some(ref s) = self.name { w.write_with_tag(26, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.capabilities { w.write_with_tag(34, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.device_software_version { w.write_with_tag(50, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.device_type { w.write_with_tag(56, |w| w.write_enum(*s as i32))?; }
+        if let Some(ref s) = self.spirc_version { w.write_with_tag(74, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.device_id { w.write_with_tag(82, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.is_private_session { w.write_with_tag(88, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.is_social_connect { w.write_with_tag(96, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.client_id { w.write_with_tag(106, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.brand { w.write_with_tag(114, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.model { w.write_with_tag(122, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Capabilities {
+    pub can_be_player: Option<bool>,
+    pub restrict_to_local: Option<bool>,
+    pub gaia_eq_connect_id: Option<bool>,
+    pub supports_logout: Option<bool>,
+    pub is_observable: Option<bool>,
+    pub volume_steps: Option<i32>,
+    pub supported_types: Vec<String>,
+    pub command_acks: Option<bool>,
+    pub supports_rename: Option<bool>,
+    pub hidden: Option<bool>,
+    pub disable_volume: Option<bool>,
+    pub connect_disabled: Option<bool>,
+    pub supports_playlist_v2: Option<bool>,
+    pub is_controllable: Option<bool>,
+    pub supports_external_episodes: Option<bool>,
+    pub supports_set_backend_metadata: Option<bool>,
+    pub supports_transfer_command: Option<bool>,
+    pub supports_command_request: Option<bool>,
+    pub is_voice_enabled: Option<bool>,
+    pub needs_full_player_state: Option<bool>,
+    pub supports_gzip_pushes: Option<bool>,
+}
+
+impl<'a> MessageRead<'a> for Capabilities {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(16) => msg.can_be_player = Some(r.read_bool(bytes)?),
+                Ok(24) => msg.restrict_to_local = Some(r.read_bool(bytes)?),
+                Ok(40) => msg.gaia_eq_connect_id = Some(r.read_bool(bytes)?),
+                Ok(48) => msg.supports_logout = Some(r.read_bool(bytes)?),
+                Ok(56) => msg.is_observable = Some(r.read_bool(bytes)?),
+                Ok(64) => msg.volume_steps = Some(r.read_int32(bytes)?),
+                Ok(74) => msg.supported_types.push(r.read_string(bytes)?.to_owned()),
+                Ok(80) => msg.command_acks = Some(r.read_bool(bytes)?),
+                Ok(88) => msg.supports_rename = Some(r.read_bool(bytes)?),
+                Ok(96) => msg.hidden = Some(r.read_bool(bytes)?),
+                Ok(104) => msg.disable_volume = Some(r.read_bool(bytes)?),
+                Ok(112) => msg.connect_disabled = Some(r.read_bool(bytes)?),
+                Ok(120) => msg.supports_playlist_v2 = Some(r.read_bool(bytes)?),
+                Ok(128) => msg.is_controllable = Some(r.read_bool(bytes)?),
+                Ok(136) => msg.supports_external_episodes = Some(r.read_bool(bytes)?),
+                Ok(144) => msg.supports_set_backend_metadata = Some(r.read_bool(bytes)?),
+                Ok(152) => msg.supports_transfer_command = Some(r.read_bool(bytes)?),
+                Ok(160) => msg.supports_command_request = Some(r.read_bool(bytes)?),
+                Ok(168) => msg.is_voice_enabled = Some(r.read_bool(bytes)?),
+                Ok(176) => msg.needs_full_player_state = Some(r.read_bool(bytes)?),
+                Ok(184) => msg.supports_gzip_pushes = Some(r.read_bool(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for Capabilities {
+    fn get_size(&self) -> usize {
+        0
+        + self.can_be_player.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.restrict_to_local.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.gaia_eq_connect_id.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.supports_logout.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.is_observable.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.volume_steps.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.supported_types.iter().map(|s| 1 + sizeof_len((s).len())).sum::<usize>()
+        + self.command_acks.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.supports_rename.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.hidden.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.disable_volume.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.connect_disabled.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.supports_playlist_v2.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.is_controllable.as_ref().map_or(0, |m| 2 + sizeof_varint(*(m) as u64))
+        + self.supports_external_episodes.as_ref().map_or(0, |m| 2 + sizeof_varint(*(m) as u64))
+        + self.supports_set_backend_metadata.as_ref().map_or(0, |m| 2 + sizeof_varint(*(m) as u64))
+        + self.supports_transfer_command.as_ref().map_or(0, |m| 2 + sizeof_varint(*(m) as u64))
+        + self.supports_command_request.as_ref().map_or(0, |m| 2 + sizeof_varint(*(m) as u64))
+        + self.is_voice_enabled.as_ref().map_or(0, |m| 2 + sizeof_varint(*(m) as u64))
+        + self.needs_full_player_state.as_ref().map_or(0, |m| 2 + sizeof_varint(*(m) as u64))
+        + self.supports_gzip_pushes.as_ref().map_or(0, |m| 2 + sizeof_varint(*(m) as u64))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.can_be_player { w.write_with_tag(16, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.restrict_to_local { w.write_with_tag(24, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.gaia_eq_connect_id { w.write_with_tag(40, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.supports_logout { w.write_with_tag(48, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.is_observable { w.write_with_tag(56, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.volume_steps { w.write_with_tag(64, |w| w.write_int32(*s))?; }
+        for s in &self.supported_types { w.write_with_tag(74, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.command_acks { w.write_with_tag(80, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.supports_rename { w.write_with_tag(88, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.hidden { w.write_with_tag(96, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.disable_volume { w.write_with_tag(104, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.connect_disabled { w.write_with_tag(112, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.supports_playlist_v2 { w.write_with_tag(120, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.is_controllable { w.write_with_tag(128, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.supports_external_episodes { w.write_with_tag(136, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.supports_set_backend_metadata { w.write_with_tag(144, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.supports_transfer_command { w.write_with_tag(152, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.supports_command_request { w.write_with_tag(160, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.is_voice_enabled { w.write_with_tag(168, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.needs_full_player_state { w.write_with_tag(176, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.supports_gzip_pushes { w.write_with_tag(184, |w| w.write_bool(*s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct PlayerState {
+    pub timestamp: Option<i64>,
+    pub context_uri: Option<String>,
+    pub context_url: Option<String>,
+    pub track: Option<connect::ProvidedTrack>,
+    pub playback_id: Option<String>,
+    pub playback_speed: Option<f64>,
+    pub position_as_of_timestamp: Option<i64>,
+    pub duration: Option<i64>,
+    pub is_playing: Option<bool>,
+    pub is_paused: Option<bool>,
+    pub is_buffering: Option<bool>,
+    pub is_system_initiated: Option<bool>,
+    pub options: Option<connect::ContextPlayerOptions>,
+    pub session_id: Option<String>,
+    pub queue_revision: Option<String>,
+    pub position: Option<i64>,
+}
+
+impl<'a> MessageRead<'a> for PlayerState {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.timestamp = Some(r.read_int64(bytes)?),
+                Ok(18) => msg.context_uri = Some(r.read_string(bytes)?.to_owned()),
+                Ok(26) => msg.context_url = Some(r.read_string(bytes)?.to_owned()),
+                Ok(58) => msg.track = Some(r.read_message::<connect::ProvidedTrack>(bytes)?),
+                Ok(66) => msg.playback_id = Some(r.read_string(bytes)?.to_owned()),
+                Ok(73) => msg.playback_speed = Some(r.read_double(bytes)?),
+                Ok(80) => msg.position_as_of_timestamp = Some(r.read_int64(bytes)?),
+                Ok(88) => msg.duration = Some(r.read_int64(bytes)?),
+                Ok(96) => msg.is_playing = Some(r.read_bool(bytes)?),
+                Ok(104) => msg.is_paused = Some(r.read_bool(bytes)?),
+                Ok(112) => msg.is_buffering = Some(r.read_bool(bytes)?),
+                Ok(120) => msg.is_system_initiated = Some(r.read_bool(bytes)?),
+                Ok(130) => msg.options = Some(r.read_message::<connect::ContextPlayerOptions>(bytes)?),
+                Ok(194) => msg.session_id = Some(r.read_string(bytes)?.to_owned()),
+                Ok(202) => msg.queue_revision = Some(r.read_string(bytes)?.to_owned()),
+                Ok(208) => msg.position = Some(r.read_int64(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for PlayerState {
+    fn get_size(&self) -> usize {
+        0
+        + self.timestamp.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.context_uri.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.context_url.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.track.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.playback_id.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.playback_speed.as_ref().map_or(0, |m| 1 + 8)
+        + self.position_as_of_timestamp.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.duration.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.is_playing.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.is_paused.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.is_buffering.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.is_system_initiated.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.options.as_ref().map_or(0, |m| 2 + sizeof_len((m).get_size()))
+        + self.session_id.as_ref().map_or(0, |m| 2 + sizeof_len((m).len()))
+        + self.queue_revision.as_ref().map_or(0, |m| 2 + sizeof_len((m).len()))
+        + self.position.as_ref().map_or(0, |m| 2 + sizeof_varint(*(m) as u64))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.timestamp { w.write_with_tag(8, |w| w.write_int64(*s))?; }
+        if let Some(ref s) = self.context_uri { w.write_with_tag(18, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.context_url { w.write_with_tag(26, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.track { w.write_with_tag(58, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.playback_id { w.write_with_tag(66, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.playback_speed { w.write_with_tag(73, |w| w.write_double(*s))?; }
+        if let Some(ref s) = self.position_as_of_timestamp { w.write_with_tag(80, |w| w.write_int64(*s))?; }
+        if let Some(ref s) = self.duration { w.write_with_tag(88, |w| w.write_int64(*s))?; }
+        if let Some(ref s) = self.is_playing { w.write_with_tag(96, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.is_paused { w.write_with_tag(104, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.is_buffering { w.write_with_tag(112, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.is_system_initiated { w.write_with_tag(120, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.options { w.write_with_tag(130, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.session_id { w.write_with_tag(194, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.queue_revision { w.write_with_tag(202, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.position { w.write_with_tag(208, |w| w.write_int64(*s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ProvidedTrack {
+    pub uri: Option<String>,
+    pub uid: Option<String>,
+    pub provider: Option<String>,
+}
+
+impl<'a> MessageRead<'a> for ProvidedTrack {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.uri = Some(r.read_string(bytes)?.to_owned()),
+                Ok(18) => msg.uid = Some(r.read_string(bytes)?.to_owned()),
+                Ok(50) => msg.provider = Some(r.read_string(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for ProvidedTrack {
+    fn get_size(&self) -> usize {
+        0
+        + self.uri.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.uid.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.provider.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.uri { w.write_with_tag(10, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.uid { w.write_with_tag(18, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.provider { w.write_with_tag(50, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ContextPlayerOptions {
+    pub shuffling_context: Option<bool>,
+    pub repeating_context: Option<bool>,
+    pub repeating_track: Option<bool>,
+}
+
+impl<'a> MessageRead<'a> for ContextPlayerOptions {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.shuffling_context = Some(r.read_bool(bytes)?),
+                Ok(16) => msg.repeating_context = Some(r.read_bool(bytes)?),
+                Ok(24) => msg.repeating_track = Some(r.read_bool(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for ContextPlayerOptions {
+    fn get_size(&self) -> usize {
+        0
+        + self.shuffling_context.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.repeating_context.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.repeating_track.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.shuffling_context { w.write_with_tag(8, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.repeating_context { w.write_with_tag(16, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.repeating_track { w.write_with_tag(24, |w| w.write_bool(*s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ClusterUpdate {
+    pub cluster: Option<connect::Cluster>,
+    pub update_reason: Option<connect::ClusterUpdateReason>,
+    pub ack_id: Option<String>,
+    pub devices_that_changed: Vec<String>,
+}
+
+impl<'a> MessageRead<'a> for ClusterUpdate {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.cluster = Some(r.read_message::<connect::Cluster>(bytes)?),
+                Ok(16) => msg.update_reason = Some(r.read_enum(bytes)?),
+                Ok(26) => msg.ack_id = Some(r.read_string(bytes)?.to_owned()),
+                Ok(34) => msg.devices_that_changed.push(r.read_string(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for ClusterUpdate {
+    fn get_size(&self) -> usize {
+        0
+        + self.cluster.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.update_reason.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.ack_id.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.devices_that_changed.iter().map(|s| 1 + sizeof_len((s).len())).sum::<usize>()
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.cluster { w.write_with_tag(10, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.update_reason { w.write_with_tag(16, |w| w.write_enum(*s as i32))?; }
+        if let Some(ref s) = self.ack_id { w.write_with_tag(26, |w| w.write_string(&**s))?; }
+        for s in &self.devices_that_changed { w.write_with_tag(34, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Cluster {
+    pub timestamp: Option<i64>,
+    pub active_device_id: Option<String>,
+    pub player_state: Option<connect::PlayerState>,
+}
+
+impl<'a> MessageRead<'a> for Cluster {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.timestamp = Some(r.read_int64(bytes)?),
+                Ok(18) => msg.active_device_id = Some(r.read_string(bytes)?.to_owned()),
+                Ok(26) => msg.player_state = Some(r.read_message::<connect::PlayerState>(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for Cluster {
+    fn get_size(&self) -> usize {
+        0
+        + self.timestamp.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.active_device_id.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.player_state.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.timestamp { w.write_with_tag(8, |w| w.write_int64(*s))?; }
+        if let Some(ref s) = self.active_device_id { w.write_with_tag(18, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.player_state { w.write_with_tag(26, |w| w.write_message(s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct SetVolumeCommand {
+    pub volume: Option<i32>,
+}
+
+impl<'a> MessageRead<'a> for SetVolumeCommand {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.volume = Some(r.read_int32(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for SetVolumeCommand {
+    fn get_size(&self) -> usize {
+        0
+        + self.volume.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.volume { w.write_with_tag(8, |w| w.write_int32(*s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct TransferState {
+    pub options: Option<connect::ContextPlayerOptions>,
+    pub playback: Option<connect::Playback>,
+    pub current_session: Option<connect::Session>,
+    pub queue: Option<connect::Queue>,
+}
+
+impl<'a> MessageRead<'a> for TransferState {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.options = Some(r.read_message::<connect::ContextPlayerOptions>(bytes)?),
+                Ok(18) => msg.playback = Some(r.read_message::<connect::Playback>(bytes)?),
+                Ok(26) => msg.current_session = Some(r.read_message::<connect::Session>(bytes)?),
+                Ok(34) => msg.queue = Some(r.read_message::<connect::Queue>(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for TransferState {
+    fn get_size(&self) -> usize {
+        0
+        + self.options.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.playback.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.current_session.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.queue.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.options { w.write_with_tag(10, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.playback { w.write_with_tag(18, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.current_session { w.write_with_tag(26, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.queue { w.write_with_tag(34, |w| w.write_message(s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Playback {
+    pub timestamp: Option<i64>,
+    pub position_as_of_timestamp: Option<i32>,
+    pub playback_speed: Option<f64>,
+    pub is_paused: Option<bool>,
+    pub current_track: Option<connect::ContextTrack>,
+}
+
+impl<'a> MessageRead<'a> for Playback {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.timestamp = Some(r.read_int64(bytes)?),
+                Ok(16) => msg.position_as_of_timestamp = Some(r.read_int32(bytes)?),
+                Ok(25) => msg.playback_speed = Some(r.read_double(bytes)?),
+                Ok(32) => msg.is_paused = Some(r.read_bool(bytes)?),
+                Ok(42) => msg.current_track = Some(r.read_message::<connect::ContextTrack>(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for Playback {
+    fn get_size(&self) -> usize {
+        0
+        + self.timestamp.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.position_as_of_timestamp.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.playback_speed.as_ref().map_or(0, |m| 1 + 8)
+        + self.is_paused.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.current_track.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.timestamp { w.write_with_tag(8, |w| w.write_int64(*s))?; }
+        if let Some(ref s) = self.position_as_of_timestamp { w.write_with_tag(16, |w| w.write_int32(*s))?; }
+        if let Some(ref s) = self.playback_speed { w.write_with_tag(25, |w| w.write_double(*s))?; }
+        if let Some(ref s) = self.is_paused { w.write_with_tag(32, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.current_track { w.write_with_tag(42, |w| w.write_message(s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Session {
+    pub context: Option<connect::Context>,
+    pub current_uid: Option<String>,
+}
+
+impl<'a> MessageRead<'a> for Session {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(18) => msg.context = Some(r.read_message::<connect::Context>(bytes)?),
+                Ok(26) => msg.current_uid = Some(r.read_string(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for Session {
+    fn get_size(&self) -> usize {
+        0
+        + self.context.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.current_uid.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.context { w.write_with_tag(18, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.current_uid { w.write_with_tag(26, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Context {
+    pub uri: Option<String>,
+    pub url: Option<String>,
+    pub pages: Vec<connect::ContextPage>,
+}
+
+impl<'a> MessageRead<'a> for Context {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.uri = Some(r.read_string(bytes)?.to_owned()),
+                Ok(18) => msg.url = Some(r.read_string(bytes)?.to_owned()),
+                Ok(42) => msg.pages.push(r.read_message::<connect::ContextPage>(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for Context {
+    fn get_size(&self) -> usize {
+        0
+        + self.uri.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.url.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.pages.iter().map(|s| 1 + sizeof_len((s).get_size())).sum::<usize>()
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.uri { w.write_with_tag(10, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.url { w.write_with_tag(18, |w| w.write_string(&**s))?; }
+        for s in &self.pages { w.write_with_tag(42, |w| w.write_message(s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ContextPage {
+    pub page_url: Option<String>,
+    pub next_page_url: Option<String>,
+    pub tracks: Vec<connect::ContextTrack>,
+}
+
+impl<'a> MessageRead<'a> for ContextPage {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.page_url = Some(r.read_string(bytes)?.to_owned()),
+                Ok(18) => msg.next_page_url = Some(r.read_string(bytes)?.to_owned()),
+                Ok(34) => msg.tracks.push(r.read_message::<connect::ContextTrack>(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for ContextPage {
+    fn get_size(&self) -> usize {
+        0
+        + self.page_url.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.next_page_url.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.tracks.iter().map(|s| 1 + sizeof_len((s).get_size())).sum::<usize>()
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.page_url { w.write_with_tag(10, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.next_page_url { w.write_with_tag(18, |w| w.write_string(&**s))?; }
+        for s in &self.tracks { w.write_with_tag(34, |w| w.write_message(s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ContextTrack {
+    pub uri: Option<String>,
+    pub uid: Option<String>,
+    pub gid: Option<Vec<u8>>,
+}
+
+impl<'a> MessageRead<'a> for ContextTrack {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.uri = Some(r.read_string(bytes)?.to_owned()),
+                Ok(18) => msg.uid = Some(r.read_string(bytes)?.to_owned()),
+                Ok(26) => msg.gid = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for ContextTrack {
+    fn get_size(&self) -> usize {
+        0
+        + self.uri.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.uid.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.gid.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.uri { w.write_with_tag(10, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.uid { w.write_with_tag(18, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.gid { w.write_with_tag(26, |w| w.write_bytes(&**s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Queue {
+    pub tracks: Vec<connect::ContextTrack>,
+    pub is_playing_queue: Option<bool>,
+}
+
+impl<'a> MessageRead<'a> for Queue {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.tracks.push(r.read_message::<connect::ContextTrack>(bytes)?),
+                Ok(16) => msg.is_playing_queue = Some(r.read_bool(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for Queue {
+    fn get_size(&self) -> usize {
+        0
+        + self.tracks.iter().map(|s| 1 + sizeof_len((s).get_size())).sum::<usize>()
+        + self.is_playing_queue.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        for s in &self.tracks { w.write_with_tag(10, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.is_playing_queue { w.write_with_tag(16, |w| w.write_bool(*s))?; }
+        Ok(())
+    }
+}